@@ -4,13 +4,16 @@
 
 //! VBDecompiler CLI - Command-line interface for decompiling VB5/6 executables
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 use std::fs;
-use std::io;
-use std::path::PathBuf;
-use vbdecompiler_core::{detect_packer, Decompiler, Error};
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+use vbdecompiler_core::diff::{diff_results, MethodChange};
+use vbdecompiler_core::{
+    analyze, detect_packer, Decompiler, DecompilerOptions, Error, IndentStyle, VBFile,
+};
 
 #[derive(Parser)]
 #[command(name = "vbdc")]
@@ -27,6 +30,11 @@ struct Cli {
     /// Quiet mode (minimal output, errors only)
     #[arg(short, long, global = true)]
     quiet: bool,
+
+    /// Disable colored output (also off when stdout is not a terminal or
+    /// NO_COLOR is set)
+    #[arg(long, global = true)]
+    no_color: bool,
 }
 
 #[derive(Subcommand)]
@@ -45,9 +53,50 @@ enum Commands {
         #[arg(short, long, value_enum, default_value = "vb6")]
         format: OutputFormat,
 
-        /// Force processing even if warnings detected
+        /// Decompile even if the file looks packed (reported as a warning)
         #[arg(long)]
         force: bool,
+
+        /// Render VB runtime calls as the VB6 code they implement
+        /// (`__vbaStrCat` as `&`, `rtcMsgBox` as `MsgBox`); pass `=false` to
+        /// keep the raw runtime symbols
+        #[arg(
+            long,
+            value_name = "BOOL",
+            default_value_t = true,
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "true",
+            action = ArgAction::Set
+        )]
+        demangle_runtime: bool,
+
+        /// List native-code methods as NASM with addresses and instruction
+        /// bytes
+        #[arg(long)]
+        listing: bool,
+
+        /// Write integers that look like flags or masks as `&H` hex literals
+        #[arg(long)]
+        hex_literals: bool,
+
+        /// Indentation of generated code: `tabs` or a number of spaces
+        #[arg(long, value_name = "STYLE", default_value = "4", value_parser = parse_indent)]
+        indent: IndentStyle,
+
+        /// Write one file per object into the output directory, splitting
+        /// modules with more than N methods into numbered parts
+        #[arg(long, value_name = "N", requires = "output")]
+        split_modules: Option<usize>,
+
+        /// Also split modules whose code exceeds this many bytes
+        #[arg(long, value_name = "BYTES", requires = "split_modules")]
+        split_bytes: Option<usize>,
+
+        /// Treat FILE as a directory and decompile every .exe/.dll/.ocx under
+        /// it, mirroring the tree into the output directory
+        #[arg(short, long, requires = "output", conflicts_with = "split_modules")]
+        recursive: bool,
     },
 
     /// Analyze a VB executable without decompiling
@@ -75,11 +124,48 @@ enum Commands {
         #[arg(short = 'x', long)]
         hex: bool,
 
+        /// Output format (text or json)
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: InfoFormat,
+
         /// Output file (default: stdout)
         #[arg(short, long, value_name = "FILE")]
         output: Option<PathBuf>,
     },
 
+    /// Dump the raw VB header and object tables, annotated by field
+    DumpStructs {
+        /// Path to VB executable
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+    },
+
+    /// List string literals from P-Code and the data section
+    Strings {
+        /// Path to VB executable
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+
+        /// Skip strings shorter than this many characters
+        #[arg(long, value_name = "N", default_value_t = 4)]
+        min_len: usize,
+    },
+
+    /// Compare the decompiled methods of two VB executables
+    Diff {
+        /// Original executable
+        #[arg(value_name = "OLD")]
+        a: PathBuf,
+
+        /// Executable to compare against it
+        #[arg(value_name = "NEW")]
+        b: PathBuf,
+
+        /// Output format (text or json)
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: InfoFormat,
+    },
+
     /// Check if executable is packed
     CheckPacker {
         /// Path to executable
@@ -103,6 +189,8 @@ enum OutputFormat {
     Json,
     /// IR (Intermediate Representation)
     Ir,
+    /// VB6 project file (.vbp); in directory mode, the modules too
+    Vbp,
 }
 
 #[derive(Clone, Copy, clap::ValueEnum)]
@@ -113,6 +201,17 @@ enum InfoFormat {
     Json,
 }
 
+/// Parse an `--indent` value: `tabs` or a number of spaces
+fn parse_indent(value: &str) -> Result<IndentStyle, String> {
+    if value.eq_ignore_ascii_case("tabs") {
+        return Ok(IndentStyle::Tabs);
+    }
+    value
+        .parse()
+        .map(IndentStyle::Spaces)
+        .map_err(|_| format!("expected `tabs` or a number of spaces, got `{}`", value))
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -124,7 +223,19 @@ fn main() {
     } else {
         "info"
     };
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level)).init();
+
+    // Keep ANSI escapes out of pipes and files (https://no-color.org); log
+    // records go to stderr, so that stream decides for them
+    let no_color = cli.no_color || std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let mut logger =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(log_level));
+    if no_color || !io::stderr().is_terminal() {
+        logger.write_style(env_logger::WriteStyle::Never);
+    }
+    logger.init();
+    if no_color || !io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
 
     // Execute command
     let result = match cli.command {
@@ -133,13 +244,56 @@ fn main() {
             output,
             format,
             force,
-        } => cmd_decompile(input, output, format, force, cli.quiet),
+            demangle_runtime,
+            listing,
+            hex_literals,
+            indent,
+            split_modules,
+            split_bytes,
+            recursive,
+        } => {
+            let options = DecompilerOptions {
+                reject_packed: !force,
+                demangle_runtime,
+                native_listing: listing,
+                hex_literals,
+                indent,
+                ..Default::default()
+            };
+            match split_modules {
+                _ if recursive => cmd_decompile_batch(
+                    input,
+                    output.unwrap_or_default(),
+                    format,
+                    options,
+                    cli.quiet,
+                ),
+                Some(max_methods) => cmd_decompile_split(
+                    input,
+                    output.unwrap_or_default(),
+                    format,
+                    max_methods,
+                    split_bytes,
+                    options,
+                    cli.quiet,
+                ),
+                None => cmd_decompile(input, output, format, options, cli.quiet),
+            }
+        }
         Commands::Info {
             input,
             detailed,
             format,
         } => cmd_info(input, detailed, format, cli.quiet),
-        Commands::Disasm { input, hex, output } => cmd_disasm(input, hex, output, cli.quiet),
+        Commands::Disasm {
+            input,
+            hex,
+            format,
+            output,
+        } => cmd_disasm(input, hex, format, output, cli.quiet),
+        Commands::DumpStructs { input } => cmd_dump_structs(input, cli.quiet),
+        Commands::Strings { input, min_len } => cmd_strings(input, min_len, cli.quiet),
+        Commands::Diff { a, b, format } => cmd_diff(a, b, format, cli.quiet),
         Commands::CheckPacker { input } => cmd_check_packer(input, cli.quiet),
         Commands::Completions { shell } => {
             cmd_completions(shell);
@@ -158,39 +312,32 @@ fn cmd_decompile(
     input: PathBuf,
     output: Option<PathBuf>,
     format: OutputFormat,
-    _force: bool,
+    options: DecompilerOptions,
     quiet: bool,
 ) -> Result<(), Error> {
     if !quiet {
         println!("{} {}", "Decompiling:".green().bold(), input.display());
     }
 
-    let mut decompiler = Decompiler::new();
-    let result = decompiler.decompile_file(input.to_str().unwrap())?;
+    let result = decompile(&input, options)?;
 
-    // Generate output based on format
-    let output_content = match format {
-        OutputFormat::Vb6 => format_vb6(&result, quiet),
-        OutputFormat::Json => format_json(&result)?,
-        OutputFormat::Ir => format_ir(&result),
-    };
+    let output_content = format_output(&result, format, quiet)?;
 
     // Write to output
     if let Some(output_path) = output {
         // Determine if output is a directory or file
-        if output_path.is_dir() {
+        if output_path.is_dir() && matches!(format, OutputFormat::Vb6 | OutputFormat::Vbp) {
+            // One .frm/.bas/.cls file per VB object
+            write_modules(&result, &output_path, format, usize::MAX, None, quiet)?;
+        } else if output_path.is_dir() {
             // Generate filename based on input
             let filename = input
                 .file_stem()
                 .unwrap_or_default()
                 .to_string_lossy()
                 .into_owned();
-            let extension = match format {
-                OutputFormat::Vb6 => "vb",
-                OutputFormat::Json => "json",
-                OutputFormat::Ir => "ir.txt",
-            };
-            let output_file = output_path.join(format!("{}.{}", filename, extension));
+            let output_file =
+                output_path.join(format!("{}.{}", filename, output_extension(format)));
 
             fs::write(&output_file, output_content)?;
 
@@ -221,6 +368,181 @@ fn cmd_decompile(
     Ok(())
 }
 
+/// Generate output based on format
+fn format_output(
+    result: &vbdecompiler_core::DecompilationResult,
+    format: OutputFormat,
+    quiet: bool,
+) -> Result<String, Error> {
+    Ok(match format {
+        OutputFormat::Vb6 => format_vb6(result, quiet),
+        OutputFormat::Json => format_json(result)?,
+        OutputFormat::Ir => format_ir(result),
+        OutputFormat::Vbp => result.project_file.clone(),
+    })
+}
+
+/// File extension for single-file output in `format`
+fn output_extension(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Vb6 => "vb",
+        OutputFormat::Json => "json",
+        OutputFormat::Ir => "ir.txt",
+        OutputFormat::Vbp => "vbp",
+    }
+}
+
+fn cmd_decompile_batch(
+    input: PathBuf,
+    output_dir: PathBuf,
+    format: OutputFormat,
+    options: DecompilerOptions,
+    quiet: bool,
+) -> Result<(), Error> {
+    if !quiet {
+        println!("{} {}", "Scanning:".green().bold(), input.display());
+    }
+
+    let mut decompiler = Decompiler::with_options(options);
+    let results = decompiler.decompile_dir(&input)?;
+
+    let mut failures = Vec::new();
+    for (relative, result) in &results {
+        let written = result
+            .as_ref()
+            .map_err(ToString::to_string)
+            .and_then(|result| {
+                // Keep the input extension so app.exe and app.dll don't collide
+                let mut file_name = relative.as_os_str().to_owned();
+                file_name.push(".");
+                file_name.push(output_extension(format));
+                let output_file = output_dir.join(file_name);
+                let content = format_output(result, format, quiet).map_err(|e| e.to_string())?;
+                output_file
+                    .parent()
+                    .map_or(Ok(()), fs::create_dir_all)
+                    .and_then(|()| fs::write(&output_file, content))
+                    .map_err(|e| e.to_string())?;
+                Ok((output_file, &result.warnings))
+            });
+
+        match written {
+            Ok((output_file, warnings)) => {
+                if !quiet {
+                    for warning in warnings {
+                        eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+                    }
+                    println!(
+                        "{} {} -> {}",
+                        "OK:".green().bold(),
+                        relative.display(),
+                        output_file.display()
+                    );
+                }
+            }
+            Err(e) => {
+                if !quiet {
+                    println!("{} {}: {}", "FAILED:".red().bold(), relative.display(), e);
+                }
+                failures.push((relative, e));
+            }
+        }
+    }
+
+    println!(
+        "{} {} decompiled, {} failed",
+        "Summary:".cyan().bold(),
+        results.len() - failures.len(),
+        failures.len()
+    );
+    if quiet {
+        for (relative, e) in &failures {
+            println!("  {}: {}", relative.display(), e);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_decompile_split(
+    input: PathBuf,
+    output_dir: PathBuf,
+    format: OutputFormat,
+    max_methods: usize,
+    max_bytes: Option<usize>,
+    options: DecompilerOptions,
+    quiet: bool,
+) -> Result<(), Error> {
+    if !matches!(format, OutputFormat::Vb6 | OutputFormat::Vbp) {
+        return Err(Error::NotImplemented(
+            "--split-modules writes source files; use --format vb6 or vbp".to_string(),
+        ));
+    }
+    if !quiet {
+        println!("{} {}", "Decompiling:".green().bold(), input.display());
+    }
+
+    let result = decompile(&input, options)?;
+
+    fs::create_dir_all(&output_dir)?;
+    write_modules(&result, &output_dir, format, max_methods, max_bytes, quiet)
+}
+
+/// Write each decompiled module into `output_dir` under its VB file name,
+/// plus the `.vbp` listing them when `format` is [`OutputFormat::Vbp`]
+fn write_modules(
+    result: &vbdecompiler_core::DecompilationResult,
+    output_dir: &Path,
+    format: OutputFormat,
+    max_methods: usize,
+    max_bytes: Option<usize>,
+    quiet: bool,
+) -> Result<(), Error> {
+    let mut written = Vec::new();
+    for module in &result.modules {
+        for file in module.split(max_methods, max_bytes) {
+            let output_file = output_dir.join(&file.file_name);
+            fs::write(&output_file, file.code)?;
+            written.push(output_file);
+        }
+    }
+
+    if matches!(format, OutputFormat::Vbp) {
+        let output_file = output_dir.join(format!("{}.vbp", result.project_name));
+        fs::write(
+            &output_file,
+            result.split_project_file(max_methods, max_bytes),
+        )?;
+        written.push(output_file);
+    }
+
+    if !quiet {
+        for output_file in written {
+            println!(
+                "{} {}",
+                "Output written to:".green().bold(),
+                output_file.display()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Decompile `input` with `options`, reporting any warnings
+fn decompile(
+    input: &Path,
+    options: DecompilerOptions,
+) -> Result<vbdecompiler_core::DecompilationResult, Error> {
+    let mut decompiler = Decompiler::with_options(options);
+    let result = decompiler.decompile_file(input)?;
+
+    for warning in &result.warnings {
+        eprintln!("{} {}", "Warning:".yellow().bold(), warning);
+    }
+    Ok(result)
+}
+
 fn format_vb6(result: &vbdecompiler_core::DecompilationResult, quiet: bool) -> String {
     let mut output = String::new();
 
@@ -230,6 +552,19 @@ fn format_vb6(result: &vbdecompiler_core::DecompilationResult, quiet: bool) -> S
         output.push_str(&format!("P-Code: {}\n", result.is_pcode));
         output.push_str(&format!("Objects: {}\n", result.object_count));
         output.push_str(&format!("Methods: {}\n", result.method_count));
+        output.push_str(&format!(
+            "Instructions: {} ({:.1}% unknown opcodes)\n",
+            result.instruction_count(),
+            result.unknown_opcode_ratio() * 100.0
+        ));
+        for (opcode, count) in result.top_opcodes(5) {
+            output.push_str(&format!(
+                "  {:02X} {:<20} {}\n",
+                opcode,
+                vbdecompiler_core::pcode::opcode_mnemonic(opcode),
+                count
+            ));
+        }
         output.push_str(&format!("{}\n\n", "=".repeat(60)));
     }
 
@@ -256,25 +591,29 @@ fn cmd_info(input: PathBuf, detailed: bool, format: InfoFormat, quiet: bool) ->
         println!("{} {}", "Analyzing:".green().bold(), input.display());
     }
 
-    // Read and analyze file
     let data = fs::read(&input)?;
+    let report = analyze(&data)?;
 
-    // Basic PE info
-    let pe_result = vbdecompiler_core::pe::PEFile::from_bytes(data.clone());
-
-    // Packer detection
-    let packer_result = detect_packer(&data);
+    // Per-method sizes need the disassembled project, so only when asked
+    let method_sizes = match &report.vb {
+        Some(_) if detailed => {
+            let pe = vbdecompiler_core::pe::PEFile::from_bytes_allow_packed(data)?;
+            Some(VBFile::from_pe(pe)?.method_sizes())
+        }
+        _ => None,
+    };
 
     // Output based on format
     match format {
         InfoFormat::Text => {
+            let pe = &report.pe;
             println!("\n{}", "=".repeat(60).blue());
             println!("{} {}", "File:".cyan().bold(), input.display());
-            println!("{} {} bytes", "Size:".cyan().bold(), data.len());
+            println!("{} {} bytes", "Size:".cyan().bold(), report.size);
 
             // Packer info
-            match packer_result {
-                Ok(Some(detection)) => {
+            match &report.packer {
+                Some(detection) => {
                     println!(
                         "{} {} ({:.0}% confidence, via {:?})",
                         "Packer:".yellow().bold(),
@@ -285,68 +624,170 @@ fn cmd_info(input: PathBuf, detailed: bool, format: InfoFormat, quiet: bool) ->
                     println!("\n{}", "Unpacking instructions:".yellow());
                     println!("{}", detection.packer.unpack_instructions());
                 }
-                Ok(None) => {
+                None => {
                     println!("{} {}", "Packer:".cyan().bold(), "None detected");
                 }
-                Err(e) => {
-                    println!("{} {}", "Packer detection error:".yellow(), e);
-                }
             }
 
             // PE info
-            match pe_result {
-                Ok(pe) => {
-                    println!("{} 0x{:08X}", "Image Base:".cyan().bold(), pe.image_base());
+            println!(
+                "{} {}",
+                "Format:".cyan().bold(),
+                if pe.is_64bit { "PE32+" } else { "PE32" }
+            );
+            println!("{} 0x{:08X}", "Image Base:".cyan().bold(), pe.image_base);
+            println!("{} 0x{:08X}", "Entry Point:".cyan().bold(), pe.entry_point);
+            println!("{} {}", "Is DLL:".cyan().bold(), pe.is_dll);
+            if let Some(vb) = &report.vb {
+                println!("{} {}", "VB Version:".cyan().bold(), vb.version);
+                if let Some(project_name) = &vb.project_name {
+                    println!("{} {}", "Project:".cyan().bold(), project_name);
+                }
+                println!(
+                    "{} {} objects, {} methods ({})",
+                    "Contents:".cyan().bold(),
+                    vb.object_count,
+                    vb.method_count,
+                    if vb.is_pcode { "P-Code" } else { "native" }
+                );
+                if pe.is_dll {
+                    if let Some(clsid) = vb.com_registration.as_ref().and_then(|r| r.clsid.as_ref())
+                    {
+                        println!("{} {}", "CLSID:".cyan().bold(), clsid);
+                    }
+                }
+            }
+            println!("{} {}", "Sections:".cyan().bold(), pe.sections.len());
+            if let Some(info) = &pe.version_info {
+                for (label, value) in [
+                    ("Company:", info.company_name()),
+                    ("Product:", info.product_name()),
+                    (
+                        "File Version:",
+                        info.get("FileVersion").or(info.file_version.as_deref()),
+                    ),
+                ] {
+                    if let Some(value) = value {
+                        println!("{} {}", label.cyan().bold(), value);
+                    }
+                }
+                if detailed && !info.strings.is_empty() {
+                    println!("\n{}", "Version Info:".cyan().bold());
+                    for (key, value) in &info.strings {
+                        println!("  {:<18} {}", key, value);
+                    }
+                }
+            }
+            if let Some(overlay) = pe.overlay {
+                println!(
+                    "{} {} bytes at 0x{:08X}",
+                    "Overlay:".cyan().bold(),
+                    overlay.size,
+                    overlay.offset
+                );
+            }
+
+            if detailed {
+                let checksum_status = if pe.checksum.valid {
+                    "valid".green()
+                } else {
+                    "mismatch".yellow()
+                };
+                println!(
+                    "{} stored 0x{:08X}, computed 0x{:08X} ({})",
+                    "Checksum:".cyan().bold(),
+                    pe.checksum.stored,
+                    pe.checksum.computed,
+                    checksum_status
+                );
+                println!(
+                    "{} {} ({})",
+                    "Relocations:".cyan().bold(),
+                    pe.relocation_count,
+                    if pe.relocatable {
+                        "ASLR enabled"
+                    } else {
+                        "fixed base"
+                    }
+                );
+
+                println!("\n{}", "Section Table:".cyan().bold());
+                for section in &pe.sections {
                     println!(
-                        "{} 0x{:08X}",
-                        "Entry Point:".cyan().bold(),
-                        pe.entry_point()
+                        "  {:<8} VA=0x{:08X} Size=0x{:08X} Entropy={:.2}",
+                        section.name,
+                        section.virtual_address,
+                        section.virtual_size,
+                        section.entropy
                     );
-                    println!("{} {}", "Is DLL:".cyan().bold(), pe.is_dll());
-                    println!("{} {}", "Sections:".cyan().bold(), pe.sections().len());
+                }
 
-                    if detailed {
-                        println!("\n{}", "Section Table:".cyan().bold());
-                        for section in pe.sections() {
-                            let name = String::from_utf8_lossy(&section.name);
-                            println!(
-                                "  {} VA=0x{:08X} Size=0x{:08X}",
-                                name.trim_end_matches('\0'),
-                                section.virtual_address,
-                                section.virtual_size
-                            );
+                println!("\n{}", "Imported DLLs:".cyan().bold());
+                for dll in &pe.imports {
+                    println!("  {}", dll);
+                }
+                for (dll, functions) in &pe.delay_load_imports {
+                    println!("  {} (delay-load: {})", dll, functions.join(", "));
+                }
+                for (dll, timestamp) in &pe.bound_imports {
+                    println!("  {} (bound, timestamp 0x{:08X})", dll, timestamp);
+                }
+
+                if !pe.exports.is_empty() {
+                    let title = if pe.is_com_server {
+                        "Exports (COM server):"
+                    } else {
+                        "Exports:"
+                    };
+                    println!("\n{}", title.cyan().bold());
+                    for export in &pe.exports {
+                        let name = export.name.as_deref().unwrap_or("<ordinal only>");
+                        match &export.forwarded {
+                            Some(target) => {
+                                println!("  #{:<4} {} -> {}", export.ordinal, name, target)
+                            }
+                            None => println!(
+                                "  #{:<4} {} RVA=0x{:08X}",
+                                export.ordinal, name, export.rva
+                            ),
                         }
+                    }
+                }
 
-                        println!("\n{}", "Imported DLLs:".cyan().bold());
-                        for dll in pe.imported_dlls() {
-                            println!("  {}", dll);
+                match &pe.rich_header {
+                    Some(rich) => {
+                        println!("\n{} key 0x{:08X}", "Rich Header:".cyan().bold(), rich.key);
+                        for entry in &rich.entries {
+                            println!(
+                                "  ProdID=0x{:04X} Build={:<5} Count={}",
+                                entry.product_id, entry.build, entry.count
+                            );
                         }
                     }
+                    None => println!("\n{} none", "Rich Header:".cyan().bold()),
                 }
-                Err(e) => {
-                    println!("{} {}", "PE parsing error:".red(), e);
+            }
+
+            if let Some(method_sizes) = method_sizes.as_ref().filter(|m| !m.is_empty()) {
+                println!("\n{}", "Methods (largest first):".cyan().bold());
+                for entry in method_sizes {
+                    let name = format!("{}.{}", entry.object, entry.method);
+                    match (entry.instructions, entry.basic_blocks) {
+                        (Some(instructions), Some(blocks)) => println!(
+                            "  {:<40} {:>6} bytes {:>6} instrs {:>4} blocks",
+                            name, entry.size, instructions, blocks
+                        ),
+                        _ => println!("  {:<40} {:>6} bytes", name, entry.size),
+                    }
                 }
             }
 
             println!("{}", "=".repeat(60).blue());
         }
         InfoFormat::Json => {
-            // JSON output
-            let json_data = serde_json::json!({
-                "file": input.to_str(),
-                "size": data.len(),
-                "packer": packer_result.ok().and_then(|p| p.map(|d| serde_json::json!({
-                    "name": d.packer.name(),
-                    "confidence": d.confidence,
-                    "method": format!("{:?}", d.method),
-                }))),
-                "pe": pe_result.as_ref().ok().map(|pe| serde_json::json!({
-                    "image_base": format!("0x{:08X}", pe.image_base()),
-                    "entry_point": format!("0x{:08X}", pe.entry_point()),
-                    "is_dll": pe.is_dll(),
-                    "section_count": pe.sections().len(),
-                })),
-            });
+            let mut json_data = serde_json::to_value(&report).unwrap();
+            json_data["file"] = serde_json::json!(input.to_string_lossy());
+            json_data["methods"] = serde_json::json!(method_sizes);
             println!("{}", serde_json::to_string_pretty(&json_data).unwrap());
         }
     }
@@ -357,6 +798,7 @@ fn cmd_info(input: PathBuf, detailed: bool, format: InfoFormat, quiet: bool) ->
 fn cmd_disasm(
     input: PathBuf,
     hex: bool,
+    format: InfoFormat,
     output: Option<PathBuf>,
     quiet: bool,
 ) -> Result<(), Error> {
@@ -364,24 +806,30 @@ fn cmd_disasm(
         println!("{} {}", "Disassembling:".green().bold(), input.display());
     }
 
-    // TODO: Full P-Code disassembly implementation
-    // For now, provide basic disassembly info
+    let vb_file = Decompiler::new().parse_file(input)?;
 
-    let mut decompiler = Decompiler::new();
-    let result = decompiler.decompile_file(input.to_str().unwrap())?;
-
-    let mut disasm_output = String::new();
-    disasm_output.push_str(&format!("; P-Code Disassembly\n"));
-    disasm_output.push_str(&format!("; Project: {}\n", result.project_name));
-    disasm_output.push_str(&format!("; Methods: {}\n\n", result.method_count));
-
-    if hex {
-        disasm_output.push_str("; Hex dump mode enabled\n\n");
-    }
-
-    disasm_output.push_str("; TODO: Full P-Code disassembly not yet implemented\n");
-    disasm_output.push_str("; Current output shows decompiled code:\n\n");
-    disasm_output.push_str(&result.vb6_code);
+    let project_name = vb_file.project_name();
+    let project_name = project_name.as_deref().unwrap_or("Unknown");
+    let disasm_output = match format {
+        InfoFormat::Text => {
+            let mut disasm_output = String::new();
+            disasm_output.push_str("; P-Code Disassembly\n");
+            disasm_output.push_str(&format!("; Project: {}\n", project_name));
+            disasm_output.push_str(&format!("; Objects: {}\n\n", vb_file.objects().len()));
+            disasm_output.push_str(&vb_file.disassembly_listing(hex));
+            disasm_output
+        }
+        InfoFormat::Json => {
+            let json_data = serde_json::json!({
+                "project": project_name,
+                "object_count": vb_file.objects().len(),
+                "methods": vb_file.disassemble_methods(),
+            });
+            let mut json = serde_json::to_string_pretty(&json_data).unwrap();
+            json.push('\n');
+            json
+        }
+    };
 
     // Write output
     if let Some(output_path) = output {
@@ -400,6 +848,84 @@ fn cmd_disasm(
     Ok(())
 }
 
+fn cmd_dump_structs(input: PathBuf, quiet: bool) -> Result<(), Error> {
+    if !quiet {
+        println!("{} {}", "Dumping:".green().bold(), input.display());
+    }
+
+    let vb_file = Decompiler::new().parse_file(input)?;
+
+    for dump in vb_file.dump_structs() {
+        println!();
+        print!("{}", dump);
+    }
+
+    Ok(())
+}
+
+fn cmd_strings(input: PathBuf, min_len: usize, quiet: bool) -> Result<(), Error> {
+    if !quiet {
+        println!(
+            "{} {}",
+            "Extracting strings:".green().bold(),
+            input.display()
+        );
+    }
+
+    let vb_file = Decompiler::new().parse_file(input)?;
+
+    for found in vb_file.strings(min_len) {
+        println!("{}", found);
+    }
+
+    Ok(())
+}
+
+fn cmd_diff(a: PathBuf, b: PathBuf, format: InfoFormat, quiet: bool) -> Result<(), Error> {
+    if !quiet {
+        println!(
+            "{} {} {}",
+            "Comparing:".green().bold(),
+            a.display(),
+            b.display()
+        );
+    }
+
+    let old = decompile(&a, DecompilerOptions::default())?;
+    let new = decompile(&b, DecompilerOptions::default())?;
+    let diff = diff_results(&old, &new);
+
+    if matches!(format, InfoFormat::Json) {
+        println!("{}", serde_json::to_string_pretty(&diff).unwrap());
+        return Ok(());
+    }
+
+    for method in &diff.methods {
+        for line in method.diff.lines() {
+            if line.starts_with("---") || line.starts_with("+++") {
+                println!("{}", line.bold());
+            } else if line.starts_with("@@") {
+                println!("{}", line.cyan());
+            } else if line.starts_with('-') {
+                println!("{}", line.red());
+            } else if line.starts_with('+') {
+                println!("{}", line.green());
+            } else {
+                println!("{}", line);
+            }
+        }
+    }
+
+    println!(
+        "{} changed, {} added, {} removed, {} unchanged",
+        diff.count(MethodChange::Changed),
+        diff.count(MethodChange::Added),
+        diff.count(MethodChange::Removed),
+        diff.unchanged
+    );
+    Ok(())
+}
+
 fn cmd_check_packer(input: PathBuf, quiet: bool) -> Result<(), Error> {
     if !quiet {
         println!("{} {}", "Checking:".green().bold(), input.display());
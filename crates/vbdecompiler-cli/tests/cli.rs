@@ -0,0 +1,201 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! End-to-end tests of the `vbdc` binary on synthetic fixtures
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use vbdecompiler_core::testutil::{build_vb_exe, ObjectSpec, ProjectSpec};
+
+/// Fresh scratch directory for one test
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A one-module project whose `Main` runs `pcode`
+fn fixture_with(project_name: &str, pcode: Vec<u8>) -> Vec<u8> {
+    let module = ObjectSpec::new("Module1", 0x01).method("Main", pcode);
+    build_vb_exe(&ProjectSpec::new(project_name).object(module))
+}
+
+/// A one-module project whose `Main` just returns
+fn fixture(project_name: &str) -> Vec<u8> {
+    fixture_with(project_name, vec![0x14])
+}
+
+fn vbdc(args: &[&str]) -> Output {
+    vbdc_with_env(args, &[])
+}
+
+fn vbdc_with_env(args: &[&str], env: &[(&str, &str)]) -> Output {
+    let output = Command::new(env!("CARGO_BIN_EXE_vbdc"))
+        .args(args)
+        .env_remove("NO_COLOR")
+        .envs(env.iter().copied())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "vbdc {:?} failed: {}",
+        args,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+#[test]
+fn test_recursive_decompile_keeps_input_extensions() {
+    let dir = scratch_dir("recursive");
+    let input = dir.join("in");
+    let output = dir.join("out");
+    fs::create_dir_all(input.join("sub")).unwrap();
+    fs::write(input.join("app.exe"), fixture("App")).unwrap();
+    fs::write(input.join("app.dll"), fixture("AppLib")).unwrap();
+    fs::write(input.join("sub").join("tool.exe"), fixture("Tool")).unwrap();
+    // A link back to the root would recurse forever if followed
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&input, input.join("sub").join("loop")).unwrap();
+
+    let stdout = vbdc(&[
+        "decompile",
+        "--recursive",
+        input.to_str().unwrap(),
+        "--output",
+        output.to_str().unwrap(),
+    ])
+    .stdout;
+
+    let exe = fs::read_to_string(output.join("app.exe.vb")).unwrap();
+    let dll = fs::read_to_string(output.join("app.dll.vb")).unwrap();
+    assert!(exe.contains("Main"));
+    assert!(dll.contains("Main"));
+    assert!(output.join("sub").join("tool.exe.vb").exists());
+    assert!(!output.join("sub").join("loop").exists());
+    assert!(String::from_utf8_lossy(&stdout).contains("3 decompiled, 0 failed"));
+}
+
+#[test]
+fn test_disasm_lists_pcode() {
+    let dir = scratch_dir("disasm");
+    let input = dir.join("app.exe");
+    // LitI2 1; ExitProc
+    fs::write(&input, fixture_with("App", vec![0x5E, 0x01, 0x14])).unwrap();
+
+    let text = vbdc(&["-q", "disasm", "--hex", input.to_str().unwrap()]).stdout;
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.starts_with("; P-Code Disassembly\n; Project: App\n; Objects: 1\n\n"));
+    assert!(text.contains("; Module1.Main\n"));
+    let lines: Vec<&str> = text
+        .lines()
+        .skip_while(|l| *l != "; Module1.Main")
+        .collect();
+    assert!(lines[1].starts_with("5E 01 "));
+    assert!(lines[1].ends_with("  LitI2  0x01"));
+    assert!(lines[2].ends_with("  ExitProc"));
+
+    let json = vbdc(&["-q", "disasm", "--format", "json", input.to_str().unwrap()]).stdout;
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    assert_eq!(json["project"], "App");
+    let method = &json["methods"][0];
+    assert_eq!(method["object"], "Module1");
+    assert_eq!(method["method"], "Main");
+    assert_eq!(method["instructions"][0]["mnemonic"], "LitI2");
+    assert_eq!(method["instructions"][1]["mnemonic"], "ExitProc");
+}
+
+#[test]
+fn test_piped_output_has_no_color() {
+    let dir = scratch_dir("no-color");
+    let input = dir.join("app.exe");
+    fs::write(&input, fixture("App")).unwrap();
+    let input = input.to_str().unwrap();
+
+    // Output is captured through pipes, so stdout is not a terminal; that
+    // must win even over a request to force colors
+    for args in [
+        vec!["info", input],
+        vec!["decompile", input],
+        vec!["disasm", input],
+    ] {
+        let output = vbdc_with_env(&args, &[("CLICOLOR_FORCE", "1")]);
+        for stream in [&output.stdout, &output.stderr] {
+            let text = String::from_utf8_lossy(stream);
+            assert!(!text.contains("\x1b["), "vbdc {:?} printed: {}", args, text);
+        }
+        assert!(!output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn test_split_modules_honors_format() {
+    let dir = scratch_dir("split");
+    let input = dir.join("split.exe");
+    let module = ObjectSpec::new("Module1", 0x01)
+        .named_constant("MAX_ITEMS", 2, 10)
+        .method("Main", vec![0x14]);
+    fs::write(
+        &input,
+        build_vb_exe(&ProjectSpec::new("Split").object(module)),
+    )
+    .unwrap();
+    let args = |format| {
+        [
+            "-q",
+            "decompile",
+            input.to_str().unwrap(),
+            "--format",
+            format,
+            "--output",
+            dir.to_str().unwrap(),
+            "--split-modules",
+            "1",
+        ]
+    };
+
+    vbdc(&args("vb6"));
+    assert!(dir.join("Module1.bas").exists());
+    assert!(!dir.join("Split.vbp").exists());
+
+    vbdc(&args("vbp"));
+    let project = fs::read_to_string(dir.join("Split.vbp")).unwrap();
+    assert!(project.contains("Module=Module1; Module1.bas\n"));
+
+    // Split output is source files; other formats are refused, not ignored
+    let json = Command::new(env!("CARGO_BIN_EXE_vbdc"))
+        .args(args("json"))
+        .output()
+        .unwrap();
+    assert!(!json.status.success());
+    assert!(String::from_utf8_lossy(&json.stderr).contains("--format vb6 or vbp"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_input_path() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let dir = scratch_dir("non-utf8");
+    let input = dir.join(OsStr::from_bytes(b"app\xFF.exe"));
+    fs::write(&input, fixture("App")).unwrap();
+
+    for command in ["decompile", "disasm", "dump-structs", "strings"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_vbdc"))
+            .args(["-q", command])
+            .arg(&input)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "vbdc {} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Time locating P-Code per method against locating it once per object
+//!
+//! Builds a synthetic project with many P-Code methods and compares the two
+//! ways the decompiler can find each method's code:
+//!
+//! - per method: `get_pcode_for_method` plus `method_frame_size`, each reading
+//!   the procedure descriptor (what every parallel worker used to do)
+//! - per object: `pcode_locations` once, then `read_pcode` per method (what
+//!   `Decompiler::decompile_file` does now)
+//!
+//! Run with `cargo run --release --example locate-pcode --features testutil`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use vbdecompiler_core::pe::PEFile;
+use vbdecompiler_core::testutil::{build_vb_exe, ObjectSpec, ProjectSpec};
+use vbdecompiler_core::{Decompiler, VBFile};
+
+// The most objects the parser accepts
+const OBJECTS: usize = 1000;
+const ROUNDS: u32 = 200;
+
+fn time(label: &str, mut pass: impl FnMut() -> usize) -> Duration {
+    let start = Instant::now();
+    let mut methods = 0;
+    for _ in 0..ROUNDS {
+        methods = black_box(pass());
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<12} {:>8.1} ns/method ({} methods x {} rounds)",
+        label,
+        elapsed.as_nanos() as f64 / (methods as f64 * ROUNDS as f64),
+        methods,
+        ROUNDS
+    );
+    elapsed
+}
+
+fn main() {
+    // The fixture lays out one well-formed P-Code method per object
+    let mut spec = ProjectSpec::new("Many");
+    for i in 0..OBJECTS {
+        // LitI2 1; ExitProc
+        let object =
+            ObjectSpec::new(&format!("Module{}", i), 0x01).method("Main", vec![0x5E, 0x01, 0x14]);
+        spec = spec.object(object);
+    }
+    let exe = build_vb_exe(&spec);
+    let vb_file = VBFile::from_pe(PEFile::from_bytes(exe.clone()).unwrap()).unwrap();
+
+    let per_method = time("per method", || {
+        let mut methods = 0;
+        for object_index in 0..vb_file.objects().len() {
+            let pcode = vb_file.get_pcode_for_method(object_index, 0);
+            let frame_size = vb_file.method_frame_size(object_index, 0);
+            black_box((pcode, frame_size));
+            methods += 1;
+        }
+        methods
+    });
+    let per_object = time("per object", || {
+        let mut methods = 0;
+        for object_index in 0..vb_file.objects().len() {
+            for location in vb_file.pcode_locations(object_index) {
+                black_box((vb_file.read_pcode(&location), location.frame_size));
+                methods += 1;
+            }
+        }
+        methods
+    });
+
+    println!(
+        "speedup: {:.2}x",
+        per_method.as_secs_f64() / per_object.as_secs_f64()
+    );
+
+    // For scale: everything else decompiling a method costs
+    let start = Instant::now();
+    let result = Decompiler::new().decompile_bytes(exe).unwrap();
+    println!(
+        "decompile    {:>8.1} ns/method ({} methods)",
+        start.elapsed().as_nanos() as f64 / OBJECTS as f64,
+        result.method_count
+    );
+}
@@ -0,0 +1,226 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Static analysis of an executable without decompiling it
+//!
+//! [`analyze`] gathers the PE, packer and VB project facts that `vbdc info`
+//! shows into one serializable [`AnalysisReport`].
+
+use crate::error::Result;
+use crate::packer::{detect_packer, PackerDetection};
+use crate::pe::{ExportEntry, PEFile, RichHeader, VersionInfo};
+use crate::vb::{ComRegistration, VBFile};
+
+/// Static facts about an executable
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AnalysisReport {
+    /// File size in bytes
+    pub size: usize,
+    /// Detected packer, if any
+    pub packer: Option<PackerDetection>,
+    /// PE headers and directories
+    pub pe: PeReport,
+    /// VB project details (None when the file holds no VB project)
+    pub vb: Option<VbReport>,
+}
+
+/// PE facts of an [`AnalysisReport`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PeReport {
+    pub is_64bit: bool,
+    pub image_base: u64,
+    /// Entry point RVA
+    pub entry_point: u32,
+    pub is_dll: bool,
+    pub is_com_server: bool,
+    pub sections: Vec<SectionReport>,
+    /// Names of the imported DLLs
+    pub imports: Vec<String>,
+    /// Delay-loaded DLLs and the functions taken from each
+    pub delay_load_imports: Vec<(String, Vec<String>)>,
+    /// Bound DLLs and their bind timestamps
+    pub bound_imports: Vec<(String, u32)>,
+    pub exports: Vec<ExportEntry>,
+    /// Data appended after the last section
+    pub overlay: Option<OverlayReport>,
+    pub version_info: Option<VersionInfo>,
+    pub rich_header: Option<RichHeader>,
+    /// Whether the image can be loaded at another base (ASLR)
+    pub relocatable: bool,
+    /// Number of base relocation fixups
+    pub relocation_count: usize,
+    pub checksum: ChecksumReport,
+}
+
+/// One section of a [`PeReport`]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SectionReport {
+    pub name: String,
+    pub virtual_address: u32,
+    pub virtual_size: u32,
+    /// Shannon entropy of the raw data (0.0-8.0)
+    pub entropy: f64,
+}
+
+/// Overlay location of a [`PeReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct OverlayReport {
+    /// File offset
+    pub offset: usize,
+    /// Size in bytes
+    pub size: usize,
+}
+
+/// Stored and computed PE checksums
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ChecksumReport {
+    pub stored: u32,
+    pub computed: u32,
+    pub valid: bool,
+}
+
+/// VB project facts of an [`AnalysisReport`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VbReport {
+    pub is_pcode: bool,
+    pub project_name: Option<String>,
+    /// VB version inferred from the runtime and headers
+    pub version: String,
+    pub object_count: usize,
+    pub method_count: usize,
+    /// COM registration of an ActiveX project
+    pub com_registration: Option<ComRegistration>,
+}
+
+impl PeReport {
+    fn new(pe: &PEFile) -> Self {
+        let sections = pe
+            .sections()
+            .iter()
+            .zip(pe.section_entropies())
+            .map(|(section, (name, entropy))| SectionReport {
+                name,
+                virtual_address: section.virtual_address,
+                virtual_size: section.virtual_size,
+                entropy,
+            })
+            .collect();
+        let overlay = pe
+            .overlay_offset()
+            .zip(pe.overlay_data())
+            .map(|(offset, data)| OverlayReport {
+                offset,
+                size: data.len(),
+            });
+
+        Self {
+            is_64bit: pe.is_64bit(),
+            image_base: pe.image_base(),
+            entry_point: pe.entry_point(),
+            is_dll: pe.is_dll(),
+            is_com_server: pe.is_com_server(),
+            sections,
+            imports: pe.imported_dlls(),
+            delay_load_imports: pe.delay_load_imports(),
+            bound_imports: pe.bound_imports(),
+            exports: pe.exports(),
+            overlay,
+            version_info: pe.version_info(),
+            rich_header: pe.rich_header(),
+            relocatable: pe.is_relocatable(),
+            relocation_count: pe
+                .base_relocations()
+                .iter()
+                .map(|block| block.entries.len())
+                .sum(),
+            checksum: ChecksumReport {
+                stored: pe.stored_checksum(),
+                computed: pe.compute_checksum(),
+                valid: pe.verify_checksum(),
+            },
+        }
+    }
+}
+
+impl VbReport {
+    fn new(vb_file: &VBFile) -> Self {
+        Self {
+            is_pcode: vb_file.is_pcode(),
+            project_name: vb_file.project_name(),
+            version: vb_file.vb_version().to_string(),
+            object_count: vb_file.objects().len(),
+            method_count: vb_file.objects().iter().map(|o| o.method_count()).sum(),
+            com_registration: vb_file.com_registration(),
+        }
+    }
+}
+
+/// Gather the static facts of an executable held in memory
+///
+/// Packed files are accepted (the packer is reported). Fails if the data is
+/// not a PE file, or is a .NET assembly rather than a VB project.
+///
+/// ```no_run
+/// let data = std::fs::read("program.exe")?;
+/// let report = vbdecompiler_core::analyze(&data)?;
+/// if let Some(vb) = &report.vb {
+///     println!("{} objects, {} methods", vb.object_count, vb.method_count);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn analyze(data: &[u8]) -> Result<AnalysisReport> {
+    let packer = detect_packer(data).ok().flatten();
+    let pe = PEFile::from_bytes_allow_packed(data.to_vec())?;
+    let pe_report = PeReport::new(&pe);
+
+    let is_dotnet = pe.is_dotnet();
+    let vb = match VBFile::from_pe(pe) {
+        Ok(vb_file) => Some(VbReport::new(&vb_file)),
+        // A .NET assembly is not VB at all: say so instead of reporting PE details
+        Err(e) if is_dotnet => return Err(e),
+        Err(_) => None,
+    };
+
+    Ok(AnalysisReport {
+        size: data.len(),
+        packer,
+        pe: pe_report,
+        vb,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{build_vb_exe, ObjectSpec, ProjectSpec, IMAGE_BASE};
+
+    #[test]
+    fn test_analyze_fixture() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(
+                ObjectSpec::new("Class1", 0x02)
+                    .method("Get", vec![0x14])
+                    .method("Set", vec![0x14]),
+            );
+        let data = build_vb_exe(&spec);
+        let report = analyze(&data).unwrap();
+
+        assert_eq!(report.size, data.len());
+        assert!(report.packer.is_none());
+        assert!(!report.pe.is_64bit);
+        assert!(!report.pe.is_dll);
+        assert_eq!(report.pe.image_base, IMAGE_BASE as u64);
+        assert!(report.pe.sections.iter().any(|s| s.name == ".text"));
+        assert!(report.pe.imports.iter().any(|dll| dll == "MSVBVM60.DLL"));
+        assert!(report.pe.overlay.is_none());
+
+        let vb = report.vb.unwrap();
+        assert!(vb.is_pcode);
+        assert_eq!(vb.project_name.as_deref(), Some("Project1"));
+        assert_eq!((vb.object_count, vb.method_count), (2, 3));
+
+        assert!(analyze(b"not a PE file").is_err());
+    }
+}
@@ -13,17 +13,267 @@
 //! - Expression generation with proper VB6 syntax
 //! - Basic control flow generation
 //! - Proper indentation
+//! - Dialect-specific peepholes (VB6 vs VB.NET)
+//! - `&H` hex literals for flag-like integers
+//! - File header banners
 
 use crate::ir::*;
+use crate::vb::{VBConstant, WithEventsMember};
+use std::collections::{HashMap, HashSet};
+
+/// Objects available without a declaration: the VB runtime's globals and
+/// the current object
+pub(crate) const GLOBAL_OBJECTS: &[&str] = &[
+    "App",
+    "Clipboard",
+    "Debug",
+    "Err",
+    "Forms",
+    "Me",
+    "Printer",
+    "Printers",
+    "Screen",
+];
+
+/// Element type of the array `name` from its indexed accesses, `Variant`
+/// when none of them is typed
+fn array_element_type(function: &Function, name: &str) -> TypeKind {
+    let mut element_type = TypeKind::Variant;
+    for stmt in function.basic_blocks.iter().flat_map(|b| &b.statements) {
+        stmt.visit_expressions(&mut |expr| {
+            if let ExpressionData::ArrayIndex { array, .. } = &expr.data {
+                let typed = !matches!(expr.expr_type.kind, TypeKind::Variant | TypeKind::Unknown);
+                if typed && matches!(&array.data, ExpressionData::Variable(v) if v.name == name) {
+                    element_type = expr.expr_type.kind;
+                }
+            }
+        });
+    }
+    element_type
+}
+
+/// Successor a block falls through or jumps to unconditionally, which is
+/// laid out right after it when possible
+fn layout_successor(block: &BasicBlock) -> Option<u32> {
+    match block.statements.last().map(|s| &s.data) {
+        Some(StatementData::Return { .. }) => None,
+        Some(StatementData::Goto { target_block }) => Some(*target_block),
+        Some(StatementData::Branch { target_block, .. }) => {
+            block.successors.iter().copied().find(|s| s != target_block)
+        }
+        _ => block.successors.first().copied(),
+    }
+}
+
+/// The non-empty blocks of a function in the order they are emitted
+fn emitted_blocks(function: &Function) -> Vec<&BasicBlock> {
+    block_order(function)
+        .into_iter()
+        .map(|index| &function.basic_blocks[index])
+        .filter(|b| !b.statements.is_empty())
+        .collect()
+}
+
+/// Indices of `function.basic_blocks` in reverse postorder from the entry
+/// block, followed by unreachable blocks (error handlers) in their original
+/// order
+///
+/// Each block's layout successor is visited last so that, unless it is also
+/// reached some other way, it is emitted right after the block.
+fn block_order(function: &Function) -> Vec<usize> {
+    let index_of: HashMap<u32, usize> = function
+        .basic_blocks
+        .iter()
+        .enumerate()
+        .map(|(index, b)| (b.id, index))
+        .collect();
+    let children = |index: usize| -> Vec<usize> {
+        let block = &function.basic_blocks[index];
+        let layout = layout_successor(block);
+        let mut ids: Vec<u32> = block
+            .successors
+            .iter()
+            .copied()
+            .filter(|&s| Some(s) != layout)
+            .collect();
+        ids.extend(layout);
+        ids.iter()
+            .filter_map(|id| index_of.get(id).copied())
+            .collect()
+    };
+
+    let mut visited = vec![false; function.basic_blocks.len()];
+    let mut postorder = Vec::with_capacity(function.basic_blocks.len());
+    if let Some(&entry) = index_of.get(&function.entry_block_id) {
+        visited[entry] = true;
+        let mut stack = vec![(entry, children(entry), 0)];
+        while let Some((index, successors, next)) = stack.last_mut() {
+            match successors.get(*next).copied() {
+                Some(child) => {
+                    *next += 1;
+                    if !visited[child] {
+                        visited[child] = true;
+                        stack.push((child, children(child), 0));
+                    }
+                }
+                None => {
+                    postorder.push(*index);
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = postorder.into_iter().rev().collect();
+    order.extend((0..visited.len()).filter(|&index| !visited[index]));
+    order
+}
+
+/// Target language dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Classic Visual Basic 6
+    #[default]
+    VB6,
+    /// VB.NET (allows `Dim x As T = value`)
+    VBNet,
+}
+
+impl Dialect {
+    /// Line comment prefix
+    pub fn comment_prefix(&self) -> &'static str {
+        match self {
+            Self::VB6 | Self::VBNet => "'",
+        }
+    }
+}
+
+/// Indentation of each nesting level of generated code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// This many spaces per level
+    Spaces(usize),
+    /// One tab per level
+    Tabs,
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        Self::Spaces(4)
+    }
+}
+
+impl IndentStyle {
+    /// Whitespace for one level
+    fn unit(self) -> String {
+        match self {
+            Self::Spaces(width) => " ".repeat(width),
+            Self::Tabs => "\t".to_string(),
+        }
+    }
+}
+
+/// Header banner emitted at the top of generated files
+///
+/// The version and source file share the attribution line, so a banner
+/// without a timestamp or warning is a single line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BannerConfig {
+    /// Decompiler version
+    pub tool_version: Option<String>,
+    /// Name of the binary the code was recovered from
+    pub source_file: Option<String>,
+    /// Generation timestamp (free-form)
+    pub timestamp: Option<String>,
+    /// Warn that the code was decompiled and may not compile as-is
+    pub warning: bool,
+}
 
 /// VB6 Code Generator
 pub struct VB6CodeGenerator {
     indent_level: usize,
+    indent_style: IndentStyle,
+    dialect: Dialect,
+    banner: BannerConfig,
+    /// Object of the enclosing `With` block, whose members render as `.member`
+    with_object: Option<Variable>,
+    /// Render flag-like integers as `&H` literals
+    hex_literals: bool,
 }
 
 impl VB6CodeGenerator {
     pub fn new() -> Self {
-        Self { indent_level: 0 }
+        Self::with_dialect(Dialect::VB6)
+    }
+
+    /// Create a generator targeting the given dialect
+    pub fn with_dialect(dialect: Dialect) -> Self {
+        Self {
+            indent_level: 0,
+            indent_style: IndentStyle::default(),
+            dialect,
+            banner: BannerConfig::default(),
+            with_object: None,
+            hex_literals: false,
+        }
+    }
+
+    /// Use the given header banner configuration
+    pub fn with_banner(mut self, banner: BannerConfig) -> Self {
+        self.banner = banner;
+        self
+    }
+
+    /// Indent nested code with the given style (four spaces by default)
+    pub fn with_indent(mut self, style: IndentStyle) -> Self {
+        self.indent_style = style;
+        self
+    }
+
+    /// Render integers that look like flags or masks as `&H` literals
+    ///
+    /// VB doesn't record the radix a literal was written in, so this is a
+    /// guess: operands of `And`/`Or`/`Xor` and large values made up of `0`
+    /// and `F` digits read as hex, everything else as decimal. Off by default.
+    pub fn with_hex_literals(mut self, enabled: bool) -> Self {
+        self.hex_literals = enabled;
+        self
+    }
+
+    /// Get the target dialect
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    /// Get the header banner configuration
+    pub fn banner(&self) -> &BannerConfig {
+        &self.banner
+    }
+
+    /// Generate the header banner comment block
+    pub fn generate_banner(&self) -> String {
+        let prefix = self.dialect.comment_prefix();
+        let mut lines = Vec::new();
+
+        let mut attribution = "Decompiled by VBDecompiler".to_string();
+        if let Some(version) = &self.banner.tool_version {
+            attribution.push_str(&format!(" {}", version));
+        }
+        if let Some(source) = &self.banner.source_file {
+            attribution.push_str(&format!(" from {}", source));
+        }
+        lines.push(attribution);
+        if let Some(timestamp) = &self.banner.timestamp {
+            lines.push(format!("Generated: {}", timestamp));
+        }
+        if self.banner.warning {
+            lines.push("WARNING: Recovered by decompilation; review before compiling.".to_string());
+        }
+
+        lines
+            .iter()
+            .map(|line| format!("{} {}\n", prefix, line))
+            .collect()
     }
 
     /// Generate VB6 code for a complete function
@@ -36,14 +286,19 @@ impl VB6CodeGenerator {
 
         self.indent_level += 1;
 
+        let (initializers, merged) = match self.dialect {
+            Dialect::VB6 => (HashMap::new(), 0),
+            Dialect::VBNet => self.collect_initializers(function),
+        };
+
         // Generate local variable declarations
         if !function.local_variables.is_empty() {
-            code.push_str(&self.generate_local_variables(function));
+            code.push_str(&self.generate_local_variables(function, &initializers));
             code.push('\n');
         }
 
         // Generate function body (statements from basic blocks)
-        code.push_str(&self.generate_function_body(function));
+        code.push_str(&self.generate_function_body(function, merged));
 
         self.indent_level -= 1;
 
@@ -53,6 +308,66 @@ impl VB6CodeGenerator {
         code
     }
 
+    /// Check that generated code for `function` would pass `Option Explicit`
+    ///
+    /// Every variable used must be a parameter, a declared local or a runtime
+    /// global object. Function results are written to an undeclared
+    /// `ReturnValue`, so any `Return` with a value fails the check.
+    pub fn declarations_complete(&self, function: &Function) -> bool {
+        let declared = |name: &str| {
+            function
+                .parameters
+                .iter()
+                .chain(&function.local_variables)
+                .map(|var| var.name.as_str())
+                .chain(GLOBAL_OBJECTS.iter().copied())
+                .any(|known| known.eq_ignore_ascii_case(name))
+        };
+
+        let mut pending: Vec<&Statement> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|block| &block.statements)
+            .collect();
+        let mut vars = Vec::new();
+        while let Some(stmt) = pending.pop() {
+            if let StatementData::Return { value: Some(_) } = stmt.data {
+                return false;
+            }
+            pending.extend(stmt.nested());
+        }
+        for stmt in function.basic_blocks.iter().flat_map(|b| &b.statements) {
+            stmt.collect_variables(&mut vars);
+        }
+        vars.iter().all(|var| declared(&var.name))
+    }
+
+    /// Generate module-level `Const` declarations
+    pub fn generate_constant_declarations(&self, constants: &[VBConstant]) -> String {
+        let mut code = String::new();
+        for constant in constants {
+            let value = Expression::constant(constant.value.clone(), Type::new(TypeKind::Variant));
+            code.push_str(&format!(
+                "Const {} = {}\n",
+                constant.name,
+                self.generate_expression(&value)
+            ));
+        }
+        code
+    }
+
+    /// Generate module-level `WithEvents` declarations
+    pub fn generate_with_events_declarations(&self, members: &[WithEventsMember]) -> String {
+        let mut code = String::new();
+        for member in members {
+            code.push_str(&format!(
+                "Private WithEvents {} As {}\n",
+                member.name, member.class_name
+            ));
+        }
+        code
+    }
+
     /// Generate function header
     fn generate_function_header(&self, function: &Function) -> String {
         let func_type = if function.return_type.kind == TypeKind::Void {
@@ -64,7 +379,14 @@ impl VB6CodeGenerator {
         let params = function
             .parameters
             .iter()
-            .map(|p| format!("{} As {}", p.name, self.format_type_kind(p.var_type)))
+            .map(|p| {
+                format!(
+                    "{} {} As {}",
+                    function.parameter_mode(p),
+                    p.name,
+                    self.format_type_kind(p.var_type)
+                )
+            })
             .collect::<Vec<_>>()
             .join(", ");
 
@@ -91,42 +413,132 @@ impl VB6CodeGenerator {
         format!("End {}", func_type)
     }
 
+    /// Find leading assignments that can become `Dim` initializers
+    ///
+    /// Only the run of assignments at the very start of the body qualifies:
+    /// each must be the first write to a local, and its value must not read
+    /// any local (which might not be initialized yet). Returns the initializer
+    /// per variable ID and the number of statements consumed.
+    fn collect_initializers(&self, function: &Function) -> (HashMap<u32, String>, usize) {
+        let mut initializers = HashMap::new();
+        let mut merged = 0;
+
+        // The first block emitted, whose leading statements generate_function_body skips
+        let Some(block) = emitted_blocks(function).into_iter().next() else {
+            return (initializers, merged);
+        };
+
+        // A block that can be jumped to may be re-entered, so its assignments
+        // must stay put
+        if !block.predecessors.is_empty() || block.is_error_handler {
+            return (initializers, merged);
+        }
+
+        for stmt in &block.statements {
+            let StatementData::Assign { target, value } = &stmt.data else {
+                break;
+            };
+            let is_local = function.local_variables.iter().any(|v| v.id == target.id);
+            let reads_local = function
+                .local_variables
+                .iter()
+                .any(|v| value.references_variable(v.id));
+            if !is_local || reads_local || initializers.contains_key(&target.id) {
+                break;
+            }
+
+            initializers.insert(target.id, self.generate_expression(value));
+            merged += 1;
+        }
+
+        (initializers, merged)
+    }
+
     /// Generate local variable declarations
-    fn generate_local_variables(&self, function: &Function) -> String {
+    fn generate_local_variables(
+        &self,
+        function: &Function,
+        initializers: &HashMap<u32, String>,
+    ) -> String {
         let mut code = String::new();
 
         for var in &function.local_variables {
             code.push_str(&self.indent());
-            code.push_str(&format!(
-                "Dim {} As {}\n",
-                var.name,
-                self.format_type_kind(var.var_type)
-            ));
+            // Arrays are declared dynamic, typed by the elements accessed
+            if var.var_type == TypeKind::Array {
+                code.push_str(&format!(
+                    "Dim {}() As {}",
+                    var.name,
+                    self.format_type_kind(array_element_type(function, &var.name))
+                ));
+            } else {
+                code.push_str(&format!(
+                    "Dim {} As {}",
+                    var.name,
+                    self.format_type_kind(var.var_type)
+                ));
+            }
+            if let Some(init) = initializers.get(&var.id) {
+                code.push_str(&format!(" = {}", init));
+            }
+            code.push('\n');
         }
 
         code
     }
 
     /// Generate function body from basic blocks
-    fn generate_function_body(&mut self, function: &Function) -> String {
+    ///
+    /// `skip` leading statements of the first non-empty block are omitted
+    /// (they were merged into declarations). A return at the very end of the
+    /// body is implied by the footer, so it emits no `Exit Sub`/`Exit Function`.
+    /// Blocks are laid out in reverse postorder, and a `GoTo` to the block
+    /// emitted next is dropped as a fall-through.
+    fn generate_function_body(&mut self, function: &Function, skip: usize) -> String {
         let mut code = String::new();
+        let mut skip = skip;
+        let blocks = emitted_blocks(function);
+        let last_block = blocks.len().checked_sub(1);
+        // Error handlers and Resume targets are reached by label only
+        let label_targets: HashSet<u32> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|b| &b.statements)
+            .filter_map(Statement::label_target)
+            .collect();
 
-        // Process blocks in order (simplified - assumes sequential order)
-        for block in &function.basic_blocks {
-            // Skip if block is entry and has no statements (common for structured code)
-            if block.statements.is_empty() {
-                continue;
-            }
-
+        for (index, block) in blocks.iter().enumerate() {
             // Add block label if it has multiple predecessors (merge point)
-            if block.predecessors.len() > 1 {
+            // or is named by On Error GoTo / Resume
+            if block.predecessors.len() > 1
+                || block.is_error_handler
+                || label_targets.contains(&block.id)
+            {
                 code.push_str(&format!("Block{}:\n", block.id));
             }
 
             // Generate statements
-            for stmt in &block.statements {
-                code.push_str(&self.generate_statement(stmt));
+            let last_statement = block.statements.len() - 1;
+            let next_block = blocks.get(index + 1).map(|b| b.id);
+            for (i, stmt) in block.statements.iter().enumerate().skip(skip) {
+                let is_final = Some(index) == last_block && i == last_statement;
+                match &stmt.data {
+                    // A jump to the block laid out next is a fall-through
+                    StatementData::Goto { target_block }
+                        if i == last_statement && Some(*target_block) == next_block => {}
+                    StatementData::Return { value } if is_final => {
+                        if let Some(v) = value {
+                            code.push_str(&self.indent());
+                            code.push_str(&format!(
+                                "ReturnValue = {}\n",
+                                self.generate_expression(v)
+                            ));
+                        }
+                    }
+                    _ => code.push_str(&self.generate_statement(stmt)),
+                }
             }
+            skip = 0;
         }
 
         code
@@ -135,6 +547,10 @@ impl VB6CodeGenerator {
     /// Generate a statement
     pub fn generate_statement(&self, stmt: &Statement) -> String {
         let mut code = self.indent();
+        if let Some(line) = stmt.source_line {
+            code.push_str(&format!("' Line {}\n", line));
+            code.push_str(&self.indent());
+        }
 
         match &stmt.data {
             StatementData::None => {
@@ -148,9 +564,14 @@ impl VB6CodeGenerator {
                 ));
             }
             StatementData::Store { address, value } => {
+                let target = self.generate_expression(address);
+                let target = match address.kind {
+                    ExpressionKind::ArrayIndex | ExpressionKind::MemberAccess => target,
+                    _ => format!("[{}]", target),
+                };
                 code.push_str(&format!(
-                    "[{}] = {}\n",
-                    self.generate_expression(address),
+                    "{} = {}\n",
+                    target,
                     self.generate_expression(value)
                 ));
             }
@@ -169,6 +590,19 @@ impl VB6CodeGenerator {
                     code.push_str(&format!("{} {}\n", function, args));
                 }
             }
+            StatementData::MethodCall { target, arguments } => {
+                let target = self.generate_expression(target);
+                if arguments.is_empty() {
+                    code.push_str(&format!("{}\n", target));
+                } else {
+                    let args = arguments
+                        .iter()
+                        .map(|a| self.generate_expression(a))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    code.push_str(&format!("{} {}\n", target, args));
+                }
+            }
             StatementData::Return { value } => {
                 if let Some(v) = value {
                     code.push_str(&format!(
@@ -198,15 +632,155 @@ impl VB6CodeGenerator {
             StatementData::Label { label_id } => {
                 code = format!("Label{}:\n", label_id);
             }
+            StatementData::ForLoop {
+                counter,
+                start,
+                end,
+                step,
+                body,
+            } => {
+                code.push_str(&format!(
+                    "For {} = {} To {}",
+                    counter.name,
+                    self.generate_expression(start),
+                    self.generate_expression(end)
+                ));
+                if let Some(step) = step {
+                    code.push_str(&format!(" Step {}", self.generate_expression(step)));
+                }
+                code.push('\n');
+                code.push_str(&self.generate_nested(body));
+                code.push_str(&self.indent());
+                code.push_str(&format!("Next {}\n", counter.name));
+            }
+            StatementData::WhileLoop {
+                loop_kind,
+                condition,
+                body,
+            } => {
+                let (head, tail) = loop_kind.clauses(&self.generate_expression(condition));
+                code.push_str(&head);
+                code.push('\n');
+                code.push_str(&self.generate_nested(body));
+                code.push_str(&self.indent());
+                code.push_str(&tail);
+                code.push('\n');
+            }
+            StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            } => {
+                code.push_str(&format!(
+                    "If {} Then\n",
+                    self.generate_expression(condition)
+                ));
+                code.push_str(&self.generate_nested(then_body));
+                if !else_body.is_empty() {
+                    code.push_str(&self.indent());
+                    code.push_str("Else\n");
+                    code.push_str(&self.generate_nested(else_body));
+                }
+                code.push_str(&self.indent());
+                code.push_str("End If\n");
+            }
+            StatementData::ReDim {
+                array,
+                bounds,
+                preserve,
+            } => {
+                let bounds = bounds
+                    .iter()
+                    .map(|(lower, upper)| {
+                        format!(
+                            "{} To {}",
+                            self.generate_expression(lower),
+                            self.generate_expression(upper)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                code.push_str(&format!(
+                    "ReDim {}{}({})\n",
+                    if *preserve { "Preserve " } else { "" },
+                    array.name,
+                    bounds
+                ));
+            }
+            StatementData::OnError { handler_block } => match handler_block {
+                Some(block) => code.push_str(&format!("On Error GoTo Block{}\n", block)),
+                None => code.push_str("On Error Resume Next\n"),
+            },
+            StatementData::Resume { target } => match target {
+                ResumeTarget::Retry => code.push_str("Resume\n"),
+                ResumeTarget::Next => code.push_str("Resume Next\n"),
+                ResumeTarget::Block(block) => code.push_str(&format!("Resume Block{}\n", block)),
+            },
+            StatementData::SelectCase {
+                subject,
+                cases,
+                else_body,
+            } => {
+                code.push_str(&format!(
+                    "Select Case {}\n",
+                    self.generate_expression(subject)
+                ));
+                let arms = self.nested();
+                for (value, body) in cases {
+                    code.push_str(&arms.indent());
+                    code.push_str(&format!("Case {}\n", arms.generate_expression(value)));
+                    code.push_str(&arms.generate_nested(body));
+                }
+                if !else_body.is_empty() {
+                    code.push_str(&arms.indent());
+                    code.push_str("Case Else\n");
+                    code.push_str(&arms.generate_nested(else_body));
+                }
+                code.push_str(&self.indent());
+                code.push_str("End Select\n");
+            }
+            StatementData::With { object, body } => {
+                code.push_str(&format!("With {}\n", object.name));
+                let mut members = self.nested();
+                members.with_object = Some(object.clone());
+                for stmt in body {
+                    code.push_str(&members.generate_statement(stmt));
+                }
+                code.push_str(&self.indent());
+                code.push_str("End With\n");
+            }
         }
 
         code
     }
 
+    /// Generate the body of a structured statement one level deeper
+    fn generate_nested(&self, body: &[Statement]) -> String {
+        let nested = self.nested();
+        body.iter().map(|s| nested.generate_statement(s)).collect()
+    }
+
+    /// Generator for statements one indentation level deeper
+    fn nested(&self) -> Self {
+        Self {
+            indent_level: self.indent_level + 1,
+            indent_style: self.indent_style,
+            dialect: self.dialect,
+            banner: BannerConfig::default(),
+            with_object: self.with_object.clone(),
+            hex_literals: self.hex_literals,
+        }
+    }
+
     /// Generate an expression
     pub fn generate_expression(&self, expr: &Expression) -> String {
         match &expr.data {
             ExpressionData::None => String::new(),
+            ExpressionData::Constant(ConstantValue::Integer(v))
+                if self.hex_literals && looks_like_flags(*v) =>
+            {
+                hex_literal(*v).unwrap_or_else(|| v.to_string())
+            }
             ExpressionData::Constant(val) => self.generate_constant(val),
             ExpressionData::Variable(var) => var.name.clone(),
             ExpressionData::Unary(operand) => {
@@ -215,15 +789,14 @@ impl VB6CodeGenerator {
                     ExpressionKind::Not => "Not ",
                     _ => "?",
                 };
-                format!("{}{}", op, self.generate_expression(operand))
+                format!("{}{}", op, self.generate_operand(operand, expr.kind, false))
             }
             ExpressionData::Binary { left, right } => {
-                let op = self.get_binary_operator(expr.kind);
                 format!(
-                    "({} {} {})",
-                    self.generate_expression(left),
-                    op,
-                    self.generate_expression(right)
+                    "{} {} {}",
+                    self.generate_operand(left, expr.kind, false),
+                    self.get_binary_operator(expr.kind),
+                    self.generate_operand(right, expr.kind, true)
                 )
             }
             ExpressionData::Call {
@@ -241,9 +814,20 @@ impl VB6CodeGenerator {
                     format!("{}({})", function, args)
                 }
             }
-            ExpressionData::MemberAccess { object, member } => {
-                format!("{}.{}", self.generate_expression(object), member)
+            ExpressionData::MethodCall { target, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(|a| self.generate_expression(a))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({})", self.generate_expression(target), args)
             }
+            ExpressionData::MemberAccess { object, member } => match &object.data {
+                ExpressionData::Variable(var) if self.with_object.as_ref() == Some(var) => {
+                    format!(".{}", member)
+                }
+                _ => format!("{}.{}", self.generate_expression(object), member),
+            },
             ExpressionData::ArrayIndex { array, indices } => {
                 let idx = indices
                     .iter()
@@ -262,11 +846,42 @@ impl VB6CodeGenerator {
         }
     }
 
+    /// Generate an operand of a `parent` operator, parenthesized only when
+    /// it binds looser than the operator, or as tightly on the right since
+    /// VB operators associate left (`a - (b - c)`)
+    ///
+    /// Integers combined bitwise read as hex unless they are single digits.
+    fn generate_operand(&self, expr: &Expression, parent: ExpressionKind, right: bool) -> String {
+        let code = match &expr.data {
+            ExpressionData::Constant(ConstantValue::Integer(v))
+                if self.hex_literals
+                    && !(0..10).contains(v)
+                    && matches!(
+                        parent,
+                        ExpressionKind::And | ExpressionKind::Or | ExpressionKind::Xor
+                    ) =>
+            {
+                return hex_literal(*v).unwrap_or_else(|| v.to_string());
+            }
+            _ => self.generate_expression(expr),
+        };
+
+        let (Some(inner), Some(outer)) = (operator_precedence(expr), precedence(parent)) else {
+            return code;
+        };
+        if inner < outer || (right && inner == outer) {
+            format!("({})", code)
+        } else {
+            code
+        }
+    }
+
     /// Generate a constant value
     fn generate_constant(&self, value: &ConstantValue) -> String {
         match value {
             ConstantValue::Integer(v) => v.to_string(),
             ConstantValue::Float(v) => v.to_string(),
+            ConstantValue::Currency(_) | ConstantValue::Date(_) => value.to_string(),
             ConstantValue::String(s) => format!("\"{}\"", s),
             ConstantValue::Boolean(b) => {
                 if *b {
@@ -275,6 +890,9 @@ impl VB6CodeGenerator {
                     "False".to_string()
                 }
             }
+            ConstantValue::Nothing => "Nothing".to_string(),
+            ConstantValue::Null => "Null".to_string(),
+            ConstantValue::Empty => "Empty".to_string(),
         }
     }
 
@@ -345,7 +963,68 @@ impl VB6CodeGenerator {
 
     /// Get current indentation string
     fn indent(&self) -> String {
-        "    ".repeat(self.indent_level)
+        self.indent_style.unit().repeat(self.indent_level)
+    }
+}
+
+/// VB precedence of an operator, higher binding tighter
+///
+/// Arithmetic binds tighter than `&`, which binds tighter than the
+/// comparisons (all equal), which bind tighter than the logical operators.
+fn precedence(kind: ExpressionKind) -> Option<u8> {
+    let level = match kind {
+        ExpressionKind::Negate => 10,
+        ExpressionKind::Multiply | ExpressionKind::Divide => 9,
+        ExpressionKind::IntDivide => 8,
+        ExpressionKind::Modulo => 7,
+        ExpressionKind::Add | ExpressionKind::Subtract => 6,
+        ExpressionKind::Concatenate => 5,
+        ExpressionKind::Equal
+        | ExpressionKind::NotEqual
+        | ExpressionKind::LessThan
+        | ExpressionKind::LessEqual
+        | ExpressionKind::GreaterThan
+        | ExpressionKind::GreaterEqual => 4,
+        ExpressionKind::Not => 3,
+        ExpressionKind::And => 2,
+        ExpressionKind::Or => 1,
+        ExpressionKind::Xor => 0,
+        _ => return None,
+    };
+    Some(level)
+}
+
+/// Precedence of the operator at the top of `expr`, None for operands that
+/// never need parentheses (variables, constants, calls)
+fn operator_precedence(expr: &Expression) -> Option<u8> {
+    match expr.data {
+        ExpressionData::Unary(_) | ExpressionData::Binary { .. } => precedence(expr.kind),
+        _ => None,
+    }
+}
+
+/// Whether an integer reads better in hex: at least `&H1000`, and all `0`
+/// and `F` digits but one (`&HFF00&`, `&H8000&`, `&HFFFF&`)
+fn looks_like_flags(value: i64) -> bool {
+    (0x1000..=i32::MAX as i64).contains(&value)
+        && format!("{:X}", value)
+            .chars()
+            .filter(|digit| !matches!(digit, '0' | 'F'))
+            .count()
+            <= 1
+}
+
+/// VB hex literal with the same value as `value`
+///
+/// Literals beyond `&H7FFF` need the `&` (Long) suffix, since `&HFFFF`
+/// alone is the Integer -1. Values outside the Long range have no hex form.
+fn hex_literal(value: i64) -> Option<String> {
+    match value {
+        0..=0x7FFF => Some(format!("&H{:X}", value)),
+        -0x8000..=-1 => Some(format!("&H{:X}", value as i16 as u16)),
+        _ => i32::try_from(value)
+            .ok()
+            .map(|v| format!("&H{:X}&", v as u32)),
     }
 }
 
@@ -392,6 +1071,15 @@ mod tests {
         let var = Variable::new(0, "x".to_string(), TypeKind::Integer);
         let var_expr = Expression::variable(var);
         assert_eq!(gen.generate_expression(&var_expr), "x");
+
+        // Test currency and date literals
+        let currency = Expression::constant(
+            ConstantValue::Currency(15_000),
+            Type::new(TypeKind::Currency),
+        );
+        assert_eq!(gen.generate_expression(&currency), "1.5000@");
+        let date = Expression::constant(ConstantValue::Date(36_526.0), Type::new(TypeKind::Date));
+        assert_eq!(gen.generate_expression(&date), "#1/1/2000#");
     }
 
     #[test]
@@ -412,6 +1100,209 @@ mod tests {
         assert!(ret_code.contains("Exit Function"));
     }
 
+    #[test]
+    fn test_source_line_comment() {
+        let gen = VB6CodeGenerator::new();
+        let var = Variable::new(0, "x".to_string(), TypeKind::Integer);
+
+        let stmt = Statement::assign(var.clone(), Expression::int_const(10)).with_source_line(120);
+        assert_eq!(gen.generate_statement(&stmt), "' Line 120\nx = 10\n");
+
+        let stmt = Statement::assign(var, Expression::int_const(10));
+        assert_eq!(gen.generate_statement(&stmt), "x = 10\n");
+    }
+
+    #[test]
+    fn test_generate_nested_if_in_for() {
+        let mut gen = VB6CodeGenerator::new();
+        let i = Variable::new(0, "i".to_string(), TypeKind::Integer);
+        let condition = Expression::binary(
+            ExpressionKind::GreaterThan,
+            Expression::variable(i.clone()),
+            Expression::int_const(5),
+            Type::new(TypeKind::Boolean),
+        );
+        let body = vec![Statement::if_then_else(
+            condition,
+            vec![Statement::call("Beep".to_string(), Vec::new())],
+            vec![Statement::assign(i.clone(), Expression::int_const(0))],
+        )];
+
+        let mut function = Function::new("Loop".to_string(), Type::new(TypeKind::Void));
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::for_loop(
+            i,
+            Expression::int_const(1),
+            Expression::int_const(10),
+            None,
+            body,
+        ));
+        block.add_statement(Statement::return_stmt(None));
+        function.add_basic_block(block);
+
+        assert_eq!(
+            gen.generate_function(&function),
+            "Sub Loop()\n\
+             \x20   For i = 1 To 10\n\
+             \x20       If i > 5 Then\n\
+             \x20           Beep\n\
+             \x20       Else\n\
+             \x20           i = 0\n\
+             \x20       End If\n\
+             \x20   Next i\n\
+             End Sub"
+        );
+    }
+
+    #[test]
+    fn test_blocks_in_reverse_postorder() {
+        let mut gen = VB6CodeGenerator::new();
+        let a = Variable::new(0, "a".to_string(), TypeKind::Integer);
+        let flag = Variable::new(1, "flag".to_string(), TypeKind::Boolean);
+        let block = |id: u32, value: i64, last: Statement, successors: &[u32], preds: &[u32]| {
+            let mut block = BasicBlock::new(id);
+            block.add_statement(Statement::assign(a.clone(), Expression::int_const(value)));
+            block.add_statement(last);
+            successors.iter().for_each(|&s| block.add_successor(s));
+            preds.iter().for_each(|&p| block.add_predecessor(p));
+            block
+        };
+
+        // 0 -> {2, 1 (fall-through)}, 1 -> 3, 2 -> 3; inserted out of order
+        let mut function = Function::new("Test".to_string(), Type::new(TypeKind::Void));
+        let branch = Statement::branch(Expression::variable(flag), 2);
+        function.add_basic_block(block(0, 1, branch, &[2, 1], &[]));
+        function.add_basic_block(block(3, 4, Statement::return_stmt(None), &[], &[1, 2]));
+        function.add_basic_block(block(2, 3, Statement::goto(3), &[3], &[0]));
+        function.add_basic_block(block(1, 2, Statement::goto(3), &[3], &[0]));
+
+        let code = gen.generate_function(&function);
+        let position = |text: &str| code.find(text).unwrap();
+        assert!(position("a = 1") < position("a = 2"));
+        assert!(position("a = 2") < position("a = 3"));
+        assert!(position("a = 3") < position("Block3:"));
+        assert!(position("Block3:") < position("a = 4"));
+        // Block 1 jumps over block 2; block 2 falls through to block 3
+        assert_eq!(code.matches("GoTo Block3").count(), 1);
+        assert!(position("GoTo Block3") < position("a = 3"));
+    }
+
+    #[test]
+    fn test_generate_redim() {
+        let gen = VB6CodeGenerator::new();
+        let arr = Variable::new(0, "arr".to_string(), TypeKind::Array);
+
+        let stmt = Statement::redim(
+            arr.clone(),
+            vec![(Expression::int_const(1), Expression::int_const(10))],
+            true,
+        );
+        assert_eq!(
+            gen.generate_statement(&stmt),
+            "ReDim Preserve arr(1 To 10)\n"
+        );
+
+        let stmt = Statement::redim(
+            arr,
+            vec![
+                (Expression::int_const(0), Expression::int_const(9)),
+                (Expression::int_const(1), Expression::int_const(5)),
+            ],
+            false,
+        );
+        assert_eq!(gen.generate_statement(&stmt), "ReDim arr(0 To 9, 1 To 5)\n");
+    }
+
+    #[test]
+    fn test_generate_with_events_declarations() {
+        let gen = VB6CodeGenerator::new();
+        let members = vec![WithEventsMember {
+            name: "m_Timer".to_string(),
+            class_name: "CTimer".to_string(),
+            events_iid: "{00000000-0000-0000-0000-000000000000}".to_string(),
+            handlers: vec!["m_Timer_Tick".to_string()],
+        }];
+
+        assert_eq!(
+            gen.generate_with_events_declarations(&members),
+            "Private WithEvents m_Timer As CTimer\n"
+        );
+    }
+
+    fn function_with_initializer() -> Function {
+        let mut func = Function::new("Init".to_string(), Type::new(TypeKind::Void));
+        let x = Variable::new(0, "x".to_string(), TypeKind::Integer);
+        func.add_local_variable(x.clone());
+
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::assign(x.clone(), Expression::int_const(5)));
+        block.add_statement(Statement::assign(
+            x.clone(),
+            Expression::add(
+                Expression::variable(x),
+                Expression::int_const(1),
+                Type::new(TypeKind::Integer),
+            ),
+        ));
+        func.add_basic_block(block);
+        func
+    }
+
+    #[test]
+    fn test_vbnet_merges_dim_initializer() {
+        let mut gen = VB6CodeGenerator::with_dialect(Dialect::VBNet);
+        let code = gen.generate_function(&function_with_initializer());
+
+        assert!(code.contains("Dim x As Integer = 5\n"));
+        assert!(!code.contains("x = 5\n"));
+        assert!(code.contains("x = x + 1\n"));
+    }
+
+    #[test]
+    fn test_vbnet_keeps_loop_head_assignments() {
+        // Block 0 is re-entered from block 1, so `x = 5` runs every iteration
+        let mut func = function_with_initializer();
+        func.basic_blocks[0].add_predecessor(1);
+        func.basic_blocks[0].add_successor(1);
+        let mut back = BasicBlock::new(1);
+        back.add_predecessor(0);
+        back.add_successor(0);
+        back.add_statement(Statement::goto(0));
+        func.add_basic_block(back);
+
+        let mut gen = VB6CodeGenerator::with_dialect(Dialect::VBNet);
+        let code = gen.generate_function(&func);
+
+        assert!(code.contains("Dim x As Integer\n"));
+        assert!(code.contains("    x = 5\n"));
+    }
+
+    #[test]
+    fn test_vb6_keeps_dim_separate() {
+        let mut gen = VB6CodeGenerator::new();
+        let code = gen.generate_function(&function_with_initializer());
+
+        assert!(code.contains("Dim x As Integer\n"));
+        assert!(code.contains("    x = 5\n"));
+    }
+
+    #[test]
+    fn test_generate_banner() {
+        let gen = VB6CodeGenerator::new();
+        assert_eq!(gen.generate_banner(), "' Decompiled by VBDecompiler\n");
+
+        let gen = VB6CodeGenerator::with_dialect(Dialect::VBNet).with_banner(BannerConfig {
+            tool_version: Some("1.2.3".to_string()),
+            source_file: Some("Project1.exe".to_string()),
+            timestamp: None,
+            warning: true,
+        });
+        let banner = gen.generate_banner();
+        assert!(banner.starts_with("' Decompiled by VBDecompiler 1.2.3 from Project1.exe\n"));
+        assert!(banner.lines().all(|line| line.starts_with("' ")));
+        assert_eq!(banner.lines().count(), 2);
+    }
+
     #[test]
     fn test_binary_operators() {
         let gen = VB6CodeGenerator::new();
@@ -425,4 +1316,199 @@ mod tests {
         let eq_expr = Expression::equal(left, right);
         assert!(gen.generate_expression(&eq_expr).contains("="));
     }
+
+    #[test]
+    fn test_operator_precedence() {
+        let gen = VB6CodeGenerator::new();
+        let var =
+            |name: &str| Expression::variable(Variable::new(0, name.to_string(), TypeKind::Long));
+        let unary = |kind, operand| Expression {
+            kind,
+            expr_type: Type::new(TypeKind::Long),
+            data: ExpressionData::Unary(Box::new(operand)),
+        };
+        let binary =
+            |kind, left, right| Expression::binary(kind, left, right, Type::new(TypeKind::Long));
+
+        let product = binary(ExpressionKind::Multiply, var("b"), var("c"));
+        let sum = binary(ExpressionKind::Add, var("a"), product);
+        assert_eq!(gen.generate_expression(&sum), "a + b * c");
+
+        let sum = binary(ExpressionKind::Add, var("a"), var("b"));
+        let product = binary(ExpressionKind::Multiply, sum, var("c"));
+        assert_eq!(gen.generate_expression(&product), "(a + b) * c");
+
+        // Left associativity: parentheses only on the right
+        let diff = binary(ExpressionKind::Subtract, var("a"), var("b"));
+        let left = binary(ExpressionKind::Subtract, diff.clone(), var("c"));
+        assert_eq!(gen.generate_expression(&left), "a - b - c");
+        let right = binary(ExpressionKind::Subtract, var("c"), diff);
+        assert_eq!(gen.generate_expression(&right), "c - (a - b)");
+        let quotient = binary(ExpressionKind::Divide, var("a"), var("b"));
+        let right = binary(ExpressionKind::Divide, var("c"), quotient);
+        assert_eq!(gen.generate_expression(&right), "c / (a / b)");
+
+        // \ binds looser than /, Mod looser than \
+        let quotient = binary(ExpressionKind::Divide, var("a"), var("b"));
+        let int_div = binary(ExpressionKind::IntDivide, quotient, var("c"));
+        assert_eq!(gen.generate_expression(&int_div), "a / b \\ c");
+        let modulo = binary(ExpressionKind::Modulo, var("a"), int_div);
+        assert_eq!(gen.generate_expression(&modulo), "a Mod a / b \\ c");
+        let modulo = binary(ExpressionKind::Modulo, var("a"), var("b"));
+        let int_div = binary(ExpressionKind::IntDivide, modulo, var("c"));
+        assert_eq!(gen.generate_expression(&int_div), "(a Mod b) \\ c");
+
+        // Comparisons under logical operators need none
+        let lt = binary(ExpressionKind::LessThan, var("a"), var("b"));
+        let gt = binary(
+            ExpressionKind::GreaterThan,
+            var("c"),
+            Expression::int_const(0),
+        );
+        let and = binary(ExpressionKind::And, lt, gt);
+        assert_eq!(gen.generate_expression(&and), "a < b And c > 0");
+        let or = binary(ExpressionKind::Or, var("a"), var("b"));
+        let and = binary(ExpressionKind::And, or.clone(), var("c"));
+        assert_eq!(gen.generate_expression(&and), "(a Or b) And c");
+
+        let negated = unary(ExpressionKind::Negate, or);
+        assert_eq!(gen.generate_expression(&negated), "-(a Or b)");
+        let not = unary(ExpressionKind::Not, var("a"));
+        let sum = binary(ExpressionKind::Add, var("b"), not);
+        assert_eq!(gen.generate_expression(&sum), "b + (Not a)");
+        let concat = binary(ExpressionKind::Concatenate, var("a"), sum);
+        assert_eq!(gen.generate_expression(&concat), "a & b + (Not a)");
+    }
+
+    #[test]
+    fn test_indent_style() {
+        let mut function = Function::new("Test".to_string(), Type::new(TypeKind::Void));
+        let i = Variable::new(0, "i".to_string(), TypeKind::Integer);
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::if_then_else(
+            Expression::binary(
+                ExpressionKind::GreaterThan,
+                Expression::variable(i.clone()),
+                Expression::int_const(5),
+                Type::new(TypeKind::Boolean),
+            ),
+            vec![Statement::assign(i, Expression::int_const(0))],
+            Vec::new(),
+        ));
+        function.add_basic_block(block);
+
+        let tabs = VB6CodeGenerator::new()
+            .with_indent(IndentStyle::Tabs)
+            .generate_function(&function);
+        assert!(
+            tabs.contains("\tIf i > 5 Then\n\t\ti = 0\n\tEnd If\n"),
+            "{}",
+            tabs
+        );
+
+        let two = VB6CodeGenerator::new()
+            .with_indent(IndentStyle::Spaces(2))
+            .generate_function(&function);
+        assert!(
+            two.contains("\n  If i > 5 Then\n    i = 0\n  End If\n"),
+            "{}",
+            two
+        );
+    }
+
+    #[test]
+    fn test_hex_literals() {
+        let gen = VB6CodeGenerator::new().with_hex_literals(true);
+        let flags = Variable::new(0, "flags".to_string(), TypeKind::Long);
+        let i = Variable::new(1, "i".to_string(), TypeKind::Long);
+
+        let or_expr = Expression::binary(
+            ExpressionKind::Or,
+            Expression::variable(flags.clone()),
+            Expression::int_const(0x40),
+            Type::new(TypeKind::Long),
+        );
+        assert_eq!(gen.generate_expression(&or_expr), "flags Or &H40");
+
+        let mask = Expression::binary(
+            ExpressionKind::And,
+            Expression::variable(flags),
+            Expression::int_const(-8),
+            Type::new(TypeKind::Long),
+        );
+        assert_eq!(gen.generate_expression(&mask), "flags And &HFFF8");
+
+        // Loop bounds stay decimal
+        let stmt = Statement::for_loop(
+            i,
+            Expression::int_const(0),
+            Expression::int_const(100),
+            None,
+            Vec::new(),
+        );
+        assert_eq!(gen.generate_statement(&stmt), "For i = 0 To 100\nNext i\n");
+
+        // Round values read as hex on their own
+        assert_eq!(
+            gen.generate_expression(&Expression::int_const(0xFF00)),
+            "&HFF00&"
+        );
+        assert_eq!(
+            gen.generate_expression(&Expression::int_const(10000)),
+            "10000"
+        );
+
+        // Decimal unless asked for
+        let decimal = VB6CodeGenerator::new();
+        assert_eq!(decimal.generate_expression(&or_expr), "flags Or 64");
+    }
+
+    #[test]
+    fn test_hex_literal_ranges() {
+        assert_eq!(hex_literal(0x7FFF).as_deref(), Some("&H7FFF"));
+        assert_eq!(hex_literal(0xFFFF).as_deref(), Some("&HFFFF&"));
+        assert_eq!(hex_literal(-1).as_deref(), Some("&HFFFF"));
+        assert_eq!(hex_literal(-0x10000).as_deref(), Some("&HFFFF0000&"));
+        assert_eq!(hex_literal(0x1_0000_0000), None);
+    }
+
+    #[test]
+    fn test_declarations_complete() {
+        let gen = VB6CodeGenerator::new();
+        let x = Variable::new(0, "x".to_string(), TypeKind::Long);
+        let debug = Expression::member_access(
+            Expression::variable(Variable::builtin_object("Debug")),
+            "Print".to_string(),
+            Type::new(TypeKind::Void),
+        );
+
+        let mut function = Function::new("Log".to_string(), Type::new(TypeKind::Void));
+        function.add_parameter(x.clone());
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::method_call(
+            debug,
+            vec![Expression::variable(x.clone())],
+        ));
+        block.add_statement(Statement::return_stmt(None));
+        function.add_basic_block(block);
+        assert!(gen.declarations_complete(&function));
+
+        // An undeclared local inside a loop body
+        let y = Variable::new(1, "local1".to_string(), TypeKind::Long);
+        function.basic_blocks[0].statements.insert(
+            0,
+            Statement::while_loop(
+                Expression::bool_const(true),
+                vec![Statement::assign(y, Expression::int_const(1))],
+            ),
+        );
+        assert!(!gen.declarations_complete(&function));
+
+        // Results go through the undeclared ReturnValue
+        let mut function = Function::new("Get".to_string(), Type::new(TypeKind::Long));
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::return_stmt(Some(Expression::int_const(1))));
+        function.add_basic_block(block);
+        assert!(!gen.declarations_complete(&function));
+    }
 }
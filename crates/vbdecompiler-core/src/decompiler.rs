@@ -7,61 +7,281 @@
 //! Wires together all decompilation stages:
 //! PE → VB → P-Code → IR → Code Generation
 
-use crate::codegen::VB6CodeGenerator;
+use crate::codegen::{BannerConfig, Dialect, IndentStyle, VB6CodeGenerator};
 use crate::error::{Error, Result};
 use crate::ir::Function;
 use crate::lifter::PCodeLifter;
-use crate::pcode::Disassembler;
+use crate::optimize;
+use crate::packer::detect_packer;
+use crate::pcode::{Disassembler, OpcodeCategory, OperandKind};
 use crate::pe::PEFile;
+use crate::structure;
+use crate::types;
 use crate::vb;
+use crate::x86::X86Disassembler;
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+/// Decompilation progress notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// Reading the PE headers and sections
+    ParsingPe,
+    /// Reading the VB project structures
+    ParsingVb,
+    /// A method is about to be decompiled (`Object_Method`)
+    MethodStarted { name: String },
+    /// `index` of `total` methods are finished (in completion order)
+    MethodDone { index: usize, total: usize },
+}
+
+/// Receiver of [`ProgressEvent`]s
+///
+/// Methods are decompiled in parallel, so the callback may run on several
+/// threads at once.
+pub type ProgressCallback = Box<dyn Fn(ProgressEvent) + Send + Sync>;
+
+/// Warning for a file a packer detector flags, when packed files are allowed
+fn packer_warning(data: &[u8]) -> Option<String> {
+    let detection = detect_packer(data).ok()??;
+    let warning = format!(
+        "Possibly packed with {} ({:.0}% confidence, via {:?}); output may be incomplete",
+        detection.packer.name(),
+        detection.confidence * 100.0,
+        detection.method
+    );
+    log::warn!("{}", warning);
+    Some(warning)
+}
+
 /// Main decompiler orchestrator
 pub struct Decompiler {
     generator: VB6CodeGenerator,
+    /// Worker thread count (None = Rayon's global pool)
+    threads: Option<usize>,
+    /// Only decompile objects with these names (empty = all objects)
+    object_filter: Vec<String>,
+    /// Emit a comment naming the object/method above each method
+    annotate_methods: bool,
+    /// Pipeline tuning
+    options: DecompilerOptions,
+    /// Progress receiver
+    progress: Option<ProgressCallback>,
 }
 
 impl Decompiler {
+    /// Create a decompiler with default options
     pub fn new() -> Self {
-        Self {
-            generator: VB6CodeGenerator::new(),
+        DecompilerBuilder::new().build()
+    }
+
+    /// Create a decompiler with the given pipeline options
+    pub fn with_options(options: DecompilerOptions) -> Self {
+        DecompilerBuilder::new().options(options).build()
+    }
+
+    /// Create a builder for a configured decompiler
+    pub fn builder() -> DecompilerBuilder {
+        DecompilerBuilder::new()
+    }
+
+    /// Report progress through `callback`, replacing any previous callback
+    pub fn set_progress_callback(&mut self, callback: ProgressCallback) {
+        self.progress = Some(callback);
+    }
+
+    /// Stop reporting progress
+    pub fn clear_progress_callback(&mut self) {
+        self.progress = None;
+    }
+
+    fn report(&self, event: ProgressEvent) {
+        if let Some(callback) = &self.progress {
+            callback(event);
         }
     }
 
-    /// Decompile a VB executable file
-    pub fn decompile_file(&mut self, path: &str) -> Result<DecompilationResult> {
-        log::info!("Decompiling file: {}", path);
+    /// Check if an object passes the object filter
+    fn includes_object(&self, name: &str) -> bool {
+        self.object_filter.is_empty()
+            || self
+                .object_filter
+                .iter()
+                .any(|filter| filter.eq_ignore_ascii_case(name))
+    }
+
+    /// Parse a VB executable's PE and VB structures without decompiling
+    ///
+    /// The returned [`vb::VBFile`] exposes the objects and per-method P-Code:
+    ///
+    /// ```no_run
+    /// use vbdecompiler_core::pcode::Disassembler;
+    /// use vbdecompiler_core::Decompiler;
+    ///
+    /// let vb_file = Decompiler::new().parse_file("program.exe")?;
+    /// for (i, object) in vb_file.objects().iter().enumerate() {
+    ///     println!("{}: {} methods", object.name, object.method_names.len());
+    ///     if let Some(pcode) = vb_file.get_pcode_for_method(i, 0) {
+    ///         let instructions = Disassembler::new(pcode).disassemble(0)?;
+    ///         println!("  first method: {} instructions", instructions.len());
+    ///     }
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse_file(&self, path: impl AsRef<Path>) -> Result<vb::VBFile> {
+        self.load_file(path.as_ref()).map(|(vb_file, _)| vb_file)
+    }
+
+    /// Parse a VB executable, returning any warnings raised on the way
+    ///
+    /// Unless packed files are rejected outright, a packer detection is
+    /// reported as a warning instead of an error.
+    fn load_file(&self, path: &Path) -> Result<(vb::VBFile, Vec<String>)> {
+        // 1. Read file (unless it's mapped)
+        if !self.options.memory_map {
+            return self.load_bytes(fs::read(path).map_err(Error::Io)?);
+        }
+
+        // 2. Parse PE file
+        log::info!("Parsing PE file...");
+        self.report(ProgressEvent::ParsingPe);
+        let mut warnings = Vec::new();
+        let pe = if self.options.reject_packed {
+            PEFile::from_path_mmap(path)?
+        } else {
+            // Mapped bytes are only reachable once parsed
+            let pe = PEFile::from_path_mmap_allow_packed(path)?;
+            warnings.extend(packer_warning(pe.data()));
+            pe
+        };
 
-        // 1. Read file
-        let data = fs::read(path).map_err(|e| Error::Io(e))?;
+        self.load_vb(pe, warnings)
+    }
 
+    /// Parse a VB executable already in memory, as [`load_file`](Self::load_file) does
+    fn load_bytes(&self, data: Vec<u8>) -> Result<(vb::VBFile, Vec<String>)> {
         // 2. Parse PE file
         log::info!("Parsing PE file...");
-        let pe = PEFile::from_bytes(data)?;
+        self.report(ProgressEvent::ParsingPe);
+        let mut warnings = Vec::new();
+        let pe = if self.options.reject_packed {
+            PEFile::from_bytes(data)?
+        } else {
+            warnings.extend(packer_warning(&data));
+            PEFile::from_bytes_allow_packed(data)?
+        };
+
+        self.load_vb(pe, warnings)
+    }
 
+    /// Parse the VB structures of a loaded PE file
+    fn load_vb(&self, pe: PEFile, mut warnings: Vec<String>) -> Result<(vb::VBFile, Vec<String>)> {
         // 3. Parse VB structures
         log::info!("Parsing VB structures...");
-        let vb_file = Arc::new(vb::VBFile::from_pe(pe)?);
+        self.report(ProgressEvent::ParsingVb);
+        let vb_file = vb::VBFile::from_pe(pe)?;
+        warnings.extend(
+            vb_file
+                .object_errors()
+                .iter()
+                .map(|e| format!("Skipped unparsable {}", e)),
+        );
+        Ok((vb_file, warnings))
+    }
+
+    /// Decompile a VB executable file
+    pub fn decompile_file(&mut self, path: impl AsRef<Path>) -> Result<DecompilationResult> {
+        let path = path.as_ref();
+        log::info!("Decompiling file: {}", path.display());
+
+        let loaded = self.load_file(path)?;
+        self.decompile_loaded(loaded, Some(path))
+    }
+
+    /// Decompile a VB executable held in memory
+    ///
+    /// For callers that already have the bytes, e.g. after unpacking in
+    /// process. The output banner names no source file.
+    pub fn decompile_bytes(&mut self, data: Vec<u8>) -> Result<DecompilationResult> {
+        log::info!("Decompiling {} bytes from memory", data.len());
+
+        let loaded = self.load_bytes(data)?;
+        self.decompile_loaded(loaded, None)
+    }
+
+    /// Decompile a parsed VB executable; `path` names it in the banner
+    fn decompile_loaded(
+        &mut self,
+        (vb_file, warnings): (vb::VBFile, Vec<String>),
+        path: Option<&Path>,
+    ) -> Result<DecompilationResult> {
+        let vb_file = Arc::new(vb_file);
 
         log::info!(
             "Found VB project: {}",
             vb_file.project_name().as_deref().unwrap_or("Unknown")
         );
 
+        if vb_file.is_native_code() {
+            return self.decompile_native(path, &vb_file, warnings);
+        }
+
         // 4. Collect all methods to decompile
         let mut methods_to_decompile = Vec::new();
+        let mut declarations = String::new();
+        let mut modules = Vec::new();
 
         for (obj_idx, object) in vb_file.objects().iter().enumerate() {
+            if !object.is_parsed() {
+                continue;
+            }
+            if !self.includes_object(&object.name) {
+                log::info!("Skipping filtered object: {}", object.name);
+                continue;
+            }
+
             log::info!("Processing object: {}", object.name);
 
+            let with_events = vb_file.with_events_members(object);
+            let mut object_declarations = self
+                .generator
+                .generate_constant_declarations(&object.constants);
+            object_declarations.push_str(
+                &self
+                    .generator
+                    .generate_with_events_declarations(&with_events),
+            );
+            declarations.push_str(&object_declarations);
+            modules.push(DecompiledModule {
+                name: object.name.clone(),
+                kind: ModuleKind::of(object),
+                declarations: object_declarations,
+                methods: Vec::new(),
+                option_explicit: true,
+            });
+
+            // Descriptors are read here, once, rather than by every worker
+            let locations = vb_file.pcode_locations(obj_idx);
             for (method_idx, method_name) in object.method_names.iter().enumerate() {
+                // Event handlers of WithEvents members are labeled in the output
+                let handles = with_events
+                    .iter()
+                    .find(|member| member.handlers.contains(method_name))
+                    .map(|member| member.name.clone());
+
                 methods_to_decompile.push((
                     obj_idx,
-                    method_idx,
+                    locations
+                        .iter()
+                        .find(|location| location.method_index == method_idx)
+                        .copied(),
                     object.name.clone(),
                     method_name.clone(),
+                    handles,
                 ));
             }
         }
@@ -71,47 +291,78 @@ impl Decompiler {
             methods_to_decompile.len()
         );
 
-        // 5. Decompile methods in parallel using Rayon
+        // 5. Decompile methods in parallel using Rayon (unless limited to one thread)
         // This provides significant speedup for executables with many methods.
         // Each method is decompiled independently on a separate thread from Rayon's thread pool.
         // Benefits:
         // - Scales with CPU cores (e.g., 8 cores → ~8x faster for 100+ methods)
         // - Memory-safe: Rust's ownership prevents data races
         // - Automatic work stealing: Rayon balances work across threads
-        let decompiled_methods: Vec<(String, String)> = methods_to_decompile
-            .par_iter()
-            .filter_map(|(obj_idx, method_idx, obj_name, method_name)| {
+        let dialect = self.generator.dialect();
+        let max_proc_size = self.options.max_proc_size as usize;
+        let hex_literals = self.options.hex_literals;
+        let indent = self.options.indent;
+        let demangle_runtime = self.options.demangle_runtime;
+        let decompile_method =
+            |(obj_idx, location, obj_name, method_name, handles): &MethodTask| {
                 log::info!("  Processing method: {}_{}", obj_name, method_name);
 
-                // Get P-Code for this specific method
-                let pcode_data = match vb_file.get_pcode_for_method(*obj_idx, *method_idx) {
-                    Some(data) => data,
-                    None => {
-                        log::info!("    No P-Code (native compiled)");
-                        return None;
-                    }
+                let Some(location) = location else {
+                    log::info!("    No P-Code (native compiled)");
+                    return None;
                 };
 
-                if pcode_data.is_empty() {
-                    log::info!("    Empty P-Code data");
+                if location.size as usize > max_proc_size {
+                    log::info!(
+                        "    Skipping: {} bytes exceeds the {} byte limit",
+                        location.size,
+                        max_proc_size
+                    );
                     return None;
                 }
 
+                // Get P-Code for this specific method
+                let Some(pcode_data) = vb_file.read_pcode(location) else {
+                    log::info!("    P-Code lies outside the image");
+                    return None;
+                };
+
                 log::info!(
                     "    P-Code found ({} bytes), disassembling...",
                     pcode_data.len()
                 );
 
-                // Disassemble P-Code
-                let mut disassembler = Disassembler::new(pcode_data);
-                let instructions = match disassembler.disassemble(0) {
+                let mut output = MethodOutput {
+                    object_name: obj_name.clone(),
+                    ..Default::default()
+                };
+                let function_name = format!("{}_{}", obj_name, method_name);
+
+                // Disassemble P-Code, naming the imports `ImpAdCall*` targets
+                let imports = Arc::clone(&vb_file);
+                let obj_idx = *obj_idx;
+                let mut disassembler = Disassembler::new(pcode_data).with_symbol_resolver(
+                    Box::new(move |kind, value| match kind {
+                        OperandKind::Import => imports.import_name(obj_idx, value as u16 as u32),
+                        _ => None,
+                    }),
+                );
+                let instructions = match disassembler.disassemble(location.rva) {
                     Ok(insns) => insns,
                     Err(e) => {
                         log::warn!("    Failed to disassemble: {}", e);
-                        return None;
+                        output.error = Some(format!("{}: {}", function_name, e));
+                        return Some(output);
                     }
                 };
 
+                if disassembler.truncated() {
+                    log::warn!(
+                        "    Stopped after {} instructions without reaching the procedure exit",
+                        instructions.len()
+                    );
+                }
+
                 if instructions.is_empty() {
                     log::warn!("    No instructions found");
                     return None;
@@ -119,28 +370,119 @@ impl Decompiler {
 
                 log::info!("    Disassembled {} instructions", instructions.len());
 
+                for instr in &instructions {
+                    *output.opcode_histogram.entry(instr.opcode).or_insert(0) += 1;
+                    if instr.category == OpcodeCategory::Unknown {
+                        output.unknown_opcode_count += 1;
+                    }
+                }
+
                 // Lift P-Code to IR
-                let mut lifter = PCodeLifter::new();
-                let function_name = format!("{}_{}", obj_name, method_name);
-                let function = match lifter.lift(&instructions, function_name.clone(), 0) {
+                let mut lifter = PCodeLifter::new()
+                    .with_runtime_demangling(demangle_runtime)
+                    .with_frame_size(location.frame_size);
+                let mut function = match lifter.lift(&instructions, function_name.clone(), 0) {
                     Ok(func) => func,
                     Err(e) => {
                         log::warn!("    Failed to lift: {}", e);
-                        return None;
+                        output.error = Some(format!("{}: {}", function_name, e));
+                        return Some(output);
                     }
                 };
 
                 log::info!("    Lifted to IR: {} blocks", function.basic_blocks.len());
+                for warning in lifter.warnings() {
+                    log::warn!("    {}", warning);
+                }
+
+                optimize::remove_unreachable_blocks(&mut function);
+                optimize::fold_constants(&mut function);
+
+                // Recover loops and If blocks; unstructurable functions keep their GoTos
+                if !structure::structure_function(&mut function) {
+                    log::info!("    Could not structure control flow");
+                }
+                types::infer_types(&mut function);
+                optimize::introduce_with_blocks(&mut function);
 
                 // Generate VB6 code (each thread gets its own generator)
-                let mut generator = VB6CodeGenerator::new();
-                let code = generator.generate_function(&function);
+                let mut generator = VB6CodeGenerator::with_dialect(dialect)
+                    .with_indent(indent)
+                    .with_hex_literals(hex_literals);
+                let mut code = generator.generate_function(&function);
+                output.declarations_complete = generator.declarations_complete(&function);
+                // Module files use the bare name, which VB binds event handlers by
+                function.name = method_name.clone();
+                let mut module_code = generator.generate_function(&function);
+                if let Some(member) = handles {
+                    code = format!("' WithEvents handler for {}\n{}", member, code);
+                    module_code = format!("' WithEvents handler for {}\n{}", member, module_code);
+                }
 
                 log::info!("    Successfully decompiled {}", function_name);
 
-                Some((function_name, code))
-            })
-            .collect();
+                output.decompiled = Some((function_name, code));
+                output.module_method = Some((method_name.clone(), module_code));
+                Some(output)
+            };
+        let total = methods_to_decompile.len();
+        let completed = AtomicUsize::new(0);
+        let report_method = |task: &MethodTask| {
+            self.report(ProgressEvent::MethodStarted {
+                name: format!("{}_{}", task.2, task.3),
+            });
+            let output = decompile_method(task);
+            let index = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            self.report(ProgressEvent::MethodDone { index, total });
+            output
+        };
+        let decompile_all = || -> Vec<MethodOutput> {
+            if self.threads != Some(1) {
+                methods_to_decompile
+                    .par_iter()
+                    .filter_map(report_method)
+                    .collect()
+            } else {
+                methods_to_decompile
+                    .iter()
+                    .filter_map(report_method)
+                    .collect()
+            }
+        };
+
+        let outputs = match self.threads {
+            Some(threads) if threads > 1 => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .map_err(|e| Error::Decompilation(format!("Failed to create thread pool: {}", e)))?
+                .install(decompile_all),
+            _ => decompile_all(),
+        };
+
+        let mut decompiled_methods = Vec::new();
+        let mut opcode_histogram = HashMap::new();
+        let mut unknown_opcode_count = 0;
+        for output in outputs {
+            if let Some(error) = output.error {
+                if !self.options.continue_on_error {
+                    return Err(Error::Decompilation(error));
+                }
+            }
+            for (opcode, count) in output.opcode_histogram {
+                *opcode_histogram.entry(opcode).or_insert(0) += count;
+            }
+            unknown_opcode_count += output.unknown_opcode_count;
+            if let (Some(decompiled), Some(module_method)) =
+                (output.decompiled, output.module_method)
+            {
+                if let Some(module) = modules.iter_mut().find(|m| m.name == output.object_name) {
+                    module.methods.push(module_method);
+                    module.option_explicit &= output.declarations_complete;
+                }
+                decompiled_methods.push(decompiled);
+            }
+        }
+        modules.retain(|module| !module.methods.is_empty());
 
         if decompiled_methods.is_empty() {
             return Err(Error::Decompilation(
@@ -148,24 +490,218 @@ impl Decompiler {
             ));
         }
 
-        // 6. Combine all decompiled code
-        let mut vb6_code = String::new();
-        for (_name, code) in &decompiled_methods {
-            vb6_code.push_str(code);
-            vb6_code.push_str("\n\n");
+        // 6. Combine all decompiled code, starting where the program starts
+        let mut vb6_code = self.generate_banner(path);
+        vb6_code.push('\n');
+        if !declarations.is_empty() {
+            vb6_code.push_str(&declarations);
+            vb6_code.push('\n');
         }
+        match vb_file.sub_main() {
+            Some((obj_idx, method_idx)) => {
+                let object = &vb_file.objects()[obj_idx];
+                let name = format!("{}_{}", object.name, object.method_names[method_idx]);
+                if let Some(position) = decompiled_methods.iter().position(|(n, _)| *n == name) {
+                    let (name, code) = decompiled_methods.remove(position);
+                    let code = format!("' Entry point: Sub Main\n{}", code);
+                    decompiled_methods.insert(0, (name, code));
+                }
+            }
+            None => {
+                if let Some(form) = vb_file.startup_form() {
+                    let name = &vb_file.objects()[form].name;
+                    vb6_code.push_str(&format!("' Startup form: {}\n\n", name));
+                }
+            }
+        }
+        vb6_code.push_str(&self.combine_methods(&decompiled_methods));
+
+        let project_file = generate_project_file(&vb_file, &modules);
 
         Ok(DecompilationResult {
             project_name: vb_file
                 .project_name()
                 .unwrap_or_else(|| "Unknown".to_string()),
             vb6_code,
+            project_file,
             is_pcode: true,
             object_count: vb_file.objects().len(),
             method_count: decompiled_methods.len(),
+            opcode_histogram,
+            unknown_opcode_count,
+            modules,
+            warnings,
+        })
+    }
+
+    /// List the x86 code of a native-compiled project's methods
+    ///
+    /// Native code is not lifted to VB yet, so `vb6_code` holds each
+    /// method's disassembly as comments under a header saying so.
+    fn decompile_native(
+        &self,
+        path: Option<&Path>,
+        vb_file: &vb::VBFile,
+        warnings: Vec<String>,
+    ) -> Result<DecompilationResult> {
+        log::info!("Native code project, disassembling x86 methods");
+
+        let image_base = vb_file.pe_file().image_base();
+        let disassembler = X86Disassembler::new_32bit().with_base_address(image_base);
+        let max_proc_size = self.options.max_proc_size as usize;
+        let mut listing = String::new();
+        let mut method_count = 0;
+        // Calls into the VB runtime go through `jmp [iat]` thunks
+        let runtime_thunks: HashMap<u64, String> = vb_file
+            .runtime_imports()
+            .into_iter()
+            .map(|(rva, name)| (image_base + rva as u64, name))
+            .collect();
+
+        for (obj_idx, object) in vb_file.objects().iter().enumerate() {
+            if !self.includes_object(&object.name) {
+                continue;
+            }
+            for (method_idx, method_name) in object.method_names.iter().enumerate() {
+                let Some((rva, mut code)) = vb_file.get_native_code_for_method(obj_idx, method_idx)
+                else {
+                    continue;
+                };
+                code.truncate(max_proc_size);
+
+                let address = image_base + rva as u64;
+                let lines = if self.options.native_listing {
+                    disassembler
+                        .disassemble_to_listing(&code, rva as u64)
+                        .map(|listing| listing.lines().map(str::to_string).collect())
+                } else {
+                    disassembler
+                        .disassemble(&code, rva as u64)
+                        .map(|instructions| {
+                            instructions
+                                .iter()
+                                .map(|instr| {
+                                    let line = format!("{:08X}  {}", instr.address, instr.text);
+                                    match instr
+                                        .branch_target
+                                        .and_then(|target| runtime_thunks.get(&target))
+                                    {
+                                        Some(name) => format!("{}  ; {}", line, name),
+                                        None => line,
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                };
+                let lines = match lines {
+                    Ok(lines) => lines,
+                    Err(e) => {
+                        log::warn!(
+                            "  Failed to disassemble {}.{}: {}",
+                            object.name,
+                            method_name,
+                            e
+                        );
+                        continue;
+                    }
+                };
+
+                listing.push_str(&format!(
+                    "\n' {}.{} at 0x{:08X} ({} bytes)\n",
+                    object.name,
+                    method_name,
+                    address,
+                    code.len()
+                ));
+                for line in lines {
+                    listing.push_str(&format!("'   {}\n", line));
+                }
+                method_count += 1;
+            }
+        }
+
+        if method_count == 0 {
+            return Err(Error::Decompilation(
+                "No native methods found to disassemble".to_string(),
+            ));
+        }
+
+        let mut vb6_code = self.generate_banner(path);
+        vb6_code.push_str("\n' Native code: x86 disassembly, not decompiled to VB\n");
+        vb6_code.push_str(&listing);
+
+        Ok(DecompilationResult {
+            project_name: vb_file
+                .project_name()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            vb6_code,
+            project_file: generate_project_file(vb_file, &[]),
+            is_pcode: false,
+            object_count: vb_file.objects().len(),
+            method_count,
+            opcode_histogram: HashMap::new(),
+            unknown_opcode_count: 0,
+            modules: Vec::new(),
+            warnings,
         })
     }
 
+    /// Output banner for `path`, filling in the file name and tool version
+    fn generate_banner(&self, path: Option<&Path>) -> String {
+        let mut banner = self.generator.banner().clone();
+        if let Some(path) = path {
+            banner.source_file.get_or_insert_with(|| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| path.to_string_lossy().into_owned())
+            });
+        }
+        banner
+            .tool_version
+            .get_or_insert_with(|| env!("CARGO_PKG_VERSION").to_string());
+
+        VB6CodeGenerator::with_dialect(self.generator.dialect())
+            .with_banner(banner)
+            .generate_banner()
+    }
+
+    /// Decompile every `.exe`/`.dll`/`.ocx` file under `dir`, recursively
+    ///
+    /// Returns each binary's path relative to `dir` with its result, sorted by
+    /// path. A file that fails to decompile doesn't stop the walk; only an
+    /// unreadable directory does.
+    pub fn decompile_dir(
+        &mut self,
+        dir: &Path,
+    ) -> Result<Vec<(PathBuf, Result<DecompilationResult>)>> {
+        let mut binaries = Vec::new();
+        collect_binaries(dir, &mut binaries)?;
+        binaries.sort();
+
+        Ok(binaries
+            .into_iter()
+            .map(|path| {
+                log::info!("Batch: {}", path.display());
+                let result = self.decompile_file(&path);
+                let relative = path.strip_prefix(dir).unwrap_or(&path).to_path_buf();
+                (relative, result)
+            })
+            .collect())
+    }
+
+    /// Concatenate decompiled methods into a single source listing
+    fn combine_methods(&self, methods: &[(String, String)]) -> String {
+        let mut vb6_code = String::new();
+        for (name, code) in methods {
+            if self.annotate_methods {
+                vb6_code.push_str(&format!("' {}\n", name));
+            }
+            vb6_code.push_str(code);
+            vb6_code.push_str("\n\n");
+        }
+        vb6_code
+    }
+
     /// Generate VB6 code from an IR function (for testing/API use)
     pub fn generate_code(&mut self, function: &Function) -> String {
         self.generator.generate_function(function)
@@ -178,25 +714,476 @@ impl Default for Decompiler {
     }
 }
 
+/// Builder for a configured [`Decompiler`]
+///
+/// ```
+/// use vbdecompiler_core::Decompiler;
+///
+/// let decompiler = Decompiler::builder()
+///     .threads(2)
+///     .object("Form1")
+///     .annotate_methods(true)
+///     .build();
+/// # drop(decompiler);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DecompilerBuilder {
+    threads: Option<usize>,
+    object_filter: Vec<String>,
+    annotate_methods: bool,
+    dialect: Dialect,
+    banner: BannerConfig,
+    options: DecompilerOptions,
+}
+
+impl DecompilerBuilder {
+    /// Create a builder with default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of worker threads used to decompile methods (0 = Rayon default,
+    /// 1 = sequentially on the calling thread)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = (threads > 0).then_some(threads);
+        self
+    }
+
+    /// Restrict decompilation to the named object (may be called repeatedly)
+    pub fn object(mut self, name: impl Into<String>) -> Self {
+        self.object_filter.push(name.into());
+        self
+    }
+
+    /// Emit a comment naming the object/method above each decompiled method
+    pub fn annotate_methods(mut self, annotate: bool) -> Self {
+        self.annotate_methods = annotate;
+        self
+    }
+
+    /// Target language dialect of the generated code
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Header banner for generated output
+    ///
+    /// Source file and tool version are filled in at decompilation time
+    /// unless set here.
+    pub fn banner(mut self, banner: BannerConfig) -> Self {
+        self.banner = banner;
+        self
+    }
+
+    /// Pipeline tuning options
+    pub fn options(mut self, options: DecompilerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Build the configured decompiler
+    pub fn build(self) -> Decompiler {
+        Decompiler {
+            generator: VB6CodeGenerator::with_dialect(self.dialect)
+                .with_banner(self.banner)
+                .with_indent(self.options.indent)
+                .with_hex_literals(self.options.hex_literals),
+            threads: self.threads,
+            object_filter: self.object_filter,
+            annotate_methods: self.annotate_methods,
+            options: self.options,
+            progress: None,
+        }
+    }
+}
+
+/// Pipeline tuning for a [`Decompiler`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecompilerOptions {
+    /// Refuse executables a packer detector flags
+    pub reject_packed: bool,
+    /// Skip methods with more P-Code bytes than this
+    pub max_proc_size: u16,
+    /// Keep going when a method fails to disassemble or lift, instead of
+    /// failing the whole file
+    pub continue_on_error: bool,
+    /// Memory-map input files instead of reading them whole
+    pub memory_map: bool,
+    /// Write flag-like integers as `&H` hex literals
+    pub hex_literals: bool,
+    /// Render VB runtime calls as the intrinsics and operators they
+    /// implement (`__vbaStrCat` as `&`) rather than their raw symbols
+    pub demangle_runtime: bool,
+    /// List native methods as NASM with their addresses and instruction
+    /// bytes (see [`X86Disassembler::disassemble_to_listing`])
+    pub native_listing: bool,
+    /// Indentation of generated code
+    pub indent: IndentStyle,
+}
+
+impl Default for DecompilerOptions {
+    fn default() -> Self {
+        Self {
+            reject_packed: true,
+            max_proc_size: u16::MAX,
+            continue_on_error: true,
+            memory_map: false,
+            hex_literals: false,
+            demangle_runtime: true,
+            native_listing: false,
+            indent: IndentStyle::default(),
+        }
+    }
+}
+
 /// Result of decompilation
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DecompilationResult {
     /// Project name
     pub project_name: String,
     /// Generated VB6 source code
     pub vb6_code: String,
+    /// `.vbp` project file tying the per-object modules together
+    #[serde(default)]
+    pub project_file: String,
     /// Whether this was P-Code or native
     pub is_pcode: bool,
     /// Number of objects decompiled
     pub object_count: usize,
     /// Number of methods decompiled
     pub method_count: usize,
+    /// Occurrences of each primary opcode across all disassembled methods
+    pub opcode_histogram: HashMap<u8, usize>,
+    /// Number of disassembled instructions the opcode table doesn't know
+    pub unknown_opcode_count: usize,
+    /// Decompiled code grouped by object, for per-file output
+    #[serde(default)]
+    pub modules: Vec<DecompiledModule>,
+    /// Non-fatal problems found along the way (e.g. a suspected packer)
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl DecompilationResult {
+    /// Total number of disassembled instructions
+    pub fn instruction_count(&self) -> usize {
+        self.opcode_histogram.values().sum()
+    }
+
+    /// Fraction of disassembled instructions with unknown opcodes
+    pub fn unknown_opcode_ratio(&self) -> f64 {
+        match self.instruction_count() {
+            0 => 0.0,
+            total => self.unknown_opcode_count as f64 / total as f64,
+        }
+    }
+
+    /// The `n` most frequent opcodes, most frequent first
+    pub fn top_opcodes(&self, n: usize) -> Vec<(u8, usize)> {
+        let mut opcodes: Vec<_> = self
+            .opcode_histogram
+            .iter()
+            .map(|(&opcode, &count)| (opcode, count))
+            .collect();
+        opcodes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        opcodes.truncate(n);
+        opcodes
+    }
+
+    /// The `.vbp` project file for modules written with
+    /// [`DecompiledModule::split`], listing each part of a split module
+    pub fn split_project_file(&self, max_methods: usize, max_bytes: Option<usize>) -> String {
+        let mut project_file = self.project_file.clone();
+        for module in &self.modules {
+            let entries: String = module
+                .split(max_methods, max_bytes)
+                .iter()
+                .filter_map(|file| file.file_name.rsplit_once('.'))
+                .map(|(stem, _)| module.kind.project_entry(stem) + "\n")
+                .collect();
+            project_file = project_file.replacen(
+                &(module.kind.project_entry(&module.name) + "\n"),
+                &entries,
+                1,
+            );
+        }
+        project_file
+    }
+}
+
+/// Kind of VB source file an object decompiles to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ModuleKind {
+    Form,
+    Module,
+    Class,
+}
+
+impl ModuleKind {
+    fn of(object: &vb::VBObject) -> Self {
+        if object.is_form() {
+            Self::Form
+        } else if object.is_class() {
+            Self::Class
+        } else {
+            Self::Module
+        }
+    }
+
+    /// Source file extension (`frm`, `bas`, `cls`)
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Form => "frm",
+            Self::Module => "bas",
+            Self::Class => "cls",
+        }
+    }
+
+    /// File preamble the VB6 IDE expects before the code of a `name` file
+    ///
+    /// Forms get a bare `Begin VB.Form` skeleton; their controls are not
+    /// recovered.
+    pub fn file_header(self, name: &str) -> String {
+        match self {
+            Self::Form => format!(
+                "VERSION 5.00\n\
+                 Begin VB.Form {name}\n\
+                 \x20  Caption         =   \"{name}\"\n\
+                 \x20  ClientHeight    =   3195\n\
+                 \x20  ClientLeft      =   60\n\
+                 \x20  ClientTop       =   345\n\
+                 \x20  ClientWidth     =   4680\n\
+                 End\n\
+                 Attribute VB_Name = \"{name}\"\n\
+                 Attribute VB_GlobalNameSpace = False\n\
+                 Attribute VB_Creatable = False\n\
+                 Attribute VB_PredeclaredId = True\n\
+                 Attribute VB_Exposed = False\n",
+                name = name
+            ),
+            Self::Class => format!(
+                "VERSION 1.0 CLASS\n\
+                 BEGIN\n\
+                 \x20 MultiUse = -1  'True\n\
+                 END\n\
+                 Attribute VB_Name = \"{name}\"\n\
+                 Attribute VB_GlobalNameSpace = False\n\
+                 Attribute VB_Creatable = True\n\
+                 Attribute VB_PredeclaredId = False\n\
+                 Attribute VB_Exposed = False\n",
+                name = name
+            ),
+            Self::Module => format!("Attribute VB_Name = \"{}\"\n", name),
+        }
+    }
+
+    /// `.vbp` line that adds the `name` file to a project
+    pub fn project_entry(self, name: &str) -> String {
+        match self {
+            Self::Form => format!("Form={}.frm", name),
+            Self::Module => format!("Module={name}; {name}.bas", name = name),
+            Self::Class => format!("Class={name}; {name}.cls", name = name),
+        }
+    }
+}
+
+/// Extensions of the binaries VB compiles to
+const BINARY_EXTENSIONS: &[&str] = &["exe", "dll", "ocx"];
+
+/// Append the VB-compilable binaries under `dir` to `binaries`
+fn collect_binaries(dir: &Path, binaries: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // Symlinked directories are skipped so that a link cycle can't recurse
+        // forever; symlinked files are still decompiled
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_binaries(&path, binaries)?;
+        } else if file_type.is_symlink() && path.is_dir() {
+            log::debug!("Skipping symlinked directory {}", path.display());
+        } else if path.extension().is_some_and(|ext| {
+            BINARY_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        }) {
+            binaries.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Build a `.vbp` project file listing `modules` and the referenced controls
+///
+/// DLL projects are written as ActiveX DLLs (`Type=OleDll`); everything else
+/// as a standard EXE. `Declare`d libraries need no project entry.
+fn generate_project_file(vb_file: &vb::VBFile, modules: &[DecompiledModule]) -> String {
+    let project_type = if vb_file.pe_file().is_dll() {
+        "OleDll"
+    } else {
+        "Exe"
+    };
+    let mut vbp = format!("Type={}\n", project_type);
+    vbp.push_str(
+        "Reference=*\\G{00020430-0000-0000-C000-000000000046}#2.0#0#stdole2.tlb#OLE Automation\n",
+    );
+    for component in vb_file.external_components() {
+        if let (vb::ExternalKind::Control, Some(guid)) = (component.kind, &component.guid) {
+            vbp.push_str(&format!("Object={}#1.0#0; {}\n", guid, component.name));
+        }
+    }
+    for module in modules {
+        vbp.push_str(&module.kind.project_entry(&module.name));
+        vbp.push('\n');
+    }
+    if let Some(form) = vb_file.startup_form() {
+        vbp.push_str(&format!("Startup=\"{}\"\n", vb_file.objects()[form].name));
+    } else {
+        vbp.push_str("Startup=\"Sub Main\"\n");
+    }
+    vbp.push_str(&format!(
+        "Name=\"{}\"\n",
+        vb_file
+            .project_name()
+            .unwrap_or_else(|| "Project1".to_string())
+    ));
+    vbp
+}
+
+/// Decompiled code of a single object
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DecompiledModule {
+    /// Object name (`Form1`, `Module1`)
+    pub name: String,
+    pub kind: ModuleKind,
+    /// Module-level declarations (`Const` values and `WithEvents` members)
+    pub declarations: String,
+    /// Method name and generated code, in original order
+    ///
+    /// Methods are generated under their bare names (`Form_Load`, not
+    /// `Form1_Form_Load`) so that event handlers bind in the module file.
+    pub methods: Vec<(String, String)>,
+    /// Every method declares the variables it uses, so the file can start
+    /// with `Option Explicit`
+    #[serde(default)]
+    pub option_explicit: bool,
+}
+
+/// One generated source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleFile {
+    /// File name including extension (`Module1_part2.bas`)
+    pub file_name: String,
+    pub code: String,
+}
+
+impl DecompiledModule {
+    /// Render the module as a single source file
+    pub fn file(&self) -> ModuleFile {
+        self.split(usize::MAX, None).remove(0)
+    }
+
+    /// Render the module as one or more source files
+    ///
+    /// Standard modules holding more than `max_methods` methods (or more
+    /// than `max_bytes` of code, if given) are split into `Name.bas`,
+    /// `Name_part2.bas`, ... with the header and declarations repeated in
+    /// each part, so every part compiles on its own. Module-level constants
+    /// are private, so the copies don't clash. Forms and classes cannot be
+    /// split; they are kept whole with a warning.
+    pub fn split(&self, max_methods: usize, max_bytes: Option<usize>) -> Vec<ModuleFile> {
+        let max_methods = max_methods.max(1);
+        let max_bytes = max_bytes.unwrap_or(usize::MAX);
+
+        let mut parts: Vec<Vec<&(String, String)>> = vec![Vec::new()];
+        let mut part_bytes = self.declarations.len();
+        for method in &self.methods {
+            let current = parts.last().unwrap();
+            let full = current.len() >= max_methods || part_bytes + method.1.len() > max_bytes;
+            if full && !current.is_empty() {
+                parts.push(Vec::new());
+                part_bytes = self.declarations.len();
+            }
+            part_bytes += method.1.len();
+            parts.last_mut().unwrap().push(method);
+        }
+
+        if parts.len() > 1 && self.kind != ModuleKind::Module {
+            log::warn!(
+                "{} exceeds the split threshold but a {:?} cannot be split",
+                self.name,
+                self.kind
+            );
+            parts = vec![self.methods.iter().collect()];
+        }
+
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(i, methods)| {
+                let stem = match i {
+                    0 => self.name.clone(),
+                    _ => format!("{}_part{}", self.name, i + 1),
+                };
+                let mut code = self.kind.file_header(&stem);
+                if self.option_explicit {
+                    code.push_str("Option Explicit\n");
+                }
+                if !self.declarations.is_empty() {
+                    code.push_str(&self.declarations);
+                    code.push('\n');
+                }
+                for (_, method_code) in methods {
+                    code.push_str(method_code);
+                    code.push_str("\n\n");
+                }
+                ModuleFile {
+                    file_name: format!("{}.{}", stem, self.kind.extension()),
+                    code,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Object index, P-Code location (None for native or empty methods), object
+/// name, method name and the `WithEvents` member the method handles events
+/// for
+type MethodTask = (
+    usize,
+    Option<vb::PcodeLocation>,
+    String,
+    String,
+    Option<String>,
+);
+
+/// Per-method output of the parallel decompilation pass
+#[derive(Default)]
+struct MethodOutput {
+    /// Object the method belongs to
+    object_name: String,
+    /// Why disassembly or lifting failed
+    error: Option<String>,
+    opcode_histogram: HashMap<u8, usize>,
+    unknown_opcode_count: usize,
+    /// Function name (`Form1_Form_Load`) and generated code, if lifting
+    /// succeeded
+    decompiled: Option<(String, String)>,
+    /// Bare method name (`Form_Load`) and the code generated under it, for
+    /// the object's own source file
+    module_method: Option<(String, String)>,
+    /// The generated code declares every variable it uses
+    declarations_complete: bool,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ir::{Expression, Statement, Type, TypeKind, Variable};
+    use crate::testutil::{
+        build_vb_exe, build_vb_section, ObjectSpec, PeBuilder, ProjectSpec, IMAGE_BASE,
+    };
 
     #[test]
     fn test_decompiler_creation() {
@@ -204,6 +1191,599 @@ mod tests {
         // Just test that it creates successfully
     }
 
+    #[test]
+    fn test_builder_options() {
+        let decompiler = Decompiler::builder()
+            .threads(2)
+            .object("Form1")
+            .object("Module1")
+            .annotate_methods(true)
+            .dialect(Dialect::VBNet)
+            .build();
+
+        assert_eq!(decompiler.threads, Some(2));
+        assert_eq!(decompiler.generator.dialect(), Dialect::VBNet);
+        assert!(decompiler.includes_object("form1"));
+        assert!(decompiler.includes_object("Module1"));
+        assert!(!decompiler.includes_object("Class1"));
+
+        let code = decompiler.combine_methods(&[(
+            "Form1_Load".to_string(),
+            "Sub Form1_Load()\nEnd Sub".to_string(),
+        )]);
+        assert!(code.starts_with("' Form1_Load\nSub Form1_Load()"));
+    }
+
+    #[test]
+    fn test_builder_defaults_match_new() {
+        let decompiler = Decompiler::new();
+        assert_eq!(decompiler.threads, None);
+        assert!(decompiler.includes_object("Anything"));
+        assert!(!decompiler.annotate_methods);
+
+        let decompiler = Decompiler::builder().threads(0).build();
+        assert_eq!(decompiler.threads, None);
+    }
+
+    #[test]
+    fn test_reject_packed_option() {
+        let spec = ProjectSpec::new("Packed")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        let mut pe = PeBuilder::new().vb_imports();
+        let rva = pe.next_section_rva();
+        pe.section("UPX1", build_vb_section(&spec, rva));
+
+        let data = pe.build();
+        let rejected = Decompiler::new().decompile_bytes(data.clone());
+        let allowed = Decompiler::with_options(DecompilerOptions {
+            reject_packed: false,
+            ..Default::default()
+        })
+        .decompile_bytes(data);
+
+        assert!(matches!(rejected, Err(e) if e.to_string().contains("Packed executable")));
+        assert_eq!(allowed.unwrap().modules[0].name, "Module1");
+    }
+
+    #[test]
+    fn test_memory_map_matches_read() {
+        let spec = ProjectSpec::new("Mapped")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x5E, 0x01, 0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+
+        let path = std::env::temp_dir().join(format!("vbdc_mmap_{}.exe", std::process::id()));
+        fs::write(&path, build_vb_exe(&spec)).unwrap();
+        let read = Decompiler::new().decompile_file(&path);
+        let mapped = Decompiler::with_options(DecompilerOptions {
+            memory_map: true,
+            ..Default::default()
+        })
+        .decompile_file(&path);
+        let pe = PEFile::from_path_mmap(&path).map(|pe| pe.data().to_vec());
+        fs::remove_file(&path).unwrap();
+
+        let (read, mapped) = (read.unwrap(), mapped.unwrap());
+        assert_eq!(mapped.method_count, 2);
+        assert_eq!(mapped.vb6_code, read.vb6_code);
+        assert_eq!(mapped.project_file, read.project_file);
+        assert_eq!(
+            pe.unwrap(),
+            PEFile::from_bytes(build_vb_exe(&spec)).unwrap().data()
+        );
+    }
+
+    #[test]
+    fn test_decompile_bytes_matches_file() {
+        let spec = ProjectSpec::new("Buffer")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x5E, 0x01, 0x14]));
+        let data = build_vb_exe(&spec);
+
+        let path = std::env::temp_dir().join(format!("vbdc_buffer_{}.exe", std::process::id()));
+        fs::write(&path, &data).unwrap();
+        let from_file = Decompiler::new().decompile_file(&path);
+        fs::remove_file(&path).unwrap();
+        let (from_file, from_bytes) = (
+            from_file.unwrap(),
+            Decompiler::new().decompile_bytes(data).unwrap(),
+        );
+
+        assert_eq!(from_bytes.method_count, 1);
+        assert_eq!(from_bytes.modules, from_file.modules);
+        assert!(from_file.vb6_code.contains(" from vbdc_buffer_"));
+        assert!(!from_bytes.vb6_code.contains(" from vbdc_buffer_"));
+        assert!(from_bytes.vb6_code.contains("Form1_Form_Load"));
+
+        assert!(Decompiler::new().decompile_bytes(vec![0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_dotnet_assembly_is_unsupported() {
+        // CLR runtime header pointing into .text
+        let mut builder = PeBuilder::new().import("mscoree.dll", &["_CorExeMain"]);
+        let text = builder.section(".text", vec![0; 0x48]);
+        let data = builder.data_directory(14, text, 0x48).build();
+
+        assert!(PEFile::from_bytes(data.clone()).unwrap().is_dotnet());
+        match Decompiler::new().decompile_bytes(data) {
+            Err(Error::Unsupported(message)) => assert_eq!(message, ".NET assembly, not VB5/6"),
+            other => panic!("expected an unsupported error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_module_constants_declared() {
+        let spec = ProjectSpec::new("Project1").object(
+            ObjectSpec::new("Module1", 0x01)
+                .named_constant("MAX_ITEMS", 2, 10)
+                .method("Main", vec![0x14]),
+        );
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+
+        assert_eq!(result.modules[0].declarations, "Const MAX_ITEMS = 10\n");
+    }
+
+    #[test]
+    fn test_numeric_condition_compared_with_zero() {
+        // FLdI2 [bp-8]; BranchF +4; LitI2 5; FStI2 [bp-12]; ExitProc
+        let pcode = vec![0x69, 0xF8, 0x1C, 0x04, 0x00, 0x5E, 0x05, 0x6D, 0xF4, 0x14];
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", pcode));
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+
+        assert!(
+            result
+                .vb6_code
+                .contains("    If local1 <> 0 Then\n        local2 = 5\n    End If\n"),
+            "{}",
+            result.vb6_code
+        );
+    }
+
+    #[test]
+    fn test_forced_packer_detection_is_warning() {
+        // A single import trips the low-confidence import-table heuristic
+        let spec = ProjectSpec::new("FewImports")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x5E, 0x01, 0x14]));
+        let mut pe = PeBuilder::new().import("MSVBVM60.DLL", &["ThunRTMain"]);
+        let rva = pe.next_section_rva();
+        pe.section(".text", build_vb_section(&spec, rva));
+
+        let data = pe.build();
+        let rejected = Decompiler::new().decompile_bytes(data.clone());
+        let forced = Decompiler::with_options(DecompilerOptions {
+            reject_packed: false,
+            ..Default::default()
+        })
+        .decompile_bytes(data);
+
+        assert!(rejected.is_err());
+        let result = forced.unwrap();
+        assert_eq!(result.method_count, 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("50% confidence"));
+    }
+
+    #[test]
+    fn test_continue_on_error_option() {
+        // AddCy on an empty stack fails to lift; LitI2 1 lifts fine
+        let spec = ProjectSpec::new("Errors")
+            .object(ObjectSpec::new("Module1", 0x01).method("Bad", vec![0xFB, 0x00, 0x13]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Good", vec![0x5E, 0x01, 0x13]));
+
+        let data = build_vb_exe(&spec);
+        let lenient = Decompiler::new().decompile_bytes(data.clone());
+        let strict = Decompiler::builder()
+            .threads(1)
+            .options(DecompilerOptions {
+                continue_on_error: false,
+                ..Default::default()
+            })
+            .build()
+            .decompile_bytes(data);
+
+        assert_eq!(lenient.unwrap().method_count, 1);
+        assert!(strict.unwrap_err().to_string().contains("Module1_Bad"));
+    }
+
+    #[test]
+    fn test_default_banner() {
+        let spec = ProjectSpec::new("Banner")
+            .object(ObjectSpec::new("Module1", 0).method("Main", vec![0x13]));
+
+        let code = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap()
+            .vb6_code;
+
+        let (banner, rest) = code.split_once('\n').unwrap();
+        assert_eq!(
+            banner,
+            format!("' Decompiled by VBDecompiler {}", env!("CARGO_PKG_VERSION"))
+        );
+        assert!(!rest.starts_with('\''));
+    }
+
+    #[test]
+    fn test_opcode_histogram() {
+        // LitI2 1; LitI2 2; AddI2; InvalidExcode (unknown); ExitProcHresult
+        let pcode = vec![0x5E, 0x01, 0x5E, 0x02, 0x95, 0x01, 0x13];
+        let spec =
+            ProjectSpec::new("Histo").object(ObjectSpec::new("Module1", 0).method("Calc", pcode));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+
+        assert_eq!(result.opcode_histogram.get(&0x5E), Some(&2));
+        assert_eq!(result.opcode_histogram.get(&0x95), Some(&1));
+        assert_eq!(result.unknown_opcode_count, 1);
+        assert_eq!(result.instruction_count(), 5);
+        assert_eq!(result.top_opcodes(1), vec![(0x5E, 2)]);
+        assert!((result.unknown_opcode_ratio() - 0.2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_native_project_lists_x86() {
+        // push ebp; mov ebp,esp; xor eax,eax; pop ebp; ret
+        let prologue = vec![0x55, 0x8B, 0xEC, 0x33, 0xC0, 0x5D, 0xC3];
+        let spec = ProjectSpec::new("Native").native().object(
+            ObjectSpec::new("Module1", 0x01)
+                .method("Main", prologue)
+                .method("Quit", vec![0xC3]),
+        );
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+
+        assert!(!result.is_pcode);
+        assert_eq!(result.method_count, 2);
+        assert!(result.vb6_code.contains("' Native code: x86 disassembly"));
+        assert!(result.vb6_code.contains("' Module1.Main at 0x"));
+        assert!(result.vb6_code.contains("push ebp"), "{}", result.vb6_code);
+        assert!(result.vb6_code.contains("xor eax,eax"));
+        // Main ends where Quit begins
+        assert_eq!(result.vb6_code.matches("ret").count(), 2);
+
+        let listing = Decompiler::with_options(DecompilerOptions {
+            native_listing: true,
+            ..Default::default()
+        })
+        .decompile_bytes(build_vb_exe(&spec))
+        .unwrap();
+        assert!(listing.vb6_code.contains("  55    push ebp\n"));
+        assert!(listing.vb6_code.contains("  33C0  xor eax,eax\n"));
+    }
+
+    #[test]
+    fn test_native_runtime_calls_are_named() {
+        // call Quit; ret | jmp [rtcMsgBox]
+        let build = |iat_va: u32| {
+            let mut thunk = vec![0xFF, 0x25];
+            thunk.extend_from_slice(&iat_va.to_le_bytes());
+            let spec = ProjectSpec::new("Native").native().object(
+                ObjectSpec::new("Module1", 0x01)
+                    .method("Main", vec![0xE8, 0x01, 0x00, 0x00, 0x00, 0xC3])
+                    .method("Quit", thunk),
+            );
+            build_vb_exe(&spec)
+        };
+        let pe = PEFile::from_bytes(build(0)).unwrap();
+        let iat_rva = (0x1000..0x10000)
+            .find(|&rva| pe.import_at_iat_rva(rva).as_deref() == Some("rtcMsgBox"))
+            .unwrap();
+
+        let result = Decompiler::new()
+            .decompile_bytes(build(IMAGE_BASE + iat_rva))
+            .unwrap();
+        let call = result
+            .vb6_code
+            .lines()
+            .find(|line| line.contains("call"))
+            .unwrap();
+        assert!(call.ends_with("  ; rtcMsgBox"), "{}", result.vb6_code);
+    }
+
+    #[test]
+    fn test_result_json_round_trip() {
+        let result = DecompilationResult {
+            project_name: "Project1".to_string(),
+            vb6_code: "Sub Main()\nEnd Sub\n".to_string(),
+            project_file: String::new(),
+            is_pcode: true,
+            object_count: 2,
+            method_count: 3,
+            opcode_histogram: HashMap::from([(0x14, 3)]),
+            unknown_opcode_count: 0,
+            modules: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        let json = serde_json::to_string_pretty(&result).unwrap();
+        assert!(json.contains("\"project_name\": \"Project1\""));
+        assert!(json.contains("\"method_count\": 3"));
+
+        let parsed: DecompilationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.project_name, result.project_name);
+        assert_eq!(parsed.vb6_code, result.vb6_code);
+        assert_eq!(parsed.method_count, 3);
+        assert_eq!(parsed.opcode_histogram.get(&0x14), Some(&3));
+    }
+
+    fn module_with_methods(kind: ModuleKind, count: usize) -> DecompiledModule {
+        DecompiledModule {
+            name: "Module1".to_string(),
+            kind,
+            declarations: "Const LIMIT = 5\n".to_string(),
+            methods: (1..=count)
+                .map(|i| (format!("Sub{}", i), format!("Sub Sub{}()\nEnd Sub", i)))
+                .collect(),
+            option_explicit: false,
+        }
+    }
+
+    #[test]
+    fn test_split_module_by_method_count() {
+        let module = module_with_methods(ModuleKind::Module, 5);
+
+        let files = module.split(2, None);
+        let names: Vec<_> = files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Module1.bas", "Module1_part2.bas", "Module1_part3.bas"]
+        );
+        for (file, part) in files
+            .iter()
+            .zip(["Module1", "Module1_part2", "Module1_part3"])
+        {
+            let header = format!("Attribute VB_Name = \"{}\"\n", part);
+            assert!(file.code.starts_with(&(header + "Const LIMIT = 5\n")));
+        }
+        assert!(files[0].code.contains("Sub Sub2()") && !files[0].code.contains("Sub Sub3()"));
+        assert!(files[2].code.contains("Sub Sub5()"));
+
+        // Under the threshold stays a single file
+        assert_eq!(module.split(5, None).len(), 1);
+        // Byte threshold splits independently of the method count; every
+        // part carries the declarations
+        assert_eq!(module.split(100, Some(40)).len(), 5);
+    }
+
+    #[test]
+    fn test_split_project_file_lists_parts() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        let mut result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+        result.modules = vec![module_with_methods(ModuleKind::Module, 3)];
+
+        assert_eq!(result.split_project_file(5, None), result.project_file);
+        let project_file = result.split_project_file(2, None);
+        assert!(project_file
+            .contains("\nModule=Module1; Module1.bas\nModule=Module1_part2; Module1_part2.bas\n"));
+    }
+
+    #[test]
+    fn test_form_object_yields_frm() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+
+        assert_eq!(result.modules.len(), 1);
+        assert_eq!(result.modules[0].kind, ModuleKind::Form);
+        let file = result.modules[0].file();
+        assert_eq!(file.file_name, "Form1.frm");
+        assert!(file.code.starts_with("VERSION 5.00\nBegin VB.Form Form1\n"));
+        assert!(file.code.contains("End\nAttribute VB_Name = \"Form1\"\n"));
+        assert!(file.code.contains("\nFunction Form_Load() As Variant\n"));
+        assert!(!file.code.contains("Form1_Form_Load"));
+        // The combined listing keeps names unique across objects
+        assert!(result.vb6_code.contains("Function Form1_Form_Load()"));
+    }
+
+    #[test]
+    fn test_class_object_yields_cls() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Class1", 0x02).method("Init", vec![0x14]));
+
+        let result = Decompiler::new().decompile_bytes(build_vb_exe(&spec));
+        let module = &result.unwrap().modules[0];
+
+        assert_eq!(module.kind, ModuleKind::Class);
+        assert!(module.option_explicit);
+        let file = module.file();
+        assert_eq!(file.file_name, "Class1.cls");
+        assert!(file.code.starts_with("VERSION 1.0 CLASS\nBEGIN\n"));
+        assert!(file
+            .code
+            .contains("Attribute VB_Exposed = False\nOption Explicit\n"));
+    }
+
+    #[test]
+    fn test_project_file_lists_objects() {
+        let mut spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(ObjectSpec::new("Class1", 0x02).method("Init", vec![0x14]));
+        spec.components
+            .push(("MSCOMCTL.OCX".to_string(), [0x11; 16]));
+
+        let result = Decompiler::new().decompile_bytes(build_vb_exe(&spec));
+        let vbp = result.unwrap().project_file;
+
+        let lines: Vec<&str> = vbp.lines().collect();
+        assert_eq!(lines[0], "Type=Exe");
+        assert!(lines.contains(&"Form=Form1.frm"));
+        assert!(lines.contains(&"Module=Module1; Module1.bas"));
+        assert!(lines.contains(&"Class=Class1; Class1.cls"));
+        assert!(
+            lines.contains(&"Object={11111111-1111-1111-1111-111111111111}#1.0#0; MSCOMCTL.OCX")
+        );
+        assert!(lines.contains(&"Startup=\"Form1\""));
+        assert!(lines.contains(&"Name=\"Project1\""));
+    }
+
+    #[test]
+    fn test_sub_main_listed_first() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec.clone().sub_main(1, 0)))
+            .unwrap();
+        let first = result.vb6_code.find("Function ").unwrap();
+        assert!(
+            result.vb6_code[..first].ends_with("' Entry point: Sub Main\n"),
+            "{}",
+            result.vb6_code
+        );
+        assert!(result.vb6_code[first..].starts_with("Function Module1_Main("));
+        assert!(result.project_file.contains("Startup=\"Sub Main\"\n"));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+        assert!(result.vb6_code.contains("' Startup form: Form1\n"));
+        assert!(!result.vb6_code.contains("Entry point"));
+        assert!(result.vb6_code.find("Form1_Form_Load") < result.vb6_code.find("Module1_Main"));
+    }
+
+    #[test]
+    fn test_startup_form_from_gui_table() {
+        // Form1 has no methods, so it gets no module of its own
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10))
+            .object(ObjectSpec::new("Form2", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec))
+            .unwrap();
+        assert!(result.vb6_code.contains("' Startup form: Form1\n"));
+        assert!(result.project_file.contains("Startup=\"Form1\"\n"));
+
+        let result = Decompiler::new()
+            .decompile_bytes(build_vb_exe(&spec.startup_form(1)))
+            .unwrap();
+        assert!(result.vb6_code.contains("' Startup form: Form2\n"));
+        assert!(result.project_file.contains("Startup=\"Form2\"\n"));
+    }
+
+    #[test]
+    fn test_decompile_dir_recursive() {
+        let dir = std::env::temp_dir().join(format!("vbdc_batch_{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        fs::write(dir.join("app.exe"), build_vb_exe(&spec)).unwrap();
+        fs::write(dir.join("nested").join("lib.DLL"), build_vb_exe(&spec)).unwrap();
+        fs::write(dir.join("nested").join("broken.ocx"), b"MZ").unwrap();
+        fs::write(dir.join("readme.txt"), b"not a binary").unwrap();
+
+        let results = Decompiler::new().decompile_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        let results = results.unwrap();
+
+        let summary: Vec<(PathBuf, bool)> = results
+            .iter()
+            .map(|(path, result)| (path.clone(), result.is_ok()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (PathBuf::from("app.exe"), true),
+                (Path::new("nested").join("broken.ocx"), false),
+                (Path::new("nested").join("lib.DLL"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_call_resolves_to_runtime_name() {
+        // LitStr "Hi"; ImpAdCallHresult #1; ExitProc
+        let pcode = vec![0x1B, b'H', b'i', 0x00, 0x09, 0x01, 0x00, 0x14];
+        let build = |iat_va: u32| {
+            let spec = ProjectSpec::new("Project1").object(
+                ObjectSpec::new("Module1", 0x01)
+                    .constant(0)
+                    .constant(iat_va)
+                    .method("Main", pcode.clone()),
+            );
+            build_vb_exe(&spec)
+        };
+
+        // The layout doesn't depend on the constant, so a first build locates the IAT slot
+        let pe = PEFile::from_bytes(build(0)).unwrap();
+        let iat_rva = (0x1000..0x10000)
+            .find(|&rva| pe.import_at_iat_rva(rva).as_deref() == Some("rtcMsgBox"))
+            .unwrap();
+
+        let result = Decompiler::new().decompile_bytes(build(IMAGE_BASE + iat_rva));
+        let code = &result.unwrap().modules[0].methods[0].1;
+
+        assert!(code.contains("MsgBox \"Hi\""), "{}", code);
+        assert!(!code.contains("func_1"));
+    }
+
+    #[test]
+    fn test_progress_callback_fires_per_method() {
+        use std::sync::Mutex;
+
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Helper", vec![0x14]))
+            .object(ObjectSpec::new("Module3", 0x01).method("Other", vec![0x14]));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut decompiler = Decompiler::new();
+        let sink = Arc::clone(&events);
+        decompiler.set_progress_callback(Box::new(move |event| sink.lock().unwrap().push(event)));
+        let result = decompiler.decompile_bytes(build_vb_exe(&spec));
+        assert_eq!(result.unwrap().method_count, 3);
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            events[..2],
+            [ProgressEvent::ParsingPe, ProgressEvent::ParsingVb]
+        );
+        let mut started: Vec<&str> = events
+            .iter()
+            .filter_map(|event| match event {
+                ProgressEvent::MethodStarted { name } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        started.sort();
+        assert_eq!(started, ["Module1_Main", "Module2_Helper", "Module3_Other"]);
+        let mut done: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match event {
+                ProgressEvent::MethodDone { index, total: 3 } => Some(*index),
+                _ => None,
+            })
+            .collect();
+        done.sort();
+        assert_eq!(done, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_split_class_kept_whole() {
+        let class = module_with_methods(ModuleKind::Class, 5);
+
+        let files = class.split(2, None);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, "Module1.cls");
+        assert!(files[0].code.contains("Sub Sub1()") && files[0].code.contains("Sub Sub5()"));
+    }
+
     #[test]
     fn test_generate_simple_function() {
         let mut decompiler = Decompiler::new();
@@ -0,0 +1,293 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Comparison of two decompilation results
+//!
+//! Methods are aligned by object and method name, so two builds of the same
+//! project (patched versions, members of a malware family) can be compared
+//! method by method. Changed methods get a unified diff of their source.
+
+use crate::decompiler::DecompilationResult;
+use std::collections::HashMap;
+
+/// Lines of context around each change in a unified diff
+const CONTEXT_LINES: usize = 3;
+
+/// How a method differs between two results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MethodChange {
+    /// Only in the second result
+    Added,
+    /// Only in the first result
+    Removed,
+    /// In both, with different source
+    Changed,
+}
+
+/// A method that differs between two results
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct MethodDiff {
+    pub object: String,
+    pub method: String,
+    pub change: MethodChange,
+    /// Unified diff of the method's source; added and removed methods diff
+    /// against nothing
+    pub diff: String,
+}
+
+/// Method-level differences between two decompilation results
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ResultDiff {
+    /// Differing methods: those of the first result in order, then the
+    /// added ones
+    pub methods: Vec<MethodDiff>,
+    /// Number of methods identical in both
+    pub unchanged: usize,
+}
+
+impl ResultDiff {
+    /// Number of methods with the given change
+    pub fn count(&self, change: MethodChange) -> usize {
+        self.methods.iter().filter(|m| m.change == change).count()
+    }
+
+    /// Check if both results have the same methods with the same source
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+}
+
+/// Compare the per-method source of two decompilation results
+pub fn diff_results(old: &DecompilationResult, new: &DecompilationResult) -> ResultDiff {
+    let methods = |result: &DecompilationResult| -> Vec<(String, String, String)> {
+        result
+            .modules
+            .iter()
+            .flat_map(|module| {
+                module
+                    .methods
+                    .iter()
+                    .map(|(name, code)| (module.name.clone(), name.clone(), code.clone()))
+            })
+            .collect()
+    };
+    let old_methods = methods(old);
+    let new_methods = methods(new);
+    let new_index: HashMap<(&str, &str), &str> = new_methods
+        .iter()
+        .map(|(object, method, code)| ((object.as_str(), method.as_str()), code.as_str()))
+        .collect();
+
+    let mut result = ResultDiff::default();
+    // Methods are named bare (`Form_Load`), so labels carry the object
+    let label = |object: &str, method: &str| format!("{}.{}", object, method);
+    for (object, method, code) in &old_methods {
+        let name = label(object, method);
+        let (change, diff) = match new_index.get(&(object.as_str(), method.as_str())) {
+            Some(new_code) if new_code == code => {
+                result.unchanged += 1;
+                continue;
+            }
+            Some(new_code) => (
+                MethodChange::Changed,
+                unified_diff(code, new_code, &name, &name),
+            ),
+            None => (
+                MethodChange::Removed,
+                unified_diff(code, "", &name, "/dev/null"),
+            ),
+        };
+        result.methods.push(MethodDiff {
+            object: object.clone(),
+            method: method.clone(),
+            change,
+            diff,
+        });
+    }
+
+    for (object, method, code) in &new_methods {
+        let in_old = old_methods
+            .iter()
+            .any(|(o, m, _)| o == object && m == method);
+        if !in_old {
+            result.methods.push(MethodDiff {
+                object: object.clone(),
+                method: method.clone(),
+                change: MethodChange::Added,
+                diff: unified_diff("", code, "/dev/null", &label(object, method)),
+            });
+        }
+    }
+
+    result
+}
+
+/// Line edit in a diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// Shortest line edit script from `old` to `new`, via a longest common
+/// subsequence table
+fn edit_script<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Edit, &'a str)> {
+    let (n, m) = (old.len(), new.len());
+
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            edits.push((Edit::Keep, old[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push((Edit::Delete, old[i]));
+            i += 1;
+        } else {
+            edits.push((Edit::Insert, new[j]));
+            j += 1;
+        }
+    }
+    edits
+}
+
+/// Unified diff of two texts, or an empty string if they are equal
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = edit_script(&old_lines, &new_lines);
+
+    // Group changes closer than twice the context into one hunk
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in edits.iter().enumerate().filter(|(_, e)| e.0 != Edit::Keep) {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + 1 + CONTEXT_LINES).min(edits.len());
+        match hunks.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    // Lines of each side consumed before each edit
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for (edit, _) in &edits {
+        positions.push((old_pos, new_pos));
+        match edit {
+            Edit::Keep => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Edit::Delete => old_pos += 1,
+            Edit::Insert => new_pos += 1,
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (start, end) in hunks {
+        let hunk = &edits[start..end];
+        let old_count = hunk.iter().filter(|e| e.0 != Edit::Insert).count();
+        let new_count = hunk.iter().filter(|e| e.0 != Edit::Delete).count();
+        // An empty range is numbered by the line before it
+        let (old_start, new_start) = positions[start];
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + usize::from(old_count > 0),
+            old_count,
+            new_start + usize::from(new_count > 0),
+            new_count
+        ));
+        for (edit, line) in hunk {
+            let prefix = match edit {
+                Edit::Keep => ' ',
+                Edit::Delete => '-',
+                Edit::Insert => '+',
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{build_vb_exe, ObjectSpec, ProjectSpec};
+    use crate::Decompiler;
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\n";
+        assert_eq!(
+            unified_diff(old, new, "old", "new"),
+            "--- old\n+++ new\n@@ -1,7 +1,7 @@\n a\n b\n c\n-d\n+D\n e\n f\n g\n"
+        );
+        assert_eq!(unified_diff(old, old, "old", "new"), "");
+        assert_eq!(
+            unified_diff("", "x\n", "/dev/null", "new"),
+            "--- /dev/null\n+++ new\n@@ -0,0 +1,1 @@\n+x\n"
+        );
+    }
+
+    #[test]
+    fn test_diff_results() {
+        // LitI2 <n>; FStI2 [bp-8]; ExitProc
+        let store = |value: u8| vec![0x5E, value, 0x6D, 0xF8, 0x14];
+        let decompile = |value: u8, extra: bool| {
+            let mut spec = ProjectSpec::new("Project1")
+                .object(ObjectSpec::new("Module1", 0x01).method("Same", store(7)))
+                .object(ObjectSpec::new("Module2", 0x01).method("Patched", store(value)));
+            if extra {
+                spec = spec.object(ObjectSpec::new("Module3", 0x01).method("Added", store(3)));
+            }
+            Decompiler::new()
+                .decompile_bytes(build_vb_exe(&spec))
+                .unwrap()
+        };
+        let old = decompile(1, false);
+
+        assert!(diff_results(&old, &decompile(1, false)).is_empty());
+
+        let diff = diff_results(&old, &decompile(2, true));
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.count(MethodChange::Changed), 1);
+        assert_eq!(diff.count(MethodChange::Added), 1);
+        assert_eq!(diff.count(MethodChange::Removed), 0);
+
+        let changed = &diff.methods[0];
+        assert_eq!(
+            (changed.object.as_str(), changed.method.as_str()),
+            ("Module2", "Patched")
+        );
+        assert_eq!(changed.change, MethodChange::Changed);
+        assert!(changed
+            .diff
+            .starts_with("--- Module2.Patched\n+++ Module2.Patched\n@@ "));
+        assert!(changed
+            .diff
+            .contains("\n-    local1 = 1\n+    local1 = 2\n"));
+        assert_eq!(diff.methods[1].method, "Added");
+    }
+}
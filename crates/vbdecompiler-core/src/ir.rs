@@ -10,6 +10,7 @@
 //! - Statements (assignments, calls, control flow)
 //! - Basic blocks and functions
 
+use std::collections::HashMap;
 use std::fmt;
 
 /// VB Type Kind - Represents Visual Basic data types
@@ -137,14 +138,10 @@ impl Type {
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.kind {
-            TypeKind::Array => {
-                write!(
-                    f,
-                    "{}({})",
-                    self.element_type.as_ref().unwrap(),
-                    self.array_dimensions
-                )
-            }
+            TypeKind::Array => match &self.element_type {
+                Some(element_type) => write!(f, "{}()", element_type),
+                None => write!(f, "{}", self.kind),
+            },
             TypeKind::UserDefined => write!(f, "{}", self.type_name.as_ref().unwrap()),
             _ => write!(f, "{}", self.kind),
         }
@@ -197,8 +194,52 @@ pub enum ExpressionKind {
 pub enum ConstantValue {
     Integer(i64),
     Float(f64),
+    /// Currency, scaled by 10000 as VB stores it
+    Currency(i64),
+    /// Date, as an OLE Automation date (days since 12/30/1899)
+    Date(f64),
     String(String),
     Boolean(bool),
+    Nothing, // Null object reference
+    Null,    // Variant containing no valid data
+    Empty,   // Uninitialized Variant
+}
+
+/// Format an OLE Automation date the way VB writes date literals
+///
+/// The integer part counts days from 12/30/1899 and the fractional part is
+/// the time of day, also for dates before the epoch. Midnight is omitted,
+/// as is the date of time-only values.
+fn format_ole_date(value: f64) -> String {
+    let days = value.trunc() as i64;
+    let seconds = ((value.fract().abs() * 86_400.0).round() as i64).min(86_399);
+
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days - 25_569 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let date = format!("{}/{}/{}", month, day, year);
+
+    let (hour, minute, second) = (seconds / 3_600, seconds / 60 % 60, seconds % 60);
+    let time = format!(
+        "{}:{:02}:{:02} {}",
+        (hour + 11) % 12 + 1,
+        minute,
+        second,
+        if hour < 12 { "AM" } else { "PM" }
+    );
+
+    match (days, seconds) {
+        (_, 0) => date,
+        (0, _) => time,
+        _ => format!("{} {}", date, time),
+    }
 }
 
 impl fmt::Display for ConstantValue {
@@ -206,8 +247,19 @@ impl fmt::Display for ConstantValue {
         match self {
             Self::Integer(v) => write!(f, "{}", v),
             Self::Float(v) => write!(f, "{}", v),
+            Self::Currency(v) => write!(
+                f,
+                "{}{}.{:04}@",
+                if *v < 0 { "-" } else { "" },
+                v.unsigned_abs() / 10_000,
+                v.unsigned_abs() % 10_000
+            ),
+            Self::Date(v) => write!(f, "#{}#", format_ole_date(*v)),
             Self::String(s) => write!(f, "\"{}\"", s),
             Self::Boolean(b) => write!(f, "{}", if *b { "True" } else { "False" }),
+            Self::Nothing => write!(f, "Nothing"),
+            Self::Null => write!(f, "Null"),
+            Self::Empty => write!(f, "Empty"),
         }
     }
 }
@@ -220,10 +272,40 @@ pub struct Variable {
     pub var_type: TypeKind,
 }
 
+/// First variable id reserved for VB's built-in objects
+///
+/// Frame slots are named by 16-bit offsets, which as ids fall below this or
+/// from `0xFFFF_8000` up, so a built-in object never shares a slot's id.
+const BUILTIN_OBJECT_IDS: u32 = 0x8000_0000;
+
+/// Built-in objects with reserved ids, in id order
+const BUILTIN_OBJECTS: &[&str] = &[
+    "Me",
+    "Debug",
+    "Err",
+    "App",
+    "Screen",
+    "Printer",
+    "Clipboard",
+];
+
 impl Variable {
     pub fn new(id: u32, name: String, var_type: TypeKind) -> Self {
         Self { id, name, var_type }
     }
+
+    /// Reference to a built-in object (`Me`, `Debug`) under its reserved id
+    pub fn builtin_object(name: &str) -> Self {
+        let index = BUILTIN_OBJECTS
+            .iter()
+            .position(|&builtin| builtin == name)
+            .unwrap_or(BUILTIN_OBJECTS.len());
+        Self::new(
+            BUILTIN_OBJECT_IDS + index as u32,
+            name.to_string(),
+            TypeKind::Object,
+        )
+    }
 }
 
 impl fmt::Display for Variable {
@@ -255,6 +337,11 @@ pub enum ExpressionData {
         function: String,
         arguments: Vec<Expression>,
     },
+    /// Call of a method reached through an object (`obj.Method(args)`)
+    MethodCall {
+        target: Box<Expression>,
+        arguments: Vec<Expression>,
+    },
     MemberAccess {
         object: Box<Expression>,
         member: String,
@@ -348,6 +435,187 @@ impl Expression {
         }
     }
 
+    /// Create a call of a method through an object; `target` is the member
+    /// access naming the method
+    pub fn method_call(target: Expression, arguments: Vec<Expression>, return_type: Type) -> Self {
+        Self {
+            kind: ExpressionKind::Call,
+            expr_type: return_type,
+            data: ExpressionData::MethodCall {
+                target: Box::new(target),
+                arguments,
+            },
+        }
+    }
+
+    /// Create a member access expression (`object.member`)
+    pub fn member_access(object: Expression, member: String, member_type: Type) -> Self {
+        Self {
+            kind: ExpressionKind::MemberAccess,
+            expr_type: member_type,
+            data: ExpressionData::MemberAccess {
+                object: Box::new(object),
+                member,
+            },
+        }
+    }
+
+    /// Create an array element access (`array(i, j)`)
+    pub fn array_index(array: Expression, indices: Vec<Expression>, element_type: Type) -> Self {
+        Self {
+            kind: ExpressionKind::ArrayIndex,
+            expr_type: element_type,
+            data: ExpressionData::ArrayIndex {
+                array: Box::new(array),
+                indices,
+            },
+        }
+    }
+
+    /// Create a reference to the current object (`Me`)
+    pub fn me() -> Self {
+        Self::variable(Variable::builtin_object("Me"))
+    }
+
+    /// Create a placeholder for an omitted optional argument
+    pub fn missing() -> Self {
+        Self {
+            kind: ExpressionKind::Constant,
+            expr_type: Type::new(TypeKind::Variant),
+            data: ExpressionData::None,
+        }
+    }
+
+    /// Make a numeric value usable as a condition by comparing it with zero
+    ///
+    /// VB treats any nonzero number as True; spelling out `x <> 0` keeps the
+    /// generated `If` from depending on that. Booleans are left alone, and
+    /// so are Variants, which may well hold one.
+    pub fn into_condition(self) -> Self {
+        if !self.expr_type.kind.is_numeric() {
+            return self;
+        }
+        Self::binary(
+            ExpressionKind::NotEqual,
+            self,
+            Self::int_const(0),
+            Type::new(TypeKind::Boolean),
+        )
+    }
+
+    /// Create the logical negation of a condition
+    ///
+    /// Comparisons are inverted (`a < b` becomes `a >= b`) and double
+    /// negations are removed, so the result reads naturally.
+    pub fn logical_not(self) -> Self {
+        let inverted = match self.kind {
+            ExpressionKind::Equal => Some(ExpressionKind::NotEqual),
+            ExpressionKind::NotEqual => Some(ExpressionKind::Equal),
+            ExpressionKind::LessThan => Some(ExpressionKind::GreaterEqual),
+            ExpressionKind::LessEqual => Some(ExpressionKind::GreaterThan),
+            ExpressionKind::GreaterThan => Some(ExpressionKind::LessEqual),
+            ExpressionKind::GreaterEqual => Some(ExpressionKind::LessThan),
+            _ => None,
+        };
+
+        match (inverted, self.data) {
+            (Some(kind), data @ ExpressionData::Binary { .. }) => Self {
+                kind,
+                expr_type: self.expr_type,
+                data,
+            },
+            (_, ExpressionData::Unary(operand)) if self.kind == ExpressionKind::Not => *operand,
+            (_, data) => Self {
+                kind: ExpressionKind::Not,
+                expr_type: Type::new(TypeKind::Boolean),
+                data: ExpressionData::Unary(Box::new(Self {
+                    kind: self.kind,
+                    expr_type: self.expr_type,
+                    data,
+                })),
+            },
+        }
+    }
+
+    /// Check if this is an omitted optional argument
+    pub fn is_missing(&self) -> bool {
+        matches!(self.data, ExpressionData::None)
+    }
+
+    /// Check if this expression reads the variable with the given ID
+    pub fn references_variable(&self, id: u32) -> bool {
+        match &self.data {
+            ExpressionData::None | ExpressionData::Constant(_) => false,
+            ExpressionData::Variable(var) => var.id == id,
+            ExpressionData::Unary(operand) => operand.references_variable(id),
+            ExpressionData::Binary { left, right } => {
+                left.references_variable(id) || right.references_variable(id)
+            }
+            ExpressionData::Call { arguments, .. } => {
+                arguments.iter().any(|a| a.references_variable(id))
+            }
+            ExpressionData::MethodCall { target, arguments } => {
+                target.references_variable(id)
+                    || arguments.iter().any(|a| a.references_variable(id))
+            }
+            ExpressionData::MemberAccess { object, .. } => object.references_variable(id),
+            ExpressionData::ArrayIndex { array, indices } => {
+                array.references_variable(id) || indices.iter().any(|i| i.references_variable(id))
+            }
+            ExpressionData::Cast { expr, .. } => expr.references_variable(id),
+        }
+    }
+
+    /// Append every variable this expression reads to `vars`
+    pub fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a Variable>) {
+        match &self.data {
+            ExpressionData::None | ExpressionData::Constant(_) => {}
+            ExpressionData::Variable(var) => vars.push(var),
+            ExpressionData::Unary(operand) => operand.collect_variables(vars),
+            ExpressionData::Binary { left, right } => {
+                left.collect_variables(vars);
+                right.collect_variables(vars);
+            }
+            ExpressionData::Call { arguments, .. } => {
+                arguments.iter().for_each(|a| a.collect_variables(vars))
+            }
+            ExpressionData::MethodCall { target, arguments } => {
+                target.collect_variables(vars);
+                arguments.iter().for_each(|a| a.collect_variables(vars));
+            }
+            ExpressionData::MemberAccess { object, .. } => object.collect_variables(vars),
+            ExpressionData::ArrayIndex { array, indices } => {
+                array.collect_variables(vars);
+                indices.iter().for_each(|i| i.collect_variables(vars));
+            }
+            ExpressionData::Cast { expr, .. } => expr.collect_variables(vars),
+        }
+    }
+
+    /// Call `f` on this expression and every subexpression, outermost first
+    pub fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Expression)) {
+        f(self);
+        match &self.data {
+            ExpressionData::None | ExpressionData::Constant(_) | ExpressionData::Variable(_) => {}
+            ExpressionData::Unary(operand) => operand.visit(f),
+            ExpressionData::Binary { left, right } => {
+                left.visit(f);
+                right.visit(f);
+            }
+            ExpressionData::Call { arguments, .. } => arguments.iter().for_each(|a| a.visit(f)),
+            ExpressionData::MethodCall { target, arguments } => {
+                target.visit(f);
+                arguments.iter().for_each(|a| a.visit(f));
+            }
+            ExpressionData::MemberAccess { object, .. } => object.visit(f),
+            ExpressionData::ArrayIndex { array, indices } => {
+                array.visit(f);
+                indices.iter().for_each(|i| i.visit(f));
+            }
+            ExpressionData::Cast { expr, .. } => expr.visit(f),
+        }
+    }
+
     /// Convert expression to VB6 source code string (simplified)
     pub fn to_vb_string(&self) -> String {
         match &self.data {
@@ -395,6 +663,14 @@ impl Expression {
                     .join(", ");
                 format!("{}({})", function, args)
             }
+            ExpressionData::MethodCall { target, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(|a| a.to_vb_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({})", target.to_vb_string(), args)
+            }
             ExpressionData::MemberAccess { object, member } => {
                 format!("{}.{}", object.to_vb_string(), member)
             }
@@ -416,14 +692,58 @@ impl Expression {
 /// Statement Kind - Types of IR statements
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum StatementKind {
-    Assign, // variable = expression
-    Store,  // [address] = expression
-    Call,   // Call subroutine (no return value)
-    Return, // Return [expression]
-    Branch, // Conditional branch
-    Goto,   // Unconditional jump
-    Label,  // Label marker
-    Nop,    // No operation
+    Assign,     // variable = expression
+    Store,      // [address] = expression
+    Call,       // Call subroutine (no return value)
+    Return,     // Return [expression]
+    Branch,     // Conditional branch
+    Goto,       // Unconditional jump
+    Label,      // Label marker
+    Nop,        // No operation
+    ForLoop,    // For counter = start To end [Step step] ... Next
+    WhileLoop,  // Do [While condition] ... Loop [While/Until condition]
+    If,         // If condition Then ... [Else ...] End If
+    ReDim,      // ReDim [Preserve] array(lb To ub, ...)
+    OnError,    // On Error GoTo label / On Error Resume Next
+    Resume,     // Resume / Resume Next / Resume label
+    SelectCase, // Select Case subject ... Case value ... [Case Else ...] End Select
+    With,       // With object ... End With
+}
+
+/// Where `Resume` continues once an error handler is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeTarget {
+    /// `Resume`: retry the statement that raised the error
+    Retry,
+    /// `Resume Next`: continue after the statement that raised the error
+    Next,
+    /// `Resume label`: continue at a block
+    Block(u32),
+}
+
+/// Where a `Do` loop tests its condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopKind {
+    /// Pre-tested: `Do While condition ... Loop` (`While ... Wend`)
+    While,
+    /// Post-tested, repeating while the condition holds: `Do ... Loop While condition`
+    DoWhile,
+    /// Post-tested, repeating until the condition holds: `Do ... Loop Until condition`
+    DoUntil,
+    /// No condition: `Do ... Loop`, left through `Exit Do` or a return
+    Infinite,
+}
+
+impl LoopKind {
+    /// Opening and closing lines of a loop testing `condition`
+    pub fn clauses(self, condition: &str) -> (String, String) {
+        match self {
+            Self::While => (format!("Do While {}", condition), "Loop".to_string()),
+            Self::DoWhile => ("Do".to_string(), format!("Loop While {}", condition)),
+            Self::DoUntil => ("Do".to_string(), format!("Loop Until {}", condition)),
+            Self::Infinite => ("Do".to_string(), "Loop".to_string()),
+        }
+    }
 }
 
 /// IR Statement
@@ -431,6 +751,8 @@ pub enum StatementKind {
 pub struct Statement {
     pub kind: StatementKind,
     pub data: StatementData,
+    /// Source line number, when the binary retains line information
+    pub source_line: Option<u32>,
 }
 
 /// Statement data payload
@@ -449,6 +771,10 @@ pub enum StatementData {
         function: String,
         arguments: Vec<Expression>,
     },
+    MethodCall {
+        target: Expression,
+        arguments: Vec<Expression>,
+    },
     Return {
         value: Option<Expression>,
     },
@@ -462,14 +788,67 @@ pub enum StatementData {
     Label {
         label_id: u32,
     },
+    ForLoop {
+        counter: Variable,
+        start: Expression,
+        end: Expression,
+        step: Option<Expression>, // None = Step 1
+        body: Vec<Statement>,
+    },
+    WhileLoop {
+        loop_kind: LoopKind,
+        condition: Expression, // Ignored for LoopKind::Infinite
+        body: Vec<Statement>,
+    },
+    If {
+        condition: Expression,
+        then_body: Vec<Statement>,
+        else_body: Vec<Statement>, // Empty = no Else
+    },
+    ReDim {
+        array: Variable,
+        bounds: Vec<(Expression, Expression)>, // (lower, upper) per dimension
+        preserve: bool,
+    },
+    OnError {
+        handler_block: Option<u32>, // None = Resume Next
+    },
+    Resume {
+        target: ResumeTarget,
+    },
+    SelectCase {
+        subject: Expression,
+        cases: Vec<(Expression, Vec<Statement>)>, // (value, body) per Case arm
+        else_body: Vec<Statement>,
+    },
+    With {
+        object: Variable,
+        body: Vec<Statement>, // Member accesses on `object` render as `.member`
+    },
 }
 
 impl Statement {
+    /// Attach the source line number this statement was compiled from
+    pub fn with_source_line(mut self, line: u32) -> Self {
+        self.source_line = Some(line);
+        self
+    }
+
     /// Create an assignment statement
     pub fn assign(target: Variable, value: Expression) -> Self {
         Self {
             kind: StatementKind::Assign,
             data: StatementData::Assign { target, value },
+            source_line: None,
+        }
+    }
+
+    /// Create a store through an address (`array(i) = value`)
+    pub fn store(address: Expression, value: Expression) -> Self {
+        Self {
+            kind: StatementKind::Store,
+            data: StatementData::Store { address, value },
+            source_line: None,
         }
     }
 
@@ -481,6 +860,16 @@ impl Statement {
                 function,
                 arguments,
             },
+            source_line: None,
+        }
+    }
+
+    /// Create a method call statement (`target` is usually a member access)
+    pub fn method_call(target: Expression, arguments: Vec<Expression>) -> Self {
+        Self {
+            kind: StatementKind::Call,
+            data: StatementData::MethodCall { target, arguments },
+            source_line: None,
         }
     }
 
@@ -489,6 +878,7 @@ impl Statement {
         Self {
             kind: StatementKind::Return,
             data: StatementData::Return { value },
+            source_line: None,
         }
     }
 
@@ -500,6 +890,7 @@ impl Statement {
                 condition,
                 target_block,
             },
+            source_line: None,
         }
     }
 
@@ -508,6 +899,7 @@ impl Statement {
         Self {
             kind: StatementKind::Goto,
             data: StatementData::Goto { target_block },
+            source_line: None,
         }
     }
 
@@ -516,6 +908,266 @@ impl Statement {
         Self {
             kind: StatementKind::Label,
             data: StatementData::Label { label_id },
+            source_line: None,
+        }
+    }
+
+    /// Create a counted loop (`For counter = start To end [Step step]`)
+    pub fn for_loop(
+        counter: Variable,
+        start: Expression,
+        end: Expression,
+        step: Option<Expression>,
+        body: Vec<Statement>,
+    ) -> Self {
+        Self {
+            kind: StatementKind::ForLoop,
+            data: StatementData::ForLoop {
+                counter,
+                start,
+                end,
+                step,
+                body,
+            },
+            source_line: None,
+        }
+    }
+
+    /// Create a pre-tested loop (`Do While condition`)
+    pub fn while_loop(condition: Expression, body: Vec<Statement>) -> Self {
+        Self::do_loop(LoopKind::While, condition, body)
+    }
+
+    /// Create a `Do` loop testing `condition` as `loop_kind` says
+    pub fn do_loop(loop_kind: LoopKind, condition: Expression, body: Vec<Statement>) -> Self {
+        Self {
+            kind: StatementKind::WhileLoop,
+            data: StatementData::WhileLoop {
+                loop_kind,
+                condition,
+                body,
+            },
+            source_line: None,
+        }
+    }
+
+    /// Create a two-way conditional (`If condition Then ... Else ... End If`)
+    pub fn if_then_else(
+        condition: Expression,
+        then_body: Vec<Statement>,
+        else_body: Vec<Statement>,
+    ) -> Self {
+        Self {
+            kind: StatementKind::If,
+            data: StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            },
+            source_line: None,
+        }
+    }
+
+    /// Create an array redimension (`ReDim [Preserve] array(lb To ub, ...)`)
+    pub fn redim(array: Variable, bounds: Vec<(Expression, Expression)>, preserve: bool) -> Self {
+        Self {
+            kind: StatementKind::ReDim,
+            data: StatementData::ReDim {
+                array,
+                bounds,
+                preserve,
+            },
+            source_line: None,
+        }
+    }
+
+    /// Create a multi-way branch on one subject (`Select Case ... End Select`)
+    pub fn select_case(
+        subject: Expression,
+        cases: Vec<(Expression, Vec<Statement>)>,
+        else_body: Vec<Statement>,
+    ) -> Self {
+        Self {
+            kind: StatementKind::SelectCase,
+            data: StatementData::SelectCase {
+                subject,
+                cases,
+                else_body,
+            },
+            source_line: None,
+        }
+    }
+
+    /// Create an error handler installation (`None` = `On Error Resume Next`)
+    pub fn on_error(handler_block: Option<u32>) -> Self {
+        Self {
+            kind: StatementKind::OnError,
+            data: StatementData::OnError { handler_block },
+            source_line: None,
+        }
+    }
+
+    /// Create a `Resume` statement ending an error handler
+    pub fn resume(target: ResumeTarget) -> Self {
+        Self {
+            kind: StatementKind::Resume,
+            data: StatementData::Resume { target },
+            source_line: None,
+        }
+    }
+
+    /// Create a `With` block over `object`
+    pub fn with_block(object: Variable, body: Vec<Statement>) -> Self {
+        Self {
+            kind: StatementKind::With,
+            data: StatementData::With { object, body },
+            source_line: None,
+        }
+    }
+
+    /// Block this statement refers to by label rather than by a control-flow
+    /// edge (`On Error GoTo` handlers, `Resume` labels)
+    pub fn label_target(&self) -> Option<u32> {
+        match self.data {
+            StatementData::OnError { handler_block } => handler_block,
+            StatementData::Resume {
+                target: ResumeTarget::Block(id),
+            } => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Statements directly nested in this one (loop, `If`, `Select Case`
+    /// and `With` bodies)
+    pub fn nested(&self) -> Vec<&Statement> {
+        match &self.data {
+            StatementData::ForLoop { body, .. }
+            | StatementData::WhileLoop { body, .. }
+            | StatementData::With { body, .. } => body.iter().collect(),
+            StatementData::If {
+                then_body,
+                else_body,
+                ..
+            } => then_body.iter().chain(else_body).collect(),
+            StatementData::SelectCase {
+                cases, else_body, ..
+            } => cases
+                .iter()
+                .flat_map(|(_, body)| body)
+                .chain(else_body)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Append every variable this statement and its nested bodies read or
+    /// write to `vars`
+    pub fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a Variable>) {
+        match &self.data {
+            StatementData::Assign { target, value } => {
+                vars.push(target);
+                value.collect_variables(vars);
+            }
+            StatementData::Store { address, value } => {
+                address.collect_variables(vars);
+                value.collect_variables(vars);
+            }
+            StatementData::Call { arguments, .. } => {
+                arguments.iter().for_each(|a| a.collect_variables(vars))
+            }
+            StatementData::MethodCall { target, arguments } => {
+                target.collect_variables(vars);
+                arguments.iter().for_each(|a| a.collect_variables(vars));
+            }
+            StatementData::Return { value: Some(value) } => value.collect_variables(vars),
+            StatementData::Branch { condition, .. }
+            | StatementData::WhileLoop { condition, .. }
+            | StatementData::If { condition, .. } => condition.collect_variables(vars),
+            StatementData::ForLoop {
+                counter,
+                start,
+                end,
+                step,
+                ..
+            } => {
+                vars.push(counter);
+                start.collect_variables(vars);
+                end.collect_variables(vars);
+                if let Some(step) = step {
+                    step.collect_variables(vars);
+                }
+            }
+            StatementData::ReDim { array, bounds, .. } => {
+                vars.push(array);
+                for (lower, upper) in bounds {
+                    lower.collect_variables(vars);
+                    upper.collect_variables(vars);
+                }
+            }
+            StatementData::SelectCase { subject, cases, .. } => {
+                subject.collect_variables(vars);
+                cases.iter().for_each(|(v, _)| v.collect_variables(vars));
+            }
+            StatementData::With { object, .. } => vars.push(object),
+            StatementData::None
+            | StatementData::Return { value: None }
+            | StatementData::Goto { .. }
+            | StatementData::Label { .. }
+            | StatementData::OnError { .. }
+            | StatementData::Resume { .. } => {}
+        }
+        for stmt in self.nested() {
+            stmt.collect_variables(vars);
+        }
+    }
+
+    /// Call `f` on every expression in this statement and its nested
+    /// bodies, including subexpressions
+    pub fn visit_expressions<'a>(&'a self, f: &mut impl FnMut(&'a Expression)) {
+        match &self.data {
+            StatementData::Assign { value, .. } => value.visit(f),
+            StatementData::Store { address, value } => {
+                address.visit(f);
+                value.visit(f);
+            }
+            StatementData::Call { arguments, .. } => arguments.iter().for_each(|a| a.visit(f)),
+            StatementData::MethodCall { target, arguments } => {
+                target.visit(f);
+                arguments.iter().for_each(|a| a.visit(f));
+            }
+            StatementData::Return { value: Some(value) } => value.visit(f),
+            StatementData::Branch { condition, .. }
+            | StatementData::WhileLoop { condition, .. }
+            | StatementData::If { condition, .. } => condition.visit(f),
+            StatementData::ForLoop {
+                start, end, step, ..
+            } => {
+                start.visit(f);
+                end.visit(f);
+                if let Some(step) = step {
+                    step.visit(f);
+                }
+            }
+            StatementData::ReDim { bounds, .. } => {
+                for (lower, upper) in bounds {
+                    lower.visit(f);
+                    upper.visit(f);
+                }
+            }
+            StatementData::SelectCase { subject, cases, .. } => {
+                subject.visit(f);
+                cases.iter().for_each(|(v, _)| v.visit(f));
+            }
+            StatementData::None
+            | StatementData::Return { value: None }
+            | StatementData::Goto { .. }
+            | StatementData::Label { .. }
+            | StatementData::OnError { .. }
+            | StatementData::Resume { .. }
+            | StatementData::With { .. } => {}
+        }
+        for stmt in self.nested() {
+            stmt.visit_expressions(f);
         }
     }
 
@@ -524,6 +1176,7 @@ impl Statement {
         Self {
             kind: StatementKind::Nop,
             data: StatementData::None,
+            source_line: None,
         }
     }
 
@@ -534,9 +1187,12 @@ impl Statement {
             StatementData::Assign { target, value } => {
                 format!("{} = {}", target, value.to_vb_string())
             }
-            StatementData::Store { address, value } => {
-                format!("[{}] = {}", address.to_vb_string(), value.to_vb_string())
-            }
+            StatementData::Store { address, value } => match address.kind {
+                ExpressionKind::ArrayIndex | ExpressionKind::MemberAccess => {
+                    format!("{} = {}", address.to_vb_string(), value.to_vb_string())
+                }
+                _ => format!("[{}] = {}", address.to_vb_string(), value.to_vb_string()),
+            },
             StatementData::Call {
                 function,
                 arguments,
@@ -552,6 +1208,18 @@ impl Statement {
                     format!("{} {}", function, args)
                 }
             }
+            StatementData::MethodCall { target, arguments } => {
+                let args = arguments
+                    .iter()
+                    .map(|a| a.to_vb_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if args.is_empty() {
+                    target.to_vb_string()
+                } else {
+                    format!("{} {}", target.to_vb_string(), args)
+                }
+            }
             StatementData::Return { value } => {
                 if let Some(v) = value {
                     format!("Return {}", v.to_vb_string())
@@ -575,10 +1243,117 @@ impl Statement {
             StatementData::Label { label_id } => {
                 format!("Label{}:", label_id)
             }
+            StatementData::ForLoop {
+                counter,
+                start,
+                end,
+                step,
+                body,
+            } => {
+                let step = step
+                    .as_ref()
+                    .map(|s| format!(" Step {}", s.to_vb_string()))
+                    .unwrap_or_default();
+                format!(
+                    "For {} = {} To {}{}\n{}Next {}",
+                    counter,
+                    start.to_vb_string(),
+                    end.to_vb_string(),
+                    step,
+                    body_to_vb_string(body),
+                    counter
+                )
+            }
+            StatementData::WhileLoop {
+                loop_kind,
+                condition,
+                body,
+            } => {
+                let (head, tail) = loop_kind.clauses(&condition.to_vb_string());
+                format!("{}\n{}{}", head, body_to_vb_string(body), tail)
+            }
+            StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            } => {
+                let mut s = format!(
+                    "If {} Then\n{}",
+                    condition.to_vb_string(),
+                    body_to_vb_string(then_body)
+                );
+                if !else_body.is_empty() {
+                    s.push_str(&format!("Else\n{}", body_to_vb_string(else_body)));
+                }
+                s.push_str("End If");
+                s
+            }
+            StatementData::ReDim {
+                array,
+                bounds,
+                preserve,
+            } => {
+                let bounds = bounds
+                    .iter()
+                    .map(|(lower, upper)| {
+                        format!("{} To {}", lower.to_vb_string(), upper.to_vb_string())
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "ReDim {}{}({})",
+                    if *preserve { "Preserve " } else { "" },
+                    array,
+                    bounds
+                )
+            }
+            StatementData::OnError { handler_block } => match handler_block {
+                Some(block) => format!("On Error Goto Block{}", block),
+                None => "On Error Resume Next".to_string(),
+            },
+            StatementData::Resume { target } => match target {
+                ResumeTarget::Retry => "Resume".to_string(),
+                ResumeTarget::Next => "Resume Next".to_string(),
+                ResumeTarget::Block(block) => format!("Resume Block{}", block),
+            },
+            StatementData::SelectCase {
+                subject,
+                cases,
+                else_body,
+            } => {
+                let mut s = format!("Select Case {}\n", subject.to_vb_string());
+                for (value, body) in cases {
+                    s.push_str(&format!(
+                        "Case {}\n{}",
+                        value.to_vb_string(),
+                        body_to_vb_string(body)
+                    ));
+                }
+                if !else_body.is_empty() {
+                    s.push_str(&format!("Case Else\n{}", body_to_vb_string(else_body)));
+                }
+                s.push_str("End Select");
+                s
+            }
+            StatementData::With { object, body } => {
+                format!("With {}\n{}End With", object, body_to_vb_string(body))
+            }
         }
     }
 }
 
+/// Render nested statements one level deeper, one per line
+fn body_to_vb_string(body: &[Statement]) -> String {
+    body.iter()
+        .flat_map(|stmt| {
+            stmt.to_vb_string()
+                .lines()
+                .map(|line| format!("    {}\n", line))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
 /// Basic Block - A sequence of statements with single entry and exit
 #[derive(Debug, Clone)]
 pub struct BasicBlock {
@@ -586,6 +1361,8 @@ pub struct BasicBlock {
     pub statements: Vec<Statement>,
     pub successors: Vec<u32>,   // Block IDs of successor blocks
     pub predecessors: Vec<u32>, // Block IDs of predecessor blocks
+    /// Entered through `On Error GoTo` rather than normal control flow
+    pub is_error_handler: bool,
 }
 
 impl BasicBlock {
@@ -595,6 +1372,7 @@ impl BasicBlock {
             statements: Vec::new(),
             successors: Vec::new(),
             predecessors: Vec::new(),
+            is_error_handler: false,
         }
     }
 
@@ -615,12 +1393,33 @@ impl BasicBlock {
     }
 }
 
+/// How an argument is passed to a parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PassingMode {
+    /// A reference to the caller's variable (VB's default)
+    #[default]
+    ByRef,
+    /// A copy of the caller's value
+    ByVal,
+}
+
+impl fmt::Display for PassingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PassingMode::ByRef => write!(f, "ByRef"),
+            PassingMode::ByVal => write!(f, "ByVal"),
+        }
+    }
+}
+
 /// IR Function - Represents a complete function/subroutine
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
     pub return_type: Type,
     pub parameters: Vec<Variable>,
+    /// Passing mode of parameters by variable ID; absent ones are `ByRef`
+    pub parameter_modes: HashMap<u32, PassingMode>,
     pub local_variables: Vec<Variable>,
     pub basic_blocks: Vec<BasicBlock>,
     pub entry_block_id: u32,
@@ -632,12 +1431,21 @@ impl Function {
             name,
             return_type,
             parameters: Vec::new(),
+            parameter_modes: HashMap::new(),
             local_variables: Vec::new(),
             basic_blocks: Vec::new(),
             entry_block_id: 0,
         }
     }
 
+    /// How the parameter `param` is passed
+    pub fn parameter_mode(&self, param: &Variable) -> PassingMode {
+        self.parameter_modes
+            .get(&param.id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     pub fn add_parameter(&mut self, param: Variable) {
         self.parameters.push(param);
     }
@@ -671,6 +1479,29 @@ mod tests {
         assert!(int_type.kind.is_numeric());
     }
 
+    #[test]
+    fn test_currency_and_date_display() {
+        assert_eq!(ConstantValue::Currency(15_000).to_string(), "1.5000@");
+        assert_eq!(ConstantValue::Currency(-25).to_string(), "-0.0025@");
+        assert_eq!(ConstantValue::Date(36_526.0).to_string(), "#1/1/2000#");
+        assert_eq!(ConstantValue::Date(0.5).to_string(), "#12:00:00 PM#");
+        assert_eq!(
+            ConstantValue::Date(36_526.25).to_string(),
+            "#1/1/2000 6:00:00 AM#"
+        );
+        // Before the epoch the time of day still counts forward
+        assert_eq!(
+            ConstantValue::Date(-1.25).to_string(),
+            "#12/29/1899 6:00:00 AM#"
+        );
+    }
+
+    #[test]
+    fn test_array_type_display() {
+        let array = Type::array(Type::new(TypeKind::Integer), 2);
+        assert_eq!(array.to_string(), "Integer()");
+    }
+
     #[test]
     fn test_expression_creation() {
         let expr = Expression::int_const(42);
@@ -678,6 +1509,18 @@ mod tests {
         assert_eq!(expr.to_vb_string(), "42");
     }
 
+    #[test]
+    fn test_builtin_objects_have_own_ids() {
+        let me = Expression::me();
+        let debug = Variable::builtin_object("Debug");
+
+        // Slot 0 is a real frame variable; neither object may alias it
+        assert!(!me.references_variable(0));
+        assert_ne!(debug.id, 0);
+        assert!(!me.references_variable(debug.id));
+        assert_eq!(debug, Variable::builtin_object("Debug"));
+    }
+
     #[test]
     fn test_binary_expression() {
         let left = Expression::int_const(1);
@@ -686,6 +1529,35 @@ mod tests {
         assert_eq!(expr.to_vb_string(), "(1 + 2)");
     }
 
+    #[test]
+    fn test_logical_not() {
+        let x = Expression::variable(Variable::new(0, "x".to_string(), TypeKind::Integer));
+        let lt = Expression::binary(
+            ExpressionKind::LessThan,
+            x.clone(),
+            Expression::int_const(10),
+            Type::new(TypeKind::Boolean),
+        );
+        assert_eq!(lt.logical_not().to_vb_string(), "(x >= 10)");
+
+        let not_x = x.clone().logical_not();
+        assert_eq!(not_x.to_vb_string(), "Not x");
+        assert_eq!(not_x.logical_not().to_vb_string(), "x");
+    }
+
+    #[test]
+    fn test_for_loop_to_vb_string() {
+        let i = Variable::new(0, "i".to_string(), TypeKind::Integer);
+        let stmt = Statement::for_loop(
+            i.clone(),
+            Expression::int_const(1),
+            Expression::int_const(10),
+            None,
+            vec![Statement::call("Beep".to_string(), Vec::new())],
+        );
+        assert_eq!(stmt.to_vb_string(), "For i = 1 To 10\n    Beep\nNext i");
+    }
+
     #[test]
     fn test_statement_creation() {
         let var = Variable::new(0, "x".to_string(), TypeKind::Integer);
@@ -15,10 +15,16 @@
 //! # Architecture
 //!
 //! - **pe**: PE file parsing
+//! - **analysis**: Static facts about an executable without decompiling
 //! - **vb**: VB structure parsing and P-Code extraction
 //! - **pcode**: P-Code disassembler
 //! - **ir**: Intermediate representation
+//! - **optimize**: IR simplification passes (constant folding)
+//! - **runtime**: VB runtime helper table
+//! - **structure**: Control flow structuring (loops and If blocks)
+//! - **types**: Type recovery for expressions and locals
 //! - **decompiler**: Control flow structuring and code generation
+//! - **diff**: Method-level comparison of two decompilation results
 //!
 //! # Example
 //!
@@ -31,18 +37,34 @@
 //! # Ok::<(), Box<dyn std::error::Error>>(())
 //! ```
 
+pub mod analysis;
 pub mod codegen;
 pub mod decompiler;
+pub mod diff;
 pub mod error;
 pub mod ir;
 pub mod lifter;
+pub mod optimize;
 pub mod packer;
 pub mod pcode;
 pub mod pe;
+pub mod runtime;
+pub mod structure;
+pub mod types;
 pub mod vb;
 pub mod x86;
 
-pub use decompiler::{DecompilationResult, Decompiler};
+#[cfg(any(test, feature = "testutil"))]
+#[doc(hidden)]
+pub mod testutil;
+
+pub use analysis::{analyze, AnalysisReport};
+pub use codegen::{BannerConfig, Dialect, IndentStyle};
+pub use decompiler::{
+    DecompilationResult, Decompiler, DecompilerBuilder, DecompilerOptions, ProgressCallback,
+    ProgressEvent,
+};
 pub use error::{Error, Result};
 pub use packer::{detect_packer, PackerDetection, PackerType};
-pub use x86::{X86Disassembler, X86Instruction};
+pub use vb::{is_vb_executable, VBFile, VBVersion};
+pub use x86::{X86BasicBlock, X86Cfg, X86Disassembler, X86FlowControl, X86Instruction, X86Syntax};
@@ -17,16 +17,44 @@
 use crate::error::{Error, Result};
 use crate::ir::*;
 use crate::pcode::{Instruction, OpcodeCategory, OperandValue, PCodeType};
-use std::collections::HashMap;
+use crate::runtime::{self, IntrinsicForm, RuntimeHelper};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Frame offset of the first argument (past the saved frame pointer and
+/// return address); each argument takes one 4-byte slot
+const FIRST_ARG_OFFSET: i32 = 8;
 
 /// P-Code to IR Lifter
 pub struct PCodeLifter {
     last_error: Option<String>,
+    warnings: Vec<String>,
+    /// Stack frame size from the procedure descriptor, if known
+    frame_size: Option<u16>,
+    /// Render runtime helper calls as the VB6 code they implement
+    demangle_runtime: bool,
 }
 
 impl PCodeLifter {
     pub fn new() -> Self {
-        Self { last_error: None }
+        Self {
+            last_error: None,
+            warnings: Vec::new(),
+            frame_size: None,
+            demangle_runtime: true,
+        }
+    }
+
+    /// Check local variable offsets against the procedure's frame size
+    pub fn with_frame_size(mut self, frame_size: u16) -> Self {
+        self.frame_size = Some(frame_size);
+        self
+    }
+
+    /// Render runtime helper calls as intrinsics and operators (the
+    /// default), or keep the raw runtime symbols with all their arguments
+    pub fn with_runtime_demangling(mut self, enabled: bool) -> Self {
+        self.demangle_runtime = enabled;
+        self
     }
 
     /// Lift a sequence of P-Code instructions to an IR function
@@ -42,31 +70,41 @@ impl PCodeLifter {
 
         // Create lifting context
         let mut ctx = LiftContext::new(function_name, start_address);
+        ctx.frame = self.recover_frame(instructions, &mut ctx.function);
 
-        // First pass: identify basic block boundaries (branch targets)
+        // First pass: identify basic block boundaries (branch and error
+        // handler targets)
         for instr in instructions {
-            if instr.is_branch {
-                if let Some(offset) = instr.branch_offset {
-                    if offset != 0 {
-                        let instr_len = instr.bytes.len() as u32;
-                        let target_addr = instr
-                            .address
-                            .wrapping_add(instr_len)
-                            .wrapping_add(offset as u32);
-                        ctx.get_or_create_block_for_address(target_addr);
-                    }
+            let is_jump = instr.is_branch
+                || instr.mnemonic == "OnErrorGoto"
+                || instr.mnemonic == "ResumeLabel";
+            if is_jump && instr.branch_offset != Some(0) {
+                if let Some(target_addr) = instr.branch_target() {
+                    ctx.get_or_create_block_for_address(target_addr);
                 }
             }
         }
 
-        // Second pass: lift instructions
-        for instr in instructions {
+        // Second pass: lift instructions, skipping the compiler's prologue
+        // bookkeeping but keeping its error handler setup. Code after a
+        // return is only lifted where a branch or error handler lands.
+        let prologue = prologue_len(instructions);
+        let mut unreachable = false;
+        for (index, instr) in instructions.iter().enumerate() {
             // Check if this address starts a new block
             if let Some(&block_id) = ctx.address_to_block.get(&instr.address) {
+                unreachable = false;
                 if block_id != ctx.current_block_id {
-                    // Connect current block to new block
+                    // Connect current block to new block unless control
+                    // already left it via a jump or return
                     if let Some(current_block) = ctx.function.get_block_mut(ctx.current_block_id) {
-                        if !current_block.statements.is_empty() {
+                        let falls_through = !matches!(
+                            current_block.statements.last().map(|s| s.kind),
+                            Some(StatementKind::Goto)
+                                | Some(StatementKind::Return)
+                                | Some(StatementKind::Resume)
+                        );
+                        if falls_through {
                             current_block.add_successor(block_id);
                         }
                     }
@@ -74,26 +112,114 @@ impl PCodeLifter {
                 }
             }
 
+            if unreachable || (index < prologue && !instr.mnemonic.starts_with("OnError")) {
+                continue;
+            }
+
             // Lift the instruction
             if let Err(e) = self.lift_instruction(instr, &mut ctx) {
                 self.last_error = Some(format!("Failed to lift {}: {}", instr.mnemonic, e));
                 return Err(e);
             }
 
-            // Stop at return
-            if instr.is_return {
-                break;
+            // Nothing falls through a return or Resume
+            if instr.is_return || instr.mnemonic.starts_with("Resume") {
+                unreachable = true;
             }
         }
 
         Ok(ctx.function)
     }
 
+    /// Name the frame slots that `FLd*`/`FSt*` opcodes address
+    ///
+    /// Arguments live at positive frame offsets and locals at negative ones.
+    /// Every argument slot up to the highest one used becomes a parameter,
+    /// so unused arguments still count toward the signature. Locals are
+    /// numbered from the frame pointer down. A slot accessed with
+    /// different types is a Variant.
+    ///
+    /// A `ByRef` argument's slot holds a pointer, so its callee writes go
+    /// through that pointer. An argument stored to directly (`FStI2`) and
+    /// never referenced (`FLdRfVar`) therefore holds a copy and is passed
+    /// `ByVal`. Reads alone look the same either way, so every other
+    /// argument keeps VB's default of `ByRef`.
+    fn recover_frame(
+        &mut self,
+        instructions: &[Instruction],
+        function: &mut Function,
+    ) -> HashMap<i32, Variable> {
+        let mut offsets: BTreeMap<i32, TypeKind> = BTreeMap::new();
+        let mut value_stores = HashSet::new();
+        let mut reference_loads = HashSet::new();
+        for instr in instructions {
+            let Some(kind) = frame_access_type(&instr.mnemonic) else {
+                continue;
+            };
+            let Some(offset) = instr.operands.first().and_then(|o| frame_offset(&o.value)) else {
+                continue;
+            };
+            if instr.mnemonic == "FLdRfVar" {
+                reference_loads.insert(offset);
+            } else if instr.mnemonic.starts_with("FSt") {
+                value_stores.insert(offset);
+            }
+            offsets
+                .entry(offset)
+                .and_modify(|k| {
+                    if *k != kind {
+                        *k = TypeKind::Variant;
+                    }
+                })
+                .or_insert(kind);
+        }
+
+        let mut frame = HashMap::new();
+        let arg_slot = |offset: i32| FIRST_ARG_OFFSET + (offset - FIRST_ARG_OFFSET) / 4 * 4;
+        if let Some(&last) = offsets.keys().filter(|&&o| o >= FIRST_ARG_OFFSET).max() {
+            for (index, slot) in (FIRST_ARG_OFFSET..=arg_slot(last)).step_by(4).enumerate() {
+                let kind = offsets.get(&slot).copied().unwrap_or(TypeKind::Variant);
+                let var = Variable::new(slot as u32, format!("arg{}", index + 1), kind);
+                if value_stores.contains(&slot) && !reference_loads.contains(&slot) {
+                    function.parameter_modes.insert(var.id, PassingMode::ByVal);
+                }
+                function.parameters.push(var.clone());
+                frame.insert(slot, var);
+            }
+            // Accesses inside a slot (the high word of a Long) share its name
+            for &offset in offsets.keys().filter(|&&o| o >= FIRST_ARG_OFFSET) {
+                let var = frame[&arg_slot(offset)].clone();
+                frame.entry(offset).or_insert(var);
+            }
+        }
+
+        for (index, (&offset, &kind)) in offsets.range(..0).rev().enumerate() {
+            if let Some(frame_size) = self.frame_size {
+                if -offset > frame_size as i32 {
+                    self.warnings.push(format!(
+                        "Local at frame offset {} lies outside the {} byte frame",
+                        offset, frame_size
+                    ));
+                }
+            }
+            let var = Variable::new(offset as u32, format!("local{}", index + 1), kind);
+            function.add_local_variable(var.clone());
+            frame.insert(offset, var);
+        }
+
+        frame
+    }
+
     /// Get last error message
     pub fn last_error(&self) -> Option<&str> {
         self.last_error.as_deref()
     }
 
+    /// Get non-fatal problems found while lifting
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     /// Lift a single instruction
     fn lift_instruction(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
         // Route to specialized lifters based on category
@@ -106,6 +232,10 @@ impl PCodeLifter {
             OpcodeCategory::ControlFlow => {
                 if instr.is_branch {
                     self.lift_branch(instr, ctx)
+                } else if instr.mnemonic.starts_with("OnError") {
+                    self.lift_on_error(instr, ctx)
+                } else if instr.mnemonic.starts_with("Resume") {
+                    self.lift_resume(instr, ctx)
                 } else if instr.is_return
                     || instr.mnemonic.contains("Exit")
                     || instr.mnemonic.contains("Return")
@@ -116,14 +246,29 @@ impl PCodeLifter {
                 }
             }
             OpcodeCategory::Call => self.lift_call(instr, ctx),
+            OpcodeCategory::String => self.lift_string(instr, ctx),
             _ => Ok(()), // Ignore unknown categories
         }
     }
 
     /// Lift arithmetic operations
     fn lift_arithmetic(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        // Negation keeps the width of its operand (NegI2, NegI4, NegR8, ...)
+        if instr.mnemonic.starts_with("Neg") {
+            let operand = ctx.pop_stack()?;
+            let result_type = mnemonic_type(&instr.mnemonic).unwrap_or(TypeKind::Variant);
+            ctx.push_stack(Expression {
+                kind: ExpressionKind::Negate,
+                expr_type: Type::new(result_type),
+                data: ExpressionData::Unary(Box::new(operand)),
+            });
+            return Ok(());
+        }
+
         // Map P-Code arithmetic to IR binary operations
-        let op = if instr.mnemonic.contains("Add") {
+        let op = if instr.mnemonic.contains("IDv") || instr.mnemonic.contains("Idiv") {
+            ExpressionKind::IntDivide
+        } else if instr.mnemonic.contains("Add") {
             ExpressionKind::Add
         } else if instr.mnemonic.contains("Sub") {
             ExpressionKind::Subtract
@@ -131,8 +276,6 @@ impl PCodeLifter {
             ExpressionKind::Multiply
         } else if instr.mnemonic.contains("Div") {
             ExpressionKind::Divide
-        } else if instr.mnemonic.contains("Idiv") {
-            ExpressionKind::IntDivide
         } else if instr.mnemonic.contains("Mod") {
             ExpressionKind::Modulo
         } else if instr.mnemonic.contains("Concat") {
@@ -145,8 +288,15 @@ impl PCodeLifter {
         let right = ctx.pop_stack()?;
         let left = ctx.pop_stack()?;
 
-        // Create binary expression
-        let result = Expression::binary(op, left, right, Type::new(TypeKind::Variant));
+        // Typed opcodes name their result width (`AddUI1` Byte, `AddI2`
+        // Integer, `AddI4` Long), which bounds constant folding to the range
+        // VB checks for overflow; `Concat` always yields a String
+        let result_type = if op == ExpressionKind::Concatenate {
+            TypeKind::String
+        } else {
+            mnemonic_type(&instr.mnemonic).unwrap_or(TypeKind::Variant)
+        };
+        let result = Expression::binary(op, left, right, Type::new(result_type));
 
         // Push result
         ctx.push_stack(result);
@@ -154,6 +304,26 @@ impl PCodeLifter {
         Ok(())
     }
 
+    /// Lift string operations
+    fn lift_string(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        // CStr2Ansi/CStr2Uni convert the top of stack between ANSI and
+        // Unicode for API marshaling; VB source never spells them out
+        if instr.mnemonic.starts_with("CStr2") {
+            return Ok(());
+        }
+
+        if instr.mnemonic.starts_with("Concat") {
+            return self.lift_arithmetic(instr, ctx);
+        }
+
+        if frame_access_type(&instr.mnemonic).is_some() {
+            return self.lift_stack(instr, ctx);
+        }
+
+        // Other string operations - to be implemented when needed
+        Ok(())
+    }
+
     /// Lift comparison operations
     fn lift_comparison(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
         // Map P-Code comparison to IR comparison operations
@@ -223,21 +393,134 @@ impl PCodeLifter {
 
     /// Lift stack operations (literals and variable loads/stores)
     fn lift_stack(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        // Frame variables (`FLdI2 local1`, `FStStr arg2`)
+        if frame_access_type(&instr.mnemonic).is_some() {
+            let offset = instr.operands.first().and_then(|o| frame_offset(&o.value));
+            let Some(var) = offset.and_then(|offset| ctx.frame.get(&offset)).cloned() else {
+                return Err(Error::Decompilation(format!(
+                    "{} does not address a frame variable",
+                    instr.mnemonic
+                )));
+            };
+
+            if instr.mnemonic.starts_with("FLd") {
+                ctx.push_stack(Expression::variable(var));
+            } else {
+                let value = ctx.pop_stack()?;
+                if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+                    block.add_statement(Statement::assign(var, value));
+                }
+            }
+            return Ok(());
+        }
+
+        // The current object, whose members `MemLd*`/`MemSt*` address
+        if instr.mnemonic == "FLdPrThis" {
+            ctx.push_stack(Expression::me());
+            return Ok(());
+        }
+
+        // Object members by data offset (`Me.m_34`)
+        let member_op = instr.mnemonic.trim_start_matches('F');
+        if member_op.starts_with("MemLd") || member_op.starts_with("MemSt") {
+            let Some(offset) = instr.operands.first().and_then(|o| frame_offset(&o.value)) else {
+                return Err(Error::Decompilation(format!(
+                    "{} with no member offset",
+                    instr.mnemonic
+                )));
+            };
+            // Without a pushed object the access is to the current one; a
+            // value on top of the stack belongs to something else
+            let pushed_object = ctx
+                .eval_stack
+                .last()
+                .is_some_and(|top| top.expr_type.kind == TypeKind::Object);
+            let object = if pushed_object {
+                ctx.pop_stack()?
+            } else {
+                Expression::me()
+            };
+            // Stores take the value from beneath the object
+            let value = if member_op.starts_with("MemSt") {
+                Some(ctx.pop_stack()?)
+            } else {
+                None
+            };
+            let member = Expression::member_access(
+                object,
+                format!("m_{:X}", offset),
+                Type::new(mnemonic_type(&instr.mnemonic).unwrap_or(TypeKind::Variant)),
+            );
+
+            match value {
+                Some(value) => {
+                    if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+                        block.add_statement(Statement::store(member, value));
+                    }
+                }
+                None => ctx.push_stack(member),
+            }
+            return Ok(());
+        }
+
         // Handle literal pushes
         if instr.mnemonic.contains("Lit") {
+            // Omitted optional argument
+            if instr.mnemonic == "LitVar_Missing" {
+                ctx.push_stack(Expression::missing());
+                return Ok(());
+            }
+
+            // Keyword literals carry no operand
+            let keyword = match instr.mnemonic.as_str() {
+                "LitVar_TRUE" => Some(Expression::bool_const(true)),
+                "LitVar_FALSE" => Some(Expression::bool_const(false)),
+                "LitVar_NULL" => Some(Expression::constant(
+                    ConstantValue::Null,
+                    Type::new(TypeKind::Variant),
+                )),
+                "LitVar_Empty" => Some(Expression::constant(
+                    ConstantValue::Empty,
+                    Type::new(TypeKind::Variant),
+                )),
+                "LitNothing" => Some(Expression::constant(
+                    ConstantValue::Nothing,
+                    Type::new(TypeKind::Object),
+                )),
+                _ => None,
+            };
+            if let Some(expr) = keyword {
+                ctx.push_stack(expr);
+                return Ok(());
+            }
+
             if instr.operands.is_empty() {
                 return Err(Error::Decompilation("Literal with no operands".to_string()));
             }
 
             let operand = &instr.operands[0];
+            let int_type = Type::new(mnemonic_type(&instr.mnemonic).unwrap_or(TypeKind::Long));
             let expr = match &operand.value {
-                OperandValue::Byte(v) => Expression::int_const(*v as i64),
-                OperandValue::Int16(v) => Expression::int_const(*v as i64),
-                OperandValue::Int32(v) => Expression::int_const(*v as i64),
+                OperandValue::Byte(v) => {
+                    Expression::constant(ConstantValue::Integer(*v as i64), int_type)
+                }
+                OperandValue::Int16(v) => {
+                    Expression::constant(ConstantValue::Integer(*v as i64), int_type)
+                }
+                OperandValue::Int32(v) => {
+                    Expression::constant(ConstantValue::Integer(*v as i64), int_type)
+                }
                 OperandValue::Float(v) => Expression::constant(
                     ConstantValue::Float(*v as f64),
                     Type::new(TypeKind::Single),
                 ),
+                // Dates are literal OLE Automation dates
+                OperandValue::Double(v) if instr.mnemonic == "LitDate" => {
+                    Expression::constant(ConstantValue::Date(*v), Type::new(TypeKind::Date))
+                }
+                OperandValue::Double(v) => {
+                    Expression::constant(ConstantValue::Float(*v), Type::new(TypeKind::Double))
+                }
                 OperandValue::String(s) => Expression::string_const(s.clone()),
                 OperandValue::None => {
                     return Err(Error::Decompilation("Literal with None value".to_string()));
@@ -311,27 +594,170 @@ impl PCodeLifter {
     }
 
     /// Lift memory operations
-    fn lift_memory(&mut self, _instr: &Instruction, _ctx: &mut LiftContext) -> Result<()> {
-        // Memory operations - to be implemented when needed
+    fn lift_memory(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        if instr.mnemonic.starts_with("Redim") {
+            return self.lift_redim(instr, ctx);
+        }
+        if ["Ary1Ld", "Ary1St", "AryLd"]
+            .iter()
+            .any(|prefix| instr.mnemonic.starts_with(prefix))
+        {
+            return self.lift_array_access(instr, ctx);
+        }
+
+        // Other memory operations - to be implemented when needed
+        Ok(())
+    }
+
+    /// Lift `ReDim [Preserve]`: operands are the array slot and the
+    /// dimension count, with one (lower, upper) pair per dimension on the stack
+    fn lift_redim(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        let (slot, dimensions) = match instr.operands.as_slice() {
+            [slot, dims, ..] => {
+                let slot = match slot.value {
+                    OperandValue::Int16(v) => v as u32,
+                    OperandValue::Byte(v) => v as u32,
+                    _ => {
+                        return Err(Error::Decompilation(
+                            "ReDim with invalid array slot".to_string(),
+                        ))
+                    }
+                };
+                let dims = match dims.value {
+                    OperandValue::Byte(v) => v as usize,
+                    _ => {
+                        return Err(Error::Decompilation(
+                            "ReDim with invalid dimension count".to_string(),
+                        ))
+                    }
+                };
+                (slot, dims)
+            }
+            _ => {
+                return Err(Error::Decompilation(
+                    "ReDim with missing operands".to_string(),
+                ))
+            }
+        };
+
+        // Pairs come off the stack last dimension first, upper before lower
+        let mut bounds = Vec::with_capacity(dimensions);
+        for _ in 0..dimensions {
+            let upper = ctx.pop_stack()?;
+            let lower = ctx.pop_stack()?;
+            bounds.push((lower, upper));
+        }
+        bounds.reverse();
+
+        let array = Variable::new(slot, format!("local{}", slot), TypeKind::Array);
+        let stmt = Statement::redim(array, bounds, instr.mnemonic == "RedimPreserve");
+
+        if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+            block.add_statement(stmt);
+        }
+        Ok(())
+    }
+
+    /// Lift an array element load (`Ary1LdI2`, `AryLdPr n`) or store
+    /// (`Ary1StI2`)
+    ///
+    /// The array is pushed before its indices; a store's value goes beneath
+    /// both. `Ary1*` opcodes take one index, `AryLd*` as many as their operand.
+    fn lift_array_access(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        let dimensions = if instr.mnemonic.starts_with("Ary1") {
+            1
+        } else {
+            match instr.operands.first().map(|o| &o.value) {
+                Some(OperandValue::Int16(n)) if *n > 0 => *n as usize,
+                _ => {
+                    return Err(Error::Decompilation(format!(
+                        "{} with invalid index count",
+                        instr.mnemonic
+                    )))
+                }
+            }
+        };
+
+        let mut indices = Vec::with_capacity(dimensions);
+        for _ in 0..dimensions {
+            indices.push(ctx.pop_stack()?);
+        }
+        indices.reverse();
+
+        // A variable indexed here is an array, whatever slot type it came with
+        let array = ctx.pop_stack()?;
+        let array = match array.data {
+            ExpressionData::Variable(var) => {
+                Expression::variable(Variable::new(var.id, var.name, TypeKind::Array))
+            }
+            _ => array,
+        };
+        let element_type = Type::new(mnemonic_type(&instr.mnemonic).unwrap_or(TypeKind::Variant));
+        let element = Expression::array_index(array, indices, element_type);
+
+        if instr.mnemonic.starts_with("Ary1St") {
+            let value = ctx.pop_stack()?;
+            if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+                block.add_statement(Statement::store(element, value));
+            }
+        } else {
+            ctx.push_stack(element);
+        }
+        Ok(())
+    }
+
+    /// Lift `On Error GoTo` / `On Error Resume Next`
+    fn lift_on_error(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        let handler_block = match instr.branch_target() {
+            Some(target_addr) if instr.mnemonic == "OnErrorGoto" => {
+                Some(ctx.get_or_create_block_for_address(target_addr))
+            }
+            _ => None,
+        };
+
+        if let Some(handler) = handler_block.and_then(|id| ctx.function.get_block_mut(id)) {
+            handler.is_error_handler = true;
+        }
+        if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+            block.add_statement(Statement::on_error(handler_block));
+        }
+        Ok(())
+    }
+
+    /// Lift `Resume Next` / `Resume label` at the end of an error handler
+    fn lift_resume(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
+        let target = match instr.mnemonic.as_str() {
+            "ResumeNext" => ResumeTarget::Next,
+            "ResumeLabel" => match instr.branch_target() {
+                Some(target_addr) => {
+                    ResumeTarget::Block(ctx.get_or_create_block_for_address(target_addr))
+                }
+                None => ResumeTarget::Retry,
+            },
+            _ => ResumeTarget::Retry,
+        };
+
+        if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+            block.add_statement(Statement::resume(target));
+        }
         Ok(())
     }
 
     /// Lift branch operations
     fn lift_branch(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
         // Calculate branch target address
-        let branch_offset = instr
-            .branch_offset
-            .ok_or_else(|| Error::Decompilation("Branch instruction with no offset".to_string()))?;
-
-        let instr_len = instr.bytes.len() as u32;
         let target_addr = instr
-            .address
-            .wrapping_add(instr_len)
-            .wrapping_add(branch_offset as u32);
+            .branch_target()
+            .ok_or_else(|| Error::Decompilation("Branch instruction with no offset".to_string()))?;
 
         if instr.is_conditional_branch {
-            // Pop condition from stack
-            let condition = ctx.pop_stack()?;
+            // Pop condition from stack; BranchF jumps when it is False
+            let condition = ctx.pop_stack()?.into_condition();
+            let condition = if instr.mnemonic.starts_with("BranchF") {
+                condition.logical_not()
+            } else {
+                condition
+            };
 
             // Get or create target block
             let target_block_id = ctx.get_or_create_block_for_address(target_addr);
@@ -372,8 +798,10 @@ impl PCodeLifter {
 
     /// Lift call operations
     fn lift_call(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
-        // Extract function name/address
-        let func_name = if !instr.operands.is_empty() {
+        // Extract function name/address, preferring a resolved symbol
+        let func_name = if let Some(symbol) = &instr.symbol {
+            symbol.clone()
+        } else if !instr.operands.is_empty() {
             let operand = &instr.operands[0];
             match &operand.value {
                 OperandValue::Int32(v) => format!("func_{}", v),
@@ -385,9 +813,37 @@ impl PCodeLifter {
             "func_unknown".to_string()
         };
 
-        // For now, create a simple call with no arguments
-        // TODO: Pop arguments from stack based on calling convention
-        let args = Vec::new();
+        // Calls into the VB runtime are rendered as the intrinsic they implement
+        if let Some(helper) = runtime::lookup(&func_name) {
+            return self.lift_intrinsic_call(instr, helper, ctx);
+        }
+
+        if instr.mnemonic.contains("VCall") {
+            return self.lift_vcall(instr, func_name, ctx);
+        }
+
+        // `Call*` opcodes carry their argument count; arguments were pushed
+        // left to right, so the last one is on top of the stack
+        let args = match (instr.mnemonic.starts_with("Call"), &instr.operands[..]) {
+            (true, [count, ..]) => {
+                let wanted = match count.value {
+                    OperandValue::Byte(v) => v as usize,
+                    OperandValue::Int16(v) => v.max(0) as usize,
+                    _ => 0,
+                };
+                let available = ctx.eval_stack.len();
+                if wanted > available {
+                    let warning = format!(
+                        "{} at 0x{:X} takes {} arguments but only {} are on the stack",
+                        instr.mnemonic, instr.address, wanted, available
+                    );
+                    log::warn!("{}", warning);
+                    self.warnings.push(warning);
+                }
+                ctx.eval_stack.split_off(available - wanted.min(available))
+            }
+            _ => Vec::new(),
+        };
 
         // If this is a function call (not sub), create call expression and push result
         if instr.mnemonic.contains("CallFunc") || instr.mnemonic.contains("CallI4") {
@@ -404,6 +860,131 @@ impl PCodeLifter {
         Ok(())
     }
 
+    /// Lift a late-bound call through an object's vtable
+    fn lift_vcall(
+        &mut self,
+        instr: &Instruction,
+        func_name: String,
+        ctx: &mut LiftContext,
+    ) -> Result<()> {
+        // The operand is a vtable offset, not an arity, and there is no
+        // signature to consult. The object is pushed before its arguments, so
+        // the arguments are the values above the topmost object reference;
+        // anything beneath it belongs to the enclosing expression.
+        // `ThisVCall*` dispatches on the current object, which is never
+        // pushed, leaving nothing to delimit its arguments.
+        let object_slot = if instr.mnemonic.starts_with("This") {
+            None
+        } else {
+            ctx.eval_stack
+                .iter()
+                .rposition(|expr| expr.expr_type.kind == TypeKind::Object)
+        };
+        let (object, args) = match object_slot {
+            Some(slot) => {
+                let args = ctx.eval_stack.split_off(slot + 1);
+                (ctx.pop_stack()?, args)
+            }
+            None => (Expression::me(), Vec::new()),
+        };
+        let target = Expression::member_access(object, func_name, Type::new(TypeKind::Void));
+
+        // Typed variants leave the method's return value on the stack
+        if instr.stack_delta > 0 {
+            ctx.push_stack(Expression::method_call(
+                target,
+                args,
+                Type::new(TypeKind::Variant),
+            ));
+        } else if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+            block.add_statement(Statement::method_call(target, args));
+        }
+
+        Ok(())
+    }
+
+    /// Lift a call into a known VB runtime helper
+    fn lift_intrinsic_call(
+        &mut self,
+        instr: &Instruction,
+        helper: &RuntimeHelper,
+        ctx: &mut LiftContext,
+    ) -> Result<()> {
+        // Arguments were pushed left to right, so pop them in reverse
+        let count = helper.arity.min(ctx.eval_stack.len());
+        let mut args = ctx.eval_stack.split_off(ctx.eval_stack.len() - count);
+
+        // Raw mode keeps the runtime symbol and every argument
+        if !self.demangle_runtime {
+            return self.lift_runtime_call(instr, helper, helper.symbol, args, ctx);
+        }
+
+        // Trailing omitted optional arguments are not written in source
+        while args.last().is_some_and(|a| a.is_missing()) {
+            args.pop();
+        }
+
+        match helper.form {
+            IntrinsicForm::Operator(op) => {
+                let [left, right]: [Expression; 2] = match args.try_into() {
+                    Ok(operands) => operands,
+                    Err(args) => {
+                        return self.lift_runtime_call(instr, helper, helper.symbol, args, ctx)
+                    }
+                };
+                let result_type = if op == ExpressionKind::Concatenate {
+                    TypeKind::String
+                } else {
+                    TypeKind::Variant
+                };
+                ctx.push_stack(Expression::binary(op, left, right, Type::new(result_type)));
+            }
+            IntrinsicForm::Member { object, member } => {
+                let object = Expression::variable(Variable::builtin_object(object));
+                let target = Expression::member_access(
+                    object,
+                    member.to_string(),
+                    Type::new(TypeKind::Void),
+                );
+                let stmt = Statement::method_call(target, args);
+                if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+                    block.add_statement(stmt);
+                }
+            }
+            IntrinsicForm::Call => {
+                return self.lift_runtime_call(instr, helper, helper.vb_name, args, ctx)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lift a runtime helper call under `name`, as a function call expression
+    /// or a call statement depending on the opcode
+    fn lift_runtime_call(
+        &mut self,
+        instr: &Instruction,
+        helper: &RuntimeHelper,
+        name: &str,
+        args: Vec<Expression>,
+        ctx: &mut LiftContext,
+    ) -> Result<()> {
+        // Operator helpers always produce a value
+        if matches!(helper.form, IntrinsicForm::Operator(_))
+            || instr.mnemonic.contains("CallFunc")
+            || instr.mnemonic.contains("CallI4")
+        {
+            let call_expr = Expression::call(name.to_string(), args, Type::new(TypeKind::Variant));
+            ctx.push_stack(call_expr);
+        } else {
+            let stmt = Statement::call(name.to_string(), args);
+            if let Some(block) = ctx.function.get_block_mut(ctx.current_block_id) {
+                block.add_statement(stmt);
+            }
+        }
+        Ok(())
+    }
+
     /// Lift return operations
     fn lift_return(&mut self, instr: &Instruction, ctx: &mut LiftContext) -> Result<()> {
         // Check if this is a function return (with value) or sub return (no value)
@@ -437,6 +1018,8 @@ struct LiftContext {
     eval_stack: Vec<Expression>,
     next_block_id: u32,
     address_to_block: HashMap<u32, u32>,
+    /// Arguments and locals by frame offset
+    frame: HashMap<i32, Variable>,
 }
 
 impl LiftContext {
@@ -454,6 +1037,7 @@ impl LiftContext {
             eval_stack: Vec::new(),
             next_block_id: 1,
             address_to_block: HashMap::new(),
+            frame: HashMap::new(),
         }
     }
 
@@ -488,6 +1072,72 @@ impl LiftContext {
     }
 }
 
+/// Number of leading instructions forming the compiler-generated prologue
+///
+/// The prologue is a run of statement markers (`LargeBos`/`Bos`), local
+/// zeroing (`FLdZeroAd`) and error handler installation (`OnError*`, with the
+/// `ImpAdLdRf` loading the handler's runtime reference just before it).
+fn prologue_len(instructions: &[Instruction]) -> usize {
+    let mut len = 0;
+    while let Some(instr) = instructions.get(len) {
+        let is_prologue = match instr.mnemonic.as_str() {
+            "LargeBos" | "Bos" | "FLdZeroAd" | "OnErrorGoto" | "OnErrorResumeNext" => true,
+            "ImpAdLdRf" => instructions
+                .get(len + 1)
+                .is_some_and(|next| next.mnemonic.starts_with("OnError")),
+            _ => false,
+        };
+        if !is_prologue {
+            break;
+        }
+        len += 1;
+    }
+    len
+}
+
+/// Type of the frame variable a load or store opcode accesses, or `None`
+/// for opcodes that don't address a variable
+fn frame_access_type(mnemonic: &str) -> Option<TypeKind> {
+    match mnemonic {
+        "FLdAd" | "FStAd" => Some(TypeKind::Object),
+        "FLdUI1" | "FLdI2" | "FLdI4" | "FLdR4" | "FLdR8" | "FLdCy" | "FLdStr" | "FLdVar"
+        | "FLdRfVar" | "FStUI1" | "FStI2" | "FStI4" | "FStR4" | "FStR8" | "FStCy" | "FStStr"
+        | "FStStrCopy" | "FStVar" | "FStVarCopy" => mnemonic_type(mnemonic),
+        _ => None,
+    }
+}
+
+/// Signed frame offset of a frame variable operand
+fn frame_offset(value: &OperandValue) -> Option<i32> {
+    match value {
+        OperandValue::Byte(v) => Some(*v as i8 as i32),
+        OperandValue::Int16(v) => Some(*v as i32),
+        OperandValue::Int32(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Type named by a typed opcode's suffix (`AddI2`, `LitR8`, `LitVarI4`)
+///
+/// `Var` is checked first: `LitVarI2` pushes a Variant holding an Integer.
+fn mnemonic_type(mnemonic: &str) -> Option<TypeKind> {
+    const SUFFIXES: &[(&str, TypeKind)] = &[
+        ("Var", TypeKind::Variant),
+        ("UI1", TypeKind::Byte),
+        ("I2", TypeKind::Integer),
+        ("I4", TypeKind::Long),
+        ("R4", TypeKind::Single),
+        ("R8", TypeKind::Double),
+        ("Cy", TypeKind::Currency),
+        ("Str", TypeKind::String),
+        ("Bool", TypeKind::Boolean),
+    ];
+    SUFFIXES
+        .iter()
+        .find(|(suffix, _)| mnemonic.contains(suffix))
+        .map(|&(_, kind)| kind)
+}
+
 /// Convert P-Code type to IR type
 fn pcode_type_to_ir_type(pcode_type: PCodeType) -> TypeKind {
     match pcode_type {
@@ -496,6 +1146,7 @@ fn pcode_type_to_ir_type(pcode_type: PCodeType) -> TypeKind {
         PCodeType::Integer => TypeKind::Integer,
         PCodeType::Long => TypeKind::Long,
         PCodeType::Single => TypeKind::Single,
+        PCodeType::Double => TypeKind::Double,
         PCodeType::String => TypeKind::String,
         PCodeType::Object => TypeKind::Object,
         PCodeType::Variant | PCodeType::Unknown => TypeKind::Variant,
@@ -505,6 +1156,7 @@ fn pcode_type_to_ir_type(pcode_type: PCodeType) -> TypeKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::pcode::Operand;
 
     #[test]
     fn test_lifter_creation() {
@@ -519,6 +1171,728 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn make_instr(address: u32, mnemonic: &str, category: OpcodeCategory) -> Instruction {
+        Instruction {
+            address,
+            opcode: 0,
+            extended_opcode: None,
+            mnemonic: mnemonic.to_string(),
+            operands: Vec::new(),
+            bytes: vec![0],
+            category,
+            stack_delta: 0,
+            is_branch: false,
+            is_conditional_branch: false,
+            is_call: false,
+            is_return: false,
+            branch_offset: None,
+            symbol: None,
+            truncated: false,
+        }
+    }
+
+    fn with_operand(mut instr: Instruction, value: OperandValue) -> Instruction {
+        instr.operands.push(Operand::new(value, PCodeType::Unknown));
+        instr
+    }
+
+    #[test]
+    fn test_lift_msgbox_intrinsic() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("Hello".to_string()),
+            ),
+            make_instr(1, "LitVar_Missing", OpcodeCategory::Stack),
+            with_operand(
+                make_instr(2, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("rtcMsgBox".to_string()),
+            ),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let stmt = &function.basic_blocks[0].statements[0];
+
+        match &stmt.data {
+            StatementData::Call {
+                function,
+                arguments,
+            } => {
+                assert_eq!(function, "MsgBox");
+                assert_eq!(arguments.len(), 1);
+                assert_eq!(arguments[0].to_vb_string(), "\"Hello\"");
+            }
+            other => panic!("expected MsgBox call, got {:?}", other),
+        }
+        assert_eq!(stmt.to_vb_string(), "MsgBox \"Hello\"");
+    }
+
+    #[test]
+    fn test_lift_typed_arithmetic() {
+        // LitI2 1, LitI2 2, AddI2, Return: the sum is an Integer
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(1),
+            ),
+            with_operand(
+                make_instr(1, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(2),
+            ),
+            make_instr(2, "AddI2", OpcodeCategory::Arithmetic),
+            make_instr(3, "Return", OpcodeCategory::ControlFlow),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+
+        match &function.basic_blocks[0].statements[0].data {
+            StatementData::Return { value: Some(sum) } => {
+                assert_eq!(sum.kind, ExpressionKind::Add);
+                assert_eq!(sum.expr_type.kind, TypeKind::Integer);
+                let ExpressionData::Binary { left, .. } = &sum.data else {
+                    panic!("expected binary expression");
+                };
+                assert_eq!(left.expr_type.kind, TypeKind::Integer);
+            }
+            other => panic!("expected return, got {:?}", other),
+        }
+        assert_eq!(mnemonic_type("LitVarI2"), Some(TypeKind::Variant));
+        assert_eq!(mnemonic_type("MulR8"), Some(TypeKind::Double));
+        assert_eq!(mnemonic_type("LitUI1"), Some(TypeKind::Byte));
+    }
+
+    #[test]
+    fn test_lift_arithmetic_width() {
+        // LitI2 a, LitI2 b, <op>, Return
+        let lift_result = |op: &str| {
+            let instructions = vec![
+                with_operand(
+                    make_instr(0, "LitI2", OpcodeCategory::Stack),
+                    OperandValue::Int16(1),
+                ),
+                with_operand(
+                    make_instr(1, "LitI2", OpcodeCategory::Stack),
+                    OperandValue::Int16(2),
+                ),
+                make_instr(2, op, OpcodeCategory::Arithmetic),
+                make_instr(3, "Return", OpcodeCategory::ControlFlow),
+            ];
+            let function = PCodeLifter::new()
+                .lift(&instructions, "test".to_string(), 0)
+                .unwrap();
+            match &function.basic_blocks[0].statements[0].data {
+                StatementData::Return { value: Some(value) } => value.clone(),
+                other => panic!("expected return, got {:?}", other),
+            }
+        };
+
+        let sum = lift_result("AddI2");
+        assert_eq!(sum.expr_type.kind, TypeKind::Integer);
+        let sum = lift_result("AddI4");
+        assert_eq!(
+            (sum.kind, sum.expr_type.kind),
+            (ExpressionKind::Add, TypeKind::Long)
+        );
+        let difference = lift_result("SubUI1");
+        assert_eq!(
+            (difference.kind, difference.expr_type.kind),
+            (ExpressionKind::Subtract, TypeKind::Byte)
+        );
+
+        // NegI2 negates the top of stack, leaving the first literal below it
+        let negated = lift_result("NegI2");
+        assert_eq!(negated.kind, ExpressionKind::Negate);
+        assert_eq!(negated.expr_type.kind, TypeKind::Integer);
+    }
+
+    #[test]
+    fn test_lift_date_literal() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitDate", OpcodeCategory::Stack),
+                OperandValue::Double(36_526.0),
+            ),
+            make_instr(9, "Return", OpcodeCategory::ControlFlow),
+        ];
+
+        let function = PCodeLifter::new()
+            .lift(&instructions, "test".to_string(), 0)
+            .unwrap();
+        match &function.basic_blocks[0].statements[0].data {
+            StatementData::Return { value: Some(value) } => {
+                assert_eq!(value.expr_type.kind, TypeKind::Date);
+                assert_eq!(value.to_vb_string(), "#1/1/2000#");
+            }
+            other => panic!("expected a return, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lift_runtime_operator() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("a".to_string()),
+            ),
+            with_operand(
+                make_instr(1, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("b".to_string()),
+            ),
+            with_operand(
+                make_instr(2, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("__vbaStrCat".to_string()),
+            ),
+            with_operand(
+                make_instr(3, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("rtcMsgBox".to_string()),
+            ),
+        ];
+
+        let function = PCodeLifter::new()
+            .lift(&instructions, "test".to_string(), 0)
+            .unwrap();
+        let stmt = &function.basic_blocks[0].statements[0];
+        assert_eq!(stmt.to_vb_string(), "MsgBox (\"a\" & \"b\")");
+
+        // Raw mode keeps the runtime symbols
+        let function = PCodeLifter::new()
+            .with_runtime_demangling(false)
+            .lift(&instructions, "test".to_string(), 0)
+            .unwrap();
+        let stmt = &function.basic_blocks[0].statements[0];
+        match &stmt.data {
+            StatementData::Call {
+                function,
+                arguments,
+            } => {
+                assert_eq!(function, "rtcMsgBox");
+                assert_eq!(arguments.len(), 1);
+                assert!(matches!(&arguments[0].kind, ExpressionKind::Call));
+            }
+            other => panic!("expected rtcMsgBox call, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_lift_debug_print_intrinsic() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(7),
+            ),
+            with_operand(
+                make_instr(1, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("__vbaPrintObj".to_string()),
+            ),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let stmt = &function.basic_blocks[0].statements[0];
+
+        match &stmt.data {
+            StatementData::MethodCall { target, .. } => {
+                assert_eq!(target.kind, ExpressionKind::MemberAccess);
+            }
+            other => panic!("expected Debug.Print, got {:?}", other),
+        }
+        assert_eq!(stmt.to_vb_string(), "Debug.Print 7");
+    }
+
+    #[test]
+    fn test_lift_cstr2ansi_transparent() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("Hello".to_string()),
+            ),
+            make_instr(1, "CStr2Ansi", OpcodeCategory::String),
+            with_operand(
+                make_instr(2, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String(" World".to_string()),
+            ),
+            make_instr(3, "CStr2Ansi", OpcodeCategory::String),
+            make_instr(4, "ConcatStr", OpcodeCategory::String),
+            make_instr(5, "CStr2Uni", OpcodeCategory::String),
+            with_operand(
+                make_instr(6, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("rtcMsgBox".to_string()),
+            ),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let statements = &function.basic_blocks[0].statements;
+
+        assert_eq!(statements.len(), 1);
+        assert_eq!(
+            statements[0].to_vb_string(),
+            "MsgBox (\"Hello\" & \" World\")"
+        );
+    }
+
+    #[test]
+    fn test_prologue_suppressed() {
+        // LargeBos; FLdZeroAd 8; ImpAdLdRf 1; OnErrorResumeNext;
+        // LitI2 1, LitI2 10, Redim slot 4 (1 dimension); ExitProc
+        let data = vec![
+            0x00, 0x00, 0x00, 0x3E, 0x08, 0x05, 0x01, 0x00, 0x4C, 0x5E, 0x01, 0x5E, 0x0A, 0xFC,
+            0x0D, 0x04, 0x00, 0x01, 0x14,
+        ];
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+        assert_eq!(prologue_len(&instructions), 4);
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let lines: Vec<_> = function.basic_blocks[0]
+            .statements
+            .iter()
+            .map(|stmt| stmt.to_vb_string())
+            .collect();
+
+        assert_eq!(lines[0], "On Error Resume Next");
+        assert_eq!(lines[1], "ReDim local4(1 To 10)");
+        assert!(lines.iter().all(|line| !line.contains("func_")));
+    }
+
+    #[test]
+    fn test_lift_on_error_goto() {
+        // OnErrorGoto +1 (handler at 4); ExitProc; handler: ExitProc
+        let data = vec![0x4B, 0x01, 0x00, 0x14, 0x14];
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let handler = function.basic_blocks[1].id;
+
+        assert_eq!(
+            function.basic_blocks[0].statements[0].to_vb_string(),
+            format!("On Error Goto Block{}", handler)
+        );
+        assert!(function.basic_blocks[1].is_error_handler);
+    }
+
+    #[test]
+    fn test_lift_error_handler_with_resume() {
+        // OnErrorGoto +1 (handler at 4); 3: ExitProc; 4: ResumeLabel -4 (back to 3)
+        let data = vec![0x4B, 0x01, 0x00, 0x14, 0x4E, 0xFC, 0xFF];
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+
+        let handler = function
+            .basic_blocks
+            .iter()
+            .find(|b| b.is_error_handler)
+            .unwrap();
+        let exit = function
+            .basic_blocks
+            .iter()
+            .find(|b| {
+                matches!(
+                    b.statements[..],
+                    [Statement {
+                        kind: StatementKind::Return,
+                        ..
+                    }]
+                )
+            })
+            .unwrap();
+
+        // The handler after ExitProc is lifted, not dropped as dead code
+        assert_eq!(
+            function.basic_blocks[0].statements[0].label_target(),
+            Some(handler.id)
+        );
+        assert_eq!(
+            handler.statements[0].to_vb_string(),
+            format!("Resume Block{}", exit.id)
+        );
+        assert!(handler.successors.is_empty());
+
+        let code = crate::codegen::VB6CodeGenerator::new().generate_function(&function);
+        assert!(
+            code.contains(&format!("On Error GoTo Block{}\n", handler.id)),
+            "{}",
+            code
+        );
+        assert!(
+            code.contains(&format!("Block{}:\n", handler.id)),
+            "{}",
+            code
+        );
+        assert!(code.contains(&format!("Block{}:\n", exit.id)), "{}", code);
+        assert!(
+            code.contains(&format!("Resume Block{}\n", exit.id)),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn test_lift_resume_next() {
+        // OnErrorGoto +1 (handler at 4); 3: ExitProc; 4: ResumeNext
+        let data = vec![0x4B, 0x01, 0x00, 0x14, 0x4D];
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+
+        let function = PCodeLifter::new()
+            .lift(&instructions, "test".to_string(), 0)
+            .unwrap();
+        let handler = function.basic_blocks.iter().find(|b| b.is_error_handler);
+
+        assert_eq!(handler.unwrap().statements[0].to_vb_string(), "Resume Next");
+    }
+
+    #[test]
+    fn test_lift_call_arguments() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(1),
+            ),
+            with_operand(
+                make_instr(1, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("two".to_string()),
+            ),
+            with_operand(
+                make_instr(2, "CallHresult", OpcodeCategory::Call),
+                OperandValue::Int16(2),
+            ),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+
+        match &function.basic_blocks[0].statements[0].data {
+            StatementData::Call { arguments, .. } => {
+                assert_eq!(arguments.len(), 2);
+                assert_eq!(arguments[0].to_vb_string(), "1");
+                assert_eq!(arguments[1].to_vb_string(), "\"two\"");
+            }
+            other => panic!("expected call, got {:?}", other),
+        }
+        assert!(lifter.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_lift_vcall_arguments() {
+        let object = || {
+            with_operand(
+                make_instr(0, "FLdAd", OpcodeCategory::Variable),
+                OperandValue::Int16(-8),
+            )
+        };
+        let lit = |value| {
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(value),
+            )
+        };
+        let mut typed_call = with_operand(
+            make_instr(0, "VCallI4", OpcodeCategory::Call),
+            OperandValue::Int16(0x1C),
+        );
+        typed_call.stack_delta = 1;
+        // local1.func_32 1, "two" then local2 = 5 + local1.func_28(3)
+        let mut instructions = vec![
+            object(),
+            lit(1),
+            with_operand(
+                make_instr(0, "LitStr", OpcodeCategory::Stack),
+                OperandValue::String("two".to_string()),
+            ),
+            with_operand(
+                make_instr(0, "VCallHresult", OpcodeCategory::Call),
+                OperandValue::Int16(0x20),
+            ),
+            lit(5),
+            object(),
+            lit(3),
+            typed_call,
+            make_instr(0, "AddI4", OpcodeCategory::Arithmetic),
+            with_operand(
+                make_instr(0, "FStI4", OpcodeCategory::Variable),
+                OperandValue::Int16(-12),
+            ),
+        ];
+        for (address, instr) in instructions.iter_mut().enumerate() {
+            instr.address = address as u32;
+        }
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let statements = &function.basic_blocks[0].statements;
+
+        match &statements[0].data {
+            StatementData::MethodCall { arguments, .. } => {
+                assert_eq!(arguments.len(), 2);
+                assert_eq!(arguments[0].to_vb_string(), "1");
+                assert_eq!(arguments[1].to_vb_string(), "\"two\"");
+            }
+            other => panic!("expected method call, got {:?}", other),
+        }
+        assert_eq!(statements[0].to_vb_string(), "local1.func_32 1, \"two\"");
+
+        // Only the values above the object are arguments; the call stays a
+        // structured member access on it
+        match &statements[1].data {
+            StatementData::Assign { value, .. } => match &value.data {
+                ExpressionData::Binary { left, right } => {
+                    assert_eq!(left.to_vb_string(), "5");
+                    match &right.data {
+                        ExpressionData::MethodCall { target, arguments } => {
+                            assert_eq!(target.kind, ExpressionKind::MemberAccess);
+                            assert_eq!(arguments.len(), 1);
+                        }
+                        other => panic!("expected method call, got {:?}", other),
+                    }
+                }
+                other => panic!("expected addition, got {:?}", other),
+            },
+            other => panic!("expected assignment, got {:?}", other),
+        }
+        assert_eq!(
+            statements[1].to_vb_string(),
+            "local2 = (5 + local1.func_28(3))"
+        );
+    }
+
+    #[test]
+    fn test_lift_call_argument_underflow() {
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(1),
+            ),
+            with_operand(
+                make_instr(1, "CallHresult", OpcodeCategory::Call),
+                OperandValue::Int16(3),
+            ),
+        ];
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+
+        match &function.basic_blocks[0].statements[0].data {
+            StatementData::Call { arguments, .. } => assert_eq!(arguments.len(), 1),
+            other => panic!("expected call, got {:?}", other),
+        }
+        assert_eq!(lifter.warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_lift_redim_preserve() {
+        // LitI2 1, LitI2 10, RedimPreserve slot 4 (1 dimension), ExitProc
+        let data = vec![0x5E, 0x01, 0x5E, 0x0A, 0xFC, 0x0E, 0x04, 0x00, 0x01, 0x14];
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+
+        let mut lifter = PCodeLifter::new();
+        let function = lifter.lift(&instructions, "test".to_string(), 0).unwrap();
+        let stmt = &function.basic_blocks[0].statements[0];
+
+        match &stmt.data {
+            StatementData::ReDim {
+                array,
+                bounds,
+                preserve,
+            } => {
+                assert_eq!(array.var_type, TypeKind::Array);
+                assert_eq!(bounds.len(), 1);
+                assert!(*preserve);
+            }
+            other => panic!("expected ReDim, got {:?}", other),
+        }
+        let code = crate::codegen::VB6CodeGenerator::new().generate_statement(stmt);
+        assert_eq!(code, "ReDim Preserve local4(1 To 10)\n");
+    }
+
+    /// Lift `data`, infer types and generate VB6
+    fn generate(data: Vec<u8>) -> String {
+        let instructions = crate::pcode::Disassembler::new(data)
+            .disassemble(0)
+            .unwrap();
+        let mut function = PCodeLifter::new()
+            .lift(&instructions, "Test".to_string(), 0)
+            .unwrap();
+        crate::types::infer_types(&mut function);
+        crate::codegen::VB6CodeGenerator::new().generate_function(&function)
+    }
+
+    #[test]
+    fn test_lift_array_access() {
+        // LitI2 5; FLdRfVar -4; LitI2 3; Ary1StI2
+        // FLdRfVar -4; LitI2 3; Ary1LdI2; FStI2 -8; ExitProc
+        let code = generate(vec![
+            0x5E, 0x05, 0x04, 0xFC, 0x5E, 0x03, 0xE5, 0x04, 0xFC, 0x5E, 0x03, 0xE8, 0x6D, 0xF8,
+            0x14,
+        ]);
+
+        assert!(code.contains("Dim local1() As Integer"), "{}", code);
+        assert!(code.contains("local1(3) = 5\n"), "{}", code);
+        assert!(code.contains("local2 = local1(3)\n"), "{}", code);
+    }
+
+    #[test]
+    fn test_lift_two_dimensional_array_access() {
+        // FLdRfVar -4; LitI2 1; LitI2 2; AryLdPr 2; FStI2 -8; ExitProc
+        let code = generate(vec![
+            0x04, 0xFC, 0x5E, 0x01, 0x5E, 0x02, 0xEE, 0x02, 0x00, 0x6D, 0xF8, 0x14,
+        ]);
+
+        assert!(code.contains("Dim local1() As Variant"), "{}", code);
+        assert!(code.contains("local2 = local1(1, 2)\n"), "{}", code);
+    }
+
+    #[test]
+    fn test_lift_members_of_me() {
+        // FLdPrThis; MemLdI4 0x34; LitI4 1; AddI4
+        // FLdPrThis; MemStI4 0x34; ExitProc
+        let code = generate(vec![
+            0x62, 0x44, 0x34, 0x00, 0x5F, 0x01, 0x00, 0x00, 0x00, 0x82, 0x62, 0x48, 0x34, 0x00,
+            0x14,
+        ]);
+
+        assert!(code.contains("    Me.m_34 = Me.m_34 + 1\n"), "{}", code);
+        assert!(!code.contains("Dim Me"), "{}", code);
+
+        // LitI4 1; MemLdI4 0x34; AddI4; LitI4 2; MemStI4 0x38; ExitProc
+        // with no object pushed, leaving the literals in place
+        let code = generate(vec![
+            0x5F, 0x01, 0x00, 0x00, 0x00, 0x44, 0x34, 0x00, 0x82, 0x5F, 0x02, 0x00, 0x00, 0x00,
+            0x48, 0x38, 0x00, 0x14,
+        ]);
+        assert!(code.contains("    Me.m_38 = 2\n"), "{}", code);
+        assert!(!code.contains("1.m_34"), "{}", code);
+    }
+
+    /// LitI2 1; FStI2 -4; LitI2 2; FStI2 -8; FLdI2 8; FStI2 -12; ExitProc
+    const FRAME_PCODE: [u8; 13] = [
+        0x5E, 0x01, 0x6D, 0xFC, 0x5E, 0x02, 0x6D, 0xF8, 0x69, 0x08, 0x6D, 0xF4, 0x14,
+    ];
+
+    #[test]
+    fn test_parameter_passing_modes() {
+        let print = |address| {
+            with_operand(
+                make_instr(address, "ImpAdCallFPR4", OpcodeCategory::Call),
+                OperandValue::String("__vbaPrintObj".to_string()),
+            )
+        };
+        // LitI2 0; FStI2 8; FLdI2 8; Debug.Print; FLdRfVar 12; Debug.Print;
+        // FLdI2 16; Debug.Print
+        let instructions = vec![
+            with_operand(
+                make_instr(0, "LitI2", OpcodeCategory::Stack),
+                OperandValue::Int16(0),
+            ),
+            with_operand(
+                make_instr(1, "FStI2", OpcodeCategory::Variable),
+                OperandValue::Byte(8),
+            ),
+            with_operand(
+                make_instr(2, "FLdI2", OpcodeCategory::Variable),
+                OperandValue::Byte(8),
+            ),
+            print(3),
+            with_operand(
+                make_instr(4, "FLdRfVar", OpcodeCategory::Variable),
+                OperandValue::Byte(12),
+            ),
+            print(5),
+            with_operand(
+                make_instr(6, "FLdI2", OpcodeCategory::Variable),
+                OperandValue::Byte(16),
+            ),
+            print(7),
+        ];
+
+        let function = PCodeLifter::new()
+            .lift(&instructions, "Test".to_string(), 0)
+            .unwrap();
+        assert_eq!(
+            function.parameter_mode(&function.parameters[0]),
+            PassingMode::ByVal
+        );
+        assert_eq!(
+            function.parameter_mode(&function.parameters[1]),
+            PassingMode::ByRef
+        );
+        // Only read: could be either, so VB's default
+        assert_eq!(
+            function.parameter_mode(&function.parameters[2]),
+            PassingMode::ByRef
+        );
+
+        let code = crate::codegen::VB6CodeGenerator::new().generate_function(&function);
+        assert!(
+            code.starts_with(
+                "Function Test(ByVal arg1 As Integer, ByRef arg2 As Variant, ByRef arg3 As Integer)"
+            ),
+            "{}",
+            code
+        );
+    }
+
+    #[test]
+    fn test_read_only_parameter_is_byref() {
+        // FLdI2 8; FStI2 -4; ExitProc
+        let instructions = crate::pcode::Disassembler::new(vec![0x69, 0x08, 0x6D, 0xFC, 0x14])
+            .disassemble(0)
+            .unwrap();
+        let function = PCodeLifter::new()
+            .lift(&instructions, "Test".to_string(), 0)
+            .unwrap();
+
+        assert_eq!(
+            function.parameter_mode(&function.parameters[0]),
+            PassingMode::ByRef
+        );
+    }
+
+    #[test]
+    fn test_recover_frame_locals() {
+        let instructions = crate::pcode::Disassembler::new(FRAME_PCODE.to_vec())
+            .disassemble(0)
+            .unwrap();
+        let mut lifter = PCodeLifter::new().with_frame_size(12);
+        let function = lifter.lift(&instructions, "Test".to_string(), 0).unwrap();
+
+        assert_eq!(function.parameters.len(), 1);
+        assert_eq!(function.parameters[0].name, "arg1");
+        assert_eq!(function.local_variables.len(), 3);
+        assert!(lifter.warnings().is_empty());
+
+        let code = crate::codegen::VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("Test(ByRef arg1 As Integer)"), "{}", code);
+        assert!(code.contains("Dim local1 As Integer"), "{}", code);
+        assert!(code.contains("Dim local2 As Integer"), "{}", code);
+        assert!(code.contains("local3 = arg1"), "{}", code);
+    }
+
+    #[test]
+    fn test_recover_frame_outside_frame_size() {
+        let instructions = crate::pcode::Disassembler::new(FRAME_PCODE.to_vec())
+            .disassemble(0)
+            .unwrap();
+        let mut lifter = PCodeLifter::new().with_frame_size(4);
+        lifter.lift(&instructions, "Test".to_string(), 0).unwrap();
+
+        assert_eq!(lifter.warnings().len(), 2);
+    }
+
     #[test]
     fn test_pcode_type_conversion() {
         assert_eq!(pcode_type_to_ir_type(PCodeType::Byte), TypeKind::Byte);
@@ -0,0 +1,815 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! IR optimization passes
+//!
+//! Simplifications applied to lifted functions before code generation.
+//! Every pass preserves VB6 semantics: an expression that would raise a
+//! runtime error (overflow, division by zero) is left for the program to
+//! evaluate.
+
+use crate::ir::*;
+use std::collections::HashSet;
+
+/// Fold operations on constants throughout `function`
+///
+/// `(3 * (4 + 5))` becomes `27` and `"a" & "b"` becomes `"ab"`. Integer
+/// results must fit the expression's type (`Integer`, `Long`, `Byte`; `Long`
+/// otherwise), and `\` and `Mod` truncate toward zero as in VB. Subtrees that
+/// involve anything but constants are left untouched.
+pub fn fold_constants(function: &mut Function) {
+    for block in &mut function.basic_blocks {
+        fold_statements(&mut block.statements);
+    }
+}
+
+/// Drop basic blocks that control can never reach, then merge straight-line chains
+///
+/// Reachability follows `successors` from the entry block and from every
+/// block named by `On Error GoTo` or `Resume` (or flagged as an error
+/// handler); predecessor lists are pruned of removed blocks.
+/// A block whose only successor has no other predecessor then absorbs that
+/// successor, dropping the `GoTo` between them. Meant to run on lifted
+/// functions, before structuring.
+pub fn remove_unreachable_blocks(function: &mut Function) {
+    let handlers = label_targets(function);
+
+    let mut reachable = HashSet::new();
+    let mut worklist: Vec<u32> = std::iter::once(function.entry_block_id)
+        .chain(handlers.iter().copied())
+        .collect();
+    while let Some(id) = worklist.pop() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        if let Some(block) = function.get_block(id) {
+            worklist.extend(block.successors.iter().copied());
+        }
+    }
+
+    function
+        .basic_blocks
+        .retain(|block| reachable.contains(&block.id));
+    for block in &mut function.basic_blocks {
+        block.predecessors.retain(|id| reachable.contains(id));
+    }
+
+    while let Some((a, b)) = find_mergeable_pair(function, &handlers) {
+        merge_blocks(function, a, b);
+    }
+}
+
+/// Blocks entered by label rather than by a control-flow edge
+fn label_targets(function: &Function) -> HashSet<u32> {
+    let blocks = &function.basic_blocks;
+    blocks
+        .iter()
+        .flat_map(|block| &block.statements)
+        .filter_map(Statement::label_target)
+        .chain(blocks.iter().filter(|b| b.is_error_handler).map(|b| b.id))
+        .collect()
+}
+
+/// Find block indices `(a, b)` where `b` can be appended to `a`
+fn find_mergeable_pair(function: &Function, handlers: &HashSet<u32>) -> Option<(usize, usize)> {
+    let blocks = &function.basic_blocks;
+    blocks.iter().enumerate().find_map(|(a, block)| {
+        let [next] = block.successors[..] else {
+            return None;
+        };
+        if next == block.id || next == function.entry_block_id || handlers.contains(&next) {
+            return None;
+        }
+        // A two-way branch whose arms coincide still evaluates its condition
+        if let Some(StatementData::Branch { .. }) = block.statements.last().map(|s| &s.data) {
+            return None;
+        }
+
+        let b = blocks.iter().position(|candidate| candidate.id == next)?;
+        if blocks[b].predecessors != [block.id] {
+            return None;
+        }
+        // A successor that falls through relies on its position in the layout
+        let falls_through = !matches!(
+            blocks[b].statements.last().map(|s| &s.data),
+            Some(
+                StatementData::Goto { .. }
+                    | StatementData::Return { .. }
+                    | StatementData::Resume { .. }
+            )
+        );
+        if falls_through && !blocks[b].successors.is_empty() && b != a + 1 {
+            return None;
+        }
+        Some((a, b))
+    })
+}
+
+/// Append block `b` to block `a` and remove `b`
+fn merge_blocks(function: &mut Function, a: usize, b: usize) {
+    let absorbed = function.basic_blocks.remove(b);
+    let a = if b < a { a - 1 } else { a };
+    let target = &mut function.basic_blocks[a];
+    let target_id = target.id;
+
+    if let Some(StatementData::Goto { target_block }) = target.statements.last().map(|s| &s.data) {
+        if *target_block == absorbed.id {
+            target.statements.pop();
+        }
+    }
+    target.statements.extend(absorbed.statements);
+    target.successors = absorbed.successors;
+
+    let successors = function.basic_blocks[a].successors.clone();
+    for id in successors {
+        if let Some(block) = function.get_block_mut(id) {
+            for pred in &mut block.predecessors {
+                if *pred == absorbed.id {
+                    *pred = target_id;
+                }
+            }
+            let mut seen = HashSet::new();
+            block.predecessors.retain(|pred| seen.insert(*pred));
+        }
+    }
+}
+
+/// Fold the expressions of `statements`, recursing into nested bodies
+fn fold_statements(statements: &mut [Statement]) {
+    for stmt in statements {
+        match &mut stmt.data {
+            StatementData::None
+            | StatementData::Goto { .. }
+            | StatementData::Label { .. }
+            | StatementData::OnError { .. }
+            | StatementData::Resume { .. } => {}
+            StatementData::Assign { value, .. } => fold_expression(value),
+            StatementData::Store { address, value } => {
+                fold_expression(address);
+                fold_expression(value);
+            }
+            StatementData::Call { arguments, .. } => arguments.iter_mut().for_each(fold_expression),
+            StatementData::MethodCall { target, arguments } => {
+                fold_expression(target);
+                arguments.iter_mut().for_each(fold_expression);
+            }
+            StatementData::Return { value } => {
+                if let Some(value) = value {
+                    fold_expression(value);
+                }
+            }
+            StatementData::Branch { condition, .. } => fold_expression(condition),
+            StatementData::ForLoop {
+                start,
+                end,
+                step,
+                body,
+                ..
+            } => {
+                fold_expression(start);
+                fold_expression(end);
+                if let Some(step) = step {
+                    fold_expression(step);
+                }
+                fold_statements(body);
+            }
+            StatementData::WhileLoop {
+                condition, body, ..
+            } => {
+                fold_expression(condition);
+                fold_statements(body);
+            }
+            StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            } => {
+                fold_expression(condition);
+                fold_statements(then_body);
+                fold_statements(else_body);
+            }
+            StatementData::ReDim { bounds, .. } => {
+                for (lower, upper) in bounds {
+                    fold_expression(lower);
+                    fold_expression(upper);
+                }
+            }
+            StatementData::SelectCase {
+                subject,
+                cases,
+                else_body,
+            } => {
+                fold_expression(subject);
+                for (value, body) in cases {
+                    fold_expression(value);
+                    fold_statements(body);
+                }
+                fold_statements(else_body);
+            }
+            StatementData::With { body, .. } => fold_statements(body),
+        }
+    }
+}
+
+/// Fold `expr` bottom-up
+pub fn fold_expression(expr: &mut Expression) {
+    match &mut expr.data {
+        ExpressionData::None | ExpressionData::Constant(_) | ExpressionData::Variable(_) => return,
+        ExpressionData::Unary(operand) => fold_expression(operand),
+        ExpressionData::Binary { left, right } => {
+            fold_expression(left);
+            fold_expression(right);
+        }
+        ExpressionData::Call { arguments, .. } => arguments.iter_mut().for_each(fold_expression),
+        ExpressionData::MethodCall { target, arguments } => {
+            fold_expression(target);
+            arguments.iter_mut().for_each(fold_expression);
+        }
+        ExpressionData::MemberAccess { object, .. } => fold_expression(object),
+        ExpressionData::ArrayIndex { array, indices } => {
+            fold_expression(array);
+            indices.iter_mut().for_each(fold_expression);
+        }
+        ExpressionData::Cast { expr, .. } => fold_expression(expr),
+    }
+
+    let folded = match &expr.data {
+        ExpressionData::Unary(operand) => match &operand.data {
+            ExpressionData::Constant(value) => fold_unary(expr.kind, value, &expr.expr_type),
+            _ => None,
+        },
+        ExpressionData::Binary { left, right } => match (&left.data, &right.data) {
+            (ExpressionData::Constant(l), ExpressionData::Constant(r)) => {
+                fold_binary(expr.kind, l, r, &expr.expr_type)
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(value) = folded {
+        let expr_type = match &value {
+            ConstantValue::Boolean(_) => Type::new(TypeKind::Boolean),
+            ConstantValue::String(_) => Type::new(TypeKind::String),
+            ConstantValue::Float(_) if expr.kind == ExpressionKind::Divide => {
+                Type::new(TypeKind::Double)
+            }
+            _ => expr.expr_type.clone(),
+        };
+        *expr = Expression::constant(value, expr_type);
+    }
+}
+
+/// Range of integer results for an expression of type `ty`
+fn integer_range(ty: &Type) -> (i64, i64) {
+    match ty.kind {
+        TypeKind::Byte => (0, u8::MAX as i64),
+        TypeKind::Integer => (i16::MIN as i64, i16::MAX as i64),
+        _ => (i32::MIN as i64, i32::MAX as i64),
+    }
+}
+
+/// Integer result, or `None` if VB would raise an overflow
+fn checked_integer(value: Option<i64>, ty: &Type) -> Option<ConstantValue> {
+    let (min, max) = integer_range(ty);
+    value
+        .filter(|v| (min..=max).contains(v))
+        .map(ConstantValue::Integer)
+}
+
+fn fold_unary(kind: ExpressionKind, value: &ConstantValue, ty: &Type) -> Option<ConstantValue> {
+    match (kind, value) {
+        (ExpressionKind::Negate, ConstantValue::Integer(v)) => checked_integer(v.checked_neg(), ty),
+        (ExpressionKind::Negate, ConstantValue::Float(v)) => Some(ConstantValue::Float(-v)),
+        (ExpressionKind::Negate, ConstantValue::Currency(v)) => {
+            v.checked_neg().map(ConstantValue::Currency)
+        }
+        (ExpressionKind::Not, ConstantValue::Boolean(b)) => Some(ConstantValue::Boolean(!b)),
+        (ExpressionKind::Not, ConstantValue::Integer(v)) => checked_integer(Some(!v), ty),
+        _ => None,
+    }
+}
+
+fn fold_binary(
+    kind: ExpressionKind,
+    left: &ConstantValue,
+    right: &ConstantValue,
+    ty: &Type,
+) -> Option<ConstantValue> {
+    use ConstantValue::{Boolean, Float, Integer};
+    use ExpressionKind as K;
+
+    match (kind, left, right) {
+        // String concatenation (`+` concatenates two strings too)
+        (K::Concatenate | K::Add, ConstantValue::String(l), ConstantValue::String(r)) => {
+            Some(ConstantValue::String(format!("{}{}", l, r)))
+        }
+        (K::Concatenate, l, r) => Some(ConstantValue::String(format!(
+            "{}{}",
+            concat_text(l)?,
+            concat_text(r)?
+        ))),
+
+        // Integer arithmetic
+        (K::Add, Integer(l), Integer(r)) => checked_integer(l.checked_add(*r), ty),
+        (K::Subtract, Integer(l), Integer(r)) => checked_integer(l.checked_sub(*r), ty),
+        (K::Multiply, Integer(l), Integer(r)) => checked_integer(l.checked_mul(*r), ty),
+        (K::IntDivide, Integer(l), Integer(r)) => checked_integer(l.checked_div(*r), ty),
+        (K::Modulo, Integer(l), Integer(r)) => checked_integer(l.checked_rem(*r), ty),
+        (K::Divide, Integer(_) | Float(_), Integer(_) | Float(_)) => {
+            let (l, r) = (as_float(left)?, as_float(right)?);
+            (r != 0.0).then(|| Float(l / r))
+        }
+
+        // Floating-point arithmetic
+        (K::Add | K::Subtract | K::Multiply, Integer(_) | Float(_), Integer(_) | Float(_)) => {
+            let (l, r) = (as_float(left)?, as_float(right)?);
+            Some(Float(match kind {
+                K::Add => l + r,
+                K::Subtract => l - r,
+                _ => l * r,
+            }))
+        }
+
+        // Comparisons
+        (K::Equal, l, r) => compare(l, r).map(|o| Boolean(o.is_eq())),
+        (K::NotEqual, l, r) => compare(l, r).map(|o| Boolean(o.is_ne())),
+        (K::LessThan, l, r) => compare(l, r).map(|o| Boolean(o.is_lt())),
+        (K::LessEqual, l, r) => compare(l, r).map(|o| Boolean(o.is_le())),
+        (K::GreaterThan, l, r) => compare(l, r).map(|o| Boolean(o.is_gt())),
+        (K::GreaterEqual, l, r) => compare(l, r).map(|o| Boolean(o.is_ge())),
+
+        // Logical on Booleans, bitwise on integers
+        (K::And, Boolean(l), Boolean(r)) => Some(Boolean(*l && *r)),
+        (K::Or, Boolean(l), Boolean(r)) => Some(Boolean(*l || *r)),
+        (K::Xor, Boolean(l), Boolean(r)) => Some(Boolean(l != r)),
+        (K::And, Integer(l), Integer(r)) => checked_integer(Some(l & r), ty),
+        (K::Or, Integer(l), Integer(r)) => checked_integer(Some(l | r), ty),
+        (K::Xor, Integer(l), Integer(r)) => checked_integer(Some(l ^ r), ty),
+
+        _ => None,
+    }
+}
+
+/// Text a constant contributes to `&`, for the values whose VB formatting is
+/// unambiguous
+fn concat_text(value: &ConstantValue) -> Option<String> {
+    match value {
+        ConstantValue::String(s) => Some(s.clone()),
+        ConstantValue::Integer(v) => Some(v.to_string()),
+        ConstantValue::Boolean(b) => Some(if *b { "True" } else { "False" }.to_string()),
+        _ => None,
+    }
+}
+
+fn as_float(value: &ConstantValue) -> Option<f64> {
+    match value {
+        ConstantValue::Integer(v) => Some(*v as f64),
+        ConstantValue::Float(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Order two constants of comparable kinds (numbers or strings, binary compare)
+fn compare(left: &ConstantValue, right: &ConstantValue) -> Option<std::cmp::Ordering> {
+    match (left, right) {
+        (ConstantValue::Integer(l), ConstantValue::Integer(r)) => Some(l.cmp(r)),
+        (ConstantValue::String(l), ConstantValue::String(r)) => Some(l.cmp(r)),
+        _ => as_float(left)?.partial_cmp(&as_float(right)?),
+    }
+}
+
+/// Group runs of statements working on the same object into `With` blocks
+///
+/// At least two consecutive simple statements must access members of one
+/// variable, and mention it nowhere else: a statement that assigns the
+/// variable or passes it along (which could reassign it `ByRef`) ends the
+/// run, since `With` evaluates its object only once. Recurses into nested
+/// bodies. Meant to run after structuring.
+pub fn introduce_with_blocks(function: &mut Function) {
+    for block in &mut function.basic_blocks {
+        collapse_with_runs(&mut block.statements);
+    }
+}
+
+/// Rewrite the `With` runs of one statement list, recursing into nested bodies
+fn collapse_with_runs(statements: &mut Vec<Statement>) {
+    let mut i = 0;
+    while i < statements.len() {
+        match &mut statements[i].data {
+            StatementData::ForLoop { body, .. } | StatementData::WhileLoop { body, .. } => {
+                collapse_with_runs(body)
+            }
+            StatementData::If {
+                then_body,
+                else_body,
+                ..
+            } => {
+                collapse_with_runs(then_body);
+                collapse_with_runs(else_body);
+            }
+            StatementData::SelectCase {
+                cases, else_body, ..
+            } => {
+                for (_, body) in cases.iter_mut() {
+                    collapse_with_runs(body);
+                }
+                collapse_with_runs(else_body);
+            }
+            _ => {}
+        }
+
+        // Longest run starting here, over every object this statement uses
+        let best = member_objects(&statements[i])
+            .into_iter()
+            .map(|object| {
+                let len = statements[i..]
+                    .iter()
+                    .take_while(|stmt| with_uses(stmt, &object).is_some_and(|n| n > 0))
+                    .count();
+                (len, object)
+            })
+            .max_by_key(|(len, _)| *len);
+
+        if let Some((len, object)) = best.filter(|(len, _)| *len >= 2) {
+            let body = statements.drain(i..i + len).collect();
+            statements.insert(i, Statement::with_block(object, body));
+        }
+        i += 1;
+    }
+}
+
+/// Expressions evaluated by a statement that may join a `With` block
+fn with_operands(stmt: &Statement) -> Option<Vec<&Expression>> {
+    match &stmt.data {
+        StatementData::Assign { value, .. } => Some(vec![value]),
+        StatementData::Store { address, value } => Some(vec![address, value]),
+        StatementData::Call { arguments, .. } => Some(arguments.iter().collect()),
+        StatementData::MethodCall { target, arguments } => {
+            Some(std::iter::once(target).chain(arguments).collect())
+        }
+        _ => None,
+    }
+}
+
+/// Variables whose members `stmt` accesses, in order of first use
+fn member_objects(stmt: &Statement) -> Vec<Variable> {
+    let mut objects = Vec::new();
+    for expr in with_operands(stmt).unwrap_or_default() {
+        collect_member_objects(expr, &mut objects);
+    }
+    objects
+}
+
+fn collect_member_objects(expr: &Expression, objects: &mut Vec<Variable>) {
+    match &expr.data {
+        ExpressionData::None | ExpressionData::Constant(_) | ExpressionData::Variable(_) => {}
+        ExpressionData::Unary(operand) => collect_member_objects(operand, objects),
+        ExpressionData::Binary { left, right } => {
+            collect_member_objects(left, objects);
+            collect_member_objects(right, objects);
+        }
+        ExpressionData::Call { arguments, .. } => {
+            for arg in arguments {
+                collect_member_objects(arg, objects);
+            }
+        }
+        ExpressionData::MethodCall { target, arguments } => {
+            collect_member_objects(target, objects);
+            for arg in arguments {
+                collect_member_objects(arg, objects);
+            }
+        }
+        ExpressionData::MemberAccess { object, .. } => match &object.data {
+            ExpressionData::Variable(var) => {
+                if !objects.contains(var) {
+                    objects.push(var.clone());
+                }
+            }
+            _ => collect_member_objects(object, objects),
+        },
+        ExpressionData::ArrayIndex { array, indices } => {
+            collect_member_objects(array, objects);
+            for index in indices {
+                collect_member_objects(index, objects);
+            }
+        }
+        ExpressionData::Cast { expr, .. } => collect_member_objects(expr, objects),
+    }
+}
+
+/// Number of member accesses on `object` in `stmt`, or `None` if `stmt`
+/// cannot sit inside a `With object` block
+fn with_uses(stmt: &Statement, object: &Variable) -> Option<usize> {
+    if let StatementData::Assign { target, .. } = &stmt.data {
+        if target == object {
+            return None;
+        }
+    }
+    let mut uses = 0;
+    for expr in with_operands(stmt)? {
+        count_member_uses(expr, object, &mut uses)?;
+    }
+    Some(uses)
+}
+
+/// Count member accesses on `object`; `None` if it is used any other way
+fn count_member_uses(expr: &Expression, object: &Variable, uses: &mut usize) -> Option<()> {
+    match &expr.data {
+        ExpressionData::None | ExpressionData::Constant(_) => {}
+        ExpressionData::Variable(var) => {
+            if var == object {
+                return None;
+            }
+        }
+        ExpressionData::Unary(operand) => count_member_uses(operand, object, uses)?,
+        ExpressionData::Binary { left, right } => {
+            count_member_uses(left, object, uses)?;
+            count_member_uses(right, object, uses)?;
+        }
+        ExpressionData::Call { arguments, .. } => {
+            for arg in arguments {
+                count_member_uses(arg, object, uses)?;
+            }
+        }
+        ExpressionData::MethodCall { target, arguments } => {
+            count_member_uses(target, object, uses)?;
+            for arg in arguments {
+                count_member_uses(arg, object, uses)?;
+            }
+        }
+        ExpressionData::MemberAccess { object: inner, .. } => match &inner.data {
+            ExpressionData::Variable(var) if var == object => *uses += 1,
+            _ => count_member_uses(inner, object, uses)?,
+        },
+        ExpressionData::ArrayIndex { array, indices } => {
+            count_member_uses(array, object, uses)?;
+            for index in indices {
+                count_member_uses(index, object, uses)?;
+            }
+        }
+        ExpressionData::Cast { expr, .. } => count_member_uses(expr, object, uses)?,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int_type() -> Type {
+        Type::new(TypeKind::Integer)
+    }
+
+    fn binary(kind: ExpressionKind, left: Expression, right: Expression) -> Expression {
+        Expression::binary(kind, left, right, int_type())
+    }
+
+    fn folded(mut expr: Expression) -> String {
+        fold_expression(&mut expr);
+        expr.to_vb_string()
+    }
+
+    #[test]
+    fn test_fold_nested_arithmetic() {
+        let sum = Expression::add(
+            Expression::int_const(4),
+            Expression::int_const(5),
+            int_type(),
+        );
+        let product = binary(ExpressionKind::Multiply, Expression::int_const(3), sum);
+
+        let mut expr = product.clone();
+        fold_expression(&mut expr);
+        assert_eq!(expr.kind, ExpressionKind::Constant);
+        assert!(matches!(
+            expr.data,
+            ExpressionData::Constant(ConstantValue::Integer(27))
+        ));
+        assert_eq!(folded(product), "27");
+    }
+
+    #[test]
+    fn test_fold_concatenation() {
+        let concat = Expression::binary(
+            ExpressionKind::Concatenate,
+            Expression::string_const("a".to_string()),
+            Expression::string_const("b".to_string()),
+            Type::new(TypeKind::String),
+        );
+        assert_eq!(folded(concat), "\"ab\"");
+    }
+
+    #[test]
+    fn test_fold_respects_vb_semantics() {
+        // Integer overflow and division by zero are runtime errors in VB
+        let overflow = binary(
+            ExpressionKind::Multiply,
+            Expression::int_const(300),
+            Expression::int_const(300),
+        );
+        assert_eq!(folded(overflow), "(300 * 300)");
+        let by_zero = binary(
+            ExpressionKind::IntDivide,
+            Expression::int_const(1),
+            Expression::int_const(0),
+        );
+        assert_eq!(folded(by_zero), "(1 \\ 0)");
+
+        // `\` truncates toward zero
+        let int_div = binary(
+            ExpressionKind::IntDivide,
+            Expression::int_const(-7),
+            Expression::int_const(2),
+        );
+        assert_eq!(folded(int_div), "-3");
+    }
+
+    #[test]
+    fn test_fold_leaves_variables_alone() {
+        let x = Variable::new(0, "x".to_string(), TypeKind::Integer);
+        let expr = Expression::add(
+            Expression::variable(x),
+            Expression::add(
+                Expression::int_const(1),
+                Expression::int_const(2),
+                int_type(),
+            ),
+            int_type(),
+        );
+        assert_eq!(folded(expr), "(x + 3)");
+    }
+
+    #[test]
+    fn test_fold_call_arguments_in_function() {
+        let mut function = Function::new("Main".to_string(), Type::new(TypeKind::Void));
+        let mut block = BasicBlock::new(0);
+        block.add_statement(Statement::call(
+            "MsgBox".to_string(),
+            vec![Expression::binary(
+                ExpressionKind::Concatenate,
+                Expression::string_const("n=".to_string()),
+                Expression::add(
+                    Expression::int_const(1),
+                    Expression::int_const(1),
+                    int_type(),
+                ),
+                Type::new(TypeKind::String),
+            )],
+        ));
+        function.add_basic_block(block);
+
+        fold_constants(&mut function);
+        assert_eq!(
+            function.basic_blocks[0].statements[0].to_vb_string(),
+            "MsgBox \"n=2\""
+        );
+    }
+
+    fn block(id: u32, statements: Vec<Statement>, successors: &[u32]) -> BasicBlock {
+        let mut block = BasicBlock::new(id);
+        block.statements = statements;
+        block.successors = successors.to_vec();
+        block
+    }
+
+    /// Fill in predecessor lists from the successor lists
+    fn link(function: &mut Function) {
+        let edges: Vec<(u32, u32)> = function
+            .basic_blocks
+            .iter()
+            .flat_map(|b| b.successors.iter().map(move |&s| (b.id, s)))
+            .collect();
+        for (from, to) in edges {
+            function.get_block_mut(to).unwrap().add_predecessor(from);
+        }
+    }
+
+    fn call(name: &str) -> Statement {
+        Statement::call(name.to_string(), vec![])
+    }
+
+    fn ids(function: &Function) -> Vec<u32> {
+        function.basic_blocks.iter().map(|b| b.id).collect()
+    }
+
+    #[test]
+    fn test_remove_dangling_block() {
+        // 0: If c GoTo 2 | 1: A; GoTo 3 | 4: dead (after GoTo) | 2: B | 3: C; Return
+        let cond = Expression::bool_const(true);
+        let mut function = Function::new("F".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(0, vec![Statement::branch(cond, 2)], &[2, 1]));
+        function.add_basic_block(block(1, vec![call("A"), Statement::goto(3)], &[3]));
+        function.add_basic_block(block(4, vec![call("Dead")], &[2]));
+        function.add_basic_block(block(2, vec![call("B")], &[3]));
+        function.add_basic_block(block(3, vec![call("C"), Statement::return_stmt(None)], &[]));
+        link(&mut function);
+
+        remove_unreachable_blocks(&mut function);
+
+        assert_eq!(ids(&function), vec![0, 1, 2, 3]);
+        assert_eq!(function.get_block(2).unwrap().predecessors, vec![0]);
+        assert_eq!(function.get_block(3).unwrap().predecessors, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_merge_straight_line_blocks() {
+        // 0: A; GoTo 2 | 1: On error handler | 2: B; Return
+        let mut function = Function::new("F".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(
+            0,
+            vec![Statement::on_error(Some(1)), call("A"), Statement::goto(2)],
+            &[2],
+        ));
+        function.add_basic_block(block(1, vec![call("Handler")], &[]));
+        function.add_basic_block(block(2, vec![call("B"), Statement::return_stmt(None)], &[]));
+        link(&mut function);
+
+        remove_unreachable_blocks(&mut function);
+
+        // The handler survives although no edge leads to it
+        assert_eq!(ids(&function), vec![0, 1]);
+        let entry = function.get_block(0).unwrap();
+        assert_eq!(entry.statements.len(), 4);
+        assert!(!entry
+            .statements
+            .iter()
+            .any(|s| matches!(s.data, StatementData::Goto { .. })));
+        assert!(entry.successors.is_empty());
+    }
+
+    fn member_call(object: &Variable, member: &str, arguments: Vec<Expression>) -> Statement {
+        let target = Expression::member_access(
+            Expression::variable(object.clone()),
+            member.to_string(),
+            Type::new(TypeKind::Void),
+        );
+        Statement::method_call(target, arguments)
+    }
+
+    fn with_function(statements: Vec<Statement>) -> Function {
+        let mut function = Function::new("F".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(0, statements, &[]));
+        function
+    }
+
+    #[test]
+    fn test_with_block_from_member_accesses() {
+        let form = Variable::new(1, "frm".to_string(), TypeKind::Object);
+        let caption = Expression::member_access(
+            Expression::variable(form.clone()),
+            "Caption".to_string(),
+            Type::new(TypeKind::String),
+        );
+        let mut function = with_function(vec![
+            member_call(&form, "Show", vec![]),
+            Statement::call("MsgBox".to_string(), vec![caption]),
+            member_call(&form, "Move", vec![Expression::int_const(0)]),
+            Statement::return_stmt(None),
+        ]);
+
+        introduce_with_blocks(&mut function);
+
+        let statements = &function.basic_blocks[0].statements;
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].kind, StatementKind::With);
+
+        let code = crate::codegen::VB6CodeGenerator::new().generate_function(&function);
+        assert_eq!(code.matches("With frm").count(), 1);
+        assert_eq!(code.matches("End With").count(), 1);
+        assert!(code.contains("        .Show\n"));
+        assert!(code.contains("        MsgBox .Caption\n"));
+        assert!(code.contains("        .Move 0\n"));
+        assert!(!code.contains("frm."));
+    }
+
+    #[test]
+    fn test_with_block_stops_at_reassignment() {
+        let form = Variable::new(1, "frm".to_string(), TypeKind::Object);
+        let other = Variable::new(2, "other".to_string(), TypeKind::Object);
+        let mut function = with_function(vec![
+            member_call(&form, "Show", vec![]),
+            Statement::assign(form.clone(), Expression::variable(other)),
+            member_call(&form, "Hide", vec![]),
+            member_call(&form, "Refresh", vec![]),
+        ]);
+
+        introduce_with_blocks(&mut function);
+
+        // Only the two calls after the assignment share one object
+        let kinds: Vec<_> = function.basic_blocks[0]
+            .statements
+            .iter()
+            .map(|s| s.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StatementKind::Call,
+                StatementKind::Assign,
+                StatementKind::With
+            ]
+        );
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! This module detects common executable packers/compressors used with VB executables.
 //! Detection methods include:
-//! - Section name analysis (UPX, ASPack, PECompact signatures)
+//! - Section name analysis (UPX, ASPack, PECompact, MPRESS, VMProtect, ... signatures)
+//! - Entry point byte signatures (PEiD-style stub patterns)
 //! - Entropy analysis (high entropy indicates compression/encryption)
 //! - Import table characteristics
 //!
@@ -13,8 +14,10 @@
 //! - Themida/WinLicense - Advanced protection
 //! - FSG (Fast Small Good) - Small free packer
 //! - Petite - Fast packer
+//! - MPRESS - Free LZMA-based compressor
+//! - Enigma Protector, VMProtect, Obsidium - Commercial protectors
 
-use goblin::pe::PE;
+use goblin::pe::{section_table::SectionTable, PE};
 use thiserror::Error;
 
 /// Error type for packer detection
@@ -54,6 +57,18 @@ pub enum PackerType {
     /// NSPack
     NSPack,
 
+    /// MPRESS compressor
+    MPRESS,
+
+    /// Enigma Protector
+    Enigma,
+
+    /// VMProtect code virtualizer
+    VMProtect,
+
+    /// Obsidium protector
+    Obsidium,
+
     /// Unknown packer detected via heuristics
     Unknown,
 }
@@ -70,6 +85,10 @@ impl PackerType {
             PackerType::Petite => "Petite",
             PackerType::MEW => "MEW",
             PackerType::NSPack => "NSPack",
+            PackerType::MPRESS => "MPRESS",
+            PackerType::Enigma => "Enigma Protector",
+            PackerType::VMProtect => "VMProtect",
+            PackerType::Obsidium => "Obsidium",
             PackerType::Unknown => "Unknown",
         }
     }
@@ -101,6 +120,18 @@ impl PackerType {
             PackerType::NSPack => {
                 "Use NSPack unpacker or a universal unpacker tool"
             }
+            PackerType::MPRESS => {
+                "MPRESS cannot decompress its own output. Use an MPRESS unpacker or dump the process at the original entry point."
+            }
+            PackerType::Enigma => {
+                "Enigma Protector uses advanced protection. Manual unpacking or specialized tools required."
+            }
+            PackerType::VMProtect => {
+                "VMProtect virtualizes code. Dump the running process after unpacking; virtualized routines cannot be restored."
+            }
+            PackerType::Obsidium => {
+                "Obsidium uses advanced protection. Manual unpacking or specialized tools required."
+            }
             PackerType::Unknown => {
                 "Manual unpacking required. Try universal unpackers like:\n  - UPX\n  - UniversalUnpacker\n  - PE-Bear"
             }
@@ -108,8 +139,15 @@ impl PackerType {
     }
 }
 
+/// Serialized as its display name
+impl serde::Serialize for PackerType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
 /// Packer detection result
-#[derive(Debug)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct PackerDetection {
     /// Detected packer type
     pub packer: PackerType,
@@ -122,11 +160,14 @@ pub struct PackerDetection {
 }
 
 /// Method used to detect packer
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum DetectionMethod {
     /// Section name signature
     SectionName,
 
+    /// Unpacking stub bytes at the entry point
+    EntryPointSignature,
+
     /// High entropy analysis
     Entropy,
 
@@ -137,6 +178,140 @@ pub enum DetectionMethod {
     Combined,
 }
 
+/// How a section name is compared against a packer signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchKind {
+    /// Name starts with the signature
+    Prefix,
+    /// Name equals the signature, ignoring ASCII case
+    Exact,
+}
+
+/// Section name signatures, checked in order
+///
+/// Adding a packer's section name is a one-line change here; both the raw
+/// and the goblin-based detectors use this table.
+const SECTION_SIGNATURES: &[(&str, PackerType, f64, MatchKind)] = &[
+    ("UPX", PackerType::UPX, 0.95, MatchKind::Prefix),
+    (".aspack", PackerType::ASPack, 0.90, MatchKind::Prefix),
+    (".adata", PackerType::ASPack, 0.90, MatchKind::Prefix),
+    ("PEC2", PackerType::PECompact, 0.90, MatchKind::Prefix),
+    ("PECompact", PackerType::PECompact, 0.90, MatchKind::Prefix),
+    (".themida", PackerType::Themida, 0.95, MatchKind::Prefix),
+    (".winlice", PackerType::Themida, 0.95, MatchKind::Prefix),
+    ("FSG!", PackerType::FSG, 0.90, MatchKind::Exact),
+    (".petite", PackerType::Petite, 0.90, MatchKind::Prefix),
+    ("MEW", PackerType::MEW, 0.85, MatchKind::Exact),
+    (".nsp", PackerType::NSPack, 0.85, MatchKind::Prefix),
+    // .MPRESS1 / .MPRESS2
+    (".MPRESS", PackerType::MPRESS, 0.95, MatchKind::Prefix),
+    // .enigma1 / .enigma2
+    (".enigma", PackerType::Enigma, 0.95, MatchKind::Prefix),
+    // .vmp0 / .vmp1 / .vmp2
+    (".vmp", PackerType::VMProtect, 0.95, MatchKind::Prefix),
+    // .obsidium truncated to the 8-byte section name field, or .obsid
+    (".obsid", PackerType::Obsidium, 0.85, MatchKind::Prefix),
+];
+
+/// Look up a (NUL-trimmed) section name in the signature table
+fn match_section_name(name: &str) -> Option<PackerDetection> {
+    SECTION_SIGNATURES
+        .iter()
+        .find(|&&(signature, _, _, kind)| match kind {
+            MatchKind::Prefix => name.starts_with(signature),
+            MatchKind::Exact => name.eq_ignore_ascii_case(signature),
+        })
+        .map(|&(_, packer, confidence, _)| PackerDetection {
+            packer,
+            confidence,
+            method: DetectionMethod::SectionName,
+        })
+}
+
+/// Decode a raw 8-byte section name and match it
+fn match_raw_section_name(name: &[u8]) -> Option<PackerDetection> {
+    let name = String::from_utf8_lossy(name);
+    match_section_name(name.trim_end_matches('\0'))
+}
+
+/// Entry point byte signatures, checked in order
+///
+/// Patterns are PEiD-style hex bytes with `??` wildcards, matched at the
+/// start of the entry point code. They catch stubs whose sections were
+/// renamed.
+const ENTRY_POINT_SIGNATURES: &[(&str, PackerType, f64)] = &[
+    // pushad; mov esi, <packed>; lea edi, [esi-<offset>]; push edi
+    (
+        "60 BE ?? ?? ?? ?? 8D BE ?? ?? ?? ?? 57",
+        PackerType::UPX,
+        0.90,
+    ),
+    // pushad; call $+8; jmp ...; pop ebp; inc ebp; push ebp; ret
+    (
+        "60 E8 03 00 00 00 E9 EB 04 5D 45 55 C3 E8 01",
+        PackerType::ASPack,
+        0.90,
+    ),
+    // mov eax, <handler>; push eax; push fs:[0]; mov fs:[0], esp; xor eax, eax
+    (
+        "B8 ?? ?? ?? ?? 50 64 FF 35 00 00 00 00 64 89 25 00 00 00 00 33 C0 89 08",
+        PackerType::PECompact,
+        0.85,
+    ),
+    // xchg [<table>], esp; popad; xchg eax, esp; push ebp; movsb
+    (
+        "87 25 ?? ?? ?? ?? 61 94 55 A4 B6 80 FF 13",
+        PackerType::FSG,
+        0.90,
+    ),
+    // mov eax, <base>; push <handler>; push fs:[0]; mov fs:[0], esp; pushfw; pushad
+    (
+        "B8 ?? ?? ?? ?? 68 ?? ?? ?? ?? 64 FF 35 00 00 00 00 64 89 25 00 00 00 00 66 9C 60",
+        PackerType::Petite,
+        0.85,
+    ),
+    // pushfd; pushad; call $+5; pop ebp
+    (
+        "9C 60 E8 00 00 00 00 5D B8 07 00 00 00 2B E8 8D B5",
+        PackerType::NSPack,
+        0.85,
+    ),
+    // pushad; call $+5; pop eax; add eax, <offset>; mov esi, [eax]
+    (
+        "60 E8 00 00 00 00 58 05 ?? ?? 00 00 8B 30 03 F0 2B C0 8B FE 66 AD C1 E0 0C",
+        PackerType::MPRESS,
+        0.90,
+    ),
+];
+
+/// Longest entry point signature, in bytes
+const MAX_SIGNATURE_LEN: usize = 32;
+
+/// Check whether `code` starts with a hex byte pattern
+fn matches_signature(code: &[u8], pattern: &str) -> bool {
+    let mut len = 0;
+    for (token, &byte) in pattern.split_whitespace().zip(code) {
+        let matched = token == "??" || u8::from_str_radix(token, 16).ok() == Some(byte);
+        if !matched {
+            return false;
+        }
+        len += 1;
+    }
+    len == pattern.split_whitespace().count()
+}
+
+/// Look up the code at the entry point in the signature table
+fn match_entry_point(code: &[u8]) -> Option<PackerDetection> {
+    ENTRY_POINT_SIGNATURES
+        .iter()
+        .find(|&&(pattern, _, _)| matches_signature(code, pattern))
+        .map(|&(_, packer, confidence)| PackerDetection {
+            packer,
+            confidence,
+            method: DetectionMethod::EntryPointSignature,
+        })
+}
+
 /// High entropy threshold (0-8 scale, 8 = maximum entropy)
 const HIGH_ENTROPY_THRESHOLD: f64 = 7.2;
 
@@ -164,6 +339,11 @@ pub fn detect_packer(pe_data: &[u8]) -> Result<Option<PackerDetection>, PackerEr
         return Ok(Some(detection));
     }
 
+    // Try entry point stub signatures (high confidence)
+    if let Some(detection) = detect_by_entry_point(&pe, pe_data) {
+        return Ok(Some(detection));
+    }
+
     // Try entropy analysis (medium confidence)
     if let Some(detection) = detect_by_entropy(&pe, pe_data) {
         return Ok(Some(detection));
@@ -179,84 +359,21 @@ pub fn detect_packer(pe_data: &[u8]) -> Result<Option<PackerDetection>, PackerEr
 
 /// Detect packer by section names
 fn detect_by_section_names(pe: &PE) -> Option<PackerDetection> {
-    for section in &pe.sections {
-        let name = String::from_utf8_lossy(&section.name);
-        let name_trimmed = name.trim_end_matches('\0');
-
-        // UPX signatures
-        if name_trimmed.starts_with("UPX") {
-            return Some(PackerDetection {
-                packer: PackerType::UPX,
-                confidence: 0.95,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // ASPack signatures
-        if name_trimmed.starts_with(".aspack") || name_trimmed.starts_with(".adata") {
-            return Some(PackerDetection {
-                packer: PackerType::ASPack,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // PECompact signatures
-        if name_trimmed.starts_with("PEC2") || name_trimmed.starts_with("PECompact") {
-            return Some(PackerDetection {
-                packer: PackerType::PECompact,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // Themida/WinLicense signatures
-        if name_trimmed.starts_with(".themida") || name_trimmed.starts_with(".winlice") {
-            return Some(PackerDetection {
-                packer: PackerType::Themida,
-                confidence: 0.95,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // FSG signatures
-        if name_trimmed.eq_ignore_ascii_case("FSG!") {
-            return Some(PackerDetection {
-                packer: PackerType::FSG,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // Petite signatures
-        if name_trimmed.starts_with(".petite") {
-            return Some(PackerDetection {
-                packer: PackerType::Petite,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // MEW signatures
-        if name_trimmed.eq_ignore_ascii_case("MEW") {
-            return Some(PackerDetection {
-                packer: PackerType::MEW,
-                confidence: 0.85,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // NSPack signatures
-        if name_trimmed.starts_with(".nsp") {
-            return Some(PackerDetection {
-                packer: PackerType::NSPack,
-                confidence: 0.85,
-                method: DetectionMethod::SectionName,
-            });
-        }
-    }
+    pe.sections
+        .iter()
+        .find_map(|section| match_raw_section_name(&section.name))
+}
 
-    None
+/// Detect packer by the unpacking stub at the entry point
+fn detect_by_entry_point(pe: &PE, pe_data: &[u8]) -> Option<PackerDetection> {
+    let entry = pe.entry;
+    let section = pe.sections.iter().find(|section| {
+        let size = section.virtual_size.max(section.size_of_raw_data);
+        entry >= section.virtual_address && entry - section.virtual_address < size
+    })?;
+    let data = section_raw_data(section, pe_data)?;
+    let code = data.get((entry - section.virtual_address) as usize..)?;
+    match_entry_point(&code[..code.len().min(MAX_SIGNATURE_LEN)])
 }
 
 /// Detect packer by section names using raw PE parsing
@@ -300,79 +417,8 @@ fn detect_by_section_names_raw(pe_data: &[u8]) -> Option<PackerDetection> {
         }
 
         let section_name = &pe_data[section_offset..section_offset + 8];
-        let name = String::from_utf8_lossy(section_name);
-        let name_trimmed = name.trim_end_matches('\0');
-
-        // UPX signatures
-        if name_trimmed.starts_with("UPX") {
-            return Some(PackerDetection {
-                packer: PackerType::UPX,
-                confidence: 0.95,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // ASPack signatures
-        if name_trimmed.starts_with(".aspack") || name_trimmed.starts_with(".adata") {
-            return Some(PackerDetection {
-                packer: PackerType::ASPack,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // PECompact signatures
-        if name_trimmed.starts_with("PEC2") || name_trimmed.starts_with("PECompact") {
-            return Some(PackerDetection {
-                packer: PackerType::PECompact,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // Themida/WinLicense signatures
-        if name_trimmed.starts_with(".themida") || name_trimmed.starts_with(".winlice") {
-            return Some(PackerDetection {
-                packer: PackerType::Themida,
-                confidence: 0.95,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // FSG signatures
-        if name_trimmed.eq_ignore_ascii_case("FSG!") {
-            return Some(PackerDetection {
-                packer: PackerType::FSG,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // Petite signatures
-        if name_trimmed.starts_with(".petite") {
-            return Some(PackerDetection {
-                packer: PackerType::Petite,
-                confidence: 0.90,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // MEW signatures
-        if name_trimmed.eq_ignore_ascii_case("MEW") {
-            return Some(PackerDetection {
-                packer: PackerType::MEW,
-                confidence: 0.85,
-                method: DetectionMethod::SectionName,
-            });
-        }
-
-        // NSPack signatures
-        if name_trimmed.starts_with(".nsp") {
-            return Some(PackerDetection {
-                packer: PackerType::NSPack,
-                confidence: 0.85,
-                method: DetectionMethod::SectionName,
-            });
+        if let Some(detection) = match_raw_section_name(section_name) {
+            return Some(detection);
         }
     }
 
@@ -406,15 +452,9 @@ fn detect_by_entropy(pe: &PE, pe_data: &[u8]) -> Option<PackerDetection> {
             continue;
         }
 
-        let start = section.pointer_to_raw_data as usize;
-        let size = section.size_of_raw_data as usize;
-
-        // Bounds check
-        if start >= pe_data.len() || start + size > pe_data.len() {
+        let Some(section_data) = section_raw_data(section, pe_data) else {
             continue;
-        }
-
-        let section_data = &pe_data[start..start + size];
+        };
         let section_entropy = calculate_shannon_entropy(section_data);
 
         total_sections += 1;
@@ -454,9 +494,16 @@ fn detect_by_imports(pe: &PE) -> Option<PackerDetection> {
     None
 }
 
+/// Raw file data of `section`, if it lies within `pe_data`
+pub(crate) fn section_raw_data<'a>(section: &SectionTable, pe_data: &'a [u8]) -> Option<&'a [u8]> {
+    let start = section.pointer_to_raw_data as usize;
+    let end = start.checked_add(section.size_of_raw_data as usize)?;
+    pe_data.get(start..end)
+}
+
 /// Calculate Shannon entropy for a byte slice
 /// Returns value from 0.0 (no entropy) to 8.0 (maximum entropy)
-fn calculate_shannon_entropy(data: &[u8]) -> f64 {
+pub(crate) fn calculate_shannon_entropy(data: &[u8]) -> f64 {
     if data.is_empty() {
         return 0.0;
     }
@@ -527,4 +574,108 @@ mod tests {
         assert!(instr.contains("upx"));
         assert!(instr.contains("-d"));
     }
+
+    #[test]
+    fn test_protector_section_names() {
+        use crate::testutil::PeBuilder;
+
+        let cases = [
+            (".MPRESS1", PackerType::MPRESS),
+            (".MPRESS2", PackerType::MPRESS),
+            (".enigma1", PackerType::Enigma),
+            (".enigma2", PackerType::Enigma),
+            (".vmp0", PackerType::VMProtect),
+            (".vmp1", PackerType::VMProtect),
+            (".obsidiu", PackerType::Obsidium),
+            (".obsid", PackerType::Obsidium),
+        ];
+        for (name, packer) in cases {
+            let mut builder = PeBuilder::new().vb_imports();
+            builder.section(".text", vec![0xC3]);
+            builder.section(name, vec![0; 0x10]);
+            let data = builder.build();
+
+            let detection = detect_packer(&data).unwrap().unwrap();
+            assert_eq!(detection.packer, packer, "{}", name);
+            assert_eq!(detection.method, DetectionMethod::SectionName);
+
+            // The goblin-based path uses the same signature table
+            let pe = PE::parse(&data).unwrap();
+            assert_eq!(detect_by_section_names(&pe).unwrap().packer, packer);
+        }
+    }
+
+    #[test]
+    fn test_entry_point_signature() {
+        use crate::testutil::PeBuilder;
+
+        // UPX stub in a renamed section
+        let mut stub = vec![
+            0x60, 0xBE, 0x00, 0x10, 0x40, 0x00, 0x8D, 0xBE, 0x00, 0xF0, 0xFF, 0xFF, 0x57,
+        ];
+        stub.extend_from_slice(&[0x83, 0xCD, 0xFF, 0xEB, 0x10]);
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".text", vec![0xC3]);
+        let entry = builder.section(".code", stub);
+        let data = builder.entry_point(entry).build();
+
+        let detection = detect_packer(&data).unwrap().unwrap();
+        assert_eq!(detection.packer, PackerType::UPX);
+        assert_eq!(detection.method, DetectionMethod::EntryPointSignature);
+    }
+
+    #[test]
+    fn test_entry_point_signature_patterns() {
+        for &(pattern, ..) in ENTRY_POINT_SIGNATURES {
+            let tokens: Vec<&str> = pattern.split_whitespace().collect();
+            assert!(tokens.len() <= MAX_SIGNATURE_LEN, "{}", pattern);
+            assert!(
+                tokens
+                    .iter()
+                    .all(|t| *t == "??" || u8::from_str_radix(t, 16).is_ok()),
+                "{}",
+                pattern
+            );
+        }
+
+        assert!(matches_signature(&[0x60, 0xBE, 0x12], "60 BE ??"));
+        assert!(!matches_signature(&[0x60, 0xBE], "60 BE ??"));
+        assert!(!matches_signature(&[0x55, 0x8B, 0xEC], "60 BE ??"));
+        assert!(match_entry_point(&[0x55, 0x8B, 0xEC, 0x6A, 0xFF]).is_none());
+    }
+
+    #[test]
+    fn test_plain_section_names_not_detected() {
+        assert!(match_section_name(".text").is_none());
+        assert!(match_section_name(".vbp").is_none());
+        assert!(match_section_name("FSG!x").is_none());
+        assert_eq!(match_section_name("fsg!").unwrap().packer, PackerType::FSG);
+    }
+
+    #[test]
+    fn test_raw_and_goblin_paths_agree() {
+        use crate::testutil::PeBuilder;
+
+        // Section names are limited to 8 bytes, so longer signatures never match
+        for &(signature, packer, confidence, _) in SECTION_SIGNATURES
+            .iter()
+            .filter(|(signature, ..)| signature.len() <= 8)
+        {
+            let mut builder = PeBuilder::new().vb_imports();
+            builder.section(".text", vec![0xC3]);
+            builder.section(signature, vec![0; 0x10]);
+            let data = builder.build();
+
+            let raw = detect_by_section_names_raw(&data).unwrap();
+            let parsed = detect_by_section_names(&PE::parse(&data).unwrap()).unwrap();
+            assert_eq!(raw.packer, packer, "{}", signature);
+            assert_eq!(raw.confidence, confidence, "{}", signature);
+            assert_eq!(
+                (raw.packer, raw.confidence, raw.method),
+                (parsed.packer, parsed.confidence, parsed.method),
+                "{}",
+                signature
+            );
+        }
+    }
 }
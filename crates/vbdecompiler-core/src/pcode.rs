@@ -8,10 +8,11 @@
 //! P-Code is a stack-based bytecode format with variable-length instructions.
 
 use crate::error::{Error, Result};
+use serde::ser::SerializeStruct;
 use std::fmt;
 
 /// P-Code opcode category
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum OpcodeCategory {
     ControlFlow, // Branch, return, exit
     Stack,       // Push/pop literals and values
@@ -29,7 +30,7 @@ pub enum OpcodeCategory {
 }
 
 /// P-Code data type specifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 pub enum PCodeType {
     Unknown,
     Byte,    // b
@@ -37,12 +38,35 @@ pub enum PCodeType {
     Integer, // % (2 bytes)
     Long,    // & (4 bytes)
     Single,  // ! (4 bytes float)
+    Double,  // # (8 bytes float)
     Variant, // ~ (Variant type)
     String,  // z (String)
     Object,  // Object reference
 }
 
 impl PCodeType {
+    /// Type named by a format-string type suffix (`%`, `&`, `!`, `#`, `~`)
+    pub fn from_suffix(suffix: u8) -> Option<Self> {
+        match suffix {
+            b'%' => Some(Self::Integer),
+            b'&' => Some(Self::Long),
+            b'!' => Some(Self::Single),
+            b'#' => Some(Self::Double),
+            b'~' => Some(Self::Variant),
+            _ => None,
+        }
+    }
+
+    /// Check if an operand read as `self` may carry the `suffix` type
+    ///
+    /// Untyped reads adopt the suffix, and byte-encoded literals may be
+    /// widened to an integer type.
+    fn accepts_suffix(self, suffix: Self) -> bool {
+        self == suffix
+            || self == Self::Unknown
+            || (self == Self::Byte && matches!(suffix, Self::Integer | Self::Long))
+    }
+
     /// Convert type to string representation
     pub fn to_string(&self) -> &'static str {
         match self {
@@ -51,6 +75,7 @@ impl PCodeType {
             Self::Integer => "Integer",
             Self::Long => "Long",
             Self::Single => "Single",
+            Self::Double => "Double",
             Self::Variant => "Variant",
             Self::String => "String",
             Self::Object => "Object",
@@ -60,13 +85,17 @@ impl PCodeType {
 }
 
 /// P-Code operand value
-#[derive(Debug, Clone)]
+///
+/// Serializes as the bare value (`null` for `None`).
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(untagged)]
 pub enum OperandValue {
     None,
     Byte(u8),
     Int16(i16),
     Int32(i32),
     Float(f32),
+    Double(f64),
     String(String),
 }
 
@@ -78,24 +107,41 @@ impl fmt::Display for OperandValue {
             Self::Int16(v) => write!(f, "{}", v),
             Self::Int32(v) => write!(f, "{}", v),
             Self::Float(v) => write!(f, "{}", v),
+            Self::Double(v) => write!(f, "{}", v),
             Self::String(s) => write!(f, "\"{}\"", s),
         }
     }
 }
 
 /// P-Code instruction operand
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Operand {
     pub value: OperandValue,
+    #[serde(rename = "type")]
     pub data_type: PCodeType,
 }
 
 impl Operand {
-    fn new(value: OperandValue, data_type: PCodeType) -> Self {
+    /// Create a new operand
+    pub fn new(value: OperandValue, data_type: PCodeType) -> Self {
         Self { value, data_type }
     }
 }
 
+/// What an address-like operand refers to, for symbol resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandKind {
+    /// Import/constant pool index of an `ImpAdCall*`
+    Import,
+    /// VTable offset of a `VCall*`
+    VTable,
+    /// Absolute branch target address
+    Branch,
+}
+
+/// Callback naming an operand value, e.g. an import index as `MessageBoxA`
+pub type SymbolResolver = Box<dyn Fn(OperandKind, i64) -> Option<String>>;
+
 /// P-Code instruction representation
 #[derive(Debug, Clone)]
 pub struct Instruction {
@@ -112,6 +158,10 @@ pub struct Instruction {
     pub is_call: bool,
     pub is_return: bool,
     pub branch_offset: Option<i32>,
+    /// Name of the call or branch target, if a resolver recognized it
+    pub symbol: Option<String>,
+    /// An operand ran into the end of the data and was cut short
+    pub truncated: bool,
 }
 
 impl Instruction {
@@ -131,17 +181,40 @@ impl Instruction {
             is_call: false,
             is_return: false,
             branch_offset: None,
+            symbol: None,
+            truncated: false,
         }
     }
 
+    /// Absolute address a branch jumps to
+    ///
+    /// The offset is relative to the end of the instruction, not its start.
+    pub fn branch_target(&self) -> Option<u32> {
+        let offset = self.branch_offset?;
+        Some(
+            self.address
+                .wrapping_add(self.bytes.len() as u32)
+                .wrapping_add(offset as u32),
+        )
+    }
+
     /// Format instruction as assembly-like string
+    ///
+    /// Branch offsets (always the last operand) are shown as absolute targets,
+    /// and a resolved symbol replaces the operand it names.
     pub fn to_string(&self) -> String {
-        let operands_str = self
+        let mut operands: Vec<_> = self
             .operands
             .iter()
             .map(|op| format!("{}", op.value))
-            .collect::<Vec<_>>()
-            .join(", ");
+            .collect();
+        if let (Some(target), Some(last)) = (self.branch_target(), operands.last_mut()) {
+            *last = format!("0x{:08X}", target);
+        }
+        if let (Some(symbol), Some(last)) = (&self.symbol, operands.last_mut()) {
+            last.clone_from(symbol);
+        }
+        let operands_str = operands.join(", ");
 
         if operands_str.is_empty() {
             format!("{:08X}  {}", self.address, self.mnemonic)
@@ -150,6 +223,11 @@ impl Instruction {
         }
     }
 
+    /// Check if this instruction transfers control to a native import stub
+    pub fn is_native_call(&self) -> bool {
+        self.mnemonic.starts_with("ImpAdCall")
+    }
+
     /// Format bytes as hex string
     pub fn bytes_to_hex(&self) -> String {
         self.bytes
@@ -160,6 +238,33 @@ impl Instruction {
     }
 }
 
+/// JSON form of an instruction: the raw fields plus hex bytes and the
+/// absolute branch target
+impl serde::Serialize for Instruction {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Instruction", 15)?;
+        state.serialize_field("address", &self.address)?;
+        state.serialize_field("opcode", &self.opcode)?;
+        state.serialize_field("extended_opcode", &self.extended_opcode)?;
+        state.serialize_field("mnemonic", &self.mnemonic)?;
+        state.serialize_field("category", &self.category)?;
+        state.serialize_field("operands", &self.operands)?;
+        state.serialize_field("bytes", &self.bytes_to_hex())?;
+        state.serialize_field("stack_delta", &self.stack_delta)?;
+        state.serialize_field("is_branch", &self.is_branch)?;
+        state.serialize_field("is_conditional_branch", &self.is_conditional_branch)?;
+        state.serialize_field("is_call", &self.is_call)?;
+        state.serialize_field("is_return", &self.is_return)?;
+        state.serialize_field("branch_offset", &self.branch_offset)?;
+        state.serialize_field("branch_target", &self.branch_target())?;
+        state.serialize_field("symbol", &self.symbol)?;
+        state.end()
+    }
+}
+
 /// Opcode information entry
 #[derive(Clone, Copy)]
 struct OpcodeInfo {
@@ -211,15 +316,26 @@ impl OpcodeInfo {
 
 /// Get opcode information for standard opcodes (0x00-0xFA)
 fn get_opcode_info(opcode: u8) -> &'static OpcodeInfo {
-    // Define only the most common/important opcodes
-    // This is a subset - expand as needed
+    // Standard (single-byte) opcode map, grouped by category
     static OPCODES: [OpcodeInfo; 256] = {
         let mut table = [OpcodeInfo::new("Unknown", "", OpcodeCategory::Unknown, 0); 256];
 
         // Control flow
+        table[0x00] = OpcodeInfo::new("LargeBos", "n", OpcodeCategory::ControlFlow, 0);
+        table[0x01] = OpcodeInfo::new("InvalidExcode", "", OpcodeCategory::Unknown, 0);
+        table[0x02] = OpcodeInfo::new("SelectCaseByte", "c", OpcodeCategory::ControlFlow, -1);
+        table[0x03] = OpcodeInfo::new("Bos", "a", OpcodeCategory::ControlFlow, 0);
         table[0x13] =
             OpcodeInfo::new("ExitProcHresult", "", OpcodeCategory::ControlFlow, 0).with_return();
         table[0x14] = OpcodeInfo::new("ExitProc", "", OpcodeCategory::ControlFlow, 0).with_return();
+        table[0x15] =
+            OpcodeInfo::new("ExitProcI2", "", OpcodeCategory::ControlFlow, -1).with_return();
+        table[0x16] =
+            OpcodeInfo::new("ExitProcStr", "", OpcodeCategory::ControlFlow, -1).with_return();
+        table[0x17] =
+            OpcodeInfo::new("ExitProcR4", "", OpcodeCategory::ControlFlow, -1).with_return();
+        table[0x18] =
+            OpcodeInfo::new("ExitProcR8", "", OpcodeCategory::ControlFlow, -1).with_return();
         table[0x1C] =
             OpcodeInfo::new("BranchF", "l", OpcodeCategory::ControlFlow, -1).with_branch(true);
         table[0x1D] =
@@ -227,6 +343,45 @@ fn get_opcode_info(opcode: u8) -> &'static OpcodeInfo {
         table[0x1E] =
             OpcodeInfo::new("Branch", "l", OpcodeCategory::ControlFlow, 0).with_branch(false);
         table[0x4B] = OpcodeInfo::new("OnErrorGoto", "l", OpcodeCategory::ControlFlow, 0);
+        table[0x4C] = OpcodeInfo::new("OnErrorResumeNext", "", OpcodeCategory::ControlFlow, 0);
+        table[0x4D] = OpcodeInfo::new("ResumeNext", "", OpcodeCategory::ControlFlow, 0);
+        table[0x4E] = OpcodeInfo::new("ResumeLabel", "l", OpcodeCategory::ControlFlow, 0);
+        table[0x4F] = OpcodeInfo::new("Stop", "", OpcodeCategory::ControlFlow, 0);
+        table[0x50] = OpcodeInfo::new("End", "", OpcodeCategory::ControlFlow, 0);
+        table[0x51] = OpcodeInfo::new("SelectCaseI2", "c", OpcodeCategory::ControlFlow, -1);
+        table[0x52] = OpcodeInfo::new("SelectCaseI4", "c", OpcodeCategory::ControlFlow, -1);
+        table[0x53] = OpcodeInfo::new("SelectCaseStr", "c", OpcodeCategory::ControlFlow, -1);
+        table[0x54] = OpcodeInfo::new("SelectCaseVar", "c", OpcodeCategory::ControlFlow, -1);
+        table[0x55] = OpcodeInfo::new("CaseDone", "l", OpcodeCategory::ControlFlow, 0);
+        table[0x56] = OpcodeInfo::new("GoSub", "l", OpcodeCategory::ControlFlow, 0);
+        table[0x57] = OpcodeInfo::new("OnGoto", "n", OpcodeCategory::ControlFlow, -1);
+        table[0x58] = OpcodeInfo::new("OnGoSub", "n", OpcodeCategory::ControlFlow, -1);
+        table[0x59] =
+            OpcodeInfo::new("ExitProcCy", "", OpcodeCategory::ControlFlow, -1).with_return();
+        table[0x5A] =
+            OpcodeInfo::new("ExitProcUI1", "", OpcodeCategory::ControlFlow, -1).with_return();
+        table[0x5B] =
+            OpcodeInfo::new("ExitProcCbHresult", "n", OpcodeCategory::ControlFlow, 0).with_return();
+        table[0x5C] =
+            OpcodeInfo::new("ExitProcCbStack", "n", OpcodeCategory::ControlFlow, 0).with_return();
+        table[0x5D] =
+            OpcodeInfo::new("BranchFVar", "l", OpcodeCategory::ControlFlow, -1).with_branch(true);
+
+        // For/Next loops (counter slot, then offset of the matching Next/For)
+        table[0xB3] = OpcodeInfo::new("ForI2", "al", OpcodeCategory::Loop, -2);
+        table[0xB4] = OpcodeInfo::new("ForI4", "al", OpcodeCategory::Loop, -2);
+        table[0xB5] = OpcodeInfo::new("ForR4", "al", OpcodeCategory::Loop, -2);
+        table[0xB6] = OpcodeInfo::new("ForR8", "al", OpcodeCategory::Loop, -2);
+        table[0xB7] = OpcodeInfo::new("ForVar", "al", OpcodeCategory::Loop, -2);
+        table[0xB8] = OpcodeInfo::new("ForStepVar", "al", OpcodeCategory::Loop, -3);
+        table[0xB9] = OpcodeInfo::new("NextI2", "al", OpcodeCategory::Loop, 0);
+        table[0xBA] = OpcodeInfo::new("NextI4", "al", OpcodeCategory::Loop, 0);
+        table[0xBB] = OpcodeInfo::new("NextR4", "al", OpcodeCategory::Loop, 0);
+        table[0xBC] = OpcodeInfo::new("NextR8", "al", OpcodeCategory::Loop, 0);
+        table[0xBD] = OpcodeInfo::new("NextVar", "al", OpcodeCategory::Loop, 0);
+        table[0xBE] = OpcodeInfo::new("NextStepVar", "al", OpcodeCategory::Loop, 0);
+        table[0xBF] = OpcodeInfo::new("ForEachCollVar", "al", OpcodeCategory::Loop, -1);
+        table[0xC0] = OpcodeInfo::new("NextEachCollVar", "al", OpcodeCategory::Loop, 0);
 
         // Stack operations - literals
         table[0x1B] = OpcodeInfo::new("LitStr", "z", OpcodeCategory::Stack, 1);
@@ -238,51 +393,202 @@ fn get_opcode_info(opcode: u8) -> &'static OpcodeInfo {
         table[0x60] = OpcodeInfo::new("LitR4", "f!", OpcodeCategory::Stack, 1);
         table[0x61] = OpcodeInfo::new("LitR8", "g#", OpcodeCategory::Stack, 1);
         table[0xA7] = OpcodeInfo::new("LitVarI2_Byte", "b%", OpcodeCategory::Stack, 1);
+        table[0xEF] = OpcodeInfo::new("LitNothing", "", OpcodeCategory::Stack, 1);
+        table[0xF0] = OpcodeInfo::new("LitVarI4", "d&", OpcodeCategory::Stack, 1);
+        table[0xF1] = OpcodeInfo::new("LitVarR4", "f!", OpcodeCategory::Stack, 1);
+        table[0xF2] = OpcodeInfo::new("LitVarR8", "g#", OpcodeCategory::Stack, 1);
+        table[0xF3] = OpcodeInfo::new("LitVarUI1", "b", OpcodeCategory::Stack, 1);
+        table[0xF4] = OpcodeInfo::new("LitI2_Byte", "b%", OpcodeCategory::Stack, 1);
+        table[0xF5] = OpcodeInfo::new("LitUI1", "b", OpcodeCategory::Stack, 1);
+        table[0xF6] = OpcodeInfo::new("LitDate", "g", OpcodeCategory::Stack, 1);
+        table[0xF7] = OpcodeInfo::new("LitVar_TRUE", "", OpcodeCategory::Stack, 1);
+        table[0xF8] = OpcodeInfo::new("LitVar_FALSE", "", OpcodeCategory::Stack, 1);
+        table[0xF9] = OpcodeInfo::new("LitVar_NULL", "", OpcodeCategory::Stack, 1);
+        table[0xFA] = OpcodeInfo::new("LitVar_Empty", "", OpcodeCategory::Stack, 1);
 
-        // Variable operations
+        // Stack operations - temporaries
+        table[0x25] = OpcodeInfo::new("PopAdLdVar", "", OpcodeCategory::Stack, 0);
+        table[0x2B] = OpcodeInfo::new("PopTmpLdAd2", "a", OpcodeCategory::Stack, 0);
+        table[0x30] = OpcodeInfo::new("PopTmpLdAd8", "a", OpcodeCategory::Stack, 0);
+        table[0x37] = OpcodeInfo::new("PopTmpLdAdStr", "a", OpcodeCategory::Stack, 0);
+        table[0x39] = OpcodeInfo::new("PopFPR4", "", OpcodeCategory::Stack, -1);
+        table[0x3C] = OpcodeInfo::new("PopFPR8", "", OpcodeCategory::Stack, -1);
+
+        // Variable operations - frame locals
         table[0x04] = OpcodeInfo::new("FLdRfVar", "a", OpcodeCategory::Variable, 1);
+        table[0x08] = OpcodeInfo::new("FLdPr", "a", OpcodeCategory::Variable, 1);
+        table[0x19] = OpcodeInfo::new("FStAdFunc", "a", OpcodeCategory::Variable, -1);
+        table[0x3E] = OpcodeInfo::new("FLdZeroAd", "a", OpcodeCategory::Variable, 1);
         table[0x43] = OpcodeInfo::new("FStStrCopy", "a", OpcodeCategory::String, -1);
         table[0x62] = OpcodeInfo::new("FLdPrThis", "", OpcodeCategory::Variable, 1);
+        table[0x63] = OpcodeInfo::new("FLdUI1", "a", OpcodeCategory::Variable, 1);
+        table[0x64] = OpcodeInfo::new("FLdR4", "a", OpcodeCategory::Variable, 1);
+        table[0x65] = OpcodeInfo::new("FLdR8", "a", OpcodeCategory::Variable, 1);
+        table[0x66] = OpcodeInfo::new("FLdStr", "a", OpcodeCategory::Variable, 1);
+        table[0x67] = OpcodeInfo::new("FLdVar", "a", OpcodeCategory::Variable, 1);
+        table[0x68] = OpcodeInfo::new("FLdAd", "a", OpcodeCategory::Variable, 1);
         table[0x69] = OpcodeInfo::new("FLdI2", "a", OpcodeCategory::Variable, 1);
         table[0x6A] = OpcodeInfo::new("FLdI4", "a", OpcodeCategory::Variable, 1);
+        table[0x6B] = OpcodeInfo::new("FLdCy", "a", OpcodeCategory::Variable, 1);
+        table[0x6C] = OpcodeInfo::new("ILdRf", "a", OpcodeCategory::Variable, 1);
         table[0x6D] = OpcodeInfo::new("FStI2", "a", OpcodeCategory::Variable, -1);
         table[0x6E] = OpcodeInfo::new("FStI4", "a", OpcodeCategory::Variable, -1);
+        table[0x6F] = OpcodeInfo::new("FStUI1", "a", OpcodeCategory::Variable, -1);
+        table[0x70] = OpcodeInfo::new("FStR4", "a", OpcodeCategory::Variable, -1);
+        table[0x71] = OpcodeInfo::new("FStR8", "a", OpcodeCategory::Variable, -1);
+        table[0x72] = OpcodeInfo::new("FStVar", "a", OpcodeCategory::Variable, -1);
+        table[0x73] = OpcodeInfo::new("FStVarCopy", "a", OpcodeCategory::Variable, -1);
+        table[0x74] = OpcodeInfo::new("FStAd", "a", OpcodeCategory::Variable, -1);
+        table[0x75] = OpcodeInfo::new("FStAdNoPop", "a", OpcodeCategory::Variable, 0);
+        table[0x76] = OpcodeInfo::new("FStCy", "a", OpcodeCategory::Variable, -1);
+
+        // Variable operations - indirect (through a reference on the stack)
+        table[0x77] = OpcodeInfo::new("ILdI2", "a", OpcodeCategory::Variable, 1);
+        table[0x78] = OpcodeInfo::new("ILdI4", "a", OpcodeCategory::Variable, 1);
+        table[0x79] = OpcodeInfo::new("ILdStr", "a", OpcodeCategory::Variable, 1);
+        table[0x7A] = OpcodeInfo::new("ILdVar", "a", OpcodeCategory::Variable, 1);
+        table[0x7B] = OpcodeInfo::new("IStI2", "a", OpcodeCategory::Variable, -1);
+        table[0x7C] = OpcodeInfo::new("IStI4", "a", OpcodeCategory::Variable, -1);
+        table[0x7D] = OpcodeInfo::new("IStStr", "a", OpcodeCategory::Variable, -1);
+        table[0x7E] = OpcodeInfo::new("IStVar", "a", OpcodeCategory::Variable, -1);
+
+        // Variable operations - object members and late binding
+        table[0x06] = OpcodeInfo::new("MemLdRfVar", "c", OpcodeCategory::Variable, 1);
+        table[0x07] = OpcodeInfo::new("FMemLdRf", "c", OpcodeCategory::Variable, 1);
+        table[0x21] = OpcodeInfo::new("MemLdI2", "c", OpcodeCategory::Variable, 1);
+        table[0x22] = OpcodeInfo::new("ImpAdLdPr", "c", OpcodeCategory::Variable, 1);
+        table[0x24] = OpcodeInfo::new("NewIfNullPr", "c", OpcodeCategory::Variable, 0);
+        table[0x2C] = OpcodeInfo::new("LateIdSt", "c", OpcodeCategory::Variable, -2);
+        table[0x3F] = OpcodeInfo::new("MemStVar", "c", OpcodeCategory::Variable, -1);
+        table[0x44] = OpcodeInfo::new("MemLdI4", "c", OpcodeCategory::Variable, 1);
+        table[0x45] = OpcodeInfo::new("MemLdStr", "c", OpcodeCategory::Variable, 1);
+        table[0x46] = OpcodeInfo::new("MemLdVar", "c", OpcodeCategory::Variable, 1);
+        table[0x47] = OpcodeInfo::new("MemStI2", "c", OpcodeCategory::Variable, -1);
+        table[0x48] = OpcodeInfo::new("MemStI4", "c", OpcodeCategory::Variable, -1);
+        table[0x49] = OpcodeInfo::new("MemStStr", "c", OpcodeCategory::Variable, -1);
 
         // Function/method calls
         table[0x05] = OpcodeInfo::new("ImpAdLdRf", "c", OpcodeCategory::Call, 1);
-        table[0x09] = OpcodeInfo::new("ImpAdCallHresult", "", OpcodeCategory::Call, 0).with_call();
+        table[0x09] = OpcodeInfo::new("ImpAdCallHresult", "c", OpcodeCategory::Call, 0).with_call();
         table[0x0A] = OpcodeInfo::new("ImpAdCallFPR4", "x", OpcodeCategory::Call, 0).with_call();
+        table[0x0B] = OpcodeInfo::new("ImpAdCallI2", "c", OpcodeCategory::Call, 1).with_call();
+        table[0x0C] = OpcodeInfo::new("ImpAdCallCy", "c", OpcodeCategory::Call, 1).with_call();
         table[0x0D] = OpcodeInfo::new("VCallHresult", "v", OpcodeCategory::Call, 0).with_call();
+        table[0x0E] = OpcodeInfo::new("VCallFPR8", "v", OpcodeCategory::Call, 1).with_call();
+        table[0x0F] = OpcodeInfo::new("VCallAd", "v", OpcodeCategory::Call, 1).with_call();
+        table[0x10] = OpcodeInfo::new("ThisVCallHresult", "v", OpcodeCategory::Call, 0).with_call();
+        table[0x11] = OpcodeInfo::new("ThisVCall", "v", OpcodeCategory::Call, 0).with_call();
+        table[0x12] = OpcodeInfo::new("ThisVCallAd", "v", OpcodeCategory::Call, 1).with_call();
         table[0x7F] = OpcodeInfo::new("CallHresult", "n", OpcodeCategory::Call, 0).with_call();
         table[0x80] = OpcodeInfo::new("CallI2", "n", OpcodeCategory::Call, 1).with_call();
         table[0x81] = OpcodeInfo::new("CallI4", "n", OpcodeCategory::Call, 1).with_call();
+        table[0xD0] = OpcodeInfo::new("ImpAdCallI4", "c", OpcodeCategory::Call, 1).with_call();
+        table[0xD1] = OpcodeInfo::new("ImpAdCallFPR8", "c", OpcodeCategory::Call, 1).with_call();
+        table[0xD2] = OpcodeInfo::new("ImpAdCallUI1", "c", OpcodeCategory::Call, 1).with_call();
+        table[0xD3] = OpcodeInfo::new("VCallI2", "v", OpcodeCategory::Call, 1).with_call();
+        table[0xD4] = OpcodeInfo::new("VCallI4", "v", OpcodeCategory::Call, 1).with_call();
+        table[0xD5] = OpcodeInfo::new("VCallStr", "v", OpcodeCategory::Call, 1).with_call();
+        table[0xD6] = OpcodeInfo::new("VCallVar", "v", OpcodeCategory::Call, 1).with_call();
+        table[0xD7] = OpcodeInfo::new("CallStr", "n", OpcodeCategory::Call, 1).with_call();
+        table[0xD8] = OpcodeInfo::new("CallVar", "n", OpcodeCategory::Call, 1).with_call();
+        table[0xD9] = OpcodeInfo::new("LateIdCall", "c", OpcodeCategory::Call, 0).with_call();
 
         // String operations
+        table[0x23] = OpcodeInfo::new("FStStrNoPop", "a", OpcodeCategory::String, 0);
         table[0x2A] = OpcodeInfo::new("ConcatStr", "", OpcodeCategory::String, -1);
         table[0x2F] = OpcodeInfo::new("FFree1Str", "", OpcodeCategory::String, 0);
+        table[0x31] = OpcodeInfo::new("FStStr", "a", OpcodeCategory::String, -1);
         table[0x32] = OpcodeInfo::new("FFreeStr", "", OpcodeCategory::String, 0);
         table[0x33] = OpcodeInfo::new("LdFixedStr", "z", OpcodeCategory::String, 1);
         table[0x34] = OpcodeInfo::new("CStr2Ansi", "", OpcodeCategory::String, 0);
         table[0x4A] = OpcodeInfo::new("FnLenStr", "", OpcodeCategory::String, 0);
+        table[0xE0] = OpcodeInfo::new("ConcatVar", "", OpcodeCategory::String, -1);
+        table[0xE1] = OpcodeInfo::new("CStr2Uni", "", OpcodeCategory::String, 0);
+        table[0xE2] = OpcodeInfo::new("FnLenVar", "", OpcodeCategory::String, 0);
+        table[0xE3] = OpcodeInfo::new("LikeStr", "", OpcodeCategory::String, -1);
+        table[0xE4] = OpcodeInfo::new("LikeVar", "", OpcodeCategory::String, -1);
 
         // Array operations
+        table[0x26] = OpcodeInfo::new("AryDescTemp", "c", OpcodeCategory::Array, 0);
+        table[0x2D] = OpcodeInfo::new("AryUnlock", "", OpcodeCategory::Array, 0);
+        table[0x2E] = OpcodeInfo::new("AryLock", "", OpcodeCategory::Array, 0);
         table[0x3B] = OpcodeInfo::new("Ary1StStrCopy", "", OpcodeCategory::Array, -2);
         table[0x40] = OpcodeInfo::new("Ary1LdRf", "", OpcodeCategory::Array, 0);
         table[0x41] = OpcodeInfo::new("Ary1LdPr", "", OpcodeCategory::Array, 0);
+        table[0xE5] = OpcodeInfo::new("Ary1StI2", "", OpcodeCategory::Array, -2);
+        table[0xE6] = OpcodeInfo::new("Ary1StI4", "", OpcodeCategory::Array, -2);
+        table[0xE7] = OpcodeInfo::new("Ary1StVar", "", OpcodeCategory::Array, -2);
+        table[0xE8] = OpcodeInfo::new("Ary1LdI2", "", OpcodeCategory::Array, 0);
+        table[0xE9] = OpcodeInfo::new("Ary1LdI4", "", OpcodeCategory::Array, 0);
+        table[0xEA] = OpcodeInfo::new("Ary1LdVar", "", OpcodeCategory::Array, 0);
+        table[0xEB] = OpcodeInfo::new("Ary1LdUI1", "", OpcodeCategory::Array, 0);
+        table[0xEC] = OpcodeInfo::new("Ary1StUI1", "", OpcodeCategory::Array, -2);
+        table[0xED] = OpcodeInfo::new("AryLdRf", "n", OpcodeCategory::Array, 0);
+        table[0xEE] = OpcodeInfo::new("AryLdPr", "n", OpcodeCategory::Array, 0);
 
         // Memory management
         table[0x1A] = OpcodeInfo::new("FFree1Ad", "", OpcodeCategory::Memory, 0);
         table[0x29] = OpcodeInfo::new("FFreeAd", "", OpcodeCategory::Memory, 0);
         table[0x35] = OpcodeInfo::new("FFree1Var", "", OpcodeCategory::Memory, 0);
         table[0x36] = OpcodeInfo::new("FFreeVar", "", OpcodeCategory::Memory, 0);
+        table[0x38] = OpcodeInfo::new("CopyBytes", "n", OpcodeCategory::Memory, 0);
 
-        // Arithmetic
+        // Type conversion
+        table[0x1F] = OpcodeInfo::new("CRec2Ansi", "c", OpcodeCategory::Conversion, 0);
+        table[0x20] = OpcodeInfo::new("CRec2Uni", "c", OpcodeCategory::Conversion, 0);
+        table[0x3D] = OpcodeInfo::new("CastAd", "c", OpcodeCategory::Conversion, 0);
+        table[0x42] = OpcodeInfo::new("CVarRef", "a", OpcodeCategory::Conversion, 0);
+        table[0xDA] = OpcodeInfo::new("CI2I4", "", OpcodeCategory::Conversion, 0);
+        table[0xDB] = OpcodeInfo::new("CI4I2", "", OpcodeCategory::Conversion, 0);
+        table[0xDC] = OpcodeInfo::new("CR8I4", "", OpcodeCategory::Conversion, 0);
+        table[0xDD] = OpcodeInfo::new("CI4R8", "", OpcodeCategory::Conversion, 0);
+        table[0xDE] = OpcodeInfo::new("CStrVar", "", OpcodeCategory::Conversion, 0);
+        table[0xDF] = OpcodeInfo::new("CVarStr", "", OpcodeCategory::Conversion, 0);
+
+        // Arithmetic - Integer (I2)
         table[0x95] = OpcodeInfo::new("AddI2", "", OpcodeCategory::Arithmetic, -1);
         table[0x96] = OpcodeInfo::new("SubI2", "", OpcodeCategory::Arithmetic, -1);
         table[0x97] = OpcodeInfo::new("MulI2", "", OpcodeCategory::Arithmetic, -1);
+        table[0x98] = OpcodeInfo::new("IDvI2", "", OpcodeCategory::Arithmetic, -1);
+        table[0x99] = OpcodeInfo::new("ModI2", "", OpcodeCategory::Arithmetic, -1);
         table[0x9A] = OpcodeInfo::new("NegI2", "", OpcodeCategory::Arithmetic, 0);
 
-        // Comparison
+        // Arithmetic - Long (I4)
+        table[0x82] = OpcodeInfo::new("AddI4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x83] = OpcodeInfo::new("SubI4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x84] = OpcodeInfo::new("MulI4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x85] = OpcodeInfo::new("IDvI4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x86] = OpcodeInfo::new("ModI4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x87] = OpcodeInfo::new("NegI4", "", OpcodeCategory::Arithmetic, 0);
+
+        // Arithmetic - Double (R8) and Single (R4)
+        table[0x88] = OpcodeInfo::new("AddR8", "", OpcodeCategory::Arithmetic, -1);
+        table[0x89] = OpcodeInfo::new("SubR8", "", OpcodeCategory::Arithmetic, -1);
+        table[0x8A] = OpcodeInfo::new("MulR8", "", OpcodeCategory::Arithmetic, -1);
+        table[0x8B] = OpcodeInfo::new("DivR8", "", OpcodeCategory::Arithmetic, -1);
+        table[0x8C] = OpcodeInfo::new("NegR8", "", OpcodeCategory::Arithmetic, 0);
+        table[0x8D] = OpcodeInfo::new("AddR4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x8E] = OpcodeInfo::new("SubR4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x8F] = OpcodeInfo::new("MulR4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x90] = OpcodeInfo::new("DivR4", "", OpcodeCategory::Arithmetic, -1);
+        table[0x9D] = OpcodeInfo::new("PwrR8R8", "", OpcodeCategory::Arithmetic, -1);
+
+        // Arithmetic - Variant
+        table[0x91] = OpcodeInfo::new("AddVar", "", OpcodeCategory::Arithmetic, -1);
+        table[0x92] = OpcodeInfo::new("SubVar", "", OpcodeCategory::Arithmetic, -1);
+        table[0x93] = OpcodeInfo::new("MulVar", "", OpcodeCategory::Arithmetic, -1);
+        table[0x94] = OpcodeInfo::new("DivVar", "", OpcodeCategory::Arithmetic, -1);
+        table[0x9B] = OpcodeInfo::new("ModVar", "", OpcodeCategory::Arithmetic, -1);
+        table[0x9C] = OpcodeInfo::new("NegVar", "", OpcodeCategory::Arithmetic, 0);
+        table[0x9E] = OpcodeInfo::new("IDvVar", "", OpcodeCategory::Arithmetic, -1);
+
+        // Logical
+        table[0x9F] = OpcodeInfo::new("XorI4", "", OpcodeCategory::Logical, -1);
+        table[0xCD] = OpcodeInfo::new("NotI4", "", OpcodeCategory::Logical, 0);
+        table[0xCE] = OpcodeInfo::new("AndI4", "", OpcodeCategory::Logical, -1);
+        table[0xCF] = OpcodeInfo::new("OrI4", "", OpcodeCategory::Logical, -1);
+
+        // Comparison - Integer (I2)
         table[0xA0] = OpcodeInfo::new("EqI2", "", OpcodeCategory::Comparison, -1);
         table[0xA1] = OpcodeInfo::new("NeI2", "", OpcodeCategory::Comparison, -1);
         table[0xA2] = OpcodeInfo::new("LeI2", "", OpcodeCategory::Comparison, -1);
@@ -290,12 +596,183 @@ fn get_opcode_info(opcode: u8) -> &'static OpcodeInfo {
         table[0xA4] = OpcodeInfo::new("LtI2", "", OpcodeCategory::Comparison, -1);
         table[0xA5] = OpcodeInfo::new("GtI2", "", OpcodeCategory::Comparison, -1);
 
+        // Comparison - Long (I4) and Double (R8)
+        table[0xA6] = OpcodeInfo::new("EqI4", "", OpcodeCategory::Comparison, -1);
+        table[0xA8] = OpcodeInfo::new("NeI4", "", OpcodeCategory::Comparison, -1);
+        table[0xA9] = OpcodeInfo::new("LeI4", "", OpcodeCategory::Comparison, -1);
+        table[0xAA] = OpcodeInfo::new("GeI4", "", OpcodeCategory::Comparison, -1);
+        table[0xAB] = OpcodeInfo::new("LtI4", "", OpcodeCategory::Comparison, -1);
+        table[0xAC] = OpcodeInfo::new("GtI4", "", OpcodeCategory::Comparison, -1);
+        table[0xAD] = OpcodeInfo::new("EqR8", "", OpcodeCategory::Comparison, -1);
+        table[0xAE] = OpcodeInfo::new("NeR8", "", OpcodeCategory::Comparison, -1);
+        table[0xAF] = OpcodeInfo::new("LeR8", "", OpcodeCategory::Comparison, -1);
+        table[0xB0] = OpcodeInfo::new("GeR8", "", OpcodeCategory::Comparison, -1);
+        table[0xB1] = OpcodeInfo::new("LtR8", "", OpcodeCategory::Comparison, -1);
+        table[0xB2] = OpcodeInfo::new("GtR8", "", OpcodeCategory::Comparison, -1);
+
+        // Comparison - String and Variant
+        table[0xC1] = OpcodeInfo::new("EqStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC2] = OpcodeInfo::new("NeStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC3] = OpcodeInfo::new("LtStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC4] = OpcodeInfo::new("GtStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC5] = OpcodeInfo::new("LeStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC6] = OpcodeInfo::new("GeStr", "", OpcodeCategory::Comparison, -1);
+        table[0xC7] = OpcodeInfo::new("EqVar", "", OpcodeCategory::Comparison, -1);
+        table[0xC8] = OpcodeInfo::new("NeVar", "", OpcodeCategory::Comparison, -1);
+        table[0xC9] = OpcodeInfo::new("LtVar", "", OpcodeCategory::Comparison, -1);
+        table[0xCA] = OpcodeInfo::new("GtVar", "", OpcodeCategory::Comparison, -1);
+        table[0xCB] = OpcodeInfo::new("LeVar", "", OpcodeCategory::Comparison, -1);
+        table[0xCC] = OpcodeInfo::new("GeVar", "", OpcodeCategory::Comparison, -1);
+
         table
     };
 
     &OPCODES[opcode as usize]
 }
 
+/// Extended opcode tables, indexed by lead byte (0xFB-0xFF) and secondary byte
+///
+/// ReDim bounds are popped as (lower, upper) pairs; the count depends on the
+/// dimension operand, so its stack delta is left at 0.
+static EXTENDED_OPCODES: [[Option<OpcodeInfo>; 256]; 5] = {
+    let mut table = [[None; 256]; 5];
+
+    // Lead 0xFB: Currency arithmetic, comparisons and frame access
+    table[0][0x00] = Some(OpcodeInfo::new("AddCy", "", OpcodeCategory::Arithmetic, -1));
+    table[0][0x01] = Some(OpcodeInfo::new("SubCy", "", OpcodeCategory::Arithmetic, -1));
+    table[0][0x02] = Some(OpcodeInfo::new("MulCy", "", OpcodeCategory::Arithmetic, -1));
+    table[0][0x03] = Some(OpcodeInfo::new("DivCy", "", OpcodeCategory::Arithmetic, -1));
+    table[0][0x04] = Some(OpcodeInfo::new("UMiCy", "", OpcodeCategory::Arithmetic, 0));
+    table[0][0x08] = Some(OpcodeInfo::new("EqCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x09] = Some(OpcodeInfo::new("NeCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x0A] = Some(OpcodeInfo::new("LtCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x0B] = Some(OpcodeInfo::new("GtCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x0C] = Some(OpcodeInfo::new("LeCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x0D] = Some(OpcodeInfo::new("GeCy", "", OpcodeCategory::Comparison, -1));
+    table[0][0x10] = Some(OpcodeInfo::new(
+        "EqDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    table[0][0x11] = Some(OpcodeInfo::new(
+        "NeDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    table[0][0x12] = Some(OpcodeInfo::new(
+        "LtDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    table[0][0x13] = Some(OpcodeInfo::new(
+        "GtDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    table[0][0x14] = Some(OpcodeInfo::new(
+        "LeDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    table[0][0x15] = Some(OpcodeInfo::new(
+        "GeDate",
+        "",
+        OpcodeCategory::Comparison,
+        -1,
+    ));
+    // FLdCy/FStCy are the standard 0x6B/0x76
+    table[0][0x32] = Some(OpcodeInfo::new("FLdDate", "c", OpcodeCategory::Variable, 1));
+    table[0][0x33] = Some(OpcodeInfo::new(
+        "FStDate",
+        "c",
+        OpcodeCategory::Variable,
+        -1,
+    ));
+
+    // Lead 0xFC: dynamic arrays
+    table[1][0x0D] = Some(OpcodeInfo::new("Redim", "ca", OpcodeCategory::Array, 0));
+    table[1][0x0E] = Some(OpcodeInfo::new(
+        "RedimPreserve",
+        "ca",
+        OpcodeCategory::Array,
+        0,
+    ));
+    table[1][0x0F] = Some(OpcodeInfo::new("Erase", "c", OpcodeCategory::Array, 0));
+    table[1][0x10] = Some(OpcodeInfo::new("LBoundAry", "a", OpcodeCategory::Array, 0));
+    table[1][0x11] = Some(OpcodeInfo::new("UBoundAry", "a", OpcodeCategory::Array, 0));
+
+    // Lead 0xFD: string operators (LikeStr/LikeVar are the standard 0xE3/0xE4)
+    table[2][0x02] = Some(OpcodeInfo::new(
+        "MidStmtStr",
+        "",
+        OpcodeCategory::String,
+        -4,
+    ));
+
+    table
+};
+
+/// Operand layouts of extended opcode ranges whose individual members are
+/// not in [`EXTENDED_OPCODES`], so unknown ops still keep the stream aligned
+static EXTENDED_OPERAND_RANGES: &[(u8, std::ops::RangeInclusive<u8>, &str)] = &[
+    (0xFB, 0x30..=0x3F, "c"),
+    (0xFC, 0x00..=0x0F, "c"),
+    (0xFC, 0x10..=0x1F, "a"),
+];
+
+/// Get opcode information for extended opcodes (lead byte 0xFB-0xFF)
+fn get_extended_opcode_info(opcode: u8, ext_opcode: u8) -> Option<&'static OpcodeInfo> {
+    EXTENDED_OPCODES.get(opcode.checked_sub(0xFB)? as usize)?[ext_opcode as usize].as_ref()
+}
+
+/// Operand format of an extended opcode missing from the table
+fn extended_fallback_format(opcode: u8, ext_opcode: u8) -> &'static str {
+    EXTENDED_OPERAND_RANGES
+        .iter()
+        .find(|(lead, range, _)| *lead == opcode && range.contains(&ext_opcode))
+        .map_or("", |(_, _, format)| format)
+}
+
+/// Get the mnemonic of a primary opcode ("Extended" for 0xFB-0xFF lead bytes)
+pub fn opcode_mnemonic(opcode: u8) -> &'static str {
+    if is_extended_opcode(opcode) {
+        "Extended"
+    } else {
+        get_opcode_info(opcode).mnemonic
+    }
+}
+
+/// Look up the symbol of a call or branch instruction's target operand
+fn resolve_symbol(instr: &Instruction, resolver: &SymbolResolver) -> Option<String> {
+    if let Some(target) = instr.branch_target() {
+        return resolver(OperandKind::Branch, target as i64);
+    }
+    if instr.category != OpcodeCategory::Call {
+        return None;
+    }
+
+    // The operand of a plain `Call*` is an argument count, not a procedure
+    let kind = if instr.mnemonic.starts_with("ImpAd") {
+        OperandKind::Import
+    } else if instr.mnemonic.contains("VCall") {
+        OperandKind::VTable
+    } else {
+        return None;
+    };
+    let value = match instr.operands.last()?.value {
+        OperandValue::Byte(v) => v as i64,
+        OperandValue::Int16(v) => v as i64,
+        OperandValue::Int32(v) => v as i64,
+        _ => return None,
+    };
+    resolver(kind, value)
+}
+
 /// Check if opcode is extended (0xFB-0xFF)
 fn is_extended_opcode(opcode: u8) -> bool {
     opcode >= 0xFB
@@ -305,35 +782,94 @@ fn is_extended_opcode(opcode: u8) -> bool {
 pub struct Disassembler {
     data: Vec<u8>,
     offset: usize,
+    strict: bool,
+    resolver: Option<SymbolResolver>,
+    max_instructions: usize,
+    truncated: bool,
 }
 
+/// Default instruction budget for a single [`Disassembler::disassemble`] call
+pub const DEFAULT_MAX_INSTRUCTIONS: usize = 100_000;
+
 impl Disassembler {
     /// Create a new disassembler for the given P-Code bytes
     pub fn new(data: Vec<u8>) -> Self {
-        Self { data, offset: 0 }
+        Self {
+            data,
+            offset: 0,
+            strict: false,
+            resolver: None,
+            max_instructions: DEFAULT_MAX_INSTRUCTIONS,
+            truncated: false,
+        }
+    }
+
+    /// Fail on malformed input (e.g. contradictory operand types) instead of
+    /// stopping quietly
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Name call and branch targets with `resolver` as they are decoded
+    pub fn with_symbol_resolver(mut self, resolver: SymbolResolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Stop after `max` instructions, so misdecoded or corrupt P-Code cannot
+    /// run on through the rest of the data
+    pub fn with_max_instructions(mut self, max: usize) -> Self {
+        self.max_instructions = max;
+        self
+    }
+
+    /// Whether the last [`disassemble`](Self::disassemble) call stopped at
+    /// the instruction budget rather than at a procedure exit
+    pub fn truncated(&self) -> bool {
+        self.truncated
     }
 
     /// Disassemble all instructions starting from the current offset
+    ///
+    /// Stops at a procedure exit unless a branch, error handler or `Resume`
+    /// target lies beyond it (an `Exit Sub` in the middle of the body).
     pub fn disassemble(&mut self, address: u32) -> Result<Vec<Instruction>> {
         let mut instructions = Vec::new();
         let mut current_address = address;
+        let mut furthest_target = None;
+        self.truncated = false;
 
         while self.offset < self.data.len() {
+            if instructions.len() >= self.max_instructions {
+                self.truncated = true;
+                break;
+            }
             match self.disassemble_one(current_address) {
                 Ok(instr) => {
                     current_address += instr.bytes.len() as u32;
 
+                    let is_jump = instr.is_branch
+                        || instr.mnemonic == "OnErrorGoto"
+                        || instr.mnemonic == "ResumeLabel";
+                    if let Some(target) = instr.branch_target().filter(|_| is_jump) {
+                        furthest_target = furthest_target.max(Some(target));
+                    }
+
                     // Check if this is a return instruction
                     let is_return = instr.is_return;
 
                     instructions.push(instr);
 
                     // Stop at procedure exit
-                    if is_return {
+                    if is_return && !furthest_target.is_some_and(|t| t >= current_address) {
                         break;
                     }
                 }
                 Err(e) => {
+                    if self.strict {
+                        return Err(e);
+                    }
                     // If we encounter an error, stop disassembly
                     eprintln!("Disassembly error at offset {}: {}", self.offset, e);
                     break;
@@ -357,14 +893,15 @@ impl Disassembler {
         let mut instr = Instruction::new(address, opcode);
 
         // Check for extended opcode
-        if is_extended_opcode(opcode) {
+        let opcode_info = if is_extended_opcode(opcode) {
             let ext_opcode = self.read_byte()?;
             instr.extended_opcode = Some(ext_opcode);
-            instr.mnemonic = format!("Extended_{:02X}_{:02X}", opcode, ext_opcode);
-            instr.category = OpcodeCategory::Unknown;
+            get_extended_opcode_info(opcode, ext_opcode)
         } else {
-            // Standard opcode
-            let opcode_info = get_opcode_info(opcode);
+            Some(get_opcode_info(opcode))
+        };
+
+        if let Some(opcode_info) = opcode_info {
             instr.mnemonic = opcode_info.mnemonic.to_string();
             instr.category = opcode_info.category;
             instr.stack_delta = opcode_info.stack_delta;
@@ -375,11 +912,19 @@ impl Disassembler {
 
             // Decode operands based on format string
             self.decode_operands(&mut instr, opcode_info.format)?;
+        } else if let Some(ext_opcode) = instr.extended_opcode {
+            instr.mnemonic = format!("Extended_{:02X}_{:02X}", opcode, ext_opcode);
+            instr.category = OpcodeCategory::Unknown;
+            self.decode_operands(&mut instr, extended_fallback_format(opcode, ext_opcode))?;
         }
 
         // Copy raw bytes
         instr.bytes = self.data[start_offset..self.offset].to_vec();
 
+        if let Some(resolver) = &self.resolver {
+            instr.symbol = resolve_symbol(&instr, resolver);
+        }
+
         Ok(instr)
     }
 
@@ -422,6 +967,13 @@ impl Disassembler {
                         .operands
                         .push(Operand::new(OperandValue::Float(val), PCodeType::Single));
                 }
+                b'g' => {
+                    // 64-bit float literal
+                    let val = self.read_f64()?;
+                    instr
+                        .operands
+                        .push(Operand::new(OperandValue::Double(val), PCodeType::Double));
+                }
                 b'l' => {
                     // Branch offset (2 bytes, signed)
                     let offset = self.read_i16()?;
@@ -454,13 +1006,15 @@ impl Disassembler {
                 }
                 b'z' => {
                     // Null-terminated string
-                    let s = self.read_string()?;
+                    let (s, terminated) = self.read_string();
+                    instr.truncated |= !terminated;
                     instr
                         .operands
                         .push(Operand::new(OperandValue::String(s), PCodeType::String));
                 }
                 b'%' | b'&' | b'!' | b'#' | b'~' => {
-                    // Type suffix - already captured in previous operand
+                    // Type suffix - authoritative type of the previous operand
+                    self.apply_type_suffix(instr, ch)?;
                 }
                 _ => {
                     // Unknown format character - skip
@@ -471,6 +1025,33 @@ impl Disassembler {
         Ok(())
     }
 
+    /// Apply a format type suffix to the most recently decoded operand
+    fn apply_type_suffix(&self, instr: &mut Instruction, suffix: u8) -> Result<()> {
+        let Some(suffix_type) = PCodeType::from_suffix(suffix) else {
+            return Ok(());
+        };
+        let Some(operand) = instr.operands.last_mut() else {
+            return Ok(());
+        };
+
+        if !operand.data_type.accepts_suffix(suffix_type) {
+            let message = format!(
+                "{} at 0x{:X}: {} operand contradicts '{}' suffix",
+                instr.mnemonic,
+                instr.address,
+                operand.data_type.to_string(),
+                suffix as char
+            );
+            if self.strict {
+                return Err(Error::PCodeDisassembly(message));
+            }
+            log::warn!("{}", message);
+        }
+
+        operand.data_type = suffix_type;
+        Ok(())
+    }
+
     /// Read a single byte
     fn read_byte(&mut self) -> Result<u8> {
         if self.offset >= self.data.len() {
@@ -506,6 +1087,17 @@ impl Disassembler {
         Ok(val)
     }
 
+    /// Read a 64-bit float (little-endian)
+    fn read_f64(&mut self) -> Result<f64> {
+        if self.offset + 8 > self.data.len() {
+            return Err(Error::parse("Unexpected end of data"));
+        }
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.data[self.offset..self.offset + 8]);
+        self.offset += 8;
+        Ok(f64::from_le_bytes(bytes))
+    }
+
     /// Read a 32-bit float (little-endian)
     fn read_f32(&mut self) -> Result<f32> {
         if self.offset + 4 > self.data.len() {
@@ -522,19 +1114,21 @@ impl Disassembler {
     }
 
     /// Read a null-terminated string
-    fn read_string(&mut self) -> Result<String> {
+    ///
+    /// A string running into the end of the data is returned as far as it
+    /// goes, with `false` for the missing terminator.
+    fn read_string(&mut self) -> (String, bool) {
         let start = self.offset;
         while self.offset < self.data.len() && self.data[self.offset] != 0 {
             self.offset += 1;
         }
 
-        if self.offset >= self.data.len() {
-            return Err(Error::parse("Unterminated string"));
-        }
-
         let s = String::from_utf8_lossy(&self.data[start..self.offset]).to_string();
-        self.offset += 1; // Skip null terminator
-        Ok(s)
+        let terminated = self.offset < self.data.len();
+        if terminated {
+            self.offset += 1; // Skip null terminator
+        }
+        (s, terminated)
     }
 }
 
@@ -565,6 +1159,291 @@ mod tests {
         assert_eq!(result[0].branch_offset, Some(16));
     }
 
+    #[test]
+    fn test_branch_target_forward_and_backward() {
+        // Branch +16 at 0x1000; Branch -6 at 0x1003 (back to 0x1000)
+        let data = vec![0x1E, 0x10, 0x00, 0x1E, 0xFA, 0xFF];
+        let mut disasm = Disassembler::new(data);
+        let result = disasm.disassemble(0x1000).unwrap();
+
+        assert_eq!(result[0].branch_target(), Some(0x1013));
+        assert_eq!(result[0].to_string(), "00001000  Branch  0x00001013");
+        assert_eq!(result[1].branch_target(), Some(0x1000));
+        assert_eq!(result[1].to_string(), "00001003  Branch  0x00001000");
+    }
+
+    #[test]
+    fn test_instruction_json() {
+        // Branch +16 at 0x1000; LitI2 7
+        let data = vec![0x1E, 0x10, 0x00, 0x5E, 0x07, 0x00];
+        let result = Disassembler::new(data).disassemble(0x1000).unwrap();
+        let json = serde_json::to_value(&result).unwrap();
+
+        let branch = &json[0];
+        assert_eq!(branch["mnemonic"], "Branch");
+        assert_eq!(branch["category"], "ControlFlow");
+        assert_eq!(branch["bytes"], "1E 10 00");
+        assert_eq!(branch["is_branch"], true);
+        assert_eq!(branch["branch_offset"], 16);
+        assert_eq!(branch["branch_target"], 0x1013);
+
+        let literal = &json[1];
+        assert_eq!(literal["branch_target"], serde_json::Value::Null);
+        assert_eq!(literal["operands"][0]["value"], 7);
+    }
+
+    #[test]
+    fn test_symbol_resolver_names_call_target() {
+        // ImpAdCallI2 12; CallHresult 12; Branch -9 (back to 0); ExitProc
+        let data = vec![0x0B, 0x0C, 0x00, 0x7F, 0x0C, 0x00, 0x1E, 0xF7, 0xFF, 0x14];
+        let mut disasm =
+            Disassembler::new(data).with_symbol_resolver(Box::new(|kind, value| {
+                match (kind, value) {
+                    (OperandKind::Import, 12) => Some("MessageBoxA".to_string()),
+                    (OperandKind::Branch, 0) => Some("loc_0".to_string()),
+                    _ => None,
+                }
+            }));
+        let result = disasm.disassemble(0).unwrap();
+
+        assert_eq!(result[0].symbol.as_deref(), Some("MessageBoxA"));
+        assert_eq!(result[0].to_string(), "00000000  ImpAdCallI2  MessageBoxA");
+        // The operand of CallHresult is an argument count
+        assert_eq!(result[1].symbol, None);
+        assert_eq!(result[2].to_string(), "00000006  Branch  loc_0");
+        assert_eq!(result[3].symbol, None);
+    }
+
+    #[test]
+    fn test_redim_preserve_extended_opcode() {
+        // RedimPreserve slot 4, 2 dimensions; then an unlisted extended opcode
+        let data = vec![0xFC, 0x0E, 0x04, 0x00, 0x02, 0xFC, 0x7F, 0x14];
+        let mut disasm = Disassembler::new(data);
+        let result = disasm.disassemble(0x1000).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].mnemonic, "RedimPreserve");
+        assert_eq!(result[0].category, OpcodeCategory::Array);
+        assert_eq!(result[0].extended_opcode, Some(0x0E));
+        assert!(matches!(
+            result[0].operands[0].value,
+            OperandValue::Int16(4)
+        ));
+        assert!(matches!(result[0].operands[1].value, OperandValue::Byte(2)));
+        assert_eq!(result[1].mnemonic, "Extended_FC_7F");
+        assert_eq!(result[1].category, OpcodeCategory::Unknown);
+    }
+
+    #[test]
+    fn test_extended_opcodes_decode() {
+        // FLdDate slot 8, FLdDate slot 12, AddCy, EqCy, Erase slot 2, ExitProc
+        let data = vec![
+            0xFB, 0x32, 0x08, 0x00, 0xFB, 0x32, 0x0C, 0x00, 0xFB, 0x00, 0xFB, 0x08, 0xFC, 0x0F,
+            0x02, 0x00, 0x14,
+        ];
+        let mut disasm = Disassembler::new(data).strict(true);
+        let result = disasm.disassemble(0x1000).unwrap();
+
+        let mnemonics: Vec<_> = result.iter().map(|i| i.mnemonic.as_str()).collect();
+        assert_eq!(
+            mnemonics,
+            ["FLdDate", "FLdDate", "AddCy", "EqCy", "Erase", "ExitProc"]
+        );
+        assert_eq!(result[0].category, OpcodeCategory::Variable);
+        assert_eq!(result[0].bytes.len(), 4);
+        assert!(matches!(
+            result[1].operands[0].value,
+            OperandValue::Int16(12)
+        ));
+        assert_eq!(result[2].category, OpcodeCategory::Arithmetic);
+        assert_eq!(result[2].stack_delta, -1);
+        assert_eq!(result[3].category, OpcodeCategory::Comparison);
+        assert_eq!(result[4].address, 0x100C);
+    }
+
+    #[test]
+    fn test_unknown_extended_opcode_keeps_alignment() {
+        // Unlisted frame-access op in a range with a known 2-byte operand
+        let data = vec![0xFB, 0x3E, 0x10, 0x00, 0x14];
+        let mut disasm = Disassembler::new(data);
+        let result = disasm.disassemble(0).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].mnemonic, "Extended_FB_3E");
+        assert_eq!(result[0].bytes.len(), 4);
+        assert_eq!(result[1].mnemonic, "ExitProc");
+    }
+
+    #[test]
+    fn test_opcode_mnemonics_unique() {
+        // Standard opcodes first, then extended ones, each with its encoding
+        let standard = (0..0xFB).map(|op| (format!("{:02X}", op), get_opcode_info(op)));
+        let extended = EXTENDED_OPCODES
+            .iter()
+            .enumerate()
+            .flat_map(|(lead, table)| {
+                table.iter().enumerate().filter_map(move |(ext, info)| {
+                    Some((format!("{:02X} {:02X}", lead + 0xFB, ext), info.as_ref()?))
+                })
+            });
+        let entries: Vec<(String, &OpcodeInfo)> = standard
+            .chain(extended)
+            .filter(|(_, info)| info.category != OpcodeCategory::Unknown)
+            .collect();
+        assert!(!entries.is_empty());
+        for (i, (encoding, info)) in entries.iter().enumerate() {
+            if let Some((other, _)) = entries[..i]
+                .iter()
+                .find(|(_, other)| other.mnemonic == info.mnemonic)
+            {
+                panic!(
+                    "{} is defined at both {} and {}",
+                    info.mnemonic, other, encoding
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_documented_opcodes_in_table() {
+        // The families every compiled method leans on: literals, frame
+        // loads/stores, I4/R4/R8 arithmetic and import calls
+        let documented = [
+            (0x04, "FLdRfVar"),
+            (0x05, "ImpAdLdRf"),
+            (0x09, "ImpAdCallHresult"),
+            (0x0A, "ImpAdCallFPR4"),
+            (0x0D, "VCallHresult"),
+            (0x13, "ExitProcHresult"),
+            (0x14, "ExitProc"),
+            (0x1B, "LitStr"),
+            (0x1C, "BranchF"),
+            (0x1D, "BranchT"),
+            (0x1E, "Branch"),
+            (0x27, "LitVar_Missing"),
+            (0x28, "LitVarI2"),
+            (0x3A, "LitVarStr"),
+            (0x43, "FStStrCopy"),
+            (0x4B, "OnErrorGoto"),
+            (0x67, "FLdVar"),
+            (0x6A, "FLdI4"),
+            (0x6E, "FStI4"),
+            (0x72, "FStVar"),
+            (0x82, "AddI4"),
+            (0x83, "SubI4"),
+            (0x84, "MulI4"),
+            (0x88, "AddR8"),
+            (0x8B, "DivR8"),
+            (0x8D, "AddR4"),
+            (0x90, "DivR4"),
+            (0xD0, "ImpAdCallI4"),
+            (0xF0, "LitVarI4"),
+            (0xF2, "LitVarR8"),
+        ];
+        for (opcode, mnemonic) in documented {
+            let info = get_opcode_info(opcode);
+            assert_eq!(info.mnemonic, mnemonic, "opcode 0x{:02X}", opcode);
+            assert_ne!(info.category, OpcodeCategory::Unknown);
+        }
+    }
+
+    /// Disassemble every method of the binary named by `VBDC_PCODE_SAMPLE`
+    ///
+    /// Synthetic bytes can only check the table against itself; run this
+    /// against a real compiled P-Code executable with
+    /// `VBDC_PCODE_SAMPLE=app.exe cargo test -- --ignored`.
+    #[test]
+    #[ignore = "needs a real VB executable in VBDC_PCODE_SAMPLE"]
+    fn test_real_methods_decode_to_return() {
+        let path = std::env::var("VBDC_PCODE_SAMPLE").expect("VBDC_PCODE_SAMPLE not set");
+        let vb_file = crate::Decompiler::new().parse_file(&path).unwrap();
+        let methods = vb_file.disassemble_methods();
+        assert!(!methods.is_empty(), "{} has no P-Code methods", path);
+
+        for method in methods {
+            let name = format!("{}.{}", method.object, method.method);
+            assert_eq!(method.error, None, "{}", name);
+            assert!(
+                method.instructions.iter().all(|i| i.mnemonic != "Unknown"),
+                "{} has unknown opcodes",
+                name
+            );
+            assert!(
+                method.instructions.iter().any(|i| i.is_return),
+                "{} never reaches a return",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_hand_assembled_method_decodes_to_return() {
+        // Hand-assembled from the opcode table, so this only guards operand
+        // widths against regressions; test_real_methods_decode_to_return
+        // checks them against compiled code. One wrong operand width anywhere
+        // misaligns everything after it and the final return is lost
+        #[rustfmt::skip]
+        let data = vec![
+            0x00, 0x0A, 0x00,                   // LargeBos 10
+            0x04, 0xF4,                         // FLdRfVar
+            0x44, 0x34, 0x00,                   // MemLdI4 0x34
+            0x5F, 0x01, 0x00, 0x00, 0x00,       // LitI4 1
+            0x82,                               // AddI4
+            0x48, 0x34, 0x00,                   // MemStI4 0x34
+            0x1B, b'H', b'i', 0x00,             // LitStr "Hi"
+            0x27, 0x27, 0x27,                   // LitVar_Missing x3
+            0x0A, 0x00,                         // ImpAdCallFPR4
+            0x35,                               // FFree1Var
+            0xF0, 0x05, 0x00, 0x00, 0x00,       // LitVarI4 5
+            0x67, 0xF0,                         // FLdVar
+            0xC7,                               // EqVar
+            0x1C, 0x04, 0x00,                   // BranchF +4
+            0xD4, 0x10, 0x00,                   // VCallI4 0x10
+            0x1A,                               // FFree1Ad
+            0x61, 0, 0, 0, 0, 0, 0, 0x04, 0x40, // LitR8 2.5
+            0x72, 0xE8,                         // FStVar
+            0x13,                               // ExitProcHresult
+        ];
+        let len = data.len();
+        let result = Disassembler::new(data).strict(true).disassemble(0).unwrap();
+
+        assert_eq!(result.len(), 21);
+        assert!(result.iter().all(|i| i.category != OpcodeCategory::Unknown));
+        assert_eq!(result.iter().map(|i| i.bytes.len()).sum::<usize>(), len);
+
+        let last = result.last().unwrap();
+        assert_eq!(last.mnemonic, "ExitProcHresult");
+        assert!(last.is_return);
+    }
+
+    /// Disassemble each `tests/fixtures/pcode/*.bin` method and compare it
+    /// against the listing in the matching `.dis` file
+    #[test]
+    fn test_pcode_fixtures_match_listing() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pcode");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "bin") {
+                continue;
+            }
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let expected = std::fs::read_to_string(path.with_extension("dis"))
+                .unwrap_or_else(|e| panic!("{}.dis: {}", name, e));
+
+            let result = Disassembler::new(std::fs::read(&path).unwrap())
+                .strict(true)
+                .disassemble(0)
+                .unwrap_or_else(|e| panic!("{}: {}", name, e));
+            let listing: String = result.iter().map(|i| i.to_string() + "\n").collect();
+
+            assert_eq!(listing, expected, "{}", name);
+            assert!(result.last().is_some_and(|i| i.is_return), "{}", name);
+            checked += 1;
+        }
+        assert!(checked > 0, "no fixtures in {}", dir.display());
+    }
+
     #[test]
     fn test_lit_i2_opcode() {
         let data = vec![0x5E, 0x2A, 0x14]; // LitI2 42, ExitProc (removed extra byte)
@@ -575,4 +1454,95 @@ mod tests {
         assert_eq!(result[0].mnemonic, "LitI2");
         assert_eq!(result[0].operands.len(), 1);
     }
+
+    #[test]
+    fn test_opcode_mnemonic() {
+        assert_eq!(opcode_mnemonic(0x5E), "LitI2");
+        assert_eq!(opcode_mnemonic(0x01), "InvalidExcode");
+        assert_eq!(opcode_mnemonic(0xFB), "Extended");
+    }
+
+    #[test]
+    fn test_imp_ad_call_operand() {
+        let data = vec![0x09, 0x03, 0x00, 0x14]; // ImpAdCallHresult #3, ExitProc
+        let mut disasm = Disassembler::new(data);
+        let result = disasm.disassemble(0).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].is_native_call());
+        assert!(matches!(
+            result[0].operands[0].value,
+            OperandValue::Int16(3)
+        ));
+        assert!(!result[1].is_native_call());
+    }
+
+    #[test]
+    fn test_lit_r8_suffix_type() {
+        let mut data = vec![0x61]; // LitR8 2.5
+        data.extend_from_slice(&2.5f64.to_le_bytes());
+        data.push(0x14);
+        let mut disasm = Disassembler::new(data).strict(true);
+        let result = disasm.disassemble(0).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].operands[0].data_type, PCodeType::Double);
+        assert!(matches!(result[0].operands[0].value, OperandValue::Double(v) if v == 2.5));
+    }
+
+    #[test]
+    fn test_suffix_sets_untyped_operand() {
+        // LitI2 reads an untyped byte; the '%' suffix types it as Integer
+        let mut disasm = Disassembler::new(vec![0x5E, 0x2A, 0x14]).strict(true);
+        let result = disasm.disassemble(0).unwrap();
+        assert_eq!(result[0].operands[0].data_type, PCodeType::Integer);
+    }
+
+    #[test]
+    fn test_contradictory_suffix() {
+        let mut instr = Instruction::new(0, 0x00);
+        instr
+            .operands
+            .push(Operand::new(OperandValue::Float(1.0), PCodeType::Single));
+
+        let strict = Disassembler::new(Vec::new()).strict(true);
+        assert!(strict.apply_type_suffix(&mut instr, b'&').is_err());
+
+        let lenient = Disassembler::new(Vec::new());
+        assert!(lenient.apply_type_suffix(&mut instr, b'&').is_ok());
+        assert_eq!(instr.operands[0].data_type, PCodeType::Long);
+    }
+
+    #[test]
+    fn test_instruction_budget() {
+        // A long run of LitI2 with no procedure exit
+        let garbage = [0x5E, 0x01].repeat(1000);
+
+        let mut disasm = Disassembler::new(garbage.clone()).with_max_instructions(100);
+        let result = disasm.disassemble(0).unwrap();
+        assert_eq!(result.len(), 100);
+        assert!(disasm.truncated());
+
+        let mut disasm = Disassembler::new(garbage);
+        let result = disasm.disassemble(0).unwrap();
+        assert_eq!(result.len(), 1000);
+        assert!(!disasm.truncated());
+    }
+
+    #[test]
+    fn test_unterminated_string() {
+        // LitI2 1; LitStr "Hel (cut off)
+        let mut disasm = Disassembler::new(vec![0x5E, 0x01, 0x1B, b'H', b'e', b'l']).strict(true);
+        let result = disasm.disassemble(0).unwrap();
+
+        assert_eq!(result.len(), 2);
+        assert!(!result[0].truncated);
+        assert_eq!(result[1].mnemonic, "LitStr");
+        assert!(result[1].truncated);
+        assert_eq!(result[1].bytes.len(), 4);
+        match &result[1].operands[0].value {
+            OperandValue::String(s) => assert_eq!(s, "Hel"),
+            other => panic!("expected a string operand, got {:?}", other),
+        }
+    }
 }
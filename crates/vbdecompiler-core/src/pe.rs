@@ -8,27 +8,175 @@
 //! - PE headers (DOS, NT, Optional)
 //! - Section headers and data
 //! - Import tables
+//! - Export tables
 //! - Resource sections
 //! - Packer detection
 
 use crate::error::{Error, Result};
-use crate::packer::detect_packer;
-use goblin::pe::{section_table::SectionTable, PE};
+use crate::packer::{calculate_shannon_entropy, detect_packer, section_raw_data};
+use goblin::pe::{export::ExportAddressTableEntry, section_table::SectionTable, PE};
+use memmap2::{MmapMut, MmapOptions};
+use std::io::Read;
+use std::ops::{Deref, DerefMut};
 use std::path::Path;
 
 /// Maximum size for a single read operation (100MB)
 const MAX_READ_SIZE: usize = 100 * 1024 * 1024;
 
+/// Exports that identify an in-process COM server (ActiveX DLL/OCX)
+const COM_SERVER_EXPORTS: &[&str] = &["DllGetClassObject", "DllCanUnloadNow"];
+
+/// Resource type ID of the VS_VERSIONINFO resource
+const RT_VERSION: u32 = 16;
+
+/// Signature of VS_FIXEDFILEINFO
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// Size of the DOS header that precedes the DOS stub
+const DOS_HEADER_SIZE: usize = 0x40;
+
+/// `DanS` marker opening the Rich header (stored XOR-encoded)
+const DANS_MARKER: u32 = 0x536E_6144;
+
+/// `Rich` marker closing the Rich header, followed by the XOR key
+const RICH_MARKER: u32 = 0x6863_6952;
+
+/// One `@comp.id` record of the Rich header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct RichEntry {
+    /// Toolchain component (compiler, linker, resource converter) ID
+    pub product_id: u16,
+    /// Build number of that component
+    pub build: u16,
+    /// Number of objects the component contributed
+    pub count: u32,
+}
+
+/// Linker-written Rich header identifying the toolchain that built the image
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RichHeader {
+    /// XOR key (a checksum over the DOS header, stub and entries)
+    pub key: u32,
+    /// File offset of the `DanS` marker
+    pub offset: usize,
+    /// Decoded entries in file order
+    pub entries: Vec<RichEntry>,
+}
+
+/// Size of a delay import descriptor
+const DELAY_DESCRIPTOR_SIZE: u32 = 32;
+
+/// Upper bound on functions read from one delay import name table
+const MAX_DELAY_IMPORTS: u32 = 4096;
+
+/// Version resource (VS_VERSIONINFO) contents
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct VersionInfo {
+    /// Binary file version from VS_FIXEDFILEINFO (`major.minor.build.revision`)
+    pub file_version: Option<String>,
+    /// Binary product version from VS_FIXEDFILEINFO
+    pub product_version: Option<String>,
+    /// StringFileInfo entries in file order (CompanyName, ProductName, ...)
+    pub strings: Vec<(String, String)>,
+}
+
+impl VersionInfo {
+    /// Look up a StringFileInfo value by key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// CompanyName string
+    pub fn company_name(&self) -> Option<&str> {
+        self.get("CompanyName")
+    }
+
+    /// ProductName string
+    pub fn product_name(&self) -> Option<&str> {
+        self.get("ProductName")
+    }
+}
+
+/// Exported symbol
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ExportEntry {
+    /// Symbol name (None for ordinal-only exports)
+    pub name: Option<String>,
+    /// Export ordinal (including the directory's ordinal base)
+    pub ordinal: u32,
+    /// RVA of the exported symbol (of the forwarder string for forwarders)
+    pub rva: u32,
+    /// Forwarder target (`DLL.Symbol` or `DLL.#ordinal`)
+    pub forwarded: Option<String>,
+}
+
+/// Block of base relocations covering one 4 KiB page
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Relocation {
+    /// RVA of the page
+    pub rva: u32,
+    /// `(type, offset within the page)` of each fixup, without the
+    /// `IMAGE_REL_BASED_ABSOLUTE` padding entries
+    pub entries: Vec<(u8, u16)>,
+}
+
+/// Backing storage of a PE file's bytes
+enum FileData {
+    /// Read into memory
+    Owned(Vec<u8>),
+    /// Private copy-on-write mapping: in-place patches never reach the file
+    Mapped(MmapMut),
+}
+
+impl FileData {
+    /// Map `path` copy-on-write
+    fn map(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: The mapping is private, so our writes stay in memory. Another
+        // process truncating or rewriting the file while it's mapped is outside
+        // our control; callers opting into mapping accept that risk.
+        let map = unsafe { MmapOptions::new().map_copy(&file)? };
+        Ok(Self::Mapped(map))
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Owned(data) => data,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+impl DerefMut for FileData {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Owned(data) => data,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
 /// PE file parser
 pub struct PEFile {
-    /// Raw file data
-    data: Vec<u8>,
+    /// Raw file data (owned or mapped; its bytes never move once parsed)
+    data: FileData,
     /// Parsed PE structure from goblin
     pe: PE<'static>,
-    /// Image base address
-    image_base: u32,
+    /// Image base address (64-bit wide to hold PE32+ bases)
+    image_base: u64,
     /// Entry point RVA
     entry_point: u32,
+    /// Resource directory (RVA, size) from the original header, before stripping
+    resource_directory: Option<(u32, u32)>,
+    /// Stored and computed checksums of the original file, before stripping
+    checksum: (u32, u32),
 }
 
 impl PEFile {
@@ -38,8 +186,42 @@ impl PEFile {
         Self::from_bytes(data)
     }
 
-    /// Parse a PE file from bytes
-    pub fn from_bytes(mut data: Vec<u8>) -> Result<Self> {
+    /// Parse a PE file by memory-mapping it instead of reading it whole
+    ///
+    /// Pages are loaded on demand, which keeps large wrapped installers from
+    /// being copied into memory. The file must not change while the
+    /// `PEFile` is alive.
+    pub fn from_path_mmap(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(FileData::map(path.as_ref())?, true)
+    }
+
+    /// Memory-map and parse a PE file even if a packer is detected
+    pub fn from_path_mmap_allow_packed(path: impl AsRef<Path>) -> Result<Self> {
+        Self::parse(FileData::map(path.as_ref())?, false)
+    }
+
+    /// Parse a PE file from bytes, refusing packed executables
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        Self::parse(FileData::Owned(data), true)
+    }
+
+    /// Parse a PE file from any byte source, refusing packed executables
+    ///
+    /// The source is read to the end into memory first, so this suits
+    /// cursors, decompressed streams and archive members. For large files on
+    /// disk prefer [`PEFile::from_path_mmap`], which avoids the copy.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(data)
+    }
+
+    /// Parse a PE file from bytes even if a packer is detected
+    pub fn from_bytes_allow_packed(data: Vec<u8>) -> Result<Self> {
+        Self::parse(FileData::Owned(data), false)
+    }
+
+    fn parse(mut data: FileData, reject_packed: bool) -> Result<Self> {
         if data.len() < 64 {
             return Err(Error::invalid_pe("File too small to contain DOS header"));
         }
@@ -50,7 +232,7 @@ impl PEFile {
         }
 
         // Check for packers early
-        if let Ok(Some(detection)) = detect_packer(&data) {
+        if let (true, Ok(Some(detection))) = (reject_packed, detect_packer(&data)) {
             log::warn!(
                 "Packed executable detected: {} (confidence: {:.0}%)",
                 detection.packer.name(),
@@ -66,12 +248,27 @@ impl PEFile {
             )));
         }
 
+        // Remember where the resources are so version_info() can walk them itself
+        let resource_directory = Self::resource_directory_offset(&data).and_then(|offset| {
+            let rva = u32::from_le_bytes(data[offset..offset + 4].try_into().ok()?);
+            let size = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().ok()?);
+            (rva != 0 && size != 0).then_some((rva, size))
+        });
+
+        // Checksum the file as it came in; stripping below changes the bytes
+        let checksum = Self::checksum_offset(&data)
+            .map(|offset| {
+                let stored = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+                (stored, Self::pe_checksum(&data, offset))
+            })
+            .unwrap_or_default();
+
         // VB6 executables often have non-standard resource structures that goblin can't parse,
         // but resources aren't needed for VB decompilation (we only need headers, sections, imports).
         // Proactively remove the resource directory to avoid parsing issues.
-        if let Some(fixed_data) = Self::try_remove_resource_directory(&data) {
+        // A correct checksum is recomputed so the stripped copy stays valid.
+        if Self::try_remove_resource_directory(&mut data, checksum.0 == checksum.1) {
             log::debug!("Removed resource directory to avoid VB6 compatibility issues");
-            data = fixed_data;
         }
 
         // Try parsing with permissive mode
@@ -80,8 +277,9 @@ impl PEFile {
 
         // Parse PE using goblin
         // SAFETY: We need to transmute the lifetime to 'static to store the PE struct.
-        // The PE struct holds references into the data vector, and we ensure both live
-        // for the same lifetime by storing them together in PEFile.
+        // The PE struct holds references into the data buffer, and we ensure both live
+        // for the same lifetime by storing them together in PEFile. Neither a Vec's
+        // heap buffer nor a mapping moves when the FileData holding it does.
         let pe: PE<'static> = unsafe {
             let data_ptr = data.as_ptr();
             let data_len = data.len();
@@ -91,11 +289,58 @@ impl PEFile {
         };
 
         // Continue with rest of validation
-        Self::validate_and_create(data, pe)
+        let mut pe_file = Self::validate_and_create(data, pe)?;
+        pe_file.resource_directory = resource_directory;
+        pe_file.checksum = checksum;
+        Ok(pe_file)
     }
 
     /// Try to remove the resource directory entry from PE optional header
-    fn try_remove_resource_directory(data: &[u8]) -> Option<Vec<u8>> {
+    ///
+    /// Patches `data` in place; a mapped file only copies the touched page.
+    /// With `update_checksum` the header checksum is recomputed afterwards.
+    fn try_remove_resource_directory(data: &mut [u8], update_checksum: bool) -> bool {
+        let Some(resource_dir_offset) = Self::resource_directory_offset(data) else {
+            return false;
+        };
+
+        // Zero out resource directory entry (8 bytes: RVA + Size)
+        data[resource_dir_offset..resource_dir_offset + 8].fill(0);
+        if update_checksum {
+            Self::write_checksum(data);
+        }
+        true
+    }
+
+    /// Recompute the checksum of the PE image in `data` and store it in the
+    /// optional header
+    ///
+    /// Returns the new checksum, or `None` if the header is truncated.
+    pub fn write_checksum(data: &mut [u8]) -> Option<u32> {
+        let offset = Self::checksum_offset(data)?;
+        let checksum = Self::pe_checksum(data, offset);
+        data[offset..offset + 4].copy_from_slice(&checksum.to_le_bytes());
+        Some(checksum)
+    }
+
+    /// Standard PE checksum: a folded 16-bit ones' complement sum of the
+    /// file, skipping the checksum field itself, plus the file length
+    fn pe_checksum(data: &[u8], checksum_offset: usize) -> u32 {
+        let mut sum = 0u32;
+        for (i, word) in data.chunks(2).enumerate() {
+            let offset = i * 2;
+            if offset == checksum_offset || offset == checksum_offset + 2 {
+                continue;
+            }
+            sum += u16::from_le_bytes([word[0], word.get(1).copied().unwrap_or(0)]) as u32;
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        sum = (sum & 0xFFFF) + (sum >> 16);
+        sum.wrapping_add(data.len() as u32)
+    }
+
+    /// File offset of the optional header and its magic
+    fn optional_header_offset(data: &[u8]) -> Option<(usize, u16)> {
         if data.len() < 0x3c + 4 {
             return None;
         }
@@ -107,24 +352,42 @@ impl PEFile {
 
         // Optional header starts after PE signature (4 bytes) + COFF header (20 bytes)
         let opt_header_offset = pe_offset + 4 + 20;
-        // Resource directory entry is at offset 112 in optional header (for PE32)
-        let resource_dir_offset = opt_header_offset + 112;
-
-        if data.len() < resource_dir_offset + 8 {
+        if data.len() < opt_header_offset + 2 {
             return None;
         }
 
-        // Create a copy and zero out resource directory entry (8 bytes: RVA + Size)
-        let mut data_copy = data.to_vec();
-        for i in resource_dir_offset..resource_dir_offset + 8 {
-            data_copy[i] = 0;
+        let magic = u16::from_le_bytes([data[opt_header_offset], data[opt_header_offset + 1]]);
+        Some((opt_header_offset, magic))
+    }
+
+    /// File offset of the checksum field, at the same place in PE32 and PE32+
+    fn checksum_offset(data: &[u8]) -> Option<usize> {
+        let (opt_header_offset, _) = Self::optional_header_offset(data)?;
+        let offset = opt_header_offset + 64;
+        (data.len() >= offset + 4).then_some(offset)
+    }
+
+    /// File offset of the resource data directory entry in the optional header
+    fn resource_directory_offset(data: &[u8]) -> Option<usize> {
+        let (opt_header_offset, magic) = Self::optional_header_offset(data)?;
+
+        // Resource directory entry is at offset 112 in the optional header (PE32),
+        // or 128 for PE32+ whose stack/heap fields are 64-bit
+        let resource_dir_offset = if magic == goblin::pe::optional_header::MAGIC_64 {
+            opt_header_offset + 128
+        } else {
+            opt_header_offset + 112
+        };
+
+        if data.len() < resource_dir_offset + 8 {
+            return None;
         }
 
-        Some(data_copy)
+        Some(resource_dir_offset)
     }
 
     /// Validate PE and create PEFile struct (extracted to reduce duplication)
-    fn validate_and_create(data: Vec<u8>, pe: PE<'static>) -> Result<Self> {
+    fn validate_and_create(data: FileData, pe: PE<'static>) -> Result<Self> {
         // Validate PE type
         if !pe.is_lib && pe.header.optional_header.is_none() {
             return Err(Error::invalid_pe("Invalid PE optional header"));
@@ -132,23 +395,36 @@ impl PEFile {
 
         // Extract image base and entry point
         let (image_base, entry_point) = if let Some(opt_header) = &pe.header.optional_header {
-            let base = opt_header.windows_fields.image_base as u32;
+            let base = opt_header.windows_fields.image_base;
             let entry = opt_header.standard_fields.address_of_entry_point as u32;
             (base, entry)
         } else {
             (0x400000, 0) // Default values
         };
 
-        // Verify we're dealing with a 32-bit PE
+        // Verify we're dealing with a PE32 or PE32+ image
         if let Some(opt_header) = &pe.header.optional_header {
-            if opt_header.standard_fields.magic != goblin::pe::optional_header::MAGIC_32 {
-                return Err(Error::invalid_pe("Only 32-bit PE files are supported"));
+            let magic = opt_header.standard_fields.magic;
+            if magic != goblin::pe::optional_header::MAGIC_32
+                && magic != goblin::pe::optional_header::MAGIC_64
+            {
+                return Err(Error::invalid_pe(format!(
+                    "Unknown optional header magic 0x{:X}",
+                    magic
+                )));
             }
         }
 
-        // Verify it's an x86 executable
-        if pe.header.coff_header.machine != goblin::pe::header::COFF_MACHINE_X86 {
-            return Err(Error::invalid_pe("Only x86 executables are supported"));
+        // Verify it's an x86 or x64 executable matching the header variant
+        let expected_machine = if pe.is_64 {
+            goblin::pe::header::COFF_MACHINE_X86_64
+        } else {
+            goblin::pe::header::COFF_MACHINE_X86
+        };
+        if pe.header.coff_header.machine != expected_machine {
+            return Err(Error::invalid_pe(
+                "Only x86 and x64 executables are supported",
+            ));
         }
 
         Ok(Self {
@@ -156,14 +432,21 @@ impl PEFile {
             pe,
             image_base,
             entry_point,
+            resource_directory: None,
+            checksum: (0, 0),
         })
     }
 
     /// Get the image base address
-    pub fn image_base(&self) -> u32 {
+    pub fn image_base(&self) -> u64 {
         self.image_base
     }
 
+    /// Check if this is a PE32+ (64-bit) image
+    pub fn is_64bit(&self) -> bool {
+        self.pe.is_64
+    }
+
     /// Get the entry point RVA
     pub fn entry_point(&self) -> u32 {
         self.entry_point
@@ -174,11 +457,82 @@ impl PEFile {
         &self.data
     }
 
+    /// Checksum stored in the optional header of the original file
+    ///
+    /// Linkers leave this 0 for most executables; only drivers and some
+    /// system DLLs are required to carry one.
+    pub fn stored_checksum(&self) -> u32 {
+        self.checksum.0
+    }
+
+    /// PE checksum of the original file, as `CheckSumMappedFile` computes it
+    pub fn compute_checksum(&self) -> u32 {
+        self.checksum.1
+    }
+
+    /// Whether the stored checksum matches the file contents
+    pub fn verify_checksum(&self) -> bool {
+        self.checksum.0 == self.checksum.1
+    }
+
+    /// DOS stub program between the DOS header and the Rich or PE header
+    pub fn dos_stub(&self) -> &[u8] {
+        let end = self
+            .rich_header()
+            .map_or(self.pe_header_offset(), |rich| rich.offset);
+        self.data.get(DOS_HEADER_SIZE..end).unwrap_or_default()
+    }
+
+    /// Decode the Rich header between the DOS stub and the PE header
+    ///
+    /// Returns `None` when there is none, which is common for packed files
+    /// and images from non-Microsoft linkers.
+    pub fn rich_header(&self) -> Option<RichHeader> {
+        parse_rich_header(self.data.get(..self.pe_header_offset())?)
+    }
+
+    /// File offset of the `PE\0\0` signature
+    fn pe_header_offset(&self) -> usize {
+        self.pe.header.dos_header.pe_pointer as usize
+    }
+
     /// Check if this is a DLL
     pub fn is_dll(&self) -> bool {
         self.pe.is_lib
     }
 
+    /// Check whether the image opts into ASLR (`DYNAMIC_BASE`)
+    pub fn is_relocatable(&self) -> bool {
+        self.pe.header.optional_header.is_some_and(|opt| {
+            opt.windows_fields.dll_characteristics
+                & goblin::pe::dll_characteristic::IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE
+                != 0
+        })
+    }
+
+    /// Base relocation blocks from the `.reloc` directory
+    ///
+    /// Returns an empty list when relocations were stripped. A malformed
+    /// block ends the list.
+    pub fn base_relocations(&self) -> Vec<Relocation> {
+        let Some(data) = &self.pe.relocation_data else {
+            return Vec::new();
+        };
+
+        data.blocks()
+            .map_while(|block| block.ok())
+            .map(|block| Relocation {
+                rva: block.rva,
+                entries: block
+                    .words()
+                    .map_while(|word| word.ok())
+                    .filter(|word| word.reloc_type() != 0)
+                    .map(|word| (word.reloc_type(), word.offset()))
+                    .collect(),
+            })
+            .collect()
+    }
+
     /// Check if this is an executable
     pub fn is_executable(&self) -> bool {
         (self.pe.header.coff_header.characteristics & 0x0002) != 0
@@ -189,6 +543,77 @@ impl PEFile {
         &self.pe.sections
     }
 
+    /// Shannon entropy (0.0-8.0) of each section's raw data, by section name
+    ///
+    /// Sections without raw data in the file report 0.0.
+    pub fn section_entropies(&self) -> Vec<(String, f64)> {
+        self.pe
+            .sections
+            .iter()
+            .map(|section| {
+                let name = String::from_utf8_lossy(&section.name)
+                    .trim_end_matches('\0')
+                    .to_string();
+                let entropy =
+                    section_raw_data(section, &self.data).map_or(0.0, calculate_shannon_entropy);
+                (name, entropy)
+            })
+            .collect()
+    }
+
+    /// Shannon entropy (0.0-8.0) of a window sliding over the raw file data
+    ///
+    /// Returns `(offset, entropy)` for windows starting every `step` bytes;
+    /// the last window is cut short at the end of the file. A zero `window`
+    /// or `step` yields an empty profile.
+    pub fn entropy_profile(&self, window: usize, step: usize) -> Vec<(usize, f64)> {
+        if window == 0 || step == 0 {
+            return Vec::new();
+        }
+
+        (0..self.data.len())
+            .step_by(step)
+            .map(|offset| {
+                let end = (offset + window).min(self.data.len());
+                (offset, calculate_shannon_entropy(&self.data[offset..end]))
+            })
+            .collect()
+    }
+
+    /// File offset where the overlay (data appended after the last section) starts
+    ///
+    /// Returns `None` when the file ends at or before the end of the last
+    /// section's raw data.
+    pub fn overlay_offset(&self) -> Option<usize> {
+        let end = self
+            .pe
+            .sections
+            .iter()
+            .map(|s| s.pointer_to_raw_data as usize + s.size_of_raw_data as usize)
+            .max()?;
+        (end < self.data.len()).then_some(end)
+    }
+
+    /// Data appended after the last section, if any
+    pub fn overlay_data(&self) -> Option<&[u8]> {
+        self.overlay_offset().map(|offset| &self.data[offset..])
+    }
+
+    /// Parse the VS_VERSIONINFO resource
+    ///
+    /// The resource tree is walked by hand since goblin chokes on many VB6
+    /// resource sections. Malformed or missing resources yield `None`.
+    pub fn version_info(&self) -> Option<VersionInfo> {
+        let (dir_rva, dir_size) = self.resource_directory?;
+        let tree = self.read_at_rva(dir_rva, dir_size as usize)?;
+        let entry = find_resource(tree, RT_VERSION)?;
+
+        let data_rva = read_u32(tree, entry)?;
+        let data_size = read_u32(tree, entry + 4)?;
+        let block = self.read_at_rva(data_rva, data_size as usize)?;
+        parse_version_info(block)
+    }
+
     /// Get a section by name
     pub fn section_by_name(&self, name: &str) -> Option<&SectionTable> {
         self.pe
@@ -247,6 +672,33 @@ impl PEFile {
         Some(&self.data[offset..offset + size])
     }
 
+    /// Read exactly `size` bytes at a given RVA
+    ///
+    /// Unlike [`read_at_rva`](Self::read_at_rva) nothing is clamped: the
+    /// whole range must lie within the raw data of the section containing
+    /// `rva`. A truncated read reports the file offset of the first missing
+    /// byte as [`Error::OutOfBounds`].
+    pub fn read_at_rva_exact(&self, rva: u32, size: usize) -> Result<&[u8]> {
+        if size > MAX_READ_SIZE {
+            return Err(Error::invalid_pe(format!(
+                "Refusing to read {} bytes at RVA 0x{:X}",
+                size, rva
+            )));
+        }
+
+        let section = self.section_by_rva(rva).ok_or_else(|| {
+            Error::invalid_pe(format!("RVA 0x{:X} is not inside any section", rva))
+        })?;
+        let start = section.pointer_to_raw_data as usize + (rva - section.virtual_address) as usize;
+        let section_end = section.pointer_to_raw_data as usize + section.size_of_raw_data as usize;
+        let available = section_end.min(self.data.len()).saturating_sub(start);
+
+        if available < size {
+            return Err(Error::out_of_bounds(start + available));
+        }
+        Ok(&self.data[start..start + size])
+    }
+
     /// Read data at a given RVA into a vector
     ///
     /// Returns an empty vector if the RVA is invalid or if the requested size exceeds MAX_READ_SIZE.
@@ -280,11 +732,412 @@ impl PEFile {
             .map(|import| import.name.to_string())
             .collect()
     }
+
+    /// Get the functions imported from a DLL with the RVAs of their IAT slots
+    pub fn import_slots(&self, dll_name: &str) -> Vec<(u32, String)> {
+        self.pe
+            .imports
+            .iter()
+            .filter(|import| import.dll.eq_ignore_ascii_case(dll_name))
+            .map(|import| (import.offset as u32, import.name.to_string()))
+            .collect()
+    }
+
+    /// Get delay-loaded DLLs with the functions imported from each
+    ///
+    /// Ordinal imports are listed as `#ordinal`. Returns an empty list when
+    /// there is no delay import directory.
+    pub fn delay_load_imports(&self) -> Vec<(String, Vec<String>)> {
+        let Some(directory) = self
+            .pe
+            .header
+            .optional_header
+            .as_ref()
+            .and_then(|h| h.data_directories.get_delay_import_descriptor())
+        else {
+            return Vec::new();
+        };
+
+        let mut imports = Vec::new();
+        for index in 0..directory.size / DELAY_DESCRIPTOR_SIZE {
+            let rva = directory.virtual_address + index * DELAY_DESCRIPTOR_SIZE;
+            let Some(descriptor) = self.read_at_rva(rva, DELAY_DESCRIPTOR_SIZE as usize) else {
+                break;
+            };
+            let (Some(attributes), Some(name), Some(names)) = (
+                read_u32(descriptor, 0),
+                read_u32(descriptor, 4),
+                read_u32(descriptor, 16),
+            ) else {
+                break;
+            };
+            if name == 0 {
+                break;
+            }
+
+            // Descriptors without the RVA attribute (VC6-era linkers) hold VAs
+            let to_rva = |address: u32| {
+                if attributes & 1 != 0 {
+                    address
+                } else {
+                    address.wrapping_sub(self.image_base as u32)
+                }
+            };
+            let Some(dll) = self.read_cstring_at_rva(to_rva(name)) else {
+                break;
+            };
+            imports.push((dll, self.delay_import_names(to_rva(names), to_rva)));
+        }
+        imports
+    }
+
+    /// Function names of a delay import name table
+    fn delay_import_names(&self, table: u32, to_rva: impl Fn(u32) -> u32) -> Vec<String> {
+        let thunk_size = if self.is_64bit() { 8 } else { 4 };
+        let mut names = Vec::new();
+        for index in 0..MAX_DELAY_IMPORTS {
+            let Some(thunk) = self.read_at_rva(table + index * thunk_size, thunk_size as usize)
+            else {
+                break;
+            };
+            let mut value = [0u8; 8];
+            value[..thunk.len()].copy_from_slice(thunk);
+            let value = u64::from_le_bytes(value);
+            if value == 0 {
+                break;
+            }
+
+            let ordinal_flag = 1u64 << (thunk_size * 8 - 1);
+            if value & ordinal_flag != 0 {
+                names.push(format!("#{}", value & 0xFFFF));
+            } else if let Some(name) = self.read_cstring_at_rva(to_rva(value as u32) + 2) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Get the DLLs the import table was bound against, with their timestamps
+    ///
+    /// The bound import directory usually sits in the headers rather than a
+    /// section. Returns an empty list when there is none.
+    pub fn bound_imports(&self) -> Vec<(String, u32)> {
+        let Some(directory) = self
+            .pe
+            .header
+            .optional_header
+            .as_ref()
+            .and_then(|h| h.data_directories.get_bound_import_table())
+        else {
+            return Vec::new();
+        };
+        let (rva, size) = (directory.virtual_address, directory.size as usize);
+        let Some(table) = self
+            .read_at_rva(rva, size)
+            .or_else(|| self.data.get(rva as usize..rva as usize + size))
+        else {
+            return Vec::new();
+        };
+
+        let mut imports = Vec::new();
+        let mut offset = 0;
+        while let (Some(timestamp), Some(name), Some(forwarders)) = (
+            read_u32(table, offset),
+            read_u16(table, offset + 4),
+            read_u16(table, offset + 6),
+        ) {
+            if timestamp == 0 && name == 0 {
+                break;
+            }
+            let Some(name) = read_cstring(table, name as usize) else {
+                break;
+            };
+            imports.push((name, timestamp));
+            // Forwarder references follow their module's descriptor
+            offset += 8 * (1 + forwarders as usize);
+        }
+        imports
+    }
+
+    /// Name of the import whose IAT slot is at `rva`
+    pub fn import_at_iat_rva(&self, rva: u32) -> Option<String> {
+        // goblin's `offset` is the IAT slot RVA; its `rva` is the hint/name entry
+        self.pe
+            .imports
+            .iter()
+            .find(|import| import.offset == rva as usize)
+            .map(|import| import.name.to_string())
+    }
+
+    /// Get exported symbols, ordered by ordinal
+    pub fn exports(&self) -> Vec<ExportEntry> {
+        let Some(export_data) = &self.pe.export_data else {
+            return Vec::new();
+        };
+
+        let directory = &export_data.export_directory_table;
+        let names = &export_data.export_name_pointer_table;
+        let ordinals = &export_data.export_ordinal_table;
+
+        export_data
+            .export_address_table
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| {
+                let name = ordinals
+                    .iter()
+                    .position(|&ordinal| ordinal as usize == index)
+                    .and_then(|name_index| names.get(name_index))
+                    .and_then(|&name_rva| self.read_cstring_at_rva(name_rva));
+
+                let (rva, forwarded) = match *entry {
+                    ExportAddressTableEntry::ExportRVA(0) => return None,
+                    ExportAddressTableEntry::ExportRVA(rva) => (rva, None),
+                    ExportAddressTableEntry::ForwarderRVA(rva) => {
+                        (rva, self.read_cstring_at_rva(rva))
+                    }
+                };
+
+                Some(ExportEntry {
+                    name,
+                    ordinal: directory.ordinal_base + index as u32,
+                    rva,
+                    forwarded,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the module name recorded in the export directory (e.g. `MyLib.dll`)
+    pub fn export_module_name(&self) -> Option<String> {
+        self.pe
+            .export_data
+            .as_ref()?
+            .name
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Check for a CLR runtime header, which marks a .NET assembly
+    pub fn is_dotnet(&self) -> bool {
+        self.pe
+            .header
+            .optional_header
+            .and_then(|opt| opt.data_directories.get_clr_runtime_header().copied())
+            .is_some_and(|dir| dir.virtual_address != 0)
+    }
+
+    /// Check if this image exports the in-process COM server entry points
+    pub fn is_com_server(&self) -> bool {
+        let exports = self.exports();
+        COM_SERVER_EXPORTS.iter().all(|required| {
+            exports
+                .iter()
+                .any(|export| export.name.as_deref() == Some(*required))
+        })
+    }
+
+    /// Read a NUL-terminated ASCII string at an RVA
+    fn read_cstring_at_rva(&self, rva: u32) -> Option<String> {
+        read_cstring(self.read_at_rva(rva, 512)?, 0)
+    }
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+/// Decode a Rich header ending before `data.len()`
+///
+/// The `Rich` marker and key are stored in the clear; everything from the
+/// `DanS` marker up to them is XORed with the key. `DanS` is followed by
+/// three zero padding dwords, then (`product_id << 16 | build`, `count`)
+/// pairs.
+fn parse_rich_header(data: &[u8]) -> Option<RichHeader> {
+    let rich = (DOS_HEADER_SIZE..data.len().saturating_sub(7))
+        .step_by(4)
+        .rev()
+        .find(|&offset| read_u32(data, offset) == Some(RICH_MARKER))?;
+    let key = read_u32(data, rich + 4)?;
+
+    let offset = (DOS_HEADER_SIZE..rich)
+        .step_by(4)
+        .rev()
+        .find(|&offset| read_u32(data, offset).map(|v| v ^ key) == Some(DANS_MARKER))?;
+    let first = offset + 16;
+    if first > rich || (rich - first) % 8 != 0 {
+        return None;
+    }
+
+    let entries = (first..rich)
+        .step_by(8)
+        .map(|pos| {
+            let comp_id = read_u32(data, pos)? ^ key;
+            Some(RichEntry {
+                product_id: (comp_id >> 16) as u16,
+                build: comp_id as u16,
+                count: read_u32(data, pos + 4)? ^ key,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(RichHeader {
+        key,
+        offset,
+        entries,
+    })
+}
+
+/// Read a NUL-terminated ASCII string at `offset`
+fn read_cstring(data: &[u8], offset: usize) -> Option<String> {
+    let bytes = data.get(offset..)?;
+    let end = bytes.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+/// Find the first data entry of resource type `type_id`
+///
+/// Walks type, name and language levels, taking the first name and language.
+/// Returns the data entry's offset within the resource directory.
+fn find_resource(tree: &[u8], type_id: u32) -> Option<usize> {
+    let mut directory = 0usize;
+    for level in 0..3 {
+        let count =
+            read_u16(tree, directory + 12)? as usize + read_u16(tree, directory + 14)? as usize;
+        let entries = (0..count).map(|i| directory + 16 + i * 8);
+        let entry = if level == 0 {
+            entries
+                .filter_map(|e| Some((read_u32(tree, e)?, read_u32(tree, e + 4)?)))
+                .find(|&(id, _)| id == type_id)?
+                .1
+        } else {
+            read_u32(tree, entries.take(1).next()? + 4)?
+        };
+
+        let offset = (entry & 0x7FFF_FFFF) as usize;
+        let is_directory = entry & 0x8000_0000 != 0;
+        match (level, is_directory) {
+            (2, false) => return Some(offset),
+            (0 | 1, true) => directory = offset,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// One block of a VS_VERSIONINFO tree
+struct VersionBlock<'a> {
+    key: String,
+    value: &'a [u8],
+    children: &'a [u8],
+}
+
+/// Parse the block at the start of `data`, returning it and its aligned length
+fn parse_version_block(data: &[u8]) -> Option<(VersionBlock<'_>, usize)> {
+    let length = read_u16(data, 0)? as usize;
+    let value_length = read_u16(data, 2)? as usize;
+    let is_text = read_u16(data, 4)? == 1;
+    if length < 6 || length > data.len() {
+        return None;
+    }
+    let data = &data[..length];
+
+    let (key, key_end) = read_utf16(data, 6);
+    let value_start = (key_end + 3) & !3;
+    let value_bytes = if is_text {
+        value_length * 2
+    } else {
+        value_length
+    };
+    let value_end = (value_start + value_bytes).min(length);
+    let value = data.get(value_start..value_end).unwrap_or_default();
+    let children = data.get((value_end + 3) & !3..).unwrap_or_default();
+
+    Some((
+        VersionBlock {
+            key,
+            value,
+            children,
+        },
+        (length + 3) & !3,
+    ))
+}
+
+/// Child blocks of a VS_VERSIONINFO block
+fn version_children(mut data: &[u8]) -> Vec<VersionBlock<'_>> {
+    let mut children = Vec::new();
+    while let Some((block, length)) = parse_version_block(data) {
+        children.push(block);
+        data = data.get(length..).unwrap_or_default();
+    }
+    children
+}
+
+/// Read a NUL-terminated UTF-16LE string, returning it and the offset past the NUL
+fn read_utf16(data: &[u8], offset: usize) -> (String, usize) {
+    let mut units = Vec::new();
+    let mut pos = offset;
+    while let Some(unit) = read_u16(data, pos) {
+        pos += 2;
+        if unit == 0 {
+            break;
+        }
+        units.push(unit);
+    }
+    (String::from_utf16_lossy(&units), pos)
+}
+
+fn parse_version_info(data: &[u8]) -> Option<VersionInfo> {
+    let (root, _) = parse_version_block(data)?;
+    if root.key != "VS_VERSION_INFO" {
+        return None;
+    }
+
+    let mut info = VersionInfo::default();
+    if read_u32(root.value, 0) == Some(FIXED_FILE_INFO_SIGNATURE) {
+        let version = |offset: usize| -> Option<String> {
+            let ms = read_u32(root.value, offset)?;
+            let ls = read_u32(root.value, offset + 4)?;
+            Some(format!(
+                "{}.{}.{}.{}",
+                ms >> 16,
+                ms & 0xFFFF,
+                ls >> 16,
+                ls & 0xFFFF
+            ))
+        };
+        info.file_version = version(8);
+        info.product_version = version(16);
+    }
+
+    let tables = version_children(root.children)
+        .into_iter()
+        .filter(|block| block.key == "StringFileInfo")
+        .flat_map(|block| version_children(block.children));
+    for table in tables {
+        for string in version_children(table.children) {
+            if info.get(&string.key).is_none() {
+                let (value, _) = read_utf16(string.value, 0);
+                info.strings.push((string.key, value));
+            }
+        }
+    }
+
+    Some(info)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::{PeBuilder, IMAGE_BASE};
 
     #[test]
     fn test_invalid_dos_signature() {
@@ -293,10 +1146,375 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_pe32_header() {
+        let pe = PEFile::from_bytes(PeBuilder::new().vb_imports().build()).unwrap();
+        assert!(!pe.is_64bit());
+        assert_eq!(pe.image_base(), IMAGE_BASE as u64);
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let data = PeBuilder::new().vb_imports().build();
+        let pe = PEFile::from_reader(std::io::Cursor::new(data)).unwrap();
+        assert_eq!(pe.image_base(), IMAGE_BASE as u64);
+
+        let truncated = std::io::Cursor::new(b"MZ".to_vec());
+        assert!(PEFile::from_reader(truncated).is_err());
+    }
+
+    #[test]
+    fn test_pe32_plus_header() {
+        let mut builder = PeBuilder::new()
+            .pe64(true)
+            .image_base(0x1_4000_0000)
+            .entry_point(0x1000)
+            .vb_imports();
+        builder.section(".text", vec![0xC3]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        assert!(pe.is_64bit());
+        assert_eq!(pe.image_base(), 0x1_4000_0000);
+        assert_eq!(pe.entry_point(), 0x1000);
+        assert_eq!(pe.sections().len(), 2);
+        assert_eq!(pe.imported_dlls(), vec!["MSVBVM60.DLL".to_string()]);
+        assert!(pe
+            .imports_from_dll("MSVBVM60.DLL")
+            .contains(&"rtcMsgBox".to_string()));
+    }
+
+    #[test]
+    fn test_dll_exports() {
+        let mut builder = PeBuilder::new()
+            .dll(true)
+            .vb_imports()
+            .export("DllCanUnloadNow", 0x1010)
+            .export("DllGetClassObject", 0x1020)
+            .export_forwarder("DllRegisterServer", "OTHER.DllRegisterServer");
+        builder.section(".text", vec![0xC3; 0x30]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        let exports = pe.exports();
+        assert_eq!(exports.len(), 3);
+        assert_eq!(exports[0].name.as_deref(), Some("DllCanUnloadNow"));
+        assert_eq!(exports[0].ordinal, 1);
+        assert_eq!(exports[0].rva, 0x1010);
+        assert_eq!(exports[1].name.as_deref(), Some("DllGetClassObject"));
+        assert_eq!(exports[1].ordinal, 2);
+        assert_eq!(exports[1].forwarded, None);
+        assert_eq!(exports[2].ordinal, 3);
+        assert_eq!(
+            exports[2].forwarded.as_deref(),
+            Some("OTHER.DllRegisterServer")
+        );
+        assert!(pe.is_dll());
+        assert!(pe.is_com_server());
+        assert_eq!(pe.export_module_name().as_deref(), Some("FIXTURE.DLL"));
+    }
+
+    #[test]
+    fn test_no_exports() {
+        let pe = PEFile::from_bytes(PeBuilder::new().vb_imports().build()).unwrap();
+        assert!(pe.exports().is_empty());
+        assert!(!pe.is_com_server());
+    }
+
     #[test]
     fn test_file_too_small() {
         let data = vec![0x4D, 0x5A]; // "MZ" but too small
         let result = PEFile::from_bytes(data);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_section_entropies() {
+        // xorshift32 noise stands in for compressed data
+        let mut state = 0x2545_F491u32;
+        let noise: Vec<u8> = (0..0x1000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".zero", vec![0; 0x200]);
+        builder.section(".noise", noise);
+        let pe = PEFile::from_bytes_allow_packed(builder.build()).unwrap();
+
+        let entropies = pe.section_entropies();
+        let entropy = |name: &str| entropies.iter().find(|(n, _)| n == name).unwrap().1;
+        assert!(entropy(".zero") < 0.01);
+        assert!(entropy(".noise") > 7.0);
+        assert_eq!(entropies.len(), pe.sections().len());
+    }
+
+    #[test]
+    fn test_entropy_profile() {
+        // 4 KiB of zeros followed by 4 KiB of LCG noise
+        let mut data = vec![0u8; 0x1000];
+        let mut state = 0x1234_5678u32;
+        data.extend((0..0x1000).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 24) as u8
+        }));
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".text", data);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+        let start = pe.sections()[0].pointer_to_raw_data as usize;
+
+        let profile = pe.entropy_profile(0x100, 0x100);
+        let entropy_at = |offset: usize| {
+            profile
+                .iter()
+                .find(|&&(o, _)| o == offset)
+                .map(|&(_, e)| e)
+                .unwrap()
+        };
+        assert!(entropy_at(start + 0x800) < 0.1);
+        assert!(entropy_at(start + 0x1800) > 7.0);
+
+        // The last window stops at EOF
+        let (last, _) = *profile.last().unwrap();
+        assert!(last < pe.data().len() && last + 0x100 >= pe.data().len());
+        assert!(pe.entropy_profile(0, 0x100).is_empty());
+    }
+
+    #[test]
+    fn test_overlay() {
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".text", vec![0xC3; 0x10]);
+        let image = builder.build();
+
+        let pe = PEFile::from_bytes(image.clone()).unwrap();
+        assert_eq!(pe.overlay_offset(), None);
+        assert!(pe.overlay_data().is_none());
+
+        let mut data = image.clone();
+        data.extend_from_slice(&[0xAB; 100]);
+        let pe = PEFile::from_bytes(data).unwrap();
+        assert_eq!(pe.overlay_offset(), Some(image.len()));
+        assert_eq!(pe.overlay_data().unwrap().len(), 100);
+
+        // Raw data claimed past the end of a truncated file is not an overlay
+        let pe = PEFile::from_bytes_allow_packed(image[..image.len() - 8].to_vec()).unwrap();
+        assert_eq!(pe.overlay_offset(), None);
+    }
+
+    #[test]
+    fn test_rich_header() {
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".text", vec![0xC3; 0x10]);
+        let mut data = builder.build();
+
+        let pe = PEFile::from_bytes(data.clone()).unwrap();
+        assert!(pe.rich_header().is_none());
+        assert_eq!(pe.dos_stub().len(), 0x40);
+
+        // Product 0x04 build 8447 (x3) and product 0x0D build 8169 (x5)
+        let key = 0x1234_5678u32;
+        let mut words = vec![DANS_MARKER ^ key, key, key, key];
+        for (comp_id, count) in [(0x0004_20FFu32, 3u32), (0x000D_1FE9, 5)] {
+            words.push(comp_id ^ key);
+            words.push(count ^ key);
+        }
+        words.extend([RICH_MARKER, key]);
+        let start = 0x48;
+        for (i, word) in words.iter().enumerate() {
+            data[start + i * 4..start + i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+        }
+
+        let pe = PEFile::from_bytes(data).unwrap();
+        let rich = pe.rich_header().unwrap();
+        assert_eq!(rich.key, key);
+        assert_eq!(rich.offset, start);
+        assert_eq!(
+            rich.entries,
+            vec![
+                RichEntry {
+                    product_id: 0x0004,
+                    build: 8447,
+                    count: 3
+                },
+                RichEntry {
+                    product_id: 0x000D,
+                    build: 8169,
+                    count: 5
+                },
+            ]
+        );
+        assert_eq!(pe.dos_stub().len(), start - 0x40);
+    }
+
+    #[test]
+    fn test_delay_load_imports() {
+        let builder = PeBuilder::new()
+            .vb_imports()
+            .delay_import("OLEAUT32.DLL", &["SysAllocString", "VariantClear"]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        assert_eq!(
+            pe.delay_load_imports(),
+            vec![(
+                "OLEAUT32.DLL".to_string(),
+                vec!["SysAllocString".to_string(), "VariantClear".to_string()]
+            )]
+        );
+        // Delay loads are not part of the regular import table
+        assert!(!pe.imported_dlls().iter().any(|dll| dll == "OLEAUT32.DLL"));
+
+        let pe = PEFile::from_bytes(PeBuilder::new().vb_imports().build()).unwrap();
+        assert!(pe.delay_load_imports().is_empty());
+        assert!(pe.bound_imports().is_empty());
+    }
+
+    #[test]
+    fn test_checksum() {
+        // 0xFFFF + 0x0001 carries back into the low word, plus the length
+        assert_eq!(PEFile::pe_checksum(&[0xFF, 0xFF, 0x01, 0x00], 8), 5);
+
+        let mut builder = PeBuilder::new()
+            .vb_imports()
+            .version_string("CompanyName", "Acme");
+        builder.section(".text", vec![0xC3; 0x10]);
+        let mut data = builder.build();
+
+        let pe = PEFile::from_bytes(data.clone()).unwrap();
+        assert_eq!(pe.stored_checksum(), 0);
+        assert!(!pe.verify_checksum());
+
+        let checksum = PEFile::write_checksum(&mut data).unwrap();
+        let pe = PEFile::from_bytes(data.clone()).unwrap();
+        assert_eq!(pe.stored_checksum(), checksum);
+        assert_eq!(pe.compute_checksum(), checksum);
+        assert!(pe.verify_checksum());
+
+        // The resource-stripped copy carries a fresh checksum of its own
+        let offset = PEFile::checksum_offset(pe.data()).unwrap();
+        let stored = u32::from_le_bytes(pe.data()[offset..offset + 4].try_into().unwrap());
+        assert_ne!(stored, checksum);
+        assert_eq!(stored, PEFile::pe_checksum(pe.data(), offset));
+
+        let last = data.len() - 1;
+        data[last] ^= 0xFF;
+        assert!(!PEFile::from_bytes(data).unwrap().verify_checksum());
+    }
+
+    #[test]
+    fn test_base_relocations() {
+        let mut builder = PeBuilder::new().vb_imports();
+        builder.section(".text", vec![0xC3; 0x10]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+        assert!(pe.base_relocations().is_empty());
+        assert!(!pe.is_relocatable());
+
+        let mut builder = PeBuilder::new()
+            .vb_imports()
+            .dynamic_base(true)
+            .relocations(&[0x1004, 0x1010, 0x1FFC, 0x2008]);
+        builder.section(".text", vec![0xC3; 0x1010]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        assert!(pe.is_relocatable());
+        assert_eq!(
+            pe.base_relocations(),
+            vec![
+                Relocation {
+                    rva: 0x1000,
+                    entries: vec![(3, 0x004), (3, 0x010), (3, 0xFFC)],
+                },
+                Relocation {
+                    rva: 0x2000,
+                    entries: vec![(3, 0x008)],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bound_imports() {
+        // Descriptor for MSVBVM60.DLL with one forwarder ref, then the terminator
+        let mut table = vec![0u8; 32];
+        table[0..4].copy_from_slice(&0x3A5F_2E10u32.to_le_bytes());
+        table[4..6].copy_from_slice(&32u16.to_le_bytes());
+        table[6..8].copy_from_slice(&1u16.to_le_bytes());
+        table.extend_from_slice(b"MSVBVM60.DLL\0");
+
+        let mut builder = PeBuilder::new().vb_imports();
+        let rva = builder.next_section_rva();
+        let size = table.len() as u32;
+        builder.section(".bound", table);
+        let pe = PEFile::from_bytes(builder.data_directory(11, rva, size).build()).unwrap();
+
+        assert_eq!(
+            pe.bound_imports(),
+            vec![("MSVBVM60.DLL".to_string(), 0x3A5F_2E10)]
+        );
+    }
+
+    #[test]
+    fn test_version_info() {
+        let builder = PeBuilder::new()
+            .vb_imports()
+            .version_string("CompanyName", "Fixture Corp")
+            .version_string("ProductName", "Fixture Product")
+            .version_string("FileVersion", "1.02.0003");
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        let info = pe.version_info().unwrap();
+        assert_eq!(info.product_name(), Some("Fixture Product"));
+        assert_eq!(info.company_name(), Some("Fixture Corp"));
+        assert_eq!(info.get("FileVersion"), Some("1.02.0003"));
+        assert_eq!(info.file_version.as_deref(), Some("1.2.3.0"));
+        assert_eq!(info.strings.len(), 3);
+    }
+
+    #[test]
+    fn test_version_info_missing_or_malformed() {
+        let pe = PEFile::from_bytes(PeBuilder::new().vb_imports().build()).unwrap();
+        assert!(pe.version_info().is_none());
+
+        // Resource directory pointing at garbage
+        let mut builder = PeBuilder::new().vb_imports();
+        let rva = builder.section(".rsrc", vec![0xFF; 0x40]);
+        let pe =
+            PEFile::from_bytes_allow_packed(builder.data_directory(2, rva, 0x40).build()).unwrap();
+        assert!(pe.version_info().is_none());
+    }
+
+    #[test]
+    fn test_read_at_rva_exact() {
+        let mut builder = PeBuilder::new().vb_imports();
+        // Virtual and raw sizes match so the whole raw data is addressable
+        let first = builder.section(".text", vec![0xAA; 0x200]);
+        let second = builder.section(".data", vec![0xBB; 0x200]);
+        let pe = PEFile::from_bytes(builder.build()).unwrap();
+
+        let text = pe.section_by_name(".text").unwrap();
+        let raw_end = text.pointer_to_raw_data as usize + text.size_of_raw_data as usize;
+        let last = first + text.size_of_raw_data - 4;
+
+        assert_eq!(pe.read_at_rva_exact(first, 4).unwrap(), &[0xAA; 4]);
+        assert_eq!(pe.read_at_rva_exact(last, 4).unwrap().len(), 4);
+
+        // Straddling the end of .text's raw data fails even though .data follows in the file
+        assert!(matches!(
+            pe.read_at_rva_exact(last, 8),
+            Err(Error::OutOfBounds { offset }) if offset == raw_end
+        ));
+        assert_eq!(pe.read_at_rva(last, 8).unwrap().len(), 8);
+
+        // A read ending exactly at the end of the raw data is fine
+        let data = pe.section_by_name(".data").unwrap();
+        let tail = second + data.size_of_raw_data - 2;
+        assert_eq!(pe.read_at_rva_exact(tail, 2).unwrap(), &[0xBB; 2]);
+        assert!(pe.read_at_rva_exact(tail, 3).is_err());
+
+        assert!(matches!(
+            pe.read_at_rva_exact(0x10_0000, 4),
+            Err(Error::InvalidPE(_))
+        ));
+    }
 }
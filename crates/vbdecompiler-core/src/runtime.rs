@@ -0,0 +1,161 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! VB runtime helper table
+//!
+//! Maps MSVBVM50/MSVBVM60 runtime helpers to the VB6 intrinsics they implement,
+//! so calls into the runtime can be rendered in their idiomatic source form
+//! (`MsgBox "text"`, `Debug.Print x`) instead of as opaque function calls.
+//! Helpers implementing operators (`__vbaStrCat`, `__vbaVarAdd`) become the
+//! operator itself.
+
+use crate::ir::ExpressionKind;
+
+/// How an intrinsic is written in VB6 source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntrinsicForm {
+    /// Plain procedure/function call (`MsgBox "text"`, `x = Left(s, 2)`)
+    Call,
+    /// Method call on a global object (`Debug.Print x`)
+    Member {
+        object: &'static str,
+        member: &'static str,
+    },
+    /// Binary operator on the two arguments (`a & b`, `a + b`)
+    Operator(ExpressionKind),
+}
+
+/// Runtime helper entry
+#[derive(Debug, Clone, Copy)]
+pub struct RuntimeHelper {
+    /// Runtime symbol name (as exported by MSVBVM60.DLL)
+    pub symbol: &'static str,
+    /// VB6 name of the intrinsic
+    pub vb_name: &'static str,
+    /// Number of arguments the helper takes off the stack
+    pub arity: usize,
+    /// Source form of the intrinsic
+    pub form: IntrinsicForm,
+}
+
+impl RuntimeHelper {
+    const fn call(symbol: &'static str, vb_name: &'static str, arity: usize) -> Self {
+        Self {
+            symbol,
+            vb_name,
+            arity,
+            form: IntrinsicForm::Call,
+        }
+    }
+
+    const fn member(
+        symbol: &'static str,
+        object: &'static str,
+        member: &'static str,
+        arity: usize,
+    ) -> Self {
+        Self {
+            symbol,
+            vb_name: member,
+            arity,
+            form: IntrinsicForm::Member { object, member },
+        }
+    }
+
+    const fn operator(symbol: &'static str, vb_name: &'static str, op: ExpressionKind) -> Self {
+        Self {
+            symbol,
+            vb_name,
+            arity: 2,
+            form: IntrinsicForm::Operator(op),
+        }
+    }
+}
+
+/// Known runtime helpers
+static RUNTIME_HELPERS: &[RuntimeHelper] = &[
+    // Interaction
+    RuntimeHelper::call("rtcMsgBox", "MsgBox", 5),
+    RuntimeHelper::call("rtcInputBox", "InputBox", 7),
+    RuntimeHelper::call("rtcBeep", "Beep", 0),
+    RuntimeHelper::call("rtcDoEvents", "DoEvents", 0),
+    RuntimeHelper::call("rtcShell", "Shell", 2),
+    // Debug output
+    RuntimeHelper::member("__vbaPrintObj", "Debug", "Print", 1),
+    // String functions
+    RuntimeHelper::call("rtcLeftCharVar", "Left", 2),
+    RuntimeHelper::call("rtcRightCharVar", "Right", 2),
+    RuntimeHelper::call("rtcMidCharVar", "Mid", 3),
+    RuntimeHelper::call("rtcUpperCaseVar", "UCase", 1),
+    RuntimeHelper::call("rtcLowerCaseVar", "LCase", 1),
+    RuntimeHelper::call("rtcTrimVar", "Trim", 1),
+    // Operators
+    RuntimeHelper::operator("__vbaStrCat", "&", ExpressionKind::Concatenate),
+    RuntimeHelper::operator("__vbaVarCat", "&", ExpressionKind::Concatenate),
+    RuntimeHelper::operator("__vbaVarAdd", "+", ExpressionKind::Add),
+    RuntimeHelper::operator("__vbaVarSub", "-", ExpressionKind::Subtract),
+    RuntimeHelper::operator("__vbaVarMul", "*", ExpressionKind::Multiply),
+    RuntimeHelper::operator("__vbaVarDiv", "/", ExpressionKind::Divide),
+    RuntimeHelper::operator("__vbaVarIdiv", "\\", ExpressionKind::IntDivide),
+    RuntimeHelper::operator("__vbaVarMod", "Mod", ExpressionKind::Modulo),
+];
+
+/// Look up a runtime helper by its symbol name (case-insensitive)
+pub fn lookup(symbol: &str) -> Option<&'static RuntimeHelper> {
+    RUNTIME_HELPERS
+        .iter()
+        .find(|helper| helper.symbol.eq_ignore_ascii_case(symbol))
+}
+
+/// Get all known runtime helpers
+pub fn helpers() -> &'static [RuntimeHelper] {
+    RUNTIME_HELPERS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_msgbox() {
+        let helper = lookup("rtcMsgBox").unwrap();
+        assert_eq!(helper.vb_name, "MsgBox");
+        assert_eq!(helper.form, IntrinsicForm::Call);
+        assert!(lookup("RTCMSGBOX").is_some());
+    }
+
+    #[test]
+    fn test_lookup_debug_print() {
+        let helper = lookup("__vbaPrintObj").unwrap();
+        assert_eq!(
+            helper.form,
+            IntrinsicForm::Member {
+                object: "Debug",
+                member: "Print"
+            }
+        );
+    }
+
+    #[test]
+    fn test_lookup_friendly_forms() {
+        assert_eq!(lookup("rtcMidCharVar").unwrap().vb_name, "Mid");
+        assert_eq!(
+            lookup("__vbaStrCat").unwrap().form,
+            IntrinsicForm::Operator(ExpressionKind::Concatenate)
+        );
+        assert_eq!(
+            lookup("__vbaVarAdd").unwrap().form,
+            IntrinsicForm::Operator(ExpressionKind::Add)
+        );
+        assert!(helpers()
+            .iter()
+            .filter(|h| matches!(h.form, IntrinsicForm::Operator(_)))
+            .all(|h| h.arity == 2));
+    }
+
+    #[test]
+    fn test_lookup_unknown() {
+        assert!(lookup("func_12").is_none());
+    }
+}
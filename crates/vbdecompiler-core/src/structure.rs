@@ -0,0 +1,979 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Control flow structuring
+//!
+//! Rewrites the flat basic-block graph produced by the lifter into nested
+//! statements. Natural loops are found through back-edges (an edge whose
+//! target dominates its source) and become `For`/`Do While` loops when the
+//! header is the exit test, or `Do ... Loop While`/`Loop Until` (or an
+//! endless `Do ... Loop`) when the test sits on the back-edge. Two-way
+//! branches whose arms reconverge at their immediate post-dominator become
+//! `If ... Then ... Else ... End If`. Chains of `If` tests comparing one
+//! variable against constants are then collapsed into `Select Case`.
+//!
+//! Structuring is all-or-nothing: if any part of the graph cannot be
+//! expressed with the supported constructs, the function is left as-is
+//! and the code generator falls back to labels and `GoTo`.
+
+use crate::ir::*;
+use std::collections::{HashMap, HashSet};
+
+/// Node standing in for "the procedure returns" in the post-dominator tree
+const VIRTUAL_EXIT: u32 = u32::MAX;
+
+/// How control leaves a basic block
+enum Terminator {
+    /// The procedure ends (explicit return or no successors)
+    End,
+    /// Unconditional transfer to another block
+    Jump(u32),
+    /// Two-way branch: jump to `target` when `condition` holds
+    Conditional {
+        condition: Expression,
+        target: u32,
+        fall_through: u32,
+    },
+}
+
+/// Control flow structurer for a single function
+pub struct Structurer<'a> {
+    function: &'a Function,
+    /// Block ID -> IDs of the blocks dominating it (including itself)
+    dominators: HashMap<u32, HashSet<u32>>,
+    /// Block ID -> IDs of the blocks post-dominating it (including itself)
+    post_dominators: HashMap<u32, HashSet<u32>>,
+    /// Loop header ID -> IDs of the blocks in the loop (including the header)
+    loops: HashMap<u32, HashSet<u32>>,
+}
+
+impl<'a> Structurer<'a> {
+    pub fn new(function: &'a Function) -> Self {
+        let mut structurer = Self {
+            function,
+            dominators: HashMap::new(),
+            post_dominators: HashMap::new(),
+            loops: HashMap::new(),
+        };
+        structurer.compute_dominators();
+        structurer.compute_post_dominators();
+        structurer.find_loops();
+        structurer
+    }
+
+    /// Natural loops found in the function, keyed by header block
+    pub fn loops(&self) -> &HashMap<u32, HashSet<u32>> {
+        &self.loops
+    }
+
+    /// Check whether block `a` dominates block `b`
+    pub fn dominates(&self, a: u32, b: u32) -> bool {
+        self.dominators
+            .get(&b)
+            .is_some_and(|doms| doms.contains(&a))
+    }
+
+    /// Structure the whole function into a flat list of nested statements
+    ///
+    /// Returns `None` if the graph contains a construct that cannot be
+    /// structured yet.
+    pub fn structure(&self) -> Option<Vec<Statement>> {
+        let mut out = Vec::new();
+        let mut visited = HashSet::new();
+        self.emit_region(
+            self.function.entry_block_id,
+            None,
+            None,
+            &mut out,
+            &mut visited,
+        )?;
+
+        // Refuse to drop code that the walk never reached
+        let complete = self
+            .function
+            .basic_blocks
+            .iter()
+            .all(|b| b.statements.is_empty() || visited.contains(&b.id));
+        if !complete {
+            return None;
+        }
+
+        collapse_select_case(&mut out);
+        Some(out)
+    }
+
+    fn successors(&self, id: u32) -> &[u32] {
+        self.function
+            .get_block(id)
+            .map_or(&[], |b| b.successors.as_slice())
+    }
+
+    /// Blocks reachable from the entry, in depth-first preorder
+    fn reachable(&self) -> Vec<u32> {
+        let mut order = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![self.function.entry_block_id];
+        while let Some(id) = stack.pop() {
+            if self.function.get_block(id).is_none() || !seen.insert(id) {
+                continue;
+            }
+            order.push(id);
+            stack.extend(self.successors(id).iter().rev());
+        }
+        order
+    }
+
+    fn compute_dominators(&mut self) {
+        let blocks = self.reachable();
+
+        let mut predecessors: HashMap<u32, Vec<u32>> = HashMap::new();
+        for &id in &blocks {
+            for &succ in self.successors(id) {
+                predecessors.entry(succ).or_default().push(id);
+            }
+        }
+
+        self.dominators = dominator_sets(&blocks, self.function.entry_block_id, &predecessors);
+    }
+
+    /// Post-dominators are dominators of the reversed graph, rooted at a
+    /// virtual exit node that every returning block flows into
+    fn compute_post_dominators(&mut self) {
+        let mut nodes = self.reachable();
+
+        let mut successors: HashMap<u32, Vec<u32>> = HashMap::new();
+        for block in nodes.iter().filter_map(|&id| self.function.get_block(id)) {
+            let returns = matches!(
+                block.statements.last().map(|s| s.kind),
+                Some(StatementKind::Return | StatementKind::Resume)
+            );
+            let succs = if returns || block.successors.is_empty() {
+                vec![VIRTUAL_EXIT]
+            } else {
+                block.successors.clone()
+            };
+            successors.insert(block.id, succs);
+        }
+        nodes.push(VIRTUAL_EXIT);
+
+        self.post_dominators = dominator_sets(&nodes, VIRTUAL_EXIT, &successors);
+    }
+
+    /// Nearest block that every path from `id` to the exit passes through
+    pub fn immediate_post_dominator(&self, id: u32) -> Option<u32> {
+        // Post-dominators form a chain, so the nearest one has the most
+        // post-dominators of its own
+        self.post_dominators
+            .get(&id)?
+            .iter()
+            .filter(|&&d| d != id)
+            .max_by_key(|d| self.post_dominators.get(d).map_or(0, HashSet::len))
+            .copied()
+            .filter(|&d| d != VIRTUAL_EXIT)
+    }
+
+    fn find_loops(&mut self) {
+        let mut predecessors: HashMap<u32, Vec<u32>> = HashMap::new();
+        for &id in self.dominators.keys() {
+            for &succ in self.successors(id) {
+                predecessors.entry(succ).or_default().push(id);
+            }
+        }
+
+        let mut loops: HashMap<u32, HashSet<u32>> = HashMap::new();
+        for &latch in self.dominators.keys() {
+            for &header in self.successors(latch) {
+                if !self.dominates(header, latch) {
+                    continue;
+                }
+
+                // Everything that reaches the latch without passing the header
+                let body = loops
+                    .entry(header)
+                    .or_insert_with(|| HashSet::from([header]));
+                let mut stack = vec![latch];
+                while let Some(id) = stack.pop() {
+                    if body.insert(id) {
+                        stack.extend(predecessors.get(&id).into_iter().flatten());
+                    }
+                }
+            }
+        }
+
+        self.loops = loops;
+    }
+
+    fn terminator(&self, block: &BasicBlock) -> Option<Terminator> {
+        match block.statements.last().map(|s| &s.data) {
+            Some(StatementData::Branch {
+                condition,
+                target_block,
+            }) => {
+                let target = *target_block;
+                match block.successors.iter().find(|&&s| s != target) {
+                    Some(&fall_through) => Some(Terminator::Conditional {
+                        condition: condition.clone(),
+                        target,
+                        fall_through,
+                    }),
+                    None => Some(Terminator::Jump(target)),
+                }
+            }
+            Some(StatementData::Goto { target_block }) => Some(Terminator::Jump(*target_block)),
+            Some(StatementData::Return { .. } | StatementData::Resume { .. }) => {
+                Some(Terminator::End)
+            }
+            _ => match block.successors.as_slice() {
+                [] => Some(Terminator::End),
+                [next] => Some(Terminator::Jump(*next)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Emit the straight-line region starting at `start` until `stop` is
+    /// reached or the path ends
+    fn emit_region(
+        &self,
+        start: u32,
+        stop: Option<u32>,
+        scope: Option<&HashSet<u32>>,
+        out: &mut Vec<Statement>,
+        visited: &mut HashSet<u32>,
+    ) -> Option<()> {
+        self.emit_blocks(start, stop, scope, None, out, visited)
+    }
+
+    /// Emit blocks from `start` like [`Self::emit_region`]
+    ///
+    /// With a `latch`, `start` is the header of a loop whose condition is
+    /// tested at the bottom: the header is emitted as plain code and the walk
+    /// ends at the latch, whose back-edge test belongs to the loop statement.
+    fn emit_blocks(
+        &self,
+        start: u32,
+        stop: Option<u32>,
+        scope: Option<&HashSet<u32>>,
+        latch: Option<u32>,
+        out: &mut Vec<Statement>,
+        visited: &mut HashSet<u32>,
+    ) -> Option<()> {
+        let mut current = start;
+        let mut entering_loop = latch.is_some();
+        loop {
+            if Some(current) == stop && !entering_loop {
+                return Some(());
+            }
+            // Leaving the enclosing loop other than through its header
+            // would need an Exit For/Exit Do
+            if scope.is_some_and(|body| !body.contains(&current)) {
+                return None;
+            }
+            if self.loops.contains_key(&current) && !entering_loop {
+                match self.emit_loop(current, out, visited)? {
+                    Some(exit) => current = exit,
+                    None => return Some(()),
+                }
+                continue;
+            }
+            entering_loop = false;
+            if !visited.insert(current) {
+                return None;
+            }
+
+            let block = self.function.get_block(current)?;
+            let terminator = self.terminator(block)?;
+            let keep = match block.statements.last().map(|s| s.kind) {
+                Some(StatementKind::Branch) | Some(StatementKind::Goto) => {
+                    block.statements.len() - 1
+                }
+                _ => block.statements.len(),
+            };
+            out.extend(block.statements[..keep].iter().cloned());
+            if Some(current) == latch {
+                return Some(());
+            }
+
+            match terminator {
+                Terminator::End => return Some(()),
+                Terminator::Jump(next) => current = next,
+                Terminator::Conditional {
+                    condition,
+                    target,
+                    fall_through,
+                } => {
+                    // Both arms run until they reconverge; if they never do,
+                    // each arm ends the procedure on its own
+                    let merge = self.immediate_post_dominator(current);
+                    let stop = merge.or(stop);
+
+                    let mut then_body = Vec::new();
+                    self.emit_blocks(target, stop, scope, latch, &mut then_body, visited)?;
+                    let mut else_body = Vec::new();
+                    self.emit_blocks(fall_through, stop, scope, latch, &mut else_body, visited)?;
+
+                    out.push(if then_body.is_empty() {
+                        Statement::if_then_else(condition.logical_not(), else_body, then_body)
+                    } else {
+                        Statement::if_then_else(condition, then_body, else_body)
+                    });
+
+                    match merge {
+                        Some(next) => current = next,
+                        None => return Some(()),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Emit the loop headed by `header`, returning the block it exits to
+    /// (`None` for a loop only a return leaves)
+    ///
+    /// A header that is nothing but the exit test makes a pre-tested
+    /// `Do While` (or `For`) loop. Otherwise the loop must have a single
+    /// latch: a conditional back-edge makes `Loop While`/`Loop Until`, an
+    /// unconditional one an endless `Do ... Loop`.
+    fn emit_loop(
+        &self,
+        header: u32,
+        out: &mut Vec<Statement>,
+        visited: &mut HashSet<u32>,
+    ) -> Option<Option<u32>> {
+        let body = &self.loops[&header];
+        let block = self.function.get_block(header)?;
+
+        if let (
+            1,
+            Some(Terminator::Conditional {
+                condition,
+                target,
+                fall_through,
+            }),
+        ) = (block.statements.len(), self.terminator(block))
+        {
+            let pre_tested = match (body.contains(&target), body.contains(&fall_through)) {
+                (true, false) => Some((condition, target, fall_through)),
+                (false, true) => Some((condition.logical_not(), fall_through, target)),
+                _ => None,
+            };
+            if let Some((condition, entry, exit)) = pre_tested {
+                if !visited.insert(header) {
+                    return None;
+                }
+                let mut statements = Vec::new();
+                self.emit_region(entry, Some(header), Some(body), &mut statements, visited)?;
+
+                let stmt = match_for_loop(&condition, out, &mut statements)
+                    .unwrap_or_else(|| Statement::while_loop(condition, statements));
+                out.push(stmt);
+                return Some(Some(exit));
+            }
+        }
+
+        let latches: Vec<u32> = body
+            .iter()
+            .copied()
+            .filter(|&id| self.successors(id).contains(&header))
+            .collect();
+        let [latch] = latches[..] else {
+            return None;
+        };
+        let (loop_kind, condition, exit) = match self.terminator(self.function.get_block(latch)?)? {
+            Terminator::Jump(_) => (LoopKind::Infinite, Expression::bool_const(true), None),
+            Terminator::Conditional {
+                condition,
+                target,
+                fall_through,
+            } if target == header && !body.contains(&fall_through) => {
+                (LoopKind::DoWhile, condition, Some(fall_through))
+            }
+            Terminator::Conditional {
+                condition,
+                target,
+                fall_through,
+            } if fall_through == header && !body.contains(&target) => {
+                (LoopKind::DoUntil, condition, Some(target))
+            }
+            _ => return None,
+        };
+
+        let mut statements = Vec::new();
+        self.emit_blocks(
+            header,
+            None,
+            Some(body),
+            Some(latch),
+            &mut statements,
+            visited,
+        )?;
+        out.push(Statement::do_loop(loop_kind, condition, statements));
+        Some(exit)
+    }
+}
+
+/// Iterative dominator computation over `nodes`, starting from `root`
+fn dominator_sets(
+    nodes: &[u32],
+    root: u32,
+    predecessors: &HashMap<u32, Vec<u32>>,
+) -> HashMap<u32, HashSet<u32>> {
+    let all: HashSet<u32> = nodes.iter().copied().collect();
+    let mut dominators: HashMap<u32, HashSet<u32>> = nodes
+        .iter()
+        .map(|&id| {
+            let init = if id == root {
+                HashSet::from([id])
+            } else {
+                all.clone()
+            };
+            (id, init)
+        })
+        .collect();
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &id in nodes.iter().filter(|&&id| id != root) {
+            let mut doms: Option<HashSet<u32>> = None;
+            for pred in predecessors.get(&id).into_iter().flatten() {
+                let Some(pred_doms) = dominators.get(pred) else {
+                    continue;
+                };
+                doms = Some(match doms {
+                    Some(d) => d.intersection(pred_doms).copied().collect(),
+                    None => pred_doms.clone(),
+                });
+            }
+            let mut doms = doms.unwrap_or_default();
+            doms.insert(id);
+            if doms != dominators[&id] {
+                dominators.insert(id, doms);
+                changed = true;
+            }
+        }
+    }
+
+    dominators
+}
+
+/// Recognize `v = start` / `Do While v <= end ... v = v + step` as a For loop
+///
+/// On success the initializer and increment are removed from `preceding`
+/// and `body`.
+fn match_for_loop(
+    condition: &Expression,
+    preceding: &mut Vec<Statement>,
+    body: &mut Vec<Statement>,
+) -> Option<Statement> {
+    let ExpressionData::Binary { left, right: end } = &condition.data else {
+        return None;
+    };
+    let ExpressionData::Variable(counter) = &left.data else {
+        return None;
+    };
+    if end.references_variable(counter.id) {
+        return None;
+    }
+
+    let step = match &body.last()?.data {
+        StatementData::Assign { target, value } if target == counter => {
+            let ExpressionData::Binary { left, right } = &value.data else {
+                return None;
+            };
+            let (ExpressionData::Variable(v), ExpressionData::Constant(ConstantValue::Integer(c))) =
+                (&left.data, &right.data)
+            else {
+                return None;
+            };
+            if v != counter {
+                return None;
+            }
+            match value.kind {
+                ExpressionKind::Add => *c,
+                ExpressionKind::Subtract => -*c,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let ascending = match condition.kind {
+        ExpressionKind::LessEqual => true,
+        ExpressionKind::GreaterEqual => false,
+        _ => return None,
+    };
+    if step == 0 || (step > 0) != ascending {
+        return None;
+    }
+
+    let start = match &preceding.last()?.data {
+        StatementData::Assign { target, value } if target == counter => value.clone(),
+        _ => return None,
+    };
+
+    preceding.pop();
+    body.pop();
+    Some(Statement::for_loop(
+        counter.clone(),
+        start,
+        (**end).clone(),
+        (step != 1).then(|| Expression::int_const(step)),
+        std::mem::take(body),
+    ))
+}
+
+/// Smallest number of `Case` arms worth turning an `If` chain into `Select Case`
+const MIN_SELECT_ARMS: usize = 2;
+
+/// Rewrite `If x = a ... Else If x = b ... Else ...` chains as `Select Case x`,
+/// recursing into nested bodies
+fn collapse_select_case(statements: &mut [Statement]) {
+    for stmt in statements.iter_mut() {
+        // Outermost first, so a chain is collapsed as a whole
+        if let Some(select) = match_select_case(stmt) {
+            *stmt = select;
+        }
+
+        match &mut stmt.data {
+            StatementData::ForLoop { body, .. } | StatementData::WhileLoop { body, .. } => {
+                collapse_select_case(body)
+            }
+            StatementData::If {
+                then_body,
+                else_body,
+                ..
+            } => {
+                collapse_select_case(then_body);
+                collapse_select_case(else_body);
+            }
+            StatementData::SelectCase {
+                cases, else_body, ..
+            } => {
+                for (_, body) in cases.iter_mut() {
+                    collapse_select_case(body);
+                }
+                collapse_select_case(else_body);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Split `subject = value` into its parts when `subject` is a variable and
+/// `value` a constant
+fn case_test(condition: &Expression) -> Option<(&Variable, &Expression)> {
+    if condition.kind != ExpressionKind::Equal {
+        return None;
+    }
+    let ExpressionData::Binary { left, right } = &condition.data else {
+        return None;
+    };
+    match (&left.data, &right.data) {
+        (ExpressionData::Variable(v), ExpressionData::Constant(_)) => Some((v, right)),
+        _ => None,
+    }
+}
+
+/// Recognize an `If` whose else-arms keep testing the same variable
+fn match_select_case(stmt: &Statement) -> Option<Statement> {
+    let mut subject = None;
+    let mut cases = Vec::new();
+    let mut rest = std::slice::from_ref(stmt);
+
+    while let [Statement {
+        data:
+            StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            },
+        ..
+    }] = rest
+    {
+        let Some((variable, value)) = case_test(condition) else {
+            break;
+        };
+        if subject.is_some_and(|s| s != variable) {
+            break;
+        }
+        subject = Some(variable);
+        cases.push((value.clone(), then_body.clone()));
+        rest = else_body;
+    }
+
+    let subject = subject?;
+    (cases.len() >= MIN_SELECT_ARMS).then(|| {
+        Statement::select_case(Expression::variable(subject.clone()), cases, rest.to_vec())
+    })
+}
+
+/// Structure `function` in place
+///
+/// On success all blocks are replaced by a single entry block holding the
+/// nested statements. Returns `false` and leaves the function untouched if
+/// the graph could not be structured.
+pub fn structure_function(function: &mut Function) -> bool {
+    let Some(statements) = Structurer::new(function).structure() else {
+        return false;
+    };
+
+    let mut block = BasicBlock::new(function.entry_block_id);
+    block.statements = statements;
+    function.basic_blocks = vec![block];
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::VB6CodeGenerator;
+
+    fn int_var(id: u32, name: &str) -> Variable {
+        Variable::new(id, name.to_string(), TypeKind::Integer)
+    }
+
+    fn block(id: u32, statements: Vec<Statement>, successors: &[u32]) -> BasicBlock {
+        let mut block = BasicBlock::new(id);
+        block.statements = statements;
+        for &succ in successors {
+            block.add_successor(succ);
+        }
+        block
+    }
+
+    fn compare(kind: ExpressionKind, var: &Variable, value: i64) -> Expression {
+        Expression::binary(
+            kind,
+            Expression::variable(var.clone()),
+            Expression::int_const(value),
+            Type::new(TypeKind::Boolean),
+        )
+    }
+
+    /// preheader -> header <-> body, header -> exit
+    fn counted_loop(end_check: ExpressionKind) -> Function {
+        let i = int_var(0, "i");
+        let mut function = Function::new("Count".to_string(), Type::new(TypeKind::Void));
+        function.add_local_variable(i.clone());
+
+        function.add_basic_block(block(
+            0,
+            vec![Statement::assign(i.clone(), Expression::int_const(1))],
+            &[1],
+        ));
+        function.add_basic_block(block(
+            1,
+            vec![Statement::branch(compare(end_check, &i, 10), 2)],
+            &[2, 3],
+        ));
+        function.add_basic_block(block(
+            2,
+            vec![
+                Statement::call("Beep".to_string(), Vec::new()),
+                Statement::assign(
+                    i.clone(),
+                    Expression::add(
+                        Expression::variable(i.clone()),
+                        Expression::int_const(1),
+                        Type::new(TypeKind::Integer),
+                    ),
+                ),
+                Statement::goto(1),
+            ],
+            &[1],
+        ));
+        function.add_basic_block(block(3, vec![Statement::return_stmt(None)], &[]));
+        function
+    }
+
+    #[test]
+    fn test_finds_single_back_edge() {
+        let function = counted_loop(ExpressionKind::LessEqual);
+        let structurer = Structurer::new(&function);
+
+        assert_eq!(structurer.loops().len(), 1);
+        assert_eq!(structurer.loops()[&1], HashSet::from([1, 2]));
+        assert!(structurer.dominates(1, 2));
+        assert!(!structurer.dominates(2, 3));
+    }
+
+    #[test]
+    fn test_structures_for_loop() {
+        let mut function = counted_loop(ExpressionKind::LessEqual);
+        assert!(structure_function(&mut function));
+        assert_eq!(function.basic_blocks.len(), 1);
+
+        let statements = &function.basic_blocks[0].statements;
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].kind, StatementKind::ForLoop);
+        assert_eq!(statements[1].kind, StatementKind::Return);
+
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("For i = 1 To 10\n"), "{}", code);
+        assert!(code.contains("Next i\n"), "{}", code);
+        assert!(!code.contains("GoTo"), "{}", code);
+    }
+
+    #[test]
+    fn test_structures_while_loop() {
+        // i > 10 cannot be a For bound, so this stays a Do While
+        let mut function = counted_loop(ExpressionKind::GreaterThan);
+        assert!(structure_function(&mut function));
+
+        assert!(matches!(
+            function.basic_blocks[0].statements[1].data,
+            StatementData::WhileLoop {
+                loop_kind: LoopKind::While,
+                ..
+            }
+        ));
+
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("Do While i > 10"), "{}", code);
+        assert!(code.contains("Loop\n"), "{}", code);
+    }
+
+    /// 0 -> 1 -> 2, where 2 tests `i < 10` and loops back to 1 through
+    /// `back_edge_on_true` (else through its fall-through), exiting to 3
+    fn bottom_tested_loop(back_edge_on_true: bool) -> Function {
+        let i = int_var(0, "i");
+        let mut function = Function::new("Repeat".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(
+            0,
+            vec![Statement::assign(i.clone(), Expression::int_const(0))],
+            &[1],
+        ));
+        function.add_basic_block(block(
+            1,
+            vec![
+                Statement::call("Beep".to_string(), Vec::new()),
+                Statement::assign(
+                    i.clone(),
+                    Expression::add(
+                        Expression::variable(i.clone()),
+                        Expression::int_const(1),
+                        Type::new(TypeKind::Integer),
+                    ),
+                ),
+            ],
+            &[2],
+        ));
+        let (target, successors) = match back_edge_on_true {
+            true => (1, [1, 3]),
+            false => (3, [3, 1]),
+        };
+        function.add_basic_block(block(
+            2,
+            vec![Statement::branch(
+                compare(ExpressionKind::LessThan, &i, 10),
+                target,
+            )],
+            &successors,
+        ));
+        function.add_basic_block(block(3, vec![Statement::return_stmt(None)], &[]));
+        function
+    }
+
+    #[test]
+    fn test_structures_bottom_tested_loops() {
+        let mut function = bottom_tested_loop(true);
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert_eq!(
+            code,
+            "Sub Repeat()\n    i = 0\n    Do\n        Beep\n        i = i + 1\n    Loop While i < 10\nEnd Sub"
+        );
+
+        let mut function = bottom_tested_loop(false);
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("    Do\n        Beep\n"), "{}", code);
+        assert!(code.contains("    Loop Until i < 10\n"), "{}", code);
+        assert!(!code.contains("GoTo"), "{}", code);
+    }
+
+    #[test]
+    fn test_structures_endless_loop() {
+        // 0 -> 1 -> 0: only a return could leave
+        let mut function = Function::new("Spin".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(
+            0,
+            vec![Statement::call("DoEvents".to_string(), Vec::new())],
+            &[1],
+        ));
+        function.add_basic_block(block(
+            1,
+            vec![
+                Statement::call("Beep".to_string(), Vec::new()),
+                Statement::goto(0),
+            ],
+            &[0],
+        ));
+
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(
+            code.contains("    Do\n        DoEvents\n        Beep\n    Loop\n"),
+            "{}",
+            code
+        );
+    }
+
+    /// Leading spaces of the first line containing `needle`
+    fn indent_of(code: &str, needle: &str) -> usize {
+        let line = code.lines().find(|l| l.contains(needle)).unwrap();
+        line.len() - line.trim_start().len()
+    }
+
+    #[test]
+    fn test_structures_if_else_diamond() {
+        // 0 -> {1, 2} -> 3
+        let x = int_var(0, "x");
+        let mut function = Function::new("Pick".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(
+            0,
+            vec![Statement::branch(compare(ExpressionKind::Equal, &x, 0), 1)],
+            &[1, 2],
+        ));
+        function.add_basic_block(block(
+            1,
+            vec![
+                Statement::call("Alpha".to_string(), Vec::new()),
+                Statement::goto(3),
+            ],
+            &[3],
+        ));
+        function.add_basic_block(block(
+            2,
+            vec![Statement::call("Beta".to_string(), Vec::new())],
+            &[3],
+        ));
+        function.add_basic_block(block(3, vec![Statement::return_stmt(None)], &[]));
+
+        let structurer = Structurer::new(&function);
+        assert_eq!(structurer.immediate_post_dominator(0), Some(3));
+        assert_eq!(structurer.immediate_post_dominator(3), None);
+
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("If x = 0 Then\n"), "{}", code);
+        assert!(code.contains("Else\n"), "{}", code);
+        assert!(code.contains("End If\n"), "{}", code);
+        assert!(!code.contains("GoTo"), "{}", code);
+
+        let outer = indent_of(&code, "If x = 0");
+        assert_eq!(indent_of(&code, "Alpha"), outer + 4);
+        assert_eq!(indent_of(&code, "Beta"), outer + 4);
+        assert_eq!(indent_of(&code, "End If"), outer);
+    }
+
+    #[test]
+    fn test_structures_select_case() {
+        // 0: x = 1 -> 1 | 2;  2: x = 2 -> 3 | 4;  4: x = 3 -> 5 | 6;
+        // arms 1, 3, 5 and default 6 all join at 7
+        let x = int_var(0, "x");
+        let mut function = Function::new("Choose".to_string(), Type::new(TypeKind::Void));
+        for (test, value, arm, next) in [(0, 1, 1, 2), (2, 2, 3, 4), (4, 3, 5, 6)] {
+            function.add_basic_block(block(
+                test,
+                vec![Statement::branch(
+                    compare(ExpressionKind::Equal, &x, value),
+                    arm,
+                )],
+                &[arm, next],
+            ));
+            function.add_basic_block(block(
+                arm,
+                vec![
+                    Statement::call(format!("Arm{}", value), Vec::new()),
+                    Statement::goto(7),
+                ],
+                &[7],
+            ));
+        }
+        function.add_basic_block(block(
+            6,
+            vec![Statement::call("Fallback".to_string(), Vec::new())],
+            &[7],
+        ));
+        function.add_basic_block(block(7, vec![Statement::return_stmt(None)], &[]));
+
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert_eq!(
+            code,
+            "Sub Choose()\n\
+             \x20   Select Case x\n\
+             \x20       Case 1\n\
+             \x20           Arm1\n\
+             \x20       Case 2\n\
+             \x20           Arm2\n\
+             \x20       Case 3\n\
+             \x20           Arm3\n\
+             \x20       Case Else\n\
+             \x20           Fallback\n\
+             \x20   End Select\n\
+             End Sub"
+        );
+    }
+
+    #[test]
+    fn test_structures_if_without_else() {
+        // The branch skips straight to the merge block, so the arm that
+        // runs is the fall-through under the negated condition
+        let x = int_var(0, "x");
+        let mut function = Function::new("Maybe".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(
+            0,
+            vec![Statement::branch(compare(ExpressionKind::Equal, &x, 0), 2)],
+            &[2, 1],
+        ));
+        function.add_basic_block(block(
+            1,
+            vec![Statement::call("Alpha".to_string(), Vec::new())],
+            &[2],
+        ));
+        function.add_basic_block(block(2, vec![Statement::return_stmt(None)], &[]));
+
+        assert!(structure_function(&mut function));
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("If x <> 0 Then\n"), "{}", code);
+        assert!(!code.contains("Else"), "{}", code);
+        assert!(code.contains("End If\n"), "{}", code);
+    }
+
+    #[test]
+    fn test_unstructurable_left_unchanged() {
+        // Leaving a loop from the middle of its body needs Exit Do
+        let i = int_var(0, "i");
+        let mut function = Function::new("Search".to_string(), Type::new(TypeKind::Void));
+        function.add_basic_block(block(0, Vec::new(), &[1]));
+        function.add_basic_block(block(
+            1,
+            vec![Statement::branch(
+                compare(ExpressionKind::LessEqual, &i, 10),
+                2,
+            )],
+            &[2, 4],
+        ));
+        function.add_basic_block(block(
+            2,
+            vec![Statement::branch(compare(ExpressionKind::Equal, &i, 5), 4)],
+            &[4, 3],
+        ));
+        function.add_basic_block(block(
+            3,
+            vec![
+                Statement::call("Beep".to_string(), Vec::new()),
+                Statement::goto(1),
+            ],
+            &[1],
+        ));
+        function.add_basic_block(block(4, vec![Statement::return_stmt(None)], &[]));
+
+        assert!(!structure_function(&mut function));
+        assert_eq!(function.basic_blocks.len(), 5);
+    }
+}
@@ -0,0 +1,1064 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Synthetic PE/VB fixtures for tests
+//!
+//! Builds minimal but well-formed PE32 (or PE32+) images in memory, optionally containing
+//! a VB5/6 project (header, project info, object table, objects and methods),
+//! so parsing code can be exercised without shipping binary samples.
+
+// Not every test module uses every builder knob
+#![allow(dead_code)]
+
+/// Default image base for fixtures
+pub const IMAGE_BASE: u32 = 0x400000;
+
+const FILE_ALIGNMENT: u32 = 0x200;
+const SECTION_ALIGNMENT: u32 = 0x1000;
+const PE_OFFSET: usize = 0x80;
+const OPTIONAL_HEADER_SIZE: usize = 224;
+const OPTIONAL_HEADER_SIZE_64: usize = 240;
+const HEADERS_SIZE: u32 = 0x400;
+
+/// Imports every fixture carries by default (enough to pass the import heuristic
+/// in packer detection)
+pub const DEFAULT_IMPORTS: &[&str] = &[
+    "ThunRTMain",
+    "__vbaStrCat",
+    "__vbaFreeStr",
+    "__vbaPrintObj",
+    "rtcMsgBox",
+    "__vbaErrRaise",
+];
+
+fn align(value: u32, alignment: u32) -> u32 {
+    value.div_ceil(alignment) * alignment
+}
+
+fn put_u16(buf: &mut [u8], offset: usize, value: u16) {
+    buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+    buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+/// Section contents laid out at a known RVA
+pub struct Blob {
+    base_rva: u32,
+    data: Vec<u8>,
+}
+
+impl Blob {
+    pub fn new(base_rva: u32) -> Self {
+        Self {
+            base_rva,
+            data: Vec::new(),
+        }
+    }
+
+    /// Append bytes (4-byte aligned) and return their RVA
+    pub fn push(&mut self, bytes: &[u8]) -> u32 {
+        while self.data.len() % 4 != 0 {
+            self.data.push(0);
+        }
+        let rva = self.base_rva + self.data.len() as u32;
+        self.data.extend_from_slice(bytes);
+        rva
+    }
+
+    /// Append bytes without alignment padding and return their RVA
+    pub fn push_raw(&mut self, bytes: &[u8]) -> u32 {
+        let rva = self.base_rva + self.data.len() as u32;
+        self.data.extend_from_slice(bytes);
+        rva
+    }
+
+    /// Append a null-terminated string and return its RVA
+    pub fn push_str(&mut self, s: &str) -> u32 {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.push(0);
+        self.push(&bytes)
+    }
+
+    /// Reserve zeroed space and return its RVA
+    pub fn reserve(&mut self, size: usize) -> u32 {
+        self.push(&vec![0u8; size])
+    }
+
+    pub fn write_u16(&mut self, rva: u32, value: u16) {
+        let offset = (rva - self.base_rva) as usize;
+        put_u16(&mut self.data, offset, value);
+    }
+
+    pub fn write_u32(&mut self, rva: u32, value: u32) {
+        let offset = (rva - self.base_rva) as usize;
+        put_u32(&mut self.data, offset, value);
+    }
+
+    pub fn write_bytes(&mut self, rva: u32, bytes: &[u8]) {
+        let offset = (rva - self.base_rva) as usize;
+        self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+struct SectionSpec {
+    name: String,
+    rva: u32,
+    data: Vec<u8>,
+    characteristics: u32,
+}
+
+/// NUL-terminated UTF-16LE encoding of `s`
+fn utf16z(s: &str) -> Vec<u8> {
+    s.encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
+
+/// Encode a VS_VERSIONINFO block (header, key, value, children), 4-byte padded
+fn version_block(
+    key: &str,
+    value: &[u8],
+    value_length: u16,
+    text: bool,
+    children: &[u8],
+) -> Vec<u8> {
+    let mut out = vec![0u8; 6];
+    put_u16(&mut out, 2, value_length);
+    put_u16(&mut out, 4, text as u16);
+    out.extend_from_slice(&utf16z(key));
+    out.resize(align(out.len() as u32, 4) as usize, 0);
+    out.extend_from_slice(value);
+    out.resize(align(out.len() as u32, 4) as usize, 0);
+    out.extend_from_slice(children);
+    let length = out.len() as u16;
+    put_u16(&mut out, 0, length);
+    out.resize(align(out.len() as u32, 4) as usize, 0);
+    out
+}
+
+/// Target of a fixture export
+#[derive(Clone)]
+enum ExportTarget {
+    Rva(u32),
+    Forwarder(String),
+}
+
+/// Minimal PE32 image builder
+pub struct PeBuilder {
+    image_base: u64,
+    pe64: bool,
+    entry_point: u32,
+    dll: bool,
+    sections: Vec<SectionSpec>,
+    imports: Vec<(String, Vec<String>)>,
+    delay_imports: Vec<(String, Vec<String>)>,
+    exports: Vec<(String, ExportTarget)>,
+    export_name: String,
+    data_directories: Vec<(usize, u32, u32)>,
+    version_strings: Vec<(String, String)>,
+    relocations: Vec<u32>,
+    dynamic_base: bool,
+}
+
+impl Default for PeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PeBuilder {
+    pub fn new() -> Self {
+        Self {
+            image_base: IMAGE_BASE as u64,
+            pe64: false,
+            entry_point: 0,
+            dll: false,
+            sections: Vec::new(),
+            imports: Vec::new(),
+            delay_imports: Vec::new(),
+            exports: Vec::new(),
+            export_name: "FIXTURE.DLL".to_string(),
+            data_directories: Vec::new(),
+            version_strings: Vec::new(),
+            relocations: Vec::new(),
+            dynamic_base: false,
+        }
+    }
+
+    pub fn image_base(mut self, image_base: u64) -> Self {
+        self.image_base = image_base;
+        self
+    }
+
+    /// Emit a PE32+ (x64) image instead of PE32
+    pub fn pe64(mut self, pe64: bool) -> Self {
+        self.pe64 = pe64;
+        self
+    }
+
+    pub fn dll(mut self, dll: bool) -> Self {
+        self.dll = dll;
+        self
+    }
+
+    pub fn entry_point(mut self, rva: u32) -> Self {
+        self.entry_point = rva;
+        self
+    }
+
+    /// Set the `DYNAMIC_BASE` (ASLR) DLL characteristic
+    pub fn dynamic_base(mut self, dynamic_base: bool) -> Self {
+        self.dynamic_base = dynamic_base;
+        self
+    }
+
+    /// Add base relocations (HIGHLOW, or DIR64 for PE32+) at the given RVAs
+    pub fn relocations(mut self, rvas: &[u32]) -> Self {
+        self.relocations.extend_from_slice(rvas);
+        self
+    }
+
+    /// RVA the next added section will be placed at
+    pub fn next_section_rva(&self) -> u32 {
+        self.sections
+            .last()
+            .map(|s| s.rva + align(s.data.len().max(1) as u32, SECTION_ALIGNMENT))
+            .unwrap_or(SECTION_ALIGNMENT)
+    }
+
+    /// Add a section and return its RVA
+    pub fn section(&mut self, name: &str, data: Vec<u8>) -> u32 {
+        let rva = self.next_section_rva();
+        self.sections.push(SectionSpec {
+            name: name.to_string(),
+            rva,
+            data,
+            characteristics: 0x6000_0020, // code | execute | read
+        });
+        rva
+    }
+
+    /// Import functions from a DLL
+    pub fn import(mut self, dll: &str, functions: &[&str]) -> Self {
+        self.imports.push((
+            dll.to_string(),
+            functions.iter().map(|f| f.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Delay-load functions from a DLL
+    pub fn delay_import(mut self, dll: &str, functions: &[&str]) -> Self {
+        self.delay_imports.push((
+            dll.to_string(),
+            functions.iter().map(|f| f.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Import the default MSVBVM60.DLL functions
+    pub fn vb_imports(self) -> Self {
+        self.import("MSVBVM60.DLL", DEFAULT_IMPORTS)
+    }
+
+    /// Export a symbol at an RVA (ordinals are assigned in call order from 1)
+    pub fn export(mut self, name: &str, rva: u32) -> Self {
+        self.exports
+            .push((name.to_string(), ExportTarget::Rva(rva)));
+        self
+    }
+
+    /// Module name recorded in the export directory
+    pub fn export_name(mut self, name: &str) -> Self {
+        self.export_name = name.to_string();
+        self
+    }
+
+    /// Export a symbol forwarded to `DLL.Symbol`
+    pub fn export_forwarder(mut self, name: &str, target: &str) -> Self {
+        self.exports.push((
+            name.to_string(),
+            ExportTarget::Forwarder(target.to_string()),
+        ));
+        self
+    }
+
+    /// Set a data directory entry
+    pub fn data_directory(mut self, index: usize, rva: u32, size: u32) -> Self {
+        self.data_directories.push((index, rva, size));
+        self
+    }
+
+    /// Build the import section, returning its RVA and size
+    fn build_imports(&mut self) {
+        if self.imports.is_empty() {
+            return;
+        }
+
+        let rva = self.next_section_rva();
+        let mut blob = Blob::new(rva);
+        let descriptors = blob.reserve((self.imports.len() + 1) * 20);
+        let mut iat_start = u32::MAX;
+        let mut iat_end = 0;
+
+        for (i, (dll, functions)) in self.imports.iter().enumerate() {
+            let name_rva = blob.push_str(dll);
+            let hint_names: Vec<u32> = functions
+                .iter()
+                .map(|f| {
+                    let mut entry = vec![0u8, 0u8];
+                    entry.extend_from_slice(f.as_bytes());
+                    entry.push(0);
+                    blob.push(&entry)
+                })
+                .collect();
+
+            let thunk_size = if self.pe64 { 8 } else { 4 };
+            let thunk_bytes: Vec<u8> = hint_names
+                .iter()
+                .chain(std::iter::once(&0))
+                .flat_map(|rva| {
+                    let mut thunk = rva.to_le_bytes().to_vec();
+                    thunk.resize(thunk_size, 0);
+                    thunk
+                })
+                .collect();
+            let ilt = blob.push(&thunk_bytes);
+            let iat = blob.push(&thunk_bytes);
+            iat_start = iat_start.min(iat);
+            iat_end = iat_end.max(iat + thunk_bytes.len() as u32);
+
+            let desc = descriptors + (i as u32) * 20;
+            blob.write_u32(desc, ilt);
+            blob.write_u32(desc + 12, name_rva);
+            blob.write_u32(desc + 16, iat);
+        }
+
+        let size = (self.imports.len() as u32 + 1) * 20;
+        self.sections.push(SectionSpec {
+            name: ".idata".to_string(),
+            rva,
+            data: blob.into_bytes(),
+            characteristics: 0xC000_0040, // initialized data | read | write
+        });
+        self.data_directories.push((1, descriptors, size));
+        self.data_directories
+            .push((12, iat_start, iat_end - iat_start));
+    }
+
+    /// Build the delay import section (RVA-based descriptors)
+    fn build_delay_imports(&mut self) {
+        if self.delay_imports.is_empty() {
+            return;
+        }
+
+        let rva = self.next_section_rva();
+        let mut blob = Blob::new(rva);
+        let descriptors = blob.reserve((self.delay_imports.len() + 1) * 32);
+
+        for (i, (dll, functions)) in self.delay_imports.iter().enumerate() {
+            let name_rva = blob.push_str(dll);
+            let thunks: Vec<u8> = functions
+                .iter()
+                .map(|f| {
+                    let mut entry = vec![0u8, 0u8];
+                    entry.extend_from_slice(f.as_bytes());
+                    entry.push(0);
+                    blob.push(&entry)
+                })
+                .chain(std::iter::once(0))
+                .flat_map(|rva| rva.to_le_bytes())
+                .collect();
+            let names = blob.push(&thunks);
+            let iat = blob.push(&thunks);
+
+            let desc = descriptors + (i as u32) * 32;
+            blob.write_u32(desc, 1); // RVA-based
+            blob.write_u32(desc + 4, name_rva);
+            blob.write_u32(desc + 12, iat);
+            blob.write_u32(desc + 16, names);
+        }
+
+        let size = (self.delay_imports.len() as u32 + 1) * 32;
+        self.sections.push(SectionSpec {
+            name: ".didat".to_string(),
+            rva,
+            data: blob.into_bytes(),
+            characteristics: 0xC000_0040, // initialized data | read | write
+        });
+        self.data_directories.push((13, descriptors, size));
+    }
+
+    /// Build the `.reloc` section, one block per 4 KiB page
+    fn build_relocations(&mut self) {
+        if self.relocations.is_empty() {
+            return;
+        }
+
+        let kind: u16 = if self.pe64 { 10 } else { 3 };
+        let mut pages: std::collections::BTreeMap<u32, Vec<u16>> = Default::default();
+        for &rva in &self.relocations {
+            pages
+                .entry(rva & !0xFFF)
+                .or_default()
+                .push(kind << 12 | (rva & 0xFFF) as u16);
+        }
+
+        let mut data = Vec::new();
+        for (page, mut entries) in pages {
+            entries.sort_unstable();
+            if entries.len() % 2 == 1 {
+                entries.push(0); // ABSOLUTE padding to a 4-byte boundary
+            }
+            data.extend_from_slice(&page.to_le_bytes());
+            data.extend_from_slice(&(8 + entries.len() as u32 * 2).to_le_bytes());
+            data.extend(entries.into_iter().flat_map(u16::to_le_bytes));
+        }
+
+        let size = data.len() as u32;
+        let rva = self.next_section_rva();
+        self.sections.push(SectionSpec {
+            name: ".reloc".to_string(),
+            rva,
+            data,
+            characteristics: 0x4200_0040, // initialized data | discardable | read
+        });
+        self.data_directories.push((5, rva, size));
+    }
+
+    /// Build the export section
+    fn build_exports(&mut self) {
+        if self.exports.is_empty() {
+            return;
+        }
+
+        let rva = self.next_section_rva();
+        let mut blob = Blob::new(rva);
+        let directory = blob.reserve(40);
+        let dll_name = blob.push_str(&self.export_name);
+
+        let addresses: Vec<u32> = self
+            .exports
+            .iter()
+            .map(|(_, target)| match target {
+                ExportTarget::Rva(rva) => *rva,
+                ExportTarget::Forwarder(forward) => blob.push_str(forward),
+            })
+            .collect();
+
+        // Name pointers must be sorted for the loader's binary search
+        let mut named: Vec<(usize, &str)> = self
+            .exports
+            .iter()
+            .enumerate()
+            .map(|(i, (name, _))| (i, name.as_str()))
+            .collect();
+        named.sort_by(|a, b| a.1.cmp(b.1));
+        let name_rvas: Vec<u32> = named.iter().map(|(_, name)| blob.push_str(name)).collect();
+
+        let eat: Vec<u8> = addresses.iter().flat_map(|a| a.to_le_bytes()).collect();
+        let eat = blob.push(&eat);
+        let names: Vec<u8> = name_rvas.iter().flat_map(|a| a.to_le_bytes()).collect();
+        let names = blob.push(&names);
+        let ordinals: Vec<u8> = named
+            .iter()
+            .flat_map(|(i, _)| (*i as u16).to_le_bytes())
+            .collect();
+        let ordinals = blob.push(&ordinals);
+
+        blob.write_u32(directory + 12, dll_name);
+        blob.write_u32(directory + 16, 1);
+        blob.write_u32(directory + 20, addresses.len() as u32);
+        blob.write_u32(directory + 24, named.len() as u32);
+        blob.write_u32(directory + 28, eat);
+        blob.write_u32(directory + 32, names);
+        blob.write_u32(directory + 36, ordinals);
+
+        let data = blob.into_bytes();
+        self.data_directories
+            .push((0, directory, data.len() as u32));
+        self.sections.push(SectionSpec {
+            name: ".edata".to_string(),
+            rva,
+            data,
+            characteristics: 0x4000_0040, // initialized data | read
+        });
+    }
+
+    /// Add a StringFileInfo entry to the version resource
+    ///
+    /// A numeric `FileVersion` also sets the VS_FIXEDFILEINFO file version.
+    pub fn version_string(mut self, key: &str, value: &str) -> Self {
+        self.version_strings
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Build the resource section holding a VS_VERSIONINFO resource
+    fn build_resources(&mut self) {
+        if self.version_strings.is_empty() {
+            return;
+        }
+
+        let strings: Vec<u8> = self
+            .version_strings
+            .iter()
+            .flat_map(|(key, value)| {
+                let units = value.encode_utf16().count() + 1;
+                version_block(key, &utf16z(value), units as u16, true, &[])
+            })
+            .collect();
+        let table = version_block("040904B0", &[], 0, true, &strings);
+        let string_info = version_block("StringFileInfo", &[], 0, true, &table);
+
+        let version: Vec<u32> = self
+            .version_strings
+            .iter()
+            .find(|(key, _)| key == "FileVersion")
+            .map(|(_, v)| v.split('.').filter_map(|p| p.parse().ok()).collect())
+            .unwrap_or_default();
+        let part = |i: usize| version.get(i).copied().unwrap_or(0);
+        let mut fixed = vec![0u8; 52];
+        put_u32(&mut fixed, 0, 0xFEEF_04BD);
+        put_u32(&mut fixed, 4, 0x0001_0000);
+        put_u32(&mut fixed, 8, part(0) << 16 | part(1));
+        put_u32(&mut fixed, 12, part(2) << 16 | part(3));
+        let root = version_block("VS_VERSION_INFO", &fixed, 52, false, &string_info);
+
+        // Type (RT_VERSION) -> name (1) -> language (0x409) -> data entry
+        let rva = self.next_section_rva();
+        let mut blob = Blob::new(rva);
+        for (id, next) in [(16, 0x8000_0018u32), (1, 0x8000_0030), (0x409, 0x48)] {
+            let directory = blob.reserve(24);
+            blob.write_u16(directory + 14, 1);
+            blob.write_u32(directory + 16, id);
+            blob.write_u32(directory + 20, next);
+        }
+        let entry = blob.reserve(16);
+        let data = blob.push(&root);
+        blob.write_u32(entry, data);
+        blob.write_u32(entry + 4, root.len() as u32);
+
+        let data = blob.into_bytes();
+        self.data_directories.push((2, rva, data.len() as u32));
+        self.sections.push(SectionSpec {
+            name: ".rsrc".to_string(),
+            rva,
+            data,
+            characteristics: 0x4000_0040, // initialized data | read
+        });
+    }
+
+    /// Build the PE image
+    pub fn build(mut self) -> Vec<u8> {
+        self.build_imports();
+        self.build_delay_imports();
+        self.build_exports();
+        self.build_resources();
+        self.build_relocations();
+
+        let mut out = vec![0u8; HEADERS_SIZE as usize];
+
+        // DOS header
+        out[0..2].copy_from_slice(b"MZ");
+        put_u32(&mut out, 0x3C, PE_OFFSET as u32);
+
+        // PE signature + COFF header
+        out[PE_OFFSET..PE_OFFSET + 4].copy_from_slice(b"PE\0\0");
+        let coff = PE_OFFSET + 4;
+        let optional_header_size = if self.pe64 {
+            OPTIONAL_HEADER_SIZE_64
+        } else {
+            OPTIONAL_HEADER_SIZE
+        };
+        let machine = if self.pe64 { 0x8664 } else { 0x014C }; // x64 / i386
+        put_u16(&mut out, coff, machine);
+        put_u16(&mut out, coff + 2, self.sections.len() as u16);
+        put_u16(&mut out, coff + 16, optional_header_size as u16);
+        let characteristics = if self.dll { 0x2102 } else { 0x0102 };
+        put_u16(&mut out, coff + 18, characteristics);
+
+        // Optional header (PE32 / PE32+)
+        let opt = coff + 20;
+        let size_of_image = align(self.next_section_rva(), SECTION_ALIGNMENT);
+        put_u16(&mut out, opt, if self.pe64 { 0x020B } else { 0x010B });
+        put_u32(&mut out, opt + 16, self.entry_point);
+        if self.pe64 {
+            out[opt + 24..opt + 32].copy_from_slice(&self.image_base.to_le_bytes());
+        } else {
+            put_u32(&mut out, opt + 28, self.image_base as u32);
+        }
+        put_u32(&mut out, opt + 32, SECTION_ALIGNMENT);
+        put_u32(&mut out, opt + 36, FILE_ALIGNMENT);
+        put_u16(&mut out, opt + 40, 4);
+        put_u16(&mut out, opt + 48, 4);
+        put_u32(&mut out, opt + 56, size_of_image);
+        put_u32(&mut out, opt + 60, HEADERS_SIZE);
+        put_u16(&mut out, opt + 68, 2); // Windows GUI
+        if self.dynamic_base {
+            put_u16(&mut out, opt + 70, 0x0040);
+        }
+        // Stack/heap sizes are 64-bit in PE32+, shifting the data directories
+        let (field_size, directories) = if self.pe64 { (8, 112) } else { (4, 96) };
+        for (i, value) in [0x100000, 0x1000, 0x100000, 0x1000].into_iter().enumerate() {
+            put_u32(&mut out, opt + 72 + i * field_size, value);
+        }
+        put_u32(&mut out, opt + directories - 4, 16);
+        for &(index, rva, size) in &self.data_directories {
+            put_u32(&mut out, opt + directories + index * 8, rva);
+            put_u32(&mut out, opt + directories + 4 + index * 8, size);
+        }
+
+        // Section table and section data
+        let mut raw_offset = HEADERS_SIZE;
+        for (i, section) in self.sections.iter().enumerate() {
+            let header = opt + optional_header_size + i * 40;
+            let name = section.name.as_bytes();
+            out[header..header + name.len().min(8)].copy_from_slice(&name[..name.len().min(8)]);
+
+            let raw_size = align(section.data.len() as u32, FILE_ALIGNMENT);
+            put_u32(&mut out, header + 8, section.data.len() as u32);
+            put_u32(&mut out, header + 12, section.rva);
+            put_u32(&mut out, header + 16, raw_size);
+            put_u32(&mut out, header + 20, raw_offset);
+            put_u32(&mut out, header + 36, section.characteristics);
+
+            raw_offset += raw_size;
+        }
+
+        for section in &self.sections {
+            let start = out.len();
+            out.extend_from_slice(&section.data);
+            out.resize(
+                start + align(section.data.len() as u32, FILE_ALIGNMENT) as usize,
+                0,
+            );
+        }
+
+        out
+    }
+}
+
+/// A method in a fixture object
+#[derive(Default, Clone)]
+pub struct MethodSpec {
+    pub name: String,
+    pub pcode: Vec<u8>,
+    /// Stack frame size recorded in the procedure descriptor
+    pub frame_size: u16,
+}
+
+impl MethodSpec {
+    pub fn new(name: &str, pcode: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            pcode,
+            frame_size: 0,
+        }
+    }
+}
+
+/// An object (form, module, class) in a fixture project
+#[derive(Default, Clone)]
+pub struct ObjectSpec {
+    pub name: String,
+    pub object_type: u32,
+    pub methods: Vec<MethodSpec>,
+    /// Event source interfaces this object sinks (`WithEvents` members)
+    pub events_iids: Vec<[u8; 16]>,
+    /// Original source file path, stored behind the object's IDE data pointer
+    pub source_file: Option<String>,
+    /// Constant pool entries (VAs)
+    pub constants: Vec<u32>,
+    /// Constant records (name, variant type, raw value), appended to the pool
+    pub named_constants: Vec<(String, u16, u32)>,
+    /// Form controls (name, index) in the control array
+    pub controls: Vec<(String, u16)>,
+}
+
+impl ObjectSpec {
+    pub fn new(name: &str, object_type: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            object_type,
+            ..Default::default()
+        }
+    }
+
+    pub fn method(mut self, name: &str, pcode: Vec<u8>) -> Self {
+        self.methods.push(MethodSpec::new(name, pcode));
+        self
+    }
+
+    pub fn method_with_frame(mut self, name: &str, pcode: Vec<u8>, frame_size: u16) -> Self {
+        self.methods.push(MethodSpec {
+            frame_size,
+            ..MethodSpec::new(name, pcode)
+        });
+        self
+    }
+
+    pub fn constant(mut self, value: u32) -> Self {
+        self.constants.push(value);
+        self
+    }
+
+    pub fn named_constant(mut self, name: &str, vartype: u16, value: u32) -> Self {
+        self.named_constants
+            .push((name.to_string(), vartype, value));
+        self
+    }
+
+    pub fn control(mut self, name: &str, index: u16) -> Self {
+        self.controls.push((name.to_string(), index));
+        self
+    }
+
+    pub fn source_file(mut self, path: &str) -> Self {
+        self.source_file = Some(path.to_string());
+        self
+    }
+}
+
+/// A VB project fixture
+#[derive(Default, Clone)]
+pub struct ProjectSpec {
+    pub project_name: String,
+    pub objects: Vec<ObjectSpec>,
+    /// ActiveX controls (file name, CLSID) in the external component table
+    pub components: Vec<(String, [u8; 16])>,
+    /// `Declare`d functions (library, function) in the external table
+    pub declares: Vec<(String, String)>,
+    /// Type library GUID of the COM registration data (ActiveX projects)
+    pub lib_id: Option<[u8; 16]>,
+    /// Registered COM objects (name, CLSID, default interface IID)
+    pub com_objects: Vec<(String, [u8; 16], [u8; 16])>,
+    /// Compiled to native code: method bodies are x86, not P-Code
+    pub native: bool,
+    /// Object and method index of `Sub Main`, pointed to by the VB header
+    pub sub_main: Option<(usize, usize)>,
+    /// Form listed first in the GUI table; every form then gets a GUI GUID
+    pub startup_form: Option<usize>,
+}
+
+impl ProjectSpec {
+    pub fn new(project_name: &str) -> Self {
+        Self {
+            project_name: project_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn object(mut self, object: ObjectSpec) -> Self {
+        self.objects.push(object);
+        self
+    }
+
+    pub fn control_component(mut self, file_name: &str, clsid: [u8; 16]) -> Self {
+        self.components.push((file_name.to_string(), clsid));
+        self
+    }
+
+    pub fn declare(mut self, library: &str, function: &str) -> Self {
+        self.declares
+            .push((library.to_string(), function.to_string()));
+        self
+    }
+
+    pub fn com_registration(mut self, lib_id: [u8; 16]) -> Self {
+        self.lib_id = Some(lib_id);
+        self
+    }
+
+    pub fn com_object(mut self, name: &str, clsid: [u8; 16], iid: [u8; 16]) -> Self {
+        self.com_objects.push((name.to_string(), clsid, iid));
+        self
+    }
+
+    pub fn native(mut self) -> Self {
+        self.native = true;
+        self
+    }
+
+    pub fn sub_main(mut self, object: usize, method: usize) -> Self {
+        self.sub_main = Some((object, method));
+        self
+    }
+
+    pub fn startup_form(mut self, object: usize) -> Self {
+        self.startup_form = Some(object);
+        self
+    }
+}
+
+/// GUI GUID of the form at `index` when the fixture has a GUI table
+fn gui_guid(index: usize) -> [u8; 16] {
+    [0xA0 + index as u8; 16]
+}
+
+/// Lay out VB structures for a project in a blob at `rva`
+pub fn build_vb_section(spec: &ProjectSpec, rva: u32) -> Vec<u8> {
+    let va = |rva: u32| IMAGE_BASE + rva;
+    let mut blob = Blob::new(rva);
+
+    // VB header
+    let header = blob.reserve(104);
+    blob.write_bytes(header, b"VB5!");
+    blob.write_bytes(header + 0x06, b"*\0");
+    blob.write_u16(
+        header + 0x44,
+        spec.objects
+            .iter()
+            .filter(|o| o.object_type & 0x10 != 0)
+            .count() as u16,
+    );
+
+    // Project info
+    let project_info = blob.reserve(564);
+    blob.write_u32(header + 0x30, va(project_info));
+    blob.write_u32(project_info, 0x1F4);
+
+    let project_name = blob.push_str(&spec.project_name);
+    blob.write_u32(header + 0x64, va(project_name));
+
+    // External component table: 40-byte entries followed by CLSID and file name
+    if !spec.components.is_empty() {
+        let mut table_rva = 0;
+        for (i, (file_name, clsid)) in spec.components.iter().enumerate() {
+            let mut entry = vec![0u8; 40];
+            entry.extend_from_slice(clsid);
+            entry.extend_from_slice(file_name.as_bytes());
+            entry.push(0);
+            entry.resize(entry.len().next_multiple_of(4), 0);
+            let size = entry.len() as u32;
+            put_u32(&mut entry, 0x00, size);
+            put_u32(&mut entry, 0x10, 40);
+            put_u32(&mut entry, 0x14, 16);
+            put_u32(&mut entry, 0x1C, 56);
+            let rva = blob.push_raw(&entry);
+            if i == 0 {
+                table_rva = rva;
+            }
+        }
+        blob.write_u16(header + 0x46, spec.components.len() as u16);
+        blob.write_u32(header + 0x50, va(table_rva));
+    }
+
+    // COM registration data: header, then per object its info, name and IID,
+    // all addressed by offsets from the header
+    if let Some(lib_id) = spec.lib_id {
+        let mut data = vec![0u8; 44];
+        data[0x10..0x20].copy_from_slice(&lib_id);
+        let mut link = 0x00;
+        for (name, clsid, iid) in &spec.com_objects {
+            let info = data.len();
+            put_u32(&mut data, link, info as u32);
+            data.resize(info + 68, 0);
+            data[info + 0x14..info + 0x24].copy_from_slice(clsid);
+            let name_offset = data.len() as u32;
+            put_u32(&mut data, info + 0x04, name_offset);
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+            data.resize(data.len().next_multiple_of(4), 0);
+            let iid_offset = data.len() as u32;
+            put_u32(&mut data, info + 0x28, iid_offset);
+            data.extend_from_slice(iid);
+            link = info;
+        }
+        let data = blob.push_raw(&data);
+        blob.write_u32(header + 0x54, va(data));
+    }
+
+    // Project external table (Declare'd functions)
+    if !spec.declares.is_empty() {
+        let mut table = Vec::new();
+        for (library, function) in &spec.declares {
+            let library = blob.push_str(library);
+            let function = blob.push_str(function);
+            let mut entry = va(library).to_le_bytes().to_vec();
+            entry.extend_from_slice(&va(function).to_le_bytes());
+            let entry = blob.push(&entry);
+            table.extend_from_slice(&7u32.to_le_bytes());
+            table.extend_from_slice(&va(entry).to_le_bytes());
+        }
+        let table = blob.push(&table);
+        blob.write_u32(project_info + 0x22C, va(table));
+        blob.write_u32(project_info + 0x230, spec.declares.len() as u32);
+    }
+
+    // GUI table: one 0x50-byte entry per form, the startup form first
+    if let Some(startup) = spec.startup_form {
+        let forms = std::iter::once(startup).chain(
+            (0..spec.objects.len())
+                .filter(|&i| i != startup && spec.objects[i].object_type & 0x10 != 0),
+        );
+        let mut table = Vec::new();
+        for index in forms {
+            let mut entry = vec![0u8; 0x50];
+            put_u32(&mut entry, 0x00, 0x50);
+            entry[0x04..0x14].copy_from_slice(&gui_guid(index));
+            table.extend_from_slice(&entry);
+        }
+        let table = blob.push(&table);
+        blob.write_u32(header + 0x4C, va(table));
+    }
+
+    // Object table
+    let object_table = blob.reserve(60);
+    blob.write_u32(project_info + 0x04, va(object_table));
+    blob.write_u16(object_table + 0x0E, spec.objects.len() as u16);
+    blob.write_u16(object_table + 0x10, spec.objects.len() as u16);
+    blob.write_u32(object_table + 0x28, va(project_name));
+
+    let descriptors = blob.reserve(48 * spec.objects.len());
+    blob.write_u32(object_table + 0x14, va(descriptors));
+
+    // Native method table slots, filled once all code is laid out
+    let mut native_slots = Vec::new();
+
+    for (index, object) in spec.objects.iter().enumerate() {
+        let descriptor = descriptors + index as u32 * 48;
+        let has_gui_guid = spec.startup_form.is_some() && object.object_type & 0x10 != 0;
+        let has_optional_info =
+            !object.events_iids.is_empty() || !object.controls.is_empty() || has_gui_guid;
+        let object_type = if has_optional_info {
+            object.object_type | 0x80
+        } else {
+            object.object_type
+        };
+
+        let name = blob.push_str(&object.name);
+        blob.write_u32(descriptor + 0x18, va(name));
+        blob.write_u32(descriptor + 0x1C, object.methods.len() as u32);
+        blob.write_u32(descriptor + 0x28, object_type);
+
+        // Object info (+ optional info)
+        let info_size = if has_optional_info { 56 + 64 } else { 56 };
+        let info = blob.reserve(info_size);
+        blob.write_u32(descriptor, va(info));
+        blob.write_u16(info + 0x02, index as u16);
+        blob.write_u32(info + 0x04, va(object_table));
+        blob.write_u16(info + 0x20, object.methods.len() as u16);
+        blob.write_u16(info + 0x22, object.methods.len() as u16);
+
+        let mut constants = object.constants.clone();
+        for (name, vartype, value) in &object.named_constants {
+            let name = blob.push_str(name);
+            let mut record = [0u8; 12];
+            put_u32(&mut record, 0, va(name));
+            put_u16(&mut record, 4, *vartype);
+            put_u32(&mut record, 8, *value);
+            constants.push(va(blob.push(&record)));
+        }
+        if !constants.is_empty() {
+            let pool: Vec<u8> = constants.iter().flat_map(|c| c.to_le_bytes()).collect();
+            let pool = blob.push(&pool);
+            blob.write_u16(info + 0x28, constants.len() as u16);
+            blob.write_u16(info + 0x2A, constants.len() as u16);
+            blob.write_u32(info + 0x34, va(pool));
+        }
+
+        if let Some(path) = &object.source_file {
+            let path = blob.push_str(path);
+            blob.write_u32(info + 0x08, va(path));
+        }
+
+        if has_optional_info {
+            let optional = info + 56;
+            if has_gui_guid {
+                let guid = blob.push(&gui_guid(index));
+                blob.write_u32(optional + 0x0C, va(guid));
+            }
+            let iids: Vec<u32> = object
+                .events_iids
+                .iter()
+                .map(|iid| va(blob.push(iid)))
+                .collect();
+            let iid_bytes: Vec<u8> = iids.iter().flat_map(|p| p.to_le_bytes()).collect();
+            let table = blob.push(&iid_bytes);
+            blob.write_u32(optional + 0x14, va(table));
+            blob.write_u32(optional + 0x18, iids.len() as u32);
+
+            // Control array: 40-byte entries
+            if !object.controls.is_empty() {
+                let array = blob.reserve(40 * object.controls.len());
+                for (i, (name, index)) in object.controls.iter().enumerate() {
+                    let entry = array + i as u32 * 40;
+                    let name = blob.push_str(name);
+                    blob.write_u16(entry + 0x0C, *index);
+                    blob.write_u32(entry + 0x1C, va(name));
+                }
+                blob.write_u32(optional + 0x20, object.controls.len() as u32);
+                blob.write_u32(optional + 0x24, va(array));
+            }
+        }
+
+        // Method names
+        if !object.methods.is_empty() {
+            let names = blob.reserve(8 * object.methods.len());
+            blob.write_u32(descriptor + 0x20, va(names));
+            for (i, method) in object.methods.iter().enumerate() {
+                let method_name = blob.push_str(&method.name);
+                blob.write_u32(names + i as u32 * 8, va(method_name));
+            }
+
+            if spec.native {
+                // Method table: one entry point per method
+                let table = blob.reserve(4 * object.methods.len());
+                blob.write_u32(info + 0x24, va(table));
+                for (i, method) in object.methods.iter().enumerate() {
+                    let is_sub_main = spec.sub_main == Some((index, i));
+                    native_slots.push((table + i as u32 * 4, &method.pcode, is_sub_main));
+                }
+                continue;
+            }
+
+            // Method table: procedure descriptor followed by its P-Code
+            let methods_rva = blob.reserve(0);
+            blob.write_u32(info + 0x24, va(methods_rva));
+            for (i, method) in object.methods.iter().enumerate() {
+                let desc = blob.push_raw(&[0u8; 30]);
+                if spec.sub_main == Some((index, i)) {
+                    blob.write_u32(header + 0x2C, va(desc));
+                }
+                blob.write_u16(desc + 0x06, method.frame_size);
+                blob.write_u16(desc + 0x08, method.pcode.len() as u16);
+                blob.push_raw(&method.pcode);
+            }
+        }
+    }
+
+    // Native code in one contiguous range, as the project info describes it
+    if spec.native {
+        let code_start = blob.reserve(0);
+        for (slot, code, is_sub_main) in native_slots {
+            let entry = blob.push_raw(code);
+            blob.write_u32(slot, va(entry));
+            if is_sub_main {
+                blob.write_u32(header + 0x2C, va(entry));
+            }
+        }
+        let code_end = blob.push_raw(&[]);
+        blob.write_u32(project_info + 0x0C, va(code_start));
+        blob.write_u32(project_info + 0x10, va(code_end));
+        blob.write_u32(project_info + 0x20, va(code_start));
+    }
+
+    blob.into_bytes()
+}
+
+/// Build a complete VB executable from a project fixture
+pub fn build_vb_exe(spec: &ProjectSpec) -> Vec<u8> {
+    let mut pe = PeBuilder::new().vb_imports();
+    let rva = pe.next_section_rva();
+    pe.section(".text", build_vb_section(spec, rva));
+    pe.build()
+}
@@ -0,0 +1,446 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Type recovery
+//!
+//! The lifter types what the opcodes spell out: literals (`LitI2`), typed
+//! arithmetic (`AddI4`) and typed local slots. This pass carries those types
+//! through untyped operations using VB's promotion rules and gives every
+//! local the type of the values stored into it, so the generated `Dim`
+//! lines can name a real type instead of `Variant`.
+
+use crate::codegen::GLOBAL_OBJECTS;
+use crate::ir::*;
+use std::collections::HashMap;
+
+/// Upper bound on inference rounds; each round can only type more locals
+const MAX_ROUNDS: usize = 16;
+
+/// Infer expression and local variable types for `function`
+///
+/// Locals read or written by the body that are not yet declared are added
+/// to `function.local_variables`. A local whose stores disagree on a type
+/// stays `Variant`.
+pub fn infer_types(function: &mut Function) {
+    let mut types: HashMap<String, TypeKind> = HashMap::new();
+    for var in function.parameters.iter().chain(&function.local_variables) {
+        if is_concrete(var.var_type) {
+            types.insert(var.name.clone(), var.var_type);
+        }
+    }
+    for stmt in function.basic_blocks.iter().flat_map(|b| &b.statements) {
+        let mut vars = Vec::new();
+        stmt.collect_variables(&mut vars);
+        for var in vars {
+            if is_concrete(var.var_type) {
+                types.entry(var.name.clone()).or_insert(var.var_type);
+            }
+        }
+    }
+
+    for _ in 0..MAX_ROUNDS {
+        let mut stores: HashMap<String, Vec<TypeKind>> = HashMap::new();
+        for block in &mut function.basic_blocks {
+            retype_statements(&mut block.statements, &types, &mut stores);
+        }
+
+        let mut changed = false;
+        for (name, kinds) in stores {
+            if types.contains_key(&name) {
+                continue;
+            }
+            if let Some(kind) = common_type(&kinds) {
+                types.insert(name, kind);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    declare_locals(function, &types);
+}
+
+/// Add every undeclared variable the body uses to `function.local_variables`
+fn declare_locals(function: &mut Function, types: &HashMap<String, TypeKind>) {
+    for var in &mut function.local_variables {
+        if let Some(&kind) = types.get(&var.name) {
+            var.var_type = kind;
+        }
+    }
+
+    let mut used: Vec<Variable> = Vec::new();
+    for stmt in function.basic_blocks.iter().flat_map(|b| &b.statements) {
+        let mut vars = Vec::new();
+        stmt.collect_variables(&mut vars);
+        for var in vars {
+            let known = function
+                .parameters
+                .iter()
+                .chain(&function.local_variables)
+                .chain(&used)
+                .any(|v| v.name.eq_ignore_ascii_case(&var.name))
+                || GLOBAL_OBJECTS
+                    .iter()
+                    .any(|g| g.eq_ignore_ascii_case(&var.name));
+            if !known {
+                let kind = types.get(&var.name).copied().unwrap_or(var.var_type);
+                used.push(Variable::new(var.id, var.name.clone(), kind));
+            }
+        }
+    }
+    for var in used {
+        function.add_local_variable(var);
+    }
+}
+
+/// True for a type worth declaring (not `Variant` or unresolved)
+fn is_concrete(kind: TypeKind) -> bool {
+    !matches!(kind, TypeKind::Variant | TypeKind::Unknown | TypeKind::Void)
+}
+
+/// The single concrete type all stores agree on
+fn common_type(kinds: &[TypeKind]) -> Option<TypeKind> {
+    let first = *kinds.first()?;
+    (is_concrete(first) && kinds.iter().all(|&k| k == first)).then_some(first)
+}
+
+fn retype_variable(var: &mut Variable, types: &HashMap<String, TypeKind>) {
+    if let Some(&kind) = types.get(&var.name) {
+        var.var_type = kind;
+    }
+}
+
+fn retype_statements(
+    statements: &mut [Statement],
+    types: &HashMap<String, TypeKind>,
+    stores: &mut HashMap<String, Vec<TypeKind>>,
+) {
+    for stmt in statements {
+        match &mut stmt.data {
+            StatementData::Assign { target, value } => {
+                retype_expression(value, types);
+                retype_variable(target, types);
+                stores
+                    .entry(target.name.clone())
+                    .or_default()
+                    .push(value.expr_type.kind);
+            }
+            StatementData::Store { address, value } => {
+                retype_expression(address, types);
+                retype_expression(value, types);
+            }
+            StatementData::Call { arguments, .. } => {
+                arguments
+                    .iter_mut()
+                    .for_each(|a| retype_expression(a, types));
+            }
+            StatementData::MethodCall { target, arguments } => {
+                retype_expression(target, types);
+                arguments
+                    .iter_mut()
+                    .for_each(|a| retype_expression(a, types));
+            }
+            StatementData::Return { value: Some(value) } => retype_expression(value, types),
+            StatementData::Branch { condition, .. } => retype_expression(condition, types),
+            StatementData::ForLoop {
+                counter,
+                start,
+                end,
+                step,
+                body,
+            } => {
+                retype_expression(start, types);
+                retype_expression(end, types);
+                if let Some(step) = step {
+                    retype_expression(step, types);
+                }
+                retype_variable(counter, types);
+                stores
+                    .entry(counter.name.clone())
+                    .or_default()
+                    .push(start.expr_type.kind);
+                retype_statements(body, types, stores);
+            }
+            StatementData::WhileLoop {
+                condition, body, ..
+            } => {
+                retype_expression(condition, types);
+                retype_statements(body, types, stores);
+            }
+            StatementData::If {
+                condition,
+                then_body,
+                else_body,
+            } => {
+                retype_expression(condition, types);
+                retype_statements(then_body, types, stores);
+                retype_statements(else_body, types, stores);
+            }
+            StatementData::ReDim { array, bounds, .. } => {
+                retype_variable(array, types);
+                for (lower, upper) in bounds {
+                    retype_expression(lower, types);
+                    retype_expression(upper, types);
+                }
+            }
+            StatementData::SelectCase {
+                subject,
+                cases,
+                else_body,
+            } => {
+                retype_expression(subject, types);
+                for (value, body) in cases {
+                    retype_expression(value, types);
+                    retype_statements(body, types, stores);
+                }
+                retype_statements(else_body, types, stores);
+            }
+            StatementData::With { object, body } => {
+                retype_variable(object, types);
+                retype_statements(body, types, stores);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Re-derive the types of `expr` bottom-up
+///
+/// Types the lifter took from an opcode are kept; only `Variant` results of
+/// arithmetic and logical operators are recomputed from their operands.
+fn retype_expression(expr: &mut Expression, types: &HashMap<String, TypeKind>) {
+    match &mut expr.data {
+        ExpressionData::None | ExpressionData::Constant(_) => {}
+        ExpressionData::Variable(var) => {
+            retype_variable(var, types);
+            if is_concrete(var.var_type) {
+                expr.expr_type = Type::new(var.var_type);
+            }
+        }
+        ExpressionData::Unary(operand) => {
+            retype_expression(operand, types);
+            if expr.kind == ExpressionKind::Negate && expr.expr_type.kind == TypeKind::Variant {
+                if let Some(kind) = negate_type(operand.expr_type.kind) {
+                    expr.expr_type = Type::new(kind);
+                }
+            }
+        }
+        ExpressionData::Binary { left, right } => {
+            retype_expression(left, types);
+            retype_expression(right, types);
+            if expr.expr_type.kind == TypeKind::Variant {
+                if let Some(kind) =
+                    binary_type(expr.kind, left.expr_type.kind, right.expr_type.kind)
+                {
+                    expr.expr_type = Type::new(kind);
+                }
+            }
+        }
+        ExpressionData::Call { arguments, .. } => {
+            arguments
+                .iter_mut()
+                .for_each(|a| retype_expression(a, types));
+        }
+        ExpressionData::MethodCall { target, arguments } => {
+            retype_expression(target, types);
+            arguments
+                .iter_mut()
+                .for_each(|a| retype_expression(a, types));
+        }
+        ExpressionData::MemberAccess { object, .. } => retype_expression(object, types),
+        ExpressionData::ArrayIndex { array, indices } => {
+            retype_expression(array, types);
+            indices.iter_mut().for_each(|i| retype_expression(i, types));
+        }
+        ExpressionData::Cast { expr: inner, .. } => retype_expression(inner, types),
+    }
+}
+
+/// Precision order VB promotes arithmetic operands along
+fn numeric_rank(kind: TypeKind) -> Option<u8> {
+    match kind {
+        TypeKind::Byte => Some(0),
+        // Booleans take part in arithmetic as Integers
+        TypeKind::Boolean | TypeKind::Integer => Some(1),
+        TypeKind::Long => Some(2),
+        TypeKind::Single => Some(3),
+        TypeKind::Currency => Some(4),
+        TypeKind::Double => Some(5),
+        _ => None,
+    }
+}
+
+fn rank_type(rank: u8) -> TypeKind {
+    match rank {
+        0 => TypeKind::Byte,
+        1 => TypeKind::Integer,
+        2 => TypeKind::Long,
+        3 => TypeKind::Single,
+        4 => TypeKind::Currency,
+        _ => TypeKind::Double,
+    }
+}
+
+fn negate_type(kind: TypeKind) -> Option<TypeKind> {
+    // Byte is unsigned, so its negation widens to Integer
+    numeric_rank(kind).map(|rank| rank_type(rank.max(1)))
+}
+
+/// Result type of a binary operator under VB's promotion rules, or `None`
+/// when it can only be known at run time
+pub fn binary_type(kind: ExpressionKind, left: TypeKind, right: TypeKind) -> Option<TypeKind> {
+    use ExpressionKind as K;
+
+    if kind == K::Concatenate {
+        return Some(TypeKind::String);
+    }
+    if kind == K::Add && left == TypeKind::String && right == TypeKind::String {
+        return Some(TypeKind::String);
+    }
+    if matches!(kind, K::And | K::Or | K::Xor)
+        && left == TypeKind::Boolean
+        && right == TypeKind::Boolean
+    {
+        return Some(TypeKind::Boolean);
+    }
+
+    let (l, r) = (numeric_rank(left)?, numeric_rank(right)?);
+    let widest = l.max(r);
+    match kind {
+        // Long and Single meet in Double, which holds both exactly
+        K::Add | K::Subtract | K::Multiply if l.min(r) == 2 && widest == 3 => {
+            Some(TypeKind::Double)
+        }
+        K::Add | K::Subtract | K::Multiply => Some(rank_type(widest)),
+        // `/` yields Single only when both sides fit in one
+        K::Divide if l != 2 && r != 2 && widest <= 3 => Some(TypeKind::Single),
+        K::Divide => Some(TypeKind::Double),
+        // `\`, `Mod` and the bitwise operators round to an integer type
+        K::IntDivide | K::Modulo | K::And | K::Or | K::Xor => Some(rank_type(widest.min(2))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::VB6CodeGenerator;
+
+    fn typed_const(value: i64, kind: TypeKind) -> Expression {
+        Expression::constant(ConstantValue::Integer(value), Type::new(kind))
+    }
+
+    fn local(id: u32) -> Variable {
+        Variable::new(id, format!("local{}", id), TypeKind::Variant)
+    }
+
+    fn function_with(statements: Vec<Statement>) -> Function {
+        let mut function = Function::new("F".to_string(), Type::new(TypeKind::Void));
+        let mut block = BasicBlock::new(0);
+        block.statements = statements;
+        function.add_basic_block(block);
+        function
+    }
+
+    #[test]
+    fn test_promotion_rules() {
+        use ExpressionKind as K;
+        use TypeKind as T;
+
+        assert_eq!(
+            binary_type(K::Add, T::Integer, T::Integer),
+            Some(T::Integer)
+        );
+        assert_eq!(binary_type(K::Add, T::Byte, T::Integer), Some(T::Integer));
+        assert_eq!(binary_type(K::Multiply, T::Integer, T::Long), Some(T::Long));
+        assert_eq!(binary_type(K::Add, T::Long, T::Single), Some(T::Double));
+        assert_eq!(
+            binary_type(K::Subtract, T::Integer, T::Single),
+            Some(T::Single)
+        );
+        assert_eq!(binary_type(K::Add, T::Currency, T::Long), Some(T::Currency));
+        assert_eq!(
+            binary_type(K::Divide, T::Integer, T::Integer),
+            Some(T::Single)
+        );
+        assert_eq!(binary_type(K::Divide, T::Long, T::Integer), Some(T::Double));
+        assert_eq!(
+            binary_type(K::IntDivide, T::Double, T::Integer),
+            Some(T::Long)
+        );
+        assert_eq!(binary_type(K::Modulo, T::Byte, T::Byte), Some(T::Byte));
+        assert_eq!(
+            binary_type(K::And, T::Boolean, T::Boolean),
+            Some(T::Boolean)
+        );
+        assert_eq!(binary_type(K::Add, T::String, T::String), Some(T::String));
+        assert_eq!(binary_type(K::Add, T::Variant, T::Integer), None);
+    }
+
+    #[test]
+    fn test_infer_local_from_integer_sum() {
+        // local1 = 1 + 2 (two LitI2 operands), then local2 = local1 * 100000&
+        let sum = Expression::binary(
+            ExpressionKind::Add,
+            typed_const(1, TypeKind::Integer),
+            typed_const(2, TypeKind::Integer),
+            Type::new(TypeKind::Variant),
+        );
+        let product = Expression::binary(
+            ExpressionKind::Multiply,
+            Expression::variable(local(1)),
+            typed_const(100000, TypeKind::Long),
+            Type::new(TypeKind::Variant),
+        );
+        let mut function = function_with(vec![
+            Statement::assign(local(1), sum),
+            Statement::assign(local(2), product),
+        ]);
+
+        infer_types(&mut function);
+
+        let StatementData::Assign { value, .. } = &function.basic_blocks[0].statements[0].data
+        else {
+            panic!("expected assignment");
+        };
+        assert_eq!(value.expr_type.kind, TypeKind::Integer);
+
+        let declared: Vec<(&str, TypeKind)> = function
+            .local_variables
+            .iter()
+            .map(|v| (v.name.as_str(), v.var_type))
+            .collect();
+        assert_eq!(
+            declared,
+            vec![("local1", TypeKind::Integer), ("local2", TypeKind::Long)]
+        );
+
+        let code = VB6CodeGenerator::new().generate_function(&function);
+        assert!(code.contains("Dim local1 As Integer"), "{}", code);
+        assert!(code.contains("Dim local2 As Long"), "{}", code);
+    }
+
+    #[test]
+    fn test_conflicting_stores_stay_variant() {
+        let mut function = function_with(vec![
+            Statement::assign(local(1), typed_const(1, TypeKind::Integer)),
+            Statement::assign(local(1), Expression::string_const("a".to_string())),
+            Statement::method_call(
+                Expression::member_access(
+                    Expression::variable(Variable::builtin_object("Debug")),
+                    "Print".to_string(),
+                    Type::new(TypeKind::Variant),
+                ),
+                vec![Expression::variable(local(1))],
+            ),
+        ]);
+
+        infer_types(&mut function);
+
+        assert_eq!(function.local_variables.len(), 1);
+        assert_eq!(function.local_variables[0].var_type, TypeKind::Variant);
+    }
+}
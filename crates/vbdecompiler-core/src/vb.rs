@@ -4,18 +4,32 @@
 
 //! Visual Basic structure parsing module
 //!
-//! Parses VB5/6 specific structures within PE executables:
+//! Parses VB5/6 specific structures within PE executables (VB4 is only
+//! detected):
 //! - VB header (VB5! signature)
 //! - Project info
 //! - Object table
 //! - Method tables and P-Code
 
 use crate::error::{Error, Result};
-use crate::pe::PEFile;
+use crate::ir::ConstantValue;
+use crate::pcode::{Disassembler, Instruction, OperandValue};
+use crate::pe::{read_u16, read_u32, PEFile};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
 /// VB5/6 Magic signature
 const VB5_MAGIC: &[u8; 4] = b"VB5!";
 
+/// Signature of the 32-bit VB4 header, which is laid out differently
+const VB4_MAGIC: &[u8; 4] = b"VB4!";
+
+/// Section flag marking executable code
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+
+/// Section flag marking a section that contains code
+const IMAGE_SCN_CNT_CODE: u32 = 0x0000_0020;
+
 /// VB5/6 Header structure (104 bytes)
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
@@ -89,7 +103,7 @@ struct VBObjectTableHeader {
 
 /// Public Object Descriptor (48 bytes)
 #[repr(C, packed)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 struct VBPublicObjectDescriptor {
     lp_object_info: u32,        // 0x00 - Object info pointer
     dw_reserved: u32,           // 0x04 - Reserved
@@ -128,6 +142,27 @@ struct VBObjectInfo {
     lp_constants: u32,      // 0x34 - Constants pointer
 }
 
+/// Named constant record a constant pool entry points at (12 bytes)
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBConstantInfo {
+    lp_sz_name: u32, // 0x00 - Constant name
+    w_type: u16,     // 0x04 - VARTYPE of the value
+    w_reserved: u16, // 0x06 - Reserved
+    dw_value: u32,   // 0x08 - Value, or string pointer for VT_BSTR
+}
+
+/// VARTYPE tags of constant values
+const VT_I2: u16 = 2;
+const VT_I4: u16 = 3;
+const VT_R4: u16 = 4;
+const VT_BSTR: u16 = 8;
+const VT_BOOL: u16 = 11;
+const VT_UI1: u16 = 17;
+
+/// Upper bound on constant pool entries examined per object
+const MAX_CONSTANTS: u16 = 4096;
+
 /// Optional Object Information (64 bytes) - for forms
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
@@ -152,6 +187,25 @@ struct VBOptionalObjectInfo {
     dw_flags: u32,              // 0x3C - Flags
 }
 
+/// Control Information (40 bytes) - one per control on a form
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBControlInfo {
+    w_flags1: u16,       // 0x00 - Flags
+    w_event_count: u16,  // 0x02 - Event handler count
+    dw_flags2: u32,      // 0x04 - Flags
+    lp_guid: u32,        // 0x08 - Control CLSID pointer
+    w_index: u16,        // 0x0C - Control index
+    w_reserved1: u16,    // 0x0E - Reserved
+    dw_null: u32,        // 0x10 - Null
+    lp_event_table: u32, // 0x14 - Event handler table pointer
+    dw_reserved2: u32,   // 0x18 - Reserved
+    lp_sz_name: u32,     // 0x1C - Control name pointer
+    w_index_copy: u16,   // 0x20 - Control index (copy)
+    w_reserved3: u16,    // 0x22 - Reserved
+    dw_reserved4: u32,   // 0x24 - Reserved
+}
+
 /// Procedure Descriptor Information (30 bytes)
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
@@ -172,6 +226,41 @@ struct VBProcDescInfo {
     w_flags: u16,      // 0x1C - Flags
 }
 
+/// External Component Table entry (variable size, 40-byte fixed part)
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBExternalComponentEntry {
+    dw_struct_size: u32,     // 0x00 - Size of this entry including strings
+    dw_reserved1: u32,       // 0x04 - Reserved
+    dw_reserved2: u32,       // 0x08 - Reserved
+    dw_reserved3: u32,       // 0x0C - Reserved
+    b_guid_offset: u32,      // 0x10 - Offset (from entry) to the CLSID
+    dw_guid_length: u32,     // 0x14 - CLSID length
+    dw_reserved4: u32,       // 0x18 - Reserved
+    b_file_name_offset: u32, // 0x1C - Offset (from entry) to the OCX file name
+    b_source_offset: u32,    // 0x20 - Offset (from entry) to the source name
+    b_name_offset: u32,      // 0x24 - Offset (from entry) to the component name
+}
+
+/// Project External Table entry (8 bytes)
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBExternalTableEntry {
+    dw_flag: u32,                 // 0x00 - Entry kind (7 = Declare'd DLL function)
+    lp_external_table_entry: u32, // 0x04 - Entry data pointer
+}
+
+/// External library entry for `Declare` statements (8 bytes)
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBExternalLibrary {
+    lp_library_name: u32,  // 0x00 - DLL name pointer
+    lp_function_name: u32, // 0x04 - Function name pointer
+}
+
+/// External table flag for `Declare`d DLL functions
+const EXTERNAL_FLAG_DECLARE: u32 = 7;
+
 /// Method Name Entry (8 bytes)
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
@@ -180,6 +269,147 @@ struct VBMethodName {
     dw_flags: u32,       // 0x04 - Flags
 }
 
+/// COM Registration Data (42 bytes) - ActiveX projects only
+///
+/// All `b_` offsets, here and in [`VBComRegInfo`], are relative to the start
+/// of this structure.
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBComRegData {
+    b_reg_info: u32,          // 0x00 - Offset to the first object's registration info
+    b_sz_project_name: u32,   // 0x04 - Offset to the type library name
+    b_sz_help_directory: u32, // 0x08 - Offset to the help directory
+    b_sz_project_description: u32, // 0x0C - Offset to the project description
+    uuid_lib_id: [u8; 16],    // 0x10 - Type library GUID
+    dw_tlb_lcid: u32,         // 0x20 - Type library LCID
+    w_unknown: u16,           // 0x24 - Unknown
+    w_tlb_ver_major: u16,     // 0x26 - Type library major version
+    w_tlb_ver_minor: u16,     // 0x28 - Type library minor version
+}
+
+/// COM Registration Info (68 bytes) - one per public creatable object
+#[repr(C, packed)]
+#[derive(Debug, Clone, Copy)]
+struct VBComRegInfo {
+    b_next_object: u32,        // 0x00 - Offset to the next object (0 = last)
+    b_object_name: u32,        // 0x04 - Offset to the object name
+    b_object_description: u32, // 0x08 - Offset to the object description
+    dw_instancing: u32,        // 0x0C - Instancing mode
+    dw_object_id: u32,         // 0x10 - Index in the object table
+    uuid_object: [u8; 16],     // 0x14 - Object CLSID
+    f_is_interface: u32,       // 0x24 - Interface flag
+    b_uuid_object_iface: u32,  // 0x28 - Offset to the default interface IID
+    b_uuid_events_iface: u32,  // 0x2C - Offset to the events interface IID
+    f_has_events: u32,         // 0x30 - Has events flag
+    dw_misc_status: u32,       // 0x34 - OLEMISC flags
+    f_class_type: u8,          // 0x38 - Class type
+    f_object_type: u8,         // 0x39 - Object type
+    w_toolbox_bitmap32: u16,   // 0x3A - Toolbox bitmap resource ID
+    w_default_icon: u16,       // 0x3C - Default icon resource ID
+    f_is_designer: u16,        // 0x3E - Designer flag
+    b_designer_data: u32,      // 0x40 - Offset to designer data
+}
+
+/// Upper bound on registered objects, against cyclic or corrupt chains
+const MAX_COM_OBJECTS: usize = 1024;
+
+/// How a raw structure field is decoded for display
+#[derive(Debug, Clone, Copy)]
+enum FieldKind {
+    Word,
+    Dword,
+    Pointer,
+    Text(usize),
+}
+
+impl FieldKind {
+    fn size(self) -> usize {
+        match self {
+            Self::Word => 2,
+            Self::Dword | Self::Pointer => 4,
+            Self::Text(len) => len,
+        }
+    }
+}
+
+/// Field layouts for `dump_structs`, in declaration order
+const VB_HEADER_LAYOUT: &[(&str, FieldKind)] = &[
+    ("sz_vb_magic", FieldKind::Text(4)),
+    ("w_runtime_build", FieldKind::Word),
+    ("sz_language_dll", FieldKind::Text(14)),
+    ("sz_sec_language_dll", FieldKind::Text(14)),
+    ("w_runtime_dll_version", FieldKind::Word),
+    ("dw_lcid", FieldKind::Dword),
+    ("dw_sec_lcid", FieldKind::Dword),
+    ("lp_sub_main", FieldKind::Pointer),
+    ("lp_project_info", FieldKind::Pointer),
+    ("f_mdl_int_objs", FieldKind::Dword),
+    ("f_mdl_int_objs2", FieldKind::Dword),
+    ("dw_thread_flags", FieldKind::Dword),
+    ("dw_thread_count", FieldKind::Dword),
+    ("w_form_count", FieldKind::Word),
+    ("w_external_count", FieldKind::Word),
+    ("dw_thunk_count", FieldKind::Dword),
+    ("lp_gui_table", FieldKind::Pointer),
+    ("lp_external_component_table", FieldKind::Pointer),
+    ("lp_com_register_data", FieldKind::Pointer),
+    ("b_sz_project_description", FieldKind::Dword),
+    ("b_sz_project_exe_name", FieldKind::Dword),
+    ("b_sz_project_help_file", FieldKind::Dword),
+    ("b_sz_project_name", FieldKind::Dword),
+];
+
+const VB_PROJECT_INFO_LAYOUT: &[(&str, FieldKind)] = &[
+    ("dw_version", FieldKind::Dword),
+    ("lp_object_table", FieldKind::Pointer),
+    ("dw_null", FieldKind::Dword),
+    ("lp_code_start", FieldKind::Pointer),
+    ("lp_code_end", FieldKind::Pointer),
+    ("dw_data_size", FieldKind::Dword),
+    ("lp_thread_space", FieldKind::Pointer),
+    ("lp_vba_seh", FieldKind::Pointer),
+    ("lp_native_code", FieldKind::Pointer),
+    ("sz_path1", FieldKind::Text(260)),
+    ("sz_path2", FieldKind::Text(260)),
+    ("lp_external_table", FieldKind::Pointer),
+    ("dw_external_count", FieldKind::Dword),
+];
+
+const VB_OBJECT_TABLE_HEADER_LAYOUT: &[(&str, FieldKind)] = &[
+    ("lp_heap_link", FieldKind::Pointer),
+    ("lp_exec_proj", FieldKind::Pointer),
+    ("lp_project_info2", FieldKind::Pointer),
+    ("w_reserved", FieldKind::Word),
+    ("w_total_objects", FieldKind::Word),
+    ("w_compiled_objects", FieldKind::Word),
+    ("w_objects_in_use", FieldKind::Word),
+    ("lp_object_array", FieldKind::Pointer),
+    ("f_ide_flag", FieldKind::Dword),
+    ("f_ide_flag2", FieldKind::Dword),
+    ("lp_ide_data", FieldKind::Pointer),
+    ("lp_ide_data2", FieldKind::Pointer),
+    ("lp_sz_project_name", FieldKind::Pointer),
+    ("dw_lcid", FieldKind::Dword),
+    ("dw_lcid2", FieldKind::Dword),
+    ("lp_ide_data3", FieldKind::Pointer),
+    ("dw_identifier", FieldKind::Dword),
+];
+
+const VB_PUBLIC_OBJECT_DESCRIPTOR_LAYOUT: &[(&str, FieldKind)] = &[
+    ("lp_object_info", FieldKind::Pointer),
+    ("dw_reserved", FieldKind::Dword),
+    ("lp_public_bytes", FieldKind::Pointer),
+    ("lp_static_bytes", FieldKind::Pointer),
+    ("lp_module_public", FieldKind::Pointer),
+    ("lp_module_static", FieldKind::Pointer),
+    ("lp_sz_object_name", FieldKind::Pointer),
+    ("dw_method_count", FieldKind::Dword),
+    ("lp_method_names_array", FieldKind::Pointer),
+    ("b_static_vars", FieldKind::Dword),
+    ("f_object_type", FieldKind::Dword),
+    ("dw_null", FieldKind::Dword),
+];
+
 /// High-level VB Object representation
 #[derive(Debug, Clone)]
 pub struct VBObject {
@@ -187,12 +417,43 @@ pub struct VBObject {
     pub object_index: u32,
     pub object_type: u32,
     pub method_names: Vec<String>,
+    source_file: Option<String>,
     descriptor: VBPublicObjectDescriptor,
     info: Option<VBObjectInfo>,
     optional_info: Option<VBOptionalObjectInfo>,
+    /// Controls placed on the form (empty for modules and classes)
+    pub controls: Vec<VBControl>,
+    /// Module-level `Const` declarations from the constant pool
+    pub constants: Vec<VBConstant>,
+    /// Whether the object's structures could be parsed
+    parsed: bool,
 }
 
 impl VBObject {
+    /// Stand-in for an object that failed to parse, keeping later objects
+    /// at their on-disk index
+    fn unparsed(index: u32) -> Self {
+        Self {
+            name: format!("<Unparsed{}>", index),
+            object_index: index,
+            object_type: 0,
+            method_names: Vec::new(),
+            source_file: None,
+            descriptor: VBPublicObjectDescriptor::default(),
+            info: None,
+            optional_info: None,
+            controls: Vec::new(),
+            constants: Vec::new(),
+            parsed: false,
+        }
+    }
+
+    /// Check if this object was parsed, rather than standing in for one
+    /// that failed to (see [`VBFile::object_errors`])
+    pub fn is_parsed(&self) -> bool {
+        self.parsed
+    }
+
     /// Check if this is a form
     pub fn is_form(&self) -> bool {
         (self.object_type & 0x10) != 0
@@ -217,6 +478,429 @@ impl VBObject {
     pub fn method_count(&self) -> usize {
         self.method_names.len()
     }
+
+    /// Original source file name (e.g. `frmMain.frm`), if the binary retains it
+    pub fn source_file_name(&self) -> Option<String> {
+        self.source_file.clone()
+    }
+
+    /// Base name for output files: the source file stem, or the object name
+    pub fn file_stem(&self) -> String {
+        self.source_file
+            .as_deref()
+            .and_then(|file| file.rsplit_once('.'))
+            .map(|(stem, _)| stem.to_string())
+            .unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// Control placed on a form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VBControl {
+    /// Control name (`Command1`)
+    pub name: String,
+    /// Control class (`VB.CommandButton`), guessed from the name for intrinsic
+    /// controls; `Object` when unknown
+    pub classname: String,
+    /// Index of the control on its form
+    pub index: u16,
+    /// CLSID of the control, if the binary records one
+    pub clsid: Option<String>,
+    /// Design-time properties (name, value) recovered so far
+    pub properties: Vec<(String, String)>,
+}
+
+/// Default-name and Hungarian prefixes of the intrinsic VB controls
+const INTRINSIC_CONTROL_PREFIXES: &[(&[&str], &str)] = &[
+    (&["Command", "cmd"], "CommandButton"),
+    (&["Text", "txt"], "TextBox"),
+    (&["Label", "lbl"], "Label"),
+    (&["Check", "chk"], "CheckBox"),
+    (&["Option", "opt"], "OptionButton"),
+    (&["Frame", "fra"], "Frame"),
+    (&["List", "lst"], "ListBox"),
+    (&["Combo", "cbo"], "ComboBox"),
+    (&["Picture", "pic"], "PictureBox"),
+    (&["Image", "img"], "Image"),
+    (&["Timer", "tmr"], "Timer"),
+    (&["HScroll", "hsb"], "HScrollBar"),
+    (&["VScroll", "vsb"], "VScrollBar"),
+    (&["Drive", "drv"], "DriveListBox"),
+    (&["Dir", "dir"], "DirListBox"),
+    (&["File", "fil"], "FileListBox"),
+    (&["Shape", "shp"], "Shape"),
+    (&["Line", "lin"], "Line"),
+    (&["Data", "dat"], "Data"),
+    (&["OLE", "ole"], "OLE"),
+];
+
+/// Guess the class of an intrinsic control from its name
+///
+/// Matches VB's default names (`Command1`) and Hungarian prefixes
+/// (`cmdOK`).
+fn intrinsic_control_class(name: &str) -> Option<&'static str> {
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    INTRINSIC_CONTROL_PREFIXES
+        .iter()
+        .find(|(prefixes, _)| {
+            prefixes[0] == base
+                || name
+                    .strip_prefix(prefixes[1])
+                    .and_then(|rest| rest.chars().next())
+                    .is_some_and(|c| c.is_ascii_uppercase())
+        })
+        .map(|(_, class)| *class)
+}
+
+/// Extensions of VB source files that can hold an object
+const SOURCE_FILE_EXTENSIONS: &[&str] = &["frm", "bas", "cls", "ctl", "dob", "pag", "dsr"];
+
+/// Extract a module file name from a (possibly full) path
+fn source_file_from_path(path: &str) -> Option<String> {
+    let file = path.rsplit(['\\', '/']).next()?;
+    let (stem, extension) = file.rsplit_once('.')?;
+    if stem.is_empty()
+        || !SOURCE_FILE_EXTENSIONS
+            .iter()
+            .any(|ext| ext.eq_ignore_ascii_case(extension))
+    {
+        return None;
+    }
+    Some(file.to_string())
+}
+
+/// Check for a VB identifier (a letter, then letters, digits or `_`)
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.len() <= 255
+}
+
+/// Why an object in the object array could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectParseError {
+    /// Index of the object in the object array
+    pub object_index: u32,
+    pub message: String,
+}
+
+impl fmt::Display for ObjectParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "object {}: {}", self.object_index, self.message)
+    }
+}
+
+/// Module-level `Const` recovered from an object's constant pool
+#[derive(Debug, Clone)]
+pub struct VBConstant {
+    pub name: String,
+    pub value: ConstantValue,
+}
+
+/// Object variable declared `WithEvents` (an event sink member)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithEventsMember {
+    /// Member variable name
+    pub name: String,
+    /// Class whose events are sunk (`Object` if not part of this project)
+    pub class_name: String,
+    /// IID of the event source interface
+    pub events_iid: String,
+    /// Methods handling this member's events (`name_EventName`)
+    pub handlers: Vec<String>,
+}
+
+/// Kind of external dependency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalKind {
+    /// ActiveX control (OCX) referenced by the project
+    Control,
+    /// DLL called through `Declare` statements
+    Library,
+}
+
+/// External component (OCX/DLL) a project depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VBExternalComponent {
+    /// File name (`MSCOMCTL.OCX`, `kernel32`)
+    pub name: String,
+    /// CLSID of the control, for ActiveX components
+    pub guid: Option<String>,
+    /// Kind of dependency
+    pub kind: ExternalKind,
+}
+
+/// Public object registered by an ActiveX DLL/OCX project
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ComObject {
+    /// Class name
+    pub name: String,
+    /// CLSID the class is registered under
+    pub clsid: String,
+    /// IID of the default interface
+    pub interface_iid: Option<String>,
+    /// IID of the events interface, if the class raises events
+    pub events_iid: Option<String>,
+}
+
+/// COM registration data of an ActiveX project, from [`VBFile::com_registration`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ComRegistration {
+    /// CLSID of the primary (first registered) object
+    pub clsid: Option<String>,
+    /// Type library GUID
+    pub lib_id: String,
+    /// Registered objects, in registration order
+    pub objects: Vec<ComObject>,
+}
+
+/// One field of a raw VB structure, as laid out in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructField {
+    /// Field name (`w_total_objects`)
+    pub name: &'static str,
+    /// Offset from the start of the structure
+    pub offset: usize,
+    /// Raw little-endian bytes
+    pub raw: Vec<u8>,
+    /// Decoded value (decimal, `0x` pointer, or quoted text)
+    pub value: String,
+}
+
+/// Raw VB structure annotated field by field, for validating the parser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDump {
+    /// Structure name (`VBHeader`, `VBPublicObjectDescriptor[0] Form1`)
+    pub name: String,
+    /// RVA the structure was read from
+    pub rva: u32,
+    pub fields: Vec<StructField>,
+}
+
+impl fmt::Display for StructDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size: usize = self.fields.iter().map(|field| field.raw.len()).sum();
+        writeln!(f, "{} @ RVA 0x{:08X} ({} bytes)", self.name, self.rva, size)?;
+        for field in &self.fields {
+            // Long text fields only show their leading bytes
+            let mut hex = field
+                .raw
+                .iter()
+                .take(16)
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<_>>()
+                .join(" ");
+            if field.raw.len() > 16 {
+                hex.push_str(" ..");
+            }
+            writeln!(
+                f,
+                "  +0x{:03X}  {:<28} {:<50} {}",
+                field.offset, field.name, hex, field.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Standard event-handler prefixes that belong to the object itself
+const SELF_EVENT_PREFIXES: &[&str] = &[
+    "Form",
+    "MDIForm",
+    "Class",
+    "UserControl",
+    "UserDocument",
+    "PropertyPage",
+];
+
+/// Cheaply check whether `data` looks like a VB executable
+///
+/// Walks the DOS and PE headers just far enough to find the first code
+/// section and scans its raw data for the `VB5!` (or `VB4!`) signature,
+/// without allocating. Meant for triaging many files; a `true` does not
+/// guarantee that [`VBFile::from_pe`] succeeds. Packed files, whose code is
+/// compressed, read as not VB.
+pub fn is_vb_executable(data: &[u8]) -> bool {
+    let Some(pe_offset) = read_u32(data, 0x3C).map(|offset| offset as usize) else {
+        return false;
+    };
+    if data.get(..2) != Some(b"MZ") || data.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0") {
+        return false;
+    }
+    let (Some(section_count), Some(optional_header_size)) = (
+        read_u16(data, pe_offset + 6),
+        read_u16(data, pe_offset + 20),
+    ) else {
+        return false;
+    };
+
+    // 40-byte section headers follow the optional header
+    let section_table = pe_offset + 24 + optional_header_size as usize;
+    (0..section_count as usize)
+        .map(|index| section_table + index * 40)
+        .find(|&header| {
+            read_u32(data, header + 36).is_some_and(|characteristics| {
+                characteristics & (IMAGE_SCN_CNT_CODE | IMAGE_SCN_MEM_EXECUTE) != 0
+            })
+        })
+        .and_then(|header| {
+            let size = read_u32(data, header + 16)? as usize;
+            let offset = read_u32(data, header + 20)? as usize;
+            data.get(offset..offset.saturating_add(size).min(data.len()))
+        })
+        .is_some_and(|code| {
+            code.windows(4)
+                .any(|window| window == VB5_MAGIC || window == VB4_MAGIC)
+        })
+}
+
+/// Format 16 raw bytes as a registry-style GUID string
+pub fn format_guid(bytes: &[u8; 16]) -> String {
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}}}",
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        u16::from_le_bytes([bytes[4], bytes[5]]),
+        u16::from_le_bytes([bytes[6], bytes[7]]),
+        bytes[8],
+        bytes[9],
+        bytes[10],
+        bytes[11],
+        bytes[12],
+        bytes[13],
+        bytes[14],
+        bytes[15]
+    )
+}
+
+/// Visual Basic version a binary was compiled with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VBVersion {
+    /// 32-bit VB4; detected but not parsed
+    VB4,
+    VB5,
+    VB6,
+    Unknown,
+}
+
+impl VBVersion {
+    /// Version implied by a runtime DLL name (`MSVBVM60.DLL`)
+    fn from_runtime_dll(name: &str) -> Option<Self> {
+        let name = name.trim_end_matches('\0');
+        if name.eq_ignore_ascii_case("MSVBVM60.DLL") {
+            Some(Self::VB6)
+        } else if name.eq_ignore_ascii_case("MSVBVM50.DLL") {
+            Some(Self::VB5)
+        } else if name.eq_ignore_ascii_case("VB40032.DLL") {
+            Some(Self::VB4)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for VBVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::VB4 => "VB4",
+            Self::VB5 => "VB5",
+            Self::VB6 => "VB6",
+            Self::Unknown => "Unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Number of basic blocks in a method's instructions
+///
+/// A block starts at the first instruction, at every branch target inside
+/// the method, and after every branch or return.
+fn basic_block_count(instructions: &[Instruction]) -> usize {
+    let addresses: HashSet<u32> = instructions.iter().map(|i| i.address).collect();
+    let mut leaders = HashSet::new();
+    let mut next_is_leader = true;
+    for instr in instructions {
+        if next_is_leader {
+            leaders.insert(instr.address);
+        }
+        if let Some(target) = instr.branch_target().filter(|t| addresses.contains(t)) {
+            leaders.insert(target);
+        }
+        next_is_leader = instr.is_branch || instr.is_return;
+    }
+    leaders.len()
+}
+
+/// First runtime build shipped with VB6 (MSVBVM60 6.0.8169)
+const VB6_FIRST_RUNTIME_BUILD: u16 = 8169;
+
+/// One method's P-Code, as returned by [`VBFile::disassemble_methods`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MethodDisassembly {
+    /// Name of the owning object
+    pub object: String,
+    /// Method name
+    pub method: String,
+    /// Decoded instructions (empty for native or failed methods)
+    pub instructions: Vec<Instruction>,
+    /// Why the method couldn't be disassembled, if it has P-Code
+    pub error: Option<String>,
+}
+
+/// Size and complexity of one method, as returned by [`VBFile::method_sizes`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct MethodSize {
+    /// Name of the owning object
+    pub object: String,
+    /// Method name
+    pub method: String,
+    /// Code size in bytes (`w_proc_size` for P-Code)
+    pub size: u32,
+    /// Number of P-Code instructions (None for native or undecodable methods)
+    pub instructions: Option<usize>,
+    /// Number of basic blocks (None for native or undecodable methods)
+    pub basic_blocks: Option<usize>,
+}
+
+/// Where a P-Code method's code lies, as returned by
+/// [`VBFile::pcode_locations`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcodeLocation {
+    /// Index of the method in its object
+    pub method_index: usize,
+    /// RVA of the first P-Code byte (right after the procedure descriptor)
+    pub rva: u32,
+    /// P-Code size in bytes (`w_proc_size`)
+    pub size: u16,
+    /// Bytes of locals (`w_frame_size`)
+    pub frame_size: u16,
+}
+
+/// A method of the project with its P-Code, as yielded by
+/// [`VBFile::project_methods`]
+struct ProjectMethod<'a> {
+    object_index: usize,
+    object: &'a VBObject,
+    method_index: usize,
+    name: &'a str,
+    /// RVA and bytes of the P-Code; `None` for native and empty methods
+    pcode: Option<(u32, Vec<u8>)>,
+}
+
+/// String literal found by [`VBFile::strings`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundString {
+    /// Where the string lives: `Object.Method` or a section name
+    pub location: String,
+    /// RVA of the instruction, or of the string within the section
+    pub address: u32,
+    pub value: String,
+}
+
+impl fmt::Display for FoundString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\t{:08X}\t{}", self.location, self.address, self.value)
+    }
 }
 
 /// VB file parser
@@ -227,12 +911,41 @@ pub struct VBFile {
     project_info: Option<VBProjectInfo>,
     object_table_header: Option<VBObjectTableHeader>,
     objects: Vec<VBObject>,
+    /// Objects that failed to parse (present in `objects` as placeholders)
+    object_errors: Vec<ObjectParseError>,
     is_native_code: bool,
+    /// Base address the VB structure pointers were computed for (None = PE image base)
+    image_base_override: Option<u32>,
 }
 
 impl VBFile {
     /// Parse VB structures from a PE file
     pub fn from_pe(pe_file: PEFile) -> Result<Self> {
+        Self::parse_with_image_base(pe_file, None)
+    }
+
+    /// Parse VB structures from an image whose pointers were relocated to `image_base`
+    ///
+    /// Use this for memory dumps taken at a base other than the PE's preferred
+    /// `image_base` (ASLR or explicit relocation): all VAs read from VB
+    /// structures are translated relative to the supplied base instead.
+    pub fn with_image_base_override(pe_file: PEFile, image_base: u32) -> Result<Self> {
+        Self::parse_with_image_base(pe_file, Some(image_base))
+    }
+
+    fn parse_with_image_base(pe_file: PEFile, image_base_override: Option<u32>) -> Result<Self> {
+        // Managed code has no VB header; don't scan every section for one
+        if pe_file.is_dotnet() {
+            return Err(Error::Unsupported(".NET assembly, not VB5/6".to_string()));
+        }
+
+        // VB5/6 only ever produced 32-bit images
+        if pe_file.is_64bit() {
+            return Err(Error::Unsupported(
+                "VB structures in PE32+ (64-bit) images".to_string(),
+            ));
+        }
+
         let mut vb_file = Self {
             pe_file,
             vb_header_rva: 0,
@@ -240,13 +953,21 @@ impl VBFile {
             project_info: None,
             object_table_header: None,
             objects: Vec::new(),
+            object_errors: Vec::new(),
             is_native_code: false,
+            image_base_override,
         };
 
         vb_file.parse()?;
         Ok(vb_file)
     }
 
+    /// Base address used to translate VAs in VB structures
+    pub fn image_base(&self) -> u32 {
+        self.image_base_override
+            .unwrap_or_else(|| self.pe_file.image_base() as u32)
+    }
+
     /// Parse all VB structures
     fn parse(&mut self) -> Result<()> {
         // Find VB5! header
@@ -278,7 +999,11 @@ impl VBFile {
     }
 
     /// Find the VB5! signature in the PE file
+    ///
+    /// A VB4 executable (`VB4!` header or VB40032.DLL runtime) fails with
+    /// [`Error::Unsupported`] rather than a missing signature.
     fn find_vb_header(&mut self) -> Result<()> {
+        let mut vb4_header = None;
         // Search for "VB5!" signature in all sections
         log::debug!(
             "Searching for VB5! signature in {} sections",
@@ -321,6 +1046,9 @@ impl VBFile {
                         );
                         return Ok(());
                     }
+                    if vb4_header.is_none() && &data[i..i + 4] == VB4_MAGIC {
+                        vb4_header = Some(start_rva + i as u32);
+                    }
                 }
                 log::debug!("    VB5! not found in section {}", idx);
             } else {
@@ -328,6 +1056,18 @@ impl VBFile {
             }
         }
 
+        let vb4_runtime = self
+            .pe_file
+            .imported_dlls()
+            .iter()
+            .any(|dll| VBVersion::from_runtime_dll(dll) == Some(VBVersion::VB4));
+        if let Some(rva) = vb4_header {
+            log::info!("Found VB4! at RVA 0x{:X}", rva);
+        }
+        if vb4_header.is_some() || vb4_runtime {
+            return Err(Error::Unsupported("VB4 not yet supported".to_string()));
+        }
+
         Err(Error::invalid_vb("VB5! signature not found"))
     }
 
@@ -415,25 +1155,48 @@ impl VBFile {
             let obj_rva =
                 object_array_rva + (i as u32 * size_of::<VBPublicObjectDescriptor>() as u32);
 
-            if let Ok(descriptor) = self.read_struct::<VBPublicObjectDescriptor>(obj_rva) {
-                match self.parse_object(descriptor, i as u32) {
-                    Ok(obj) => {
-                        log::info!("    Successfully parsed object: {}", obj.name);
-                        self.objects.push(obj);
-                    }
-                    Err(e) => {
-                        log::warn!("    Failed to parse object {}: {}", i, e);
-                    }
+            let parsed = self
+                .read_struct::<VBPublicObjectDescriptor>(obj_rva)
+                .and_then(|descriptor| self.parse_object(descriptor, i as u32));
+            match parsed {
+                Ok(obj) => {
+                    log::info!("    Successfully parsed object: {}", obj.name);
+                    self.objects.push(obj);
+                }
+                Err(e) => {
+                    // Keep a placeholder so later objects keep their indices
+                    log::warn!("    Failed to parse object {}: {}", i, e);
+                    self.object_errors.push(ObjectParseError {
+                        object_index: i as u32,
+                        message: e.to_string(),
+                    });
+                    self.objects.push(VBObject::unparsed(i as u32));
                 }
-            } else {
-                log::warn!("    Failed to read descriptor for object {}", i);
             }
         }
 
-        log::info!("Successfully parsed {} objects", self.objects.len());
+        log::info!(
+            "Successfully parsed {} of {} objects",
+            self.objects.len() - self.object_errors.len(),
+            total_objects
+        );
         Ok(())
     }
 
+    /// Recover an object's original source file name from its IDE/project data
+    fn read_source_file(&self, info: &VBObjectInfo) -> Option<String> {
+        [
+            info.lp_ide_data,
+            info.lp_ide_data2,
+            info.lp_ide_data3,
+            info.lp_project_data,
+        ]
+        .into_iter()
+        .filter(|&ptr| ptr != 0)
+        .filter_map(|ptr| self.read_string_at_rva(self.va_to_rva(ptr), 260))
+        .find_map(|path| source_file_from_path(&path))
+    }
+
     /// Parse a single object
     fn parse_object(&self, descriptor: VBPublicObjectDescriptor, index: u32) -> Result<VBObject> {
         let mut obj = VBObject {
@@ -441,11 +1204,22 @@ impl VBFile {
             object_index: index,
             object_type: descriptor.f_object_type,
             method_names: Vec::new(),
+            source_file: None,
             descriptor,
             info: None,
             optional_info: None,
+            controls: Vec::new(),
+            constants: Vec::new(),
+            parsed: true,
         };
 
+        // The object info's method count is 16-bit
+        if descriptor.dw_method_count > u16::MAX as u32 {
+            return Err(Error::invalid_vb(format!("unrealistic method count {}", {
+                descriptor.dw_method_count
+            })));
+        }
+
         // Parse object name
         if descriptor.lp_sz_object_name != 0 {
             obj.name = self
@@ -460,12 +1234,17 @@ impl VBFile {
             let info_rva = self.va_to_rva(descriptor.lp_object_info);
             if let Ok(info) = self.read_struct::<VBObjectInfo>(info_rva) {
                 obj.info = Some(info);
+                obj.source_file = self.read_source_file(&info);
+                obj.constants = self.parse_constants(&info);
 
                 // Parse optional info if present
                 if (descriptor.f_object_type & 0x80) != 0 {
                     let opt_info_rva = info_rva + size_of::<VBObjectInfo>() as u32;
                     if let Ok(opt_info) = self.read_struct::<VBOptionalObjectInfo>(opt_info_rva) {
                         obj.optional_info = Some(opt_info);
+                        if (descriptor.f_object_type & 0x10) != 0 {
+                            obj.controls = self.parse_controls(&opt_info, &obj.name);
+                        }
                     }
                 }
             }
@@ -477,6 +1256,110 @@ impl VBFile {
         Ok(obj)
     }
 
+    /// Collect the named constants of an object's constant pool
+    ///
+    /// The pool mostly holds string literals and import thunks; only entries
+    /// pointing at a constant record with an identifier name and a known
+    /// value type are taken.
+    fn parse_constants(&self, info: &VBObjectInfo) -> Vec<VBConstant> {
+        let count = info.w_constants.min(MAX_CONSTANTS);
+        (0..count as u32)
+            .filter_map(|index| self.constant_va(info, index))
+            .filter(|&va| va != 0)
+            .filter_map(|va| self.read_constant(self.va_to_rva(va)))
+            .collect()
+    }
+
+    /// Read the constant record at `rva`, if it is one
+    fn read_constant(&self, rva: u32) -> Option<VBConstant> {
+        let record = self.read_struct::<VBConstantInfo>(rva).ok()?;
+        let (name_ptr, value) = (record.lp_sz_name, record.dw_value);
+        if name_ptr == 0 {
+            return None;
+        }
+        let name = self.read_string_at_rva(self.va_to_rva(name_ptr), 256)?;
+        if !is_identifier(&name) {
+            return None;
+        }
+
+        let value = match record.w_type {
+            VT_I2 => ConstantValue::Integer(value as i16 as i64),
+            VT_I4 => ConstantValue::Integer(value as i32 as i64),
+            VT_UI1 => ConstantValue::Integer(value as u8 as i64),
+            VT_R4 => ConstantValue::Float(f32::from_bits(value) as f64),
+            VT_BOOL => ConstantValue::Boolean(value as u16 != 0),
+            VT_BSTR => {
+                ConstantValue::String(self.read_string_at_rva(self.va_to_rva(value), 65536)?)
+            }
+            _ => return None,
+        };
+        Some(VBConstant { name, value })
+    }
+
+    /// Parse the control array of a form
+    ///
+    /// A malformed count or array pointer yields no controls rather than
+    /// failing the whole object.
+    fn parse_controls(&self, optional_info: &VBOptionalObjectInfo, form: &str) -> Vec<VBControl> {
+        let count = optional_info.dw_control_count;
+        let array = optional_info.lp_control_array;
+        if count == 0 {
+            return Vec::new();
+        }
+
+        // The VB6 form designer caps a form at 254 distinct control names;
+        // control arrays stretch that, but not past a few thousand
+        if count > 4096 || array == 0 {
+            log::warn!(
+                "Ignoring control array of form {}: count {}, pointer 0x{:08X}",
+                form,
+                count,
+                array
+            );
+            return Vec::new();
+        }
+
+        let array_rva = self.va_to_rva(array);
+        let mut controls = Vec::new();
+        for i in 0..count {
+            let entry_rva = array_rva + i * size_of::<VBControlInfo>() as u32;
+            let Ok(entry) = self.read_struct::<VBControlInfo>(entry_rva) else {
+                log::warn!(
+                    "Control array of form {} is truncated after {} of {} controls",
+                    form,
+                    i,
+                    count
+                );
+                return Vec::new();
+            };
+
+            let name = match entry.lp_sz_name {
+                0 => None,
+                ptr => self.read_string_at_rva(self.va_to_rva(ptr), 256),
+            }
+            .unwrap_or_else(|| format!("Control{}", i + 1));
+            let clsid = match entry.lp_guid {
+                0 => None,
+                ptr => self
+                    .read_struct::<[u8; 16]>(self.va_to_rva(ptr))
+                    .ok()
+                    .map(|bytes| format_guid(&bytes)),
+            };
+            let classname = intrinsic_control_class(&name)
+                .map(|class| format!("VB.{}", class))
+                .unwrap_or_else(|| "Object".to_string());
+
+            controls.push(VBControl {
+                name,
+                classname,
+                index: entry.w_index,
+                clsid,
+                properties: Vec::new(),
+            });
+        }
+        controls
+    }
+
     /// Parse method names for an object
     fn parse_method_names(&self, obj: &mut VBObject) -> Result<()> {
         if obj.descriptor.dw_method_count == 0 || obj.descriptor.lp_method_names_array == 0 {
@@ -507,21 +1390,9 @@ impl VBFile {
 
     /// Read a structure at an RVA
     fn read_struct<T: Copy>(&self, rva: u32) -> Result<T> {
-        let size = size_of::<T>();
-        let data = self.pe_file.read_at_rva(rva, size).ok_or_else(|| {
-            Error::invalid_vb(format!("Failed to read structure at RVA 0x{:X}", rva))
-        })?;
-
-        if data.len() < size {
-            return Err(Error::invalid_vb(format!(
-                "Insufficient data at RVA 0x{:X}: expected {} bytes, got {}",
-                rva,
-                size,
-                data.len()
-            )));
-        }
+        let data = self.pe_file.read_at_rva_exact(rva, size_of::<T>())?;
 
-        // SAFETY: We've verified the size matches and T is Copy.
+        // SAFETY: read_at_rva_exact returned exactly size_of::<T>() bytes and T is Copy.
         // The packed repr ensures no alignment issues.
         unsafe { Ok(std::ptr::read_unaligned(data.as_ptr() as *const T)) }
     }
@@ -538,7 +1409,7 @@ impl VBFile {
 
     /// Convert Virtual Address to Relative Virtual Address
     fn va_to_rva(&self, va: u32) -> u32 {
-        va.saturating_sub(self.pe_file.image_base())
+        va.saturating_sub(self.image_base())
     }
 
     /// Check if this is a valid VB file
@@ -556,27 +1427,294 @@ impl VBFile {
         self.is_native_code && self.vb_header.is_some()
     }
 
+    /// Detect whether the binary targets the VB5 or the VB6 runtime
+    ///
+    /// Checks the runtime named in the VB header, then the imported runtime
+    /// DLL, then falls back to the runtime build number.
+    pub fn vb_version(&self) -> VBVersion {
+        let Some(vb_header) = self.vb_header else {
+            return VBVersion::Unknown;
+        };
+
+        let language_dll = String::from_utf8_lossy(&vb_header.sz_language_dll).into_owned();
+        if let Some(version) = VBVersion::from_runtime_dll(&language_dll) {
+            return version;
+        }
+
+        if let Some(version) = self
+            .pe_file
+            .imported_dlls()
+            .iter()
+            .find_map(|dll| VBVersion::from_runtime_dll(dll))
+        {
+            return version;
+        }
+
+        let runtime_build = vb_header.w_runtime_build;
+        match runtime_build {
+            0 => VBVersion::Unknown,
+            build if build >= VB6_FIRST_RUNTIME_BUILD => VBVersion::VB6,
+            _ => VBVersion::VB5,
+        }
+    }
+
     /// Get all parsed objects
     pub fn objects(&self) -> &[VBObject] {
         &self.objects
     }
 
+    /// Objects that failed to parse
+    ///
+    /// Each stands in [`objects`](Self::objects) as a placeholder named
+    /// `<UnparsedN>`, so object indices match the on-disk object array.
+    pub fn object_errors(&self) -> &[ObjectParseError] {
+        &self.object_errors
+    }
+
     /// Get object by index
     pub fn object(&self, index: usize) -> Option<&VBObject> {
         self.objects.get(index)
     }
 
+    /// Name of a method, by object and method index
+    pub fn method_name(&self, object_index: usize, method_index: usize) -> Option<&str> {
+        self.objects
+            .get(object_index)?
+            .method_names
+            .get(method_index)
+            .map(String::as_str)
+    }
+
     /// Get object by name
     pub fn object_by_name(&self, name: &str) -> Option<&VBObject> {
         self.objects.iter().find(|obj| obj.name == name)
     }
 
+    /// Read the events IID table of an object
+    fn events_iids(&self, obj: &VBObject) -> Vec<[u8; 16]> {
+        let Some(optional_info) = obj.optional_info.as_ref() else {
+            return Vec::new();
+        };
+
+        let count = optional_info.dw_events_iid_count;
+        if count == 0 || optional_info.lp_events_iid_table == 0 {
+            return Vec::new();
+        }
+
+        // Objects sink at most a handful of event sources
+        if count > 256 {
+            log::warn!(
+                "Unrealistic events IID count {} for object {}",
+                count,
+                obj.name
+            );
+            return Vec::new();
+        }
+
+        let table_rva = self.va_to_rva(optional_info.lp_events_iid_table);
+        (0..count)
+            .filter_map(|i| {
+                let iid_va = self.read_struct::<u32>(table_rva + i * 4).ok()?;
+                if iid_va == 0 {
+                    return None;
+                }
+                self.read_struct::<[u8; 16]>(self.va_to_rva(iid_va)).ok()
+            })
+            .collect()
+    }
+
+    /// Get the `WithEvents` members of an object
+    ///
+    /// Each entry in the object's events IID table is one event-sinking member.
+    /// The class is the one whose own event source interface, listed first in
+    /// its events IID table, has that IID; objects that merely sink the same
+    /// events don't count. The member name is recovered from the
+    /// `name_Event` handler methods, falling back to `withEventsN`.
+    pub fn with_events_members(&self, obj: &VBObject) -> Vec<WithEventsMember> {
+        let iids = self.events_iids(obj);
+        if iids.is_empty() {
+            return Vec::new();
+        }
+
+        // Handler prefixes that don't belong to the object itself, in order
+        let mut prefixes: Vec<&str> = Vec::new();
+        for method in &obj.method_names {
+            if let Some((prefix, _)) = method.rsplit_once('_') {
+                if !prefix.is_empty()
+                    && prefix != obj.name
+                    && !SELF_EVENT_PREFIXES.contains(&prefix)
+                    && !prefixes.contains(&prefix)
+                {
+                    prefixes.push(prefix);
+                }
+            }
+        }
+
+        iids.iter()
+            .enumerate()
+            .map(|(i, iid)| {
+                let class_name = self
+                    .objects
+                    .iter()
+                    .filter(|other| other.object_index != obj.object_index && other.is_class())
+                    .find(|other| self.events_iids(other).first() == Some(iid))
+                    .map(|other| other.name.clone())
+                    .unwrap_or_else(|| "Object".to_string());
+
+                let name = prefixes
+                    .get(i)
+                    .map(|p| p.to_string())
+                    .unwrap_or_else(|| format!("withEvents{}", i + 1));
+
+                let handler_prefix = format!("{}_", name);
+                let handlers = obj
+                    .method_names
+                    .iter()
+                    .filter(|m| m.starts_with(&handler_prefix))
+                    .cloned()
+                    .collect();
+
+                WithEventsMember {
+                    name,
+                    class_name,
+                    events_iid: format_guid(iid),
+                    handlers,
+                }
+            })
+            .collect()
+    }
+
     /// Get P-Code bytes for a specific method
     pub fn get_pcode_for_method(
         &self,
         object_index: usize,
         method_index: usize,
     ) -> Option<Vec<u8>> {
+        self.read_pcode(&self.pcode_location(object_index, method_index)?)
+    }
+
+    /// Locate the P-Code of every method of an object, reading each
+    /// procedure descriptor once
+    ///
+    /// Native and empty methods are omitted. Decompiling many methods in
+    /// parallel resolves these up front so that the workers only read the
+    /// bytes with [`read_pcode`](Self::read_pcode).
+    pub fn pcode_locations(&self, object_index: usize) -> Vec<PcodeLocation> {
+        let method_count = self
+            .objects
+            .get(object_index)
+            .map_or(0, |obj| obj.method_names.len());
+        (0..method_count)
+            .filter_map(|method_index| self.pcode_location(object_index, method_index))
+            .collect()
+    }
+
+    /// Read the P-Code bytes at a location from [`pcode_locations`](Self::pcode_locations)
+    pub fn read_pcode(&self, location: &PcodeLocation) -> Option<Vec<u8>> {
+        self.pe_file
+            .read_at_rva(location.rva, location.size as usize)
+            .map(<[u8]>::to_vec)
+    }
+
+    /// Locate a P-Code method's code from its procedure descriptor
+    fn pcode_location(&self, object_index: usize, method_index: usize) -> Option<PcodeLocation> {
+        let (proc_desc_rva, proc_desc) = self.proc_desc(object_index, method_index)?;
+
+        if proc_desc.w_proc_size == 0 {
+            return None;
+        }
+
+        // P-Code follows the descriptor
+        Some(PcodeLocation {
+            method_index,
+            rva: proc_desc_rva + size_of::<VBProcDescInfo>() as u32,
+            size: proc_desc.w_proc_size,
+            frame_size: proc_desc.w_frame_size,
+        })
+    }
+
+    /// Every method of every object, in object order, with its P-Code
+    fn project_methods(&self) -> impl Iterator<Item = ProjectMethod<'_>> + '_ {
+        self.objects
+            .iter()
+            .enumerate()
+            .flat_map(move |(object_index, object)| {
+                object
+                    .method_names
+                    .iter()
+                    .enumerate()
+                    .map(move |(method_index, name)| ProjectMethod {
+                        object_index,
+                        object,
+                        method_index,
+                        name,
+                        pcode: self
+                            .pcode_location(object_index, method_index)
+                            .and_then(|location| Some((location.rva, self.read_pcode(&location)?))),
+                    })
+            })
+    }
+
+    /// Disassemble a P-Code method
+    ///
+    /// Instruction addresses are RVAs.
+    /// `ImpAdCall*` instructions get the name of the import they call as
+    /// their symbol. Native methods have no P-Code and yield an
+    /// [`Error::Unsupported`].
+    ///
+    /// ```no_run
+    /// use vbdecompiler_core::Decompiler;
+    ///
+    /// let vb_file = Decompiler::new().parse_file("program.exe")?;
+    /// for instr in vb_file.disassemble_method(0, 0)? {
+    ///     println!("{:04X}: {}", instr.address, instr.mnemonic);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn disassemble_method(
+        &self,
+        object_index: usize,
+        method_index: usize,
+    ) -> Result<Vec<Instruction>> {
+        let name = self
+            .method_name(object_index, method_index)
+            .ok_or_else(|| {
+                Error::invalid_vb(format!(
+                    "no method {} in object {}",
+                    method_index, object_index
+                ))
+            })?;
+        if !self.is_pcode() {
+            return Err(Error::Unsupported(format!(
+                "{} is native code, not P-Code",
+                name
+            )));
+        }
+        let (rva, pcode) = self
+            .pcode_location(object_index, method_index)
+            .and_then(|location| Some((location.rva, self.read_pcode(&location)?)))
+            .ok_or_else(|| Error::invalid_vb(format!("{} has no P-Code", name)))?;
+
+        let mut instructions = Disassembler::new(pcode).disassemble(rva)?;
+        for instr in instructions.iter_mut().filter(|i| i.is_native_call()) {
+            let index = match instr.operands.last().map(|op| &op.value) {
+                Some(OperandValue::Int16(v)) => *v as u16 as u32,
+                Some(OperandValue::Byte(v)) => *v as u32,
+                _ => continue,
+            };
+            instr.symbol = self.import_name(object_index, index);
+        }
+        Ok(instructions)
+    }
+
+    /// Get the stack frame size (bytes of locals) of a P-Code method
+    pub fn method_frame_size(&self, object_index: usize, method_index: usize) -> Option<u16> {
+        self.proc_desc(object_index, method_index)
+            .map(|(_, proc_desc)| proc_desc.w_frame_size)
+    }
+
+    /// Read a P-Code method's procedure descriptor and its RVA
+    fn proc_desc(&self, object_index: usize, method_index: usize) -> Option<(u32, VBProcDescInfo)> {
         if !self.is_pcode() {
             return None;
         }
@@ -594,18 +1732,562 @@ impl VBFile {
             method_table_rva + (method_index as u32 * size_of::<VBProcDescInfo>() as u32);
 
         let proc_desc = self.read_struct::<VBProcDescInfo>(proc_desc_rva).ok()?;
+        Some((proc_desc_rva, proc_desc))
+    }
 
-        if proc_desc.w_proc_size == 0 {
+    /// Get the P-Code bytes of every method, keyed by (object, method) index
+    ///
+    /// Native and empty methods are omitted.
+    pub fn all_pcode(&self) -> HashMap<(usize, usize), Vec<u8>> {
+        self.project_methods()
+            .filter_map(|method| {
+                let (_, bytes) = method.pcode?;
+                (!bytes.is_empty()).then_some(((method.object_index, method.method_index), bytes))
+            })
+            .collect()
+    }
+
+    /// Get the x86 code of a method in a native-compiled project
+    ///
+    /// Native method tables hold one procedure entry point (VA) per method.
+    /// A procedure runs up to the next entry point, bounded by the project's
+    /// code range (or the end of its section when that is unset). Returns the
+    /// procedure's RVA and bytes.
+    pub fn get_native_code_for_method(
+        &self,
+        object_index: usize,
+        method_index: usize,
+    ) -> Option<(u32, Vec<u8>)> {
+        if !self.is_native_code() {
             return None;
         }
 
-        // P-Code follows the descriptor
-        let pcode_rva = proc_desc_rva + size_of::<VBProcDescInfo>() as u32;
-        let pcode_bytes = self
-            .pe_file
-            .read_at_rva(pcode_rva, proc_desc.w_proc_size as usize)?;
+        let start = self.native_entry_point(object_index, method_index)?;
+        let end = self.native_procedure_end(start)?;
+        let code = self.pe_file.read_at_rva(start, (end - start) as usize)?;
+        Some((start, code.to_vec()))
+    }
+
+    /// RVA of a native method's entry point
+    fn native_entry_point(&self, object_index: usize, method_index: usize) -> Option<u32> {
+        let info = self.objects.get(object_index)?.info.as_ref()?;
+        if info.lp_methods == 0 || method_index >= info.w_method_count as usize {
+            return None;
+        }
+
+        let entry_rva = self.va_to_rva(info.lp_methods) + method_index as u32 * 4;
+        let va = self.read_struct::<u32>(entry_rva).ok()?;
+        (va != 0).then(|| self.va_to_rva(va))
+    }
+
+    /// End RVA of the native procedure starting at `start`
+    fn native_procedure_end(&self, start: u32) -> Option<u32> {
+        let project_info = self.project_info.as_ref()?;
+        let (code_start, code_end) = (project_info.lp_code_start, project_info.lp_code_end);
+        let limit = if code_start != 0 && code_end != 0 {
+            if start < self.va_to_rva(code_start) {
+                return None;
+            }
+            self.va_to_rva(code_end)
+        } else {
+            let section = self.pe_file.section_by_rva(start)?;
+            section.virtual_address + section.virtual_size.max(section.size_of_raw_data)
+        };
+
+        let next = self
+            .objects
+            .iter()
+            .enumerate()
+            .flat_map(|(object_index, object)| {
+                (0..object.method_names.len()).filter_map(move |method_index| {
+                    self.native_entry_point(object_index, method_index)
+                })
+            })
+            .filter(|&entry| entry > start)
+            .min()
+            .unwrap_or(limit);
+
+        let end = next.min(limit);
+        (end > start).then_some(end)
+    }
+
+    /// Disassemble every method that has P-Code, in object order
+    ///
+    /// Methods without P-Code (native code) are left out. Instruction
+    /// addresses are RVAs.
+    pub fn disassemble_methods(&self) -> Vec<MethodDisassembly> {
+        self.project_methods()
+            .filter_map(|method| {
+                let (rva, pcode) = method.pcode?;
+                let (instructions, error) = match Disassembler::new(pcode).disassemble(rva) {
+                    Ok(instructions) => (instructions, None),
+                    Err(e) => (Vec::new(), Some(e.to_string())),
+                };
+                Some(MethodDisassembly {
+                    object: method.object.name.clone(),
+                    method: method.name.to_string(),
+                    instructions,
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    /// Size and complexity of every method, largest first
+    ///
+    /// P-Code methods are disassembled to count their instructions and basic
+    /// blocks; native methods only report the size of their procedure.
+    pub fn method_sizes(&self) -> Vec<MethodSize> {
+        let mut sizes: Vec<MethodSize> = self
+            .project_methods()
+            .map(|method| {
+                let mut entry = MethodSize {
+                    object: method.object.name.clone(),
+                    method: method.name.to_string(),
+                    size: 0,
+                    instructions: None,
+                    basic_blocks: None,
+                };
+                if let Some((rva, pcode)) = method.pcode {
+                    entry.size = pcode.len() as u32;
+                    if let Ok(instructions) = Disassembler::new(pcode).disassemble(rva) {
+                        entry.instructions = Some(instructions.len());
+                        entry.basic_blocks = Some(basic_block_count(&instructions));
+                    }
+                } else if let Some((_, code)) =
+                    self.get_native_code_for_method(method.object_index, method.method_index)
+                {
+                    entry.size = code.len() as u32;
+                }
+                entry
+            })
+            .collect();
+        // Stable, so equal sizes keep object order
+        sizes.sort_by_key(|m| std::cmp::Reverse(m.size));
+        sizes
+    }
 
-        Some(pcode_bytes.to_vec())
+    /// Render a P-Code listing of every method, grouped under a
+    /// `; Object.Method` header, optionally with the raw instruction bytes
+    ///
+    /// Instructions are addressed by RVA.
+    pub fn disassembly_listing(&self, hex: bool) -> String {
+        let mut listing = String::new();
+        for method in self.project_methods() {
+            listing.push_str(&format!("; {}.{}\n", method.object.name, method.name));
+
+            let Some((rva, pcode)) = method.pcode else {
+                listing.push_str("; (no P-Code)\n\n");
+                continue;
+            };
+            match Disassembler::new(pcode).disassemble(rva) {
+                Ok(instructions) => {
+                    for instr in &instructions {
+                        if hex {
+                            listing.push_str(&format!("{:<24}", instr.bytes_to_hex()));
+                        }
+                        listing.push_str(&instr.to_string());
+                        listing.push('\n');
+                    }
+                }
+                Err(e) => listing.push_str(&format!("; disassembly failed: {}\n", e)),
+            }
+            listing.push('\n');
+        }
+        listing
+    }
+
+    /// Collect string literals at least `min_len` characters long
+    ///
+    /// Gathers the string operands of every method's P-Code (`LitStr`,
+    /// `LitVarStr`, `LdFixedStr`, ...) followed by the printable ASCII runs of
+    /// the `.data` section. Nothing is lifted or decompiled.
+    pub fn strings(&self, min_len: usize) -> Vec<FoundString> {
+        let mut strings = Vec::new();
+        for method in self.project_methods() {
+            let Some((rva, pcode)) = method.pcode else {
+                continue;
+            };
+            let instructions = Disassembler::new(pcode)
+                .disassemble(rva)
+                .unwrap_or_default();
+            for instr in &instructions {
+                for operand in &instr.operands {
+                    if let OperandValue::String(value) = &operand.value {
+                        if value.chars().count() >= min_len {
+                            strings.push(FoundString {
+                                location: format!("{}.{}", method.object.name, method.name),
+                                address: instr.address,
+                                value: value.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(section) = self.pe_file.section_by_name(".data") {
+            let size = section.virtual_size.min(section.size_of_raw_data) as usize;
+            let data = self.pe_file.read_at_rva_vec(section.virtual_address, size);
+            let mut start = 0;
+            for (i, &byte) in data.iter().chain(std::iter::once(&0)).enumerate() {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    continue;
+                }
+                if i - start >= min_len.max(1) {
+                    strings.push(FoundString {
+                        location: ".data".to_string(),
+                        address: section.virtual_address + start as u32,
+                        value: String::from_utf8_lossy(&data[start..i]).into_owned(),
+                    });
+                }
+                start = i + 1;
+            }
+        }
+
+        strings
+    }
+
+    /// Get the OCX controls and `Declare`d DLLs the project references
+    pub fn external_components(&self) -> Vec<VBExternalComponent> {
+        let mut components = self.external_controls();
+        for library in self.external_libraries() {
+            if !components
+                .iter()
+                .any(|c| c.kind == ExternalKind::Library && c.name.eq_ignore_ascii_case(&library))
+            {
+                components.push(VBExternalComponent {
+                    name: library,
+                    guid: None,
+                    kind: ExternalKind::Library,
+                });
+            }
+        }
+        components
+    }
+
+    /// Get the COM registration data of an ActiveX DLL/OCX project
+    ///
+    /// Returns `None` for standard EXEs, whose header carries no registration
+    /// data, and when the data can't be read.
+    pub fn com_registration(&self) -> Option<ComRegistration> {
+        let vb_header = self.vb_header.as_ref()?;
+        let lp_com_register_data = vb_header.lp_com_register_data;
+        if lp_com_register_data == 0 {
+            return None;
+        }
+
+        let base = self.va_to_rva(lp_com_register_data);
+        let data = self.read_struct::<VBComRegData>(base).ok()?;
+        let read_guid = |offset: u32| {
+            (offset != 0)
+                .then(|| self.read_struct::<[u8; 16]>(base + offset).ok())
+                .flatten()
+                .map(|bytes| format_guid(&bytes))
+        };
+
+        let mut objects = Vec::new();
+        let mut offset = data.b_reg_info;
+        while offset != 0 && objects.len() < MAX_COM_OBJECTS {
+            let Ok(info) = self.read_struct::<VBComRegInfo>(base + offset) else {
+                break;
+            };
+            let name = (info.b_object_name != 0)
+                .then(|| self.read_string_at_rva(base + info.b_object_name, 256))
+                .flatten()
+                .unwrap_or_default();
+            objects.push(ComObject {
+                name,
+                clsid: format_guid(&info.uuid_object),
+                interface_iid: read_guid(info.b_uuid_object_iface),
+                events_iid: read_guid(info.b_uuid_events_iface),
+            });
+
+            // Entries follow one another; a backward link means a corrupt chain
+            if info.b_next_object <= offset {
+                break;
+            }
+            offset = info.b_next_object;
+        }
+
+        Some(ComRegistration {
+            clsid: objects.first().map(|object| object.clsid.clone()),
+            lib_id: format_guid(&data.uuid_lib_id),
+            objects,
+        })
+    }
+
+    /// Walk the VB header's external component table (ActiveX controls)
+    fn external_controls(&self) -> Vec<VBExternalComponent> {
+        let Some(vb_header) = self.vb_header.as_ref() else {
+            return Vec::new();
+        };
+        let count = vb_header.w_external_count;
+        if count == 0 || vb_header.lp_external_component_table == 0 {
+            return Vec::new();
+        }
+
+        let mut components = Vec::new();
+        let mut entry_rva = self.va_to_rva(vb_header.lp_external_component_table);
+        for _ in 0..count {
+            let Ok(entry) = self.read_struct::<VBExternalComponentEntry>(entry_rva) else {
+                break;
+            };
+
+            let read_at = |offset: u32| {
+                (offset != 0)
+                    .then(|| self.read_string_at_rva(entry_rva + offset, 260))
+                    .flatten()
+                    .filter(|s| !s.is_empty())
+            };
+            let name = read_at(entry.b_file_name_offset).or_else(|| read_at(entry.b_name_offset));
+            let guid = (entry.b_guid_offset != 0 && entry.dw_guid_length >= 16)
+                .then(|| {
+                    self.read_struct::<[u8; 16]>(entry_rva + entry.b_guid_offset)
+                        .ok()
+                })
+                .flatten()
+                .map(|bytes| format_guid(&bytes));
+
+            if let Some(name) = name {
+                components.push(VBExternalComponent {
+                    name,
+                    guid,
+                    kind: ExternalKind::Control,
+                });
+            }
+
+            // Entries are variable-sized; a bogus size means the table is corrupt
+            let size = entry.dw_struct_size;
+            if (size as usize) < size_of::<VBExternalComponentEntry>() || size > 0x1000 {
+                break;
+            }
+            entry_rva += size;
+        }
+        components
+    }
+
+    /// Walk the project's external table for `Declare`d DLL names
+    fn external_libraries(&self) -> Vec<String> {
+        let Some(project_info) = self.project_info.as_ref() else {
+            return Vec::new();
+        };
+        let count = project_info.dw_external_count;
+        if count == 0 || project_info.lp_external_table == 0 {
+            return Vec::new();
+        }
+        if count > 4096 {
+            log::warn!("Unrealistic external table count {}", count);
+            return Vec::new();
+        }
+
+        let table_rva = self.va_to_rva(project_info.lp_external_table);
+        (0..count)
+            .filter_map(|i| {
+                let entry = self
+                    .read_struct::<VBExternalTableEntry>(
+                        table_rva + i * size_of::<VBExternalTableEntry>() as u32,
+                    )
+                    .ok()?;
+                if entry.dw_flag != EXTERNAL_FLAG_DECLARE || entry.lp_external_table_entry == 0 {
+                    return None;
+                }
+                let library = self
+                    .read_struct::<VBExternalLibrary>(self.va_to_rva(entry.lp_external_table_entry))
+                    .ok()?;
+                if library.lp_library_name == 0 {
+                    return None;
+                }
+                self.read_string_at_rva(self.va_to_rva(library.lp_library_name), 260)
+                    .filter(|name| !name.is_empty())
+            })
+            .collect()
+    }
+
+    /// Get the RVAs of native stubs called from a P-Code method
+    ///
+    /// `ImpAdCall*` opcodes leave P-Code for native import thunks; their
+    /// operand indexes the object's constant pool, which holds the thunk VA.
+    pub fn native_call_targets(&self, object_index: usize, method_index: usize) -> Vec<u32> {
+        let Some(pcode) = self.get_pcode_for_method(object_index, method_index) else {
+            return Vec::new();
+        };
+        let Some(info) = self.objects.get(object_index).and_then(|o| o.info) else {
+            return Vec::new();
+        };
+
+        let Ok(instructions) = Disassembler::new(pcode).disassemble(0) else {
+            return Vec::new();
+        };
+
+        let mut targets = Vec::new();
+        for instr in instructions.iter().filter(|i| i.is_native_call()) {
+            let index = match instr.operands.first().map(|op| &op.value) {
+                Some(OperandValue::Int16(v)) => *v as u16 as u32,
+                Some(OperandValue::Byte(v)) => *v as u32,
+                _ => continue,
+            };
+            let Some(target_va) = self.constant_va(&info, index) else {
+                continue;
+            };
+            if target_va != 0 && !targets.contains(&self.va_to_rva(target_va)) {
+                targets.push(self.va_to_rva(target_va));
+            }
+        }
+        targets
+    }
+
+    /// Read entry `index` of an object's constant pool
+    fn constant_va(&self, info: &VBObjectInfo, index: u32) -> Option<u32> {
+        if info.lp_constants == 0 {
+            return None;
+        }
+        let constants_rva = self.va_to_rva(info.lp_constants);
+        self.read_struct::<u32>(constants_rva + index * 4).ok()
+    }
+
+    /// Name of the imported function an `ImpAdCall*` operand refers to
+    ///
+    /// `index` is the operand: a slot of the object's constant pool holding
+    /// either the IAT entry itself or a `jmp [iat]` thunk in front of it.
+    pub fn import_name(&self, object_index: usize, index: u32) -> Option<String> {
+        let info = self.objects.get(object_index)?.info?;
+        let target_rva = self.va_to_rva(self.constant_va(&info, index)?);
+        if let Some(name) = self.pe_file.import_at_iat_rva(target_rva) {
+            return Some(name);
+        }
+
+        match self.pe_file.read_at_rva(target_rva, 6)? {
+            [0xFF, 0x25, iat @ ..] => {
+                let iat_va = u32::from_le_bytes(iat.try_into().ok()?);
+                self.pe_file.import_at_iat_rva(self.va_to_rva(iat_va))
+            }
+            _ => None,
+        }
+    }
+
+    /// Locate the `jmp [iat]` thunks that native code calls the VB runtime
+    /// through, with the runtime function each one reaches
+    ///
+    /// Executable sections are scanned for jumps through an MSVBVM60.DLL or
+    /// MSVBVM50.DLL IAT slot. Returns (thunk RVA, function name) pairs by
+    /// RVA, or nothing when the runtime is not imported.
+    pub fn runtime_imports(&self) -> Vec<(u32, String)> {
+        let slots: HashMap<u32, String> = ["MSVBVM60.DLL", "MSVBVM50.DLL"]
+            .iter()
+            .flat_map(|dll| self.pe_file.import_slots(dll))
+            .collect();
+        if slots.is_empty() {
+            return Vec::new();
+        }
+
+        let mut thunks = Vec::new();
+        for section in self.pe_file.sections() {
+            if section.characteristics & IMAGE_SCN_MEM_EXECUTE == 0 {
+                continue;
+            }
+            let size = section.virtual_size.min(section.size_of_raw_data) as usize;
+            let Some(data) = self.pe_file.read_at_rva(section.virtual_address, size) else {
+                continue;
+            };
+            for (offset, window) in data.windows(6).enumerate() {
+                let [0xFF, 0x25, iat @ ..] = window else {
+                    continue;
+                };
+                let iat_va = u32::from_le_bytes(iat.try_into().unwrap_or_default());
+                if let Some(name) = slots.get(&self.va_to_rva(iat_va)) {
+                    thunks.push((section.virtual_address + offset as u32, name.clone()));
+                }
+            }
+        }
+        thunks
+    }
+
+    /// Raw VB tables annotated field by field
+    ///
+    /// Covers the VB header, project info, object table header and every
+    /// public object descriptor. Structures that cannot be read are skipped.
+    pub fn dump_structs(&self) -> Vec<StructDump> {
+        let mut dumps = Vec::new();
+        dumps.extend(self.dump_struct("VBHeader", self.vb_header_rva, VB_HEADER_LAYOUT));
+
+        if let Some(header) = &self.vb_header {
+            dumps.extend(self.dump_struct(
+                "VBProjectInfo",
+                self.va_to_rva(header.lp_project_info),
+                VB_PROJECT_INFO_LAYOUT,
+            ));
+        }
+        if let Some(project_info) = &self.project_info {
+            dumps.extend(self.dump_struct(
+                "VBObjectTableHeader",
+                self.va_to_rva(project_info.lp_object_table),
+                VB_OBJECT_TABLE_HEADER_LAYOUT,
+            ));
+        }
+        if let Some(table) = &self.object_table_header {
+            let array_rva = self.va_to_rva(table.lp_object_array);
+            for i in 0..table.w_total_objects.min(1000) as u32 {
+                let name = match self.objects.iter().find(|o| o.object_index == i) {
+                    Some(obj) => format!("VBPublicObjectDescriptor[{}] {}", i, obj.name),
+                    None => format!("VBPublicObjectDescriptor[{}]", i),
+                };
+                let rva = array_rva + i * size_of::<VBPublicObjectDescriptor>() as u32;
+                dumps.extend(self.dump_struct(&name, rva, VB_PUBLIC_OBJECT_DESCRIPTOR_LAYOUT));
+            }
+        }
+
+        dumps
+    }
+
+    /// Read a structure's raw bytes and split them according to `layout`
+    fn dump_struct(
+        &self,
+        name: &str,
+        rva: u32,
+        layout: &[(&'static str, FieldKind)],
+    ) -> Option<StructDump> {
+        let size = layout.iter().map(|(_, kind)| kind.size()).sum();
+        let data = self.pe_file.read_at_rva(rva, size)?;
+        if data.len() < size {
+            return None;
+        }
+
+        let mut offset = 0;
+        let fields = layout
+            .iter()
+            .map(|&(field_name, kind)| {
+                let raw = &data[offset..offset + kind.size()];
+                let value = match kind {
+                    FieldKind::Word => u16::from_le_bytes([raw[0], raw[1]]).to_string(),
+                    FieldKind::Dword => {
+                        u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]).to_string()
+                    }
+                    FieldKind::Pointer => format!(
+                        "0x{:08X}",
+                        u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]])
+                    ),
+                    FieldKind::Text(_) => {
+                        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+                        format!("\"{}\"", String::from_utf8_lossy(&raw[..end]))
+                    }
+                };
+                let field = StructField {
+                    name: field_name,
+                    offset,
+                    raw: raw.to_vec(),
+                    value,
+                };
+                offset += kind.size();
+                field
+            })
+            .collect();
+
+        Some(StructDump {
+            name: name.to_string(),
+            rva,
+            fields,
+        })
     }
 
     /// Get the underlying PE file
@@ -613,6 +2295,70 @@ impl VBFile {
         &self.pe_file
     }
 
+    /// Object and method index of `Sub Main`, the program's entry point
+    ///
+    /// The VB header points at the method's procedure descriptor (or its
+    /// P-Code) in P-Code projects and at its code in native ones. None for
+    /// projects that start with a form.
+    pub fn sub_main(&self) -> Option<(usize, usize)> {
+        let sub_main = self.vb_header.as_ref()?.lp_sub_main;
+        if sub_main == 0 {
+            return None;
+        }
+        let target = self.va_to_rva(sub_main);
+
+        (0..self.objects.len()).find_map(|object_index| {
+            let method_count = self.objects[object_index].method_names.len();
+            (0..method_count)
+                .find(|&method_index| {
+                    if self.is_pcode() {
+                        self.pcode_location(object_index, method_index)
+                            .is_some_and(|location| {
+                                let descriptor = location.rva - size_of::<VBProcDescInfo>() as u32;
+                                target == descriptor || target == location.rva
+                            })
+                    } else {
+                        self.get_native_code_for_method(object_index, method_index)
+                            .is_some_and(|(rva, _)| rva == target)
+                    }
+                })
+                .map(|method_index| (object_index, method_index))
+        })
+    }
+
+    /// Object index of the form the program starts with
+    ///
+    /// The runtime loads the first form of the VB header's GUI table, matched
+    /// to its object by the GUI GUID in the form's optional info. Without a
+    /// usable GUI table this falls back to the first form in the object
+    /// table. None for projects that start in `Sub Main`.
+    pub fn startup_form(&self) -> Option<usize> {
+        if self.sub_main().is_some() {
+            return None;
+        }
+
+        let gui_table = self.vb_header.as_ref()?.lp_gui_table;
+        let first_gui = (gui_table != 0)
+            .then(|| {
+                self.read_struct::<[u8; 16]>(self.va_to_rva(gui_table) + 0x04)
+                    .ok()
+            })
+            .flatten();
+        let from_gui_table = first_gui.and_then(|guid| {
+            self.objects.iter().position(|obj| {
+                obj.is_form()
+                    && obj.optional_info.is_some_and(|info| {
+                        info.lp_guid_object_gui != 0
+                            && self
+                                .read_struct::<[u8; 16]>(self.va_to_rva(info.lp_guid_object_gui))
+                                .is_ok_and(|gui| gui == guid)
+                    })
+            })
+        });
+
+        from_gui_table.or_else(|| self.objects.iter().position(VBObject::is_form))
+    }
+
     /// Get project name if available
     pub fn project_name(&self) -> Option<String> {
         let vb_header = self.vb_header.as_ref()?;
@@ -628,6 +2374,16 @@ impl VBFile {
             }
         }
 
+        // DLLs/OCXs record their module name in the export directory
+        if let Some(module_name) = self.pe_file.export_module_name() {
+            let stem = module_name
+                .rsplit_once('.')
+                .map_or(module_name.as_str(), |(stem, _)| stem);
+            if !stem.is_empty() {
+                return Some(stem.to_string());
+            }
+        }
+
         // Try path from project info
         if let Some(project_info) = &self.project_info {
             if project_info.sz_path1[0] != 0 {
@@ -647,6 +2403,9 @@ impl VBFile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testutil::{
+        build_vb_exe, build_vb_section, ObjectSpec, PeBuilder, ProjectSpec, IMAGE_BASE,
+    };
 
     #[test]
     fn test_vb5_magic() {
@@ -665,5 +2424,709 @@ mod tests {
         assert_eq!(size_of::<VBOptionalObjectInfo>(), 64);
         assert_eq!(size_of::<VBProcDescInfo>(), 30);
         assert_eq!(size_of::<VBMethodName>(), 8);
+        assert_eq!(size_of::<VBComRegData>(), 42);
+        assert_eq!(size_of::<VBComRegInfo>(), 68);
+    }
+
+    #[test]
+    fn test_dump_layouts_match_structs() {
+        use std::mem::size_of;
+
+        let total = |layout: &[(&str, FieldKind)]| -> usize {
+            layout.iter().map(|(_, kind)| kind.size()).sum()
+        };
+        assert_eq!(total(VB_HEADER_LAYOUT), size_of::<VBHeader>());
+        assert_eq!(total(VB_PROJECT_INFO_LAYOUT), size_of::<VBProjectInfo>());
+        assert_eq!(
+            total(VB_OBJECT_TABLE_HEADER_LAYOUT),
+            size_of::<VBObjectTableHeader>()
+        );
+        assert_eq!(
+            total(VB_PUBLIC_OBJECT_DESCRIPTOR_LAYOUT),
+            size_of::<VBPublicObjectDescriptor>()
+        );
+    }
+
+    fn parse_fixture(spec: &ProjectSpec) -> VBFile {
+        let pe = PEFile::from_bytes(build_vb_exe(spec)).unwrap();
+        VBFile::from_pe(pe).unwrap()
+    }
+
+    #[test]
+    fn test_parse_fixture_project() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]));
+        let vb_file = parse_fixture(&spec);
+
+        assert_eq!(vb_file.project_name().as_deref(), Some("Project1"));
+        assert_eq!(vb_file.objects().len(), 1);
+        assert_eq!(vb_file.objects()[0].name, "Form1");
+        assert_eq!(vb_file.objects()[0].method_names, vec!["Form_Load"]);
+        assert_eq!(vb_file.get_pcode_for_method(0, 0), Some(vec![0x14]));
+    }
+
+    #[test]
+    fn test_all_pcode() {
+        // LitI2 1; ExitProc | ExitProc | no P-Code
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x5E, 0x01, 0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Stub", vec![]));
+        let vb_file = parse_fixture(&spec);
+
+        let pcode = vb_file.all_pcode();
+        assert_eq!(pcode.len(), 2);
+        assert_eq!(pcode[&(0, 0)].len(), 3);
+        assert_eq!(pcode[&(1, 0)].len(), 1);
+        assert!(!pcode.contains_key(&(2, 0)));
+    }
+
+    #[test]
+    fn test_pcode_locations() {
+        // LitI2 1; ExitProc | ExitProc | no P-Code
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method_with_frame(
+                "Form_Load",
+                vec![0x5E, 0x01, 0x14],
+                8,
+            ))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Stub", vec![]));
+        let vb_file = parse_fixture(&spec);
+
+        for object_index in 0..vb_file.objects().len() {
+            let locations = vb_file.pcode_locations(object_index);
+            let method_count = vb_file.objects()[object_index].method_names.len();
+            for method_index in 0..method_count {
+                let location = locations.iter().find(|l| l.method_index == method_index);
+                assert_eq!(
+                    location.and_then(|l| vb_file.read_pcode(l)),
+                    vb_file.get_pcode_for_method(object_index, method_index)
+                );
+                assert_eq!(
+                    location.map(|l| l.frame_size),
+                    vb_file
+                        .method_frame_size(object_index, method_index)
+                        .filter(|_| location.is_some())
+                );
+            }
+        }
+
+        let locations = vb_file.pcode_locations(0);
+        assert_eq!((locations[0].size, locations[0].frame_size), (3, 8));
+        assert!(vb_file.pcode_locations(2).is_empty());
+        assert!(vb_file.pcode_locations(9).is_empty());
+    }
+
+    #[test]
+    fn test_disassembly_listing() {
+        // LitI2 1; ExitProc
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x5E, 0x01, 0x14]));
+        let vb_file = parse_fixture(&spec);
+
+        // Instructions are addressed by the RVA of the method's P-Code
+        let rva = vb_file.pcode_locations(0)[0].rva;
+        assert_ne!(rva, 0);
+
+        let listing = vb_file.disassembly_listing(false);
+        assert_eq!(
+            listing,
+            format!(
+                "; Form1.Form_Load\n{:08X}  LitI2  0x01\n{:08X}  ExitProc\n\n",
+                rva,
+                rva + 2
+            )
+        );
+        assert!(!listing.contains("TODO"));
+
+        let listing = vb_file.disassembly_listing(true);
+        assert!(listing.contains(&format!(
+            "5E 01                   {:08X}  LitI2  0x01\n",
+            rva
+        )));
+
+        let methods = vb_file.disassemble_methods();
+        assert_eq!(methods[0].instructions[0].address, rva);
+    }
+
+    #[test]
+    fn test_dump_structs() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Module1", 0).method("Main", vec![0x14]));
+        let dumps = parse_fixture(&spec).dump_structs();
+
+        let names: Vec<_> = dumps.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "VBHeader",
+                "VBProjectInfo",
+                "VBObjectTableHeader",
+                "VBPublicObjectDescriptor[0] Form1",
+                "VBPublicObjectDescriptor[1] Module1",
+            ]
+        );
+
+        let magic = &dumps[0].fields[0];
+        assert_eq!(magic.raw, b"VB5!");
+        assert_eq!(magic.value, "\"VB5!\"");
+
+        let total = dumps[2]
+            .fields
+            .iter()
+            .find(|f| f.name == "w_total_objects")
+            .unwrap();
+        assert_eq!(total.offset, 0x0E);
+        assert_eq!(total.raw, vec![2, 0]);
+        assert_eq!(total.value, "2");
+
+        let text: String = dumps.iter().map(|d| d.to_string()).collect();
+        assert!(text.contains("+0x000  sz_vb_magic"));
+        assert!(text.contains("56 42 35 21"));
+        assert!(text.contains("w_total_objects"));
+    }
+
+    #[test]
+    fn test_image_base_override() {
+        // Structures relocated to 0x400000, but the header claims a DLL-style base
+        let spec = ProjectSpec::new("Dumped")
+            .object(ObjectSpec::new("Module1", 0).method("Main", vec![0x14]));
+        let mut pe = PeBuilder::new().image_base(0x1000_0000).vb_imports();
+        let rva = pe.next_section_rva();
+        pe.section(".text", build_vb_section(&spec, rva));
+        let bytes = pe.build();
+
+        // Pointers don't resolve against the preferred base
+        assert!(VBFile::from_pe(PEFile::from_bytes(bytes.clone()).unwrap()).is_err());
+
+        let vb = VBFile::with_image_base_override(PEFile::from_bytes(bytes).unwrap(), IMAGE_BASE)
+            .unwrap();
+        assert_eq!(vb.image_base(), IMAGE_BASE);
+        assert_eq!(vb.va_to_rva(IMAGE_BASE + 0x2000), 0x2000);
+        assert_eq!(vb.project_name().as_deref(), Some("Dumped"));
+        assert_eq!(vb.objects().len(), 1);
+    }
+
+    #[test]
+    fn test_source_file_name() {
+        let spec = ProjectSpec::new("Project1")
+            .object(
+                ObjectSpec::new("Form1", 0x10)
+                    .source_file("C:\\Projects\\Demo\\frmMain.frm")
+                    .method("Form_Load", vec![0x14]),
+            )
+            .object(ObjectSpec::new("Module1", 0x01));
+        let vb = parse_fixture(&spec);
+
+        let form = &vb.objects()[0];
+        assert_eq!(form.source_file_name().as_deref(), Some("frmMain.frm"));
+        assert_eq!(form.file_stem(), "frmMain");
+
+        let module = &vb.objects()[1];
+        assert_eq!(module.source_file_name(), None);
+        assert_eq!(module.file_stem(), "Module1");
+
+        assert_eq!(source_file_from_path("README"), None);
+        assert_eq!(source_file_from_path("lib/util.txt"), None);
+    }
+
+    #[test]
+    fn test_method_frame_size() {
+        let spec = ProjectSpec::new("Project1").object(
+            ObjectSpec::new("Module1", 0x01)
+                .method_with_frame("Main", vec![0x14], 0x10)
+                .method("Other", vec![0x14]),
+        );
+        let vb = parse_fixture(&spec);
+
+        assert_eq!(vb.method_frame_size(0, 0), Some(0x10));
+        assert_eq!(vb.method_frame_size(0, 1), Some(0));
+        assert_eq!(vb.method_frame_size(0, 2), None);
+    }
+
+    #[test]
+    fn test_is_vb_executable() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        assert!(is_vb_executable(&build_vb_exe(&spec)));
+
+        // push ebp; mov ebp,esp; pop ebp; ret
+        let mut pe = PeBuilder::new().import("KERNEL32.DLL", &["ExitProcess"]);
+        pe.section(".text", vec![0x55, 0x8B, 0xEC, 0x5D, 0xC3]);
+        assert!(!is_vb_executable(&pe.build()));
+
+        assert!(!is_vb_executable(b"MZ garbage that is not a PE file"));
+        assert!(!is_vb_executable(&[]));
+    }
+
+    #[test]
+    fn test_sub_main() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        assert_eq!(parse_fixture(&spec).sub_main(), None);
+        assert_eq!(parse_fixture(&spec.sub_main(1, 0)).sub_main(), Some((1, 0)));
+
+        let native = ProjectSpec::new("Native").native().sub_main(0, 1).object(
+            ObjectSpec::new("Module1", 0x01)
+                .method("Helper", vec![0xC3])
+                .method("Main", vec![0xC3]),
+        );
+        assert_eq!(parse_fixture(&native).sub_main(), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_startup_form() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]))
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .object(ObjectSpec::new("Form2", 0x10).method("Form_Load", vec![0x14]));
+
+        // No GUI table: the first form of the object table
+        assert_eq!(parse_fixture(&spec).startup_form(), Some(1));
+        assert_eq!(
+            parse_fixture(&spec.clone().startup_form(2)).startup_form(),
+            Some(2)
+        );
+        assert_eq!(parse_fixture(&spec.sub_main(0, 0)).startup_form(), None);
+    }
+
+    #[test]
+    fn test_runtime_imports() {
+        // call Quit; ret | jmp [rtcMsgBox]
+        let build = |iat_va: u32| {
+            let mut thunk = vec![0xFF, 0x25];
+            thunk.extend_from_slice(&iat_va.to_le_bytes());
+            let spec = ProjectSpec::new("Native").native().object(
+                ObjectSpec::new("Module1", 0x01)
+                    .method("Main", vec![0xE8, 0x01, 0x00, 0x00, 0x00, 0xC3])
+                    .method("Quit", thunk),
+            );
+            build_vb_exe(&spec)
+        };
+
+        // The layout doesn't depend on the thunk operand, so a first build locates the IAT slot
+        let pe = PEFile::from_bytes(build(0)).unwrap();
+        let iat_rva = (0x1000..0x10000)
+            .find(|&rva| pe.import_at_iat_rva(rva).as_deref() == Some("rtcMsgBox"))
+            .unwrap();
+        assert!(VBFile::from_pe(pe).unwrap().runtime_imports().is_empty());
+
+        let vb_file =
+            VBFile::from_pe(PEFile::from_bytes(build(IMAGE_BASE + iat_rva)).unwrap()).unwrap();
+        let (thunk_rva, _) = vb_file.get_native_code_for_method(0, 1).unwrap();
+        assert_eq!(
+            vb_file.runtime_imports(),
+            [(thunk_rva, "rtcMsgBox".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_runtime_imports_without_runtime() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        let mut pe = PeBuilder::new();
+        let rva = pe.next_section_rva();
+        pe.section(".text", build_vb_section(&spec, rva));
+        let pe = PEFile::from_bytes_allow_packed(pe.build()).unwrap();
+
+        assert!(VBFile::from_pe(pe).unwrap().runtime_imports().is_empty());
+    }
+
+    #[test]
+    fn test_native_call_targets() {
+        // ImpAdCallHresult #1; ExitProc
+        let spec = ProjectSpec::new("Project1").object(
+            ObjectSpec::new("Module1", 0x01)
+                .constant(0)
+                .constant(IMAGE_BASE + 0x5010)
+                .method("Main", vec![0x09, 0x01, 0x00, 0x14]),
+        );
+        let vb = parse_fixture(&spec);
+
+        assert_eq!(vb.native_call_targets(0, 0), vec![0x5010]);
+        assert!(vb.native_call_targets(0, 1).is_empty());
+    }
+
+    #[test]
+    fn test_constant_records() {
+        let spec = ProjectSpec::new("Project1").object(
+            ObjectSpec::new("Module1", 0x01)
+                .constant(IMAGE_BASE + 0x5010)
+                .named_constant("MAX_ITEMS", 2, 10)
+                .named_constant("DEBUG_MODE", 11, 0xFFFF)
+                .named_constant("bad name", 3, 1),
+        );
+        let vb = parse_fixture(&spec);
+
+        let constants = &vb.objects()[0].constants;
+        assert_eq!(constants.len(), 2);
+        assert_eq!(constants[0].name, "MAX_ITEMS");
+        assert!(matches!(constants[0].value, ConstantValue::Integer(10)));
+        assert_eq!(constants[1].name, "DEBUG_MODE");
+        assert!(matches!(constants[1].value, ConstantValue::Boolean(true)));
+    }
+
+    #[test]
+    fn test_disassemble_method() {
+        // LitI2 5; ExitProc
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x5E, 0x05, 0x14]));
+        let vb = parse_fixture(&spec);
+
+        let mnemonics: Vec<String> = vb
+            .disassemble_method(0, 0)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.mnemonic)
+            .collect();
+        assert_eq!(mnemonics, ["LitI2", "ExitProc"]);
+        assert!(matches!(
+            vb.disassemble_method(0, 1),
+            Err(Error::InvalidVB(_))
+        ));
+
+        let native = parse_fixture(&ProjectSpec {
+            native: true,
+            ..spec
+        });
+        assert!(matches!(
+            native.disassemble_method(0, 0),
+            Err(Error::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_method_sizes() {
+        // LitI2 1; BranchF +3; LitI2 2; ExitProc; ExitProc
+        let branchy = vec![0x5E, 0x01, 0x1C, 0x03, 0x00, 0x5E, 0x02, 0x14, 0x14];
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Small", vec![0x5E, 0x05, 0x14]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Big", branchy));
+        let vb = parse_fixture(&spec);
+
+        assert_eq!(
+            vb.method_sizes(),
+            [
+                MethodSize {
+                    object: "Module2".to_string(),
+                    method: "Big".to_string(),
+                    size: 9,
+                    instructions: Some(5),
+                    basic_blocks: Some(3),
+                },
+                MethodSize {
+                    object: "Module1".to_string(),
+                    method: "Small".to_string(),
+                    size: 3,
+                    instructions: Some(2),
+                    basic_blocks: Some(1),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unparsed_object_keeps_indices() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("First", vec![0x14]))
+            .object(ObjectSpec::new("Module2", 0x01).method("Second", vec![0x14]))
+            .object(ObjectSpec::new("Module3", 0x01).method("Third", vec![0x14]));
+        let mut data = build_vb_exe(&spec);
+
+        // Corrupt the method count of the second object's descriptor
+        let vb = VBFile::from_pe(PEFile::from_bytes(data.clone()).unwrap()).unwrap();
+        let array_va = vb.object_table_header.unwrap().lp_object_array;
+        let count_rva =
+            vb.va_to_rva(array_va) + size_of::<VBPublicObjectDescriptor>() as u32 + 0x1C;
+        let offset = vb.pe_file.rva_to_offset(count_rva).unwrap();
+        data[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        let vb = VBFile::from_pe(PEFile::from_bytes(data).unwrap()).unwrap();
+        assert_eq!(vb.objects().len(), 3);
+        assert_eq!(vb.object_errors().len(), 1);
+        assert_eq!(vb.object_errors()[0].object_index, 1);
+
+        let unparsed = &vb.objects()[1];
+        assert!(!unparsed.is_parsed());
+        assert_eq!(unparsed.name, "<Unparsed1>");
+        assert_eq!(unparsed.method_count(), 0);
+
+        assert!(vb.objects()[2].is_parsed());
+        assert_eq!(vb.method_name(2, 0), Some("Third"));
+        assert!(vb.get_pcode_for_method(2, 0).is_some());
+    }
+
+    #[test]
+    fn test_pe32_plus_unsupported() {
+        let pe = PEFile::from_bytes(PeBuilder::new().pe64(true).vb_imports().build()).unwrap();
+        assert!(matches!(VBFile::from_pe(pe), Err(Error::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_external_components() {
+        let clsid = [
+            0x1E, 0x6A, 0xC1, 0x0F, 0x72, 0x83, 0xD0, 0x11, 0x9F, 0x4E, 0x00, 0xA0, 0xC9, 0x12,
+            0x10, 0xBE,
+        ];
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", vec![0x14]))
+            .control_component("MSCOMCTL.OCX", clsid)
+            .declare("kernel32", "Sleep")
+            .declare("KERNEL32", "GetTickCount")
+            .declare("user32", "MessageBeep");
+        let vb = parse_fixture(&spec);
+
+        let components = vb.external_components();
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[0].name, "MSCOMCTL.OCX");
+        assert_eq!(components[0].kind, ExternalKind::Control);
+        assert_eq!(components[0].guid, Some(format_guid(&clsid)));
+        assert_eq!(components[1].name, "kernel32");
+        assert_eq!(components[1].kind, ExternalKind::Library);
+        assert_eq!(components[1].guid, None);
+        assert_eq!(components[2].name, "user32");
+    }
+
+    #[test]
+    fn test_no_external_components() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        assert!(parse_fixture(&spec).external_components().is_empty());
+    }
+
+    #[test]
+    fn test_com_registration() {
+        let lib_id = [
+            0x78, 0x56, 0x34, 0x12, 0xBC, 0x9A, 0xF0, 0xDE, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+            0xCD, 0xEF,
+        ];
+        let clsid = [
+            0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x03, 0x00, 0xC0, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x46,
+        ];
+        let iid = [0xAA; 16];
+        let spec = ProjectSpec::new("MyLib")
+            .object(ObjectSpec::new("Class1", 0x02).method("Init", vec![0x14]))
+            .com_registration(lib_id)
+            .com_object("Class1", clsid, iid)
+            .com_object("Class2", [0x11; 16], [0x22; 16]);
+        let registration = parse_fixture(&spec).com_registration().unwrap();
+
+        assert_eq!(
+            registration.clsid.as_deref(),
+            Some("{00000001-0002-0003-C000-000000000046}")
+        );
+        assert_eq!(
+            registration.lib_id,
+            "{12345678-9ABC-DEF0-0123-456789ABCDEF}"
+        );
+        assert_eq!(registration.objects.len(), 2);
+        assert_eq!(registration.objects[0].name, "Class1");
+        assert_eq!(
+            registration.objects[0].interface_iid,
+            Some(format_guid(&iid))
+        );
+        assert_eq!(registration.objects[0].events_iid, None);
+        assert_eq!(registration.objects[1].name, "Class2");
+        assert_eq!(registration.objects[1].clsid, format_guid(&[0x11; 16]));
+    }
+
+    #[test]
+    fn test_no_com_registration() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        assert_eq!(parse_fixture(&spec).com_registration(), None);
+    }
+
+    #[test]
+    fn test_project_name_from_export_name() {
+        let spec =
+            ProjectSpec::new("").object(ObjectSpec::new("Class1", 0x02).method("Init", vec![0x14]));
+        let mut pe = PeBuilder::new()
+            .dll(true)
+            .vb_imports()
+            .export_name("MyLib.dll")
+            .export("DllGetClassObject", 0x1000);
+        let rva = pe.next_section_rva();
+        pe.section(".text", build_vb_section(&spec, rva));
+        let vb = VBFile::from_pe(PEFile::from_bytes(pe.build()).unwrap()).unwrap();
+
+        assert_eq!(vb.project_name().as_deref(), Some("MyLib"));
+    }
+
+    #[test]
+    fn test_format_guid() {
+        let bytes = [
+            0x78, 0x56, 0x34, 0x12, 0xBC, 0x9A, 0xF0, 0xDE, 0x01, 0x23, 0x45, 0x67, 0x89, 0xAB,
+            0xCD, 0xEF,
+        ];
+        assert_eq!(
+            format_guid(&bytes),
+            "{12345678-9ABC-DEF0-0123-456789ABCDEF}"
+        );
+    }
+
+    #[test]
+    fn test_with_events_members() {
+        let iid = [0x11; 16];
+        let mut form = ObjectSpec::new("Form1", 0x10)
+            .method("Form_Load", vec![0x14])
+            .method("m_Timer_Tick", vec![0x14]);
+        form.events_iids.push(iid);
+        let spec = ProjectSpec::new("Project1").object(form);
+        let vb_file = parse_fixture(&spec);
+
+        let members = vb_file.with_events_members(&vb_file.objects()[0]);
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "m_Timer");
+        assert_eq!(members[0].class_name, "Object");
+        assert_eq!(
+            members[0].events_iid,
+            "{11111111-1111-1111-1111-111111111111}"
+        );
+        assert_eq!(members[0].handlers, vec!["m_Timer_Tick"]);
+    }
+
+    #[test]
+    fn test_with_events_class_matches_source_iid() {
+        let timer_iid = [0x22; 16];
+        let mut form = ObjectSpec::new("Form1", 0x10)
+            .method("Form_Load", vec![0x14])
+            .method("tmr_Tick", vec![0x14]);
+        form.events_iids.push(timer_iid);
+        // Another sink of the same events is not their source
+        let mut other = ObjectSpec::new("Form2", 0x10).method("tmr_Tick", vec![0x14]);
+        other.events_iids.push(timer_iid);
+        let mut class = ObjectSpec::new("CTimer", 0x02).method("Start", vec![0x14]);
+        class.events_iids.push(timer_iid);
+        let spec = ProjectSpec::new("Project1")
+            .object(form)
+            .object(other)
+            .object(class);
+        let vb_file = parse_fixture(&spec);
+
+        let members = vb_file.with_events_members(&vb_file.objects()[0]);
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "tmr");
+        assert_eq!(members[0].class_name, "CTimer");
+    }
+
+    #[test]
+    fn test_form_controls() {
+        let form = ObjectSpec::new("Form1", 0x10)
+            .method("Form_Load", vec![0x14])
+            .control("Command1", 1)
+            .control("txtName", 2)
+            .control("Grid1", 3);
+        let module = ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]);
+        let spec = ProjectSpec::new("Project1").object(form).object(module);
+        let vb_file = parse_fixture(&spec);
+
+        let controls = &vb_file.objects()[0].controls;
+        let summary: Vec<(&str, &str, u16)> = controls
+            .iter()
+            .map(|c| (c.name.as_str(), c.classname.as_str(), c.index))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Command1", "VB.CommandButton", 1),
+                ("txtName", "VB.TextBox", 2),
+                ("Grid1", "Object", 3),
+            ]
+        );
+        assert!(controls.iter().all(|c| c.clsid.is_none()));
+        assert!(vb_file.objects()[1].controls.is_empty());
+    }
+
+    #[test]
+    fn test_vb_version() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        let parse = |runtime: &[u8]| {
+            let mut pe = PeBuilder::new();
+            let rva = pe.next_section_rva();
+            let mut section = build_vb_section(&spec, rva);
+            section[0x06..0x06 + runtime.len()].copy_from_slice(runtime);
+            pe.section(".text", section);
+            let pe = PEFile::from_bytes_allow_packed(pe.build()).unwrap();
+            VBFile::from_pe(pe).unwrap()
+        };
+
+        assert_eq!(parse(b"MSVBVM60.DLL\0").vb_version(), VBVersion::VB6);
+        assert_eq!(parse(b"MSVBVM50.DLL\0").vb_version(), VBVersion::VB5);
+        assert_eq!(parse(b"*\0").vb_version(), VBVersion::Unknown);
+
+        // Falls back to the imported runtime
+        assert_eq!(parse_fixture(&spec).vb_version(), VBVersion::VB6);
+    }
+
+    #[test]
+    fn test_vb4_unsupported() {
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Module1", 0x01).method("Main", vec![0x14]));
+        let parse = |magic: &[u8; 4], runtime: &str| {
+            let mut pe = PeBuilder::new().import(runtime, &["ThunRTMain"]);
+            let rva = pe.next_section_rva();
+            let mut section = build_vb_section(&spec, rva);
+            section[..4].copy_from_slice(magic);
+            pe.section(".text", section);
+            let pe = PEFile::from_bytes_allow_packed(pe.build()).unwrap();
+            VBFile::from_pe(pe)
+        };
+
+        let is_vb4 = |result: Result<VBFile>| matches!(result, Err(Error::Unsupported(msg)) if msg == "VB4 not yet supported");
+        assert!(is_vb4(parse(b"VB4!", "MSVBVM60.DLL")));
+        assert!(is_vb4(parse(b"\0\0\0\0", "VB40032.DLL")));
+        assert!(matches!(
+            parse(b"\0\0\0\0", "MSVBVM60.DLL"),
+            Err(Error::InvalidVB(_))
+        ));
+
+        assert_eq!(
+            VBVersion::from_runtime_dll("VB40032.DLL"),
+            Some(VBVersion::VB4)
+        );
+        assert_eq!(VBVersion::VB4.to_string(), "VB4");
+    }
+
+    #[test]
+    fn test_strings() {
+        // LitStr "Hello"; LitStr "Hi"; ExitProc
+        let pcode = vec![
+            0x1B, b'H', b'e', b'l', b'l', b'o', 0x00, 0x1B, b'H', b'i', 0x00, 0x14,
+        ];
+        let spec = ProjectSpec::new("Project1")
+            .object(ObjectSpec::new("Form1", 0x10).method("Form_Load", pcode));
+        let mut pe = PeBuilder::new().vb_imports();
+        let rva = pe.next_section_rva();
+        pe.section(".text", build_vb_section(&spec, rva));
+        let data_rva = pe.section(".data", b"\x01\x02Caption text\0ab\0".to_vec());
+        let vb_file = VBFile::from_pe(PEFile::from_bytes(pe.build()).unwrap()).unwrap();
+
+        let strings = vb_file.strings(3);
+        assert_eq!(
+            strings,
+            vec![
+                FoundString {
+                    location: "Form1.Form_Load".to_string(),
+                    address: vb_file.pcode_locations(0)[0].rva,
+                    value: "Hello".to_string(),
+                },
+                FoundString {
+                    location: ".data".to_string(),
+                    address: data_rva + 2,
+                    value: "Caption text".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            strings[0].to_string(),
+            format!("Form1.Form_Load\t{:08X}\tHello", strings[0].address)
+        );
+        assert_eq!(vb_file.strings(2).len(), 4);
     }
 }
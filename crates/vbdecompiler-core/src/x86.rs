@@ -7,7 +7,12 @@
 //! Provides x86 disassembly for native-compiled VB executables
 
 use crate::error::{Error, Result};
-use iced_x86::{Decoder, DecoderOptions, Formatter, IntelFormatter};
+use iced_x86::{
+    Decoder, DecoderOptions, FlowControl, Formatter, GasFormatter, Instruction, IntelFormatter,
+    MasmFormatter, NasmFormatter, OpKind,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
 
 /// x86 instruction representation
 #[derive(Debug, Clone)]
@@ -20,11 +25,121 @@ pub struct X86Instruction {
     pub text: String,
     /// Instruction length in bytes
     pub length: usize,
+    /// How the instruction transfers control
+    pub flow: X86FlowControl,
+    /// Target of a direct near branch or call
+    pub branch_target: Option<u64>,
+}
+
+impl X86Instruction {
+    fn from_iced(instr: &Instruction, bytes: &[u8], text: String) -> Self {
+        let branch_target = match instr.op0_kind() {
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+                Some(instr.near_branch_target())
+            }
+            _ => None,
+        };
+
+        Self {
+            address: instr.ip(),
+            bytes: bytes[..instr.len()].to_vec(),
+            text,
+            length: instr.len(),
+            flow: instr.flow_control().into(),
+            branch_target,
+        }
+    }
+
+    /// Address of the instruction that follows this one
+    pub fn next_address(&self) -> u64 {
+        self.address + self.length as u64
+    }
+}
+
+/// Control-flow class of an x86 instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X86FlowControl {
+    /// Execution continues with the next instruction
+    Next,
+    /// Direct call, returns to the next instruction
+    Call,
+    /// Call through a register or memory operand
+    IndirectCall,
+    /// Jcc, LOOP or JCXZ
+    ConditionalBranch,
+    /// Direct JMP
+    UnconditionalBranch,
+    /// JMP through a register or memory operand
+    IndirectBranch,
+    /// RET / IRET
+    Return,
+    /// Software interrupt (INT, INT3, SYSCALL)
+    Interrupt,
+    /// Instruction that always faults (UD2)
+    Exception,
+}
+
+impl X86FlowControl {
+    /// Whether this instruction ends a basic block
+    pub fn ends_block(self) -> bool {
+        matches!(
+            self,
+            X86FlowControl::ConditionalBranch
+                | X86FlowControl::UnconditionalBranch
+                | X86FlowControl::IndirectBranch
+                | X86FlowControl::Return
+                | X86FlowControl::Exception
+        )
+    }
+}
+
+impl From<FlowControl> for X86FlowControl {
+    fn from(flow: FlowControl) -> Self {
+        match flow {
+            FlowControl::Call => X86FlowControl::Call,
+            FlowControl::IndirectCall => X86FlowControl::IndirectCall,
+            FlowControl::ConditionalBranch => X86FlowControl::ConditionalBranch,
+            FlowControl::UnconditionalBranch => X86FlowControl::UnconditionalBranch,
+            FlowControl::IndirectBranch => X86FlowControl::IndirectBranch,
+            FlowControl::Return => X86FlowControl::Return,
+            FlowControl::Interrupt => X86FlowControl::Interrupt,
+            FlowControl::Exception => X86FlowControl::Exception,
+            _ => X86FlowControl::Next,
+        }
+    }
+}
+
+/// Assembly syntax used when formatting instructions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum X86Syntax {
+    /// Intel syntax (`mov eax,2Ah`)
+    #[default]
+    Intel,
+    /// AT&T / GNU assembler syntax (`mov $0x2a,%eax`)
+    ATT,
+    /// Microsoft assembler syntax
+    Masm,
+    /// Netwide assembler syntax
+    Nasm,
+}
+
+impl X86Syntax {
+    /// Create the iced-x86 formatter for this syntax
+    fn formatter(self) -> Box<dyn Formatter> {
+        match self {
+            X86Syntax::Intel => Box::new(IntelFormatter::new()),
+            X86Syntax::ATT => Box::new(GasFormatter::new()),
+            X86Syntax::Masm => Box::new(MasmFormatter::new()),
+            X86Syntax::Nasm => Box::new(NasmFormatter::new()),
+        }
+    }
 }
 
 /// x86 Disassembler using iced-x86
 pub struct X86Disassembler {
     bitness: u32,
+    syntax: X86Syntax,
+    base_address: u64,
 }
 
 impl X86Disassembler {
@@ -33,7 +148,31 @@ impl X86Disassembler {
     /// # Arguments
     /// * `bitness` - 16, 32, or 64 bit mode (VB is typically 32-bit)
     pub fn new(bitness: u32) -> Self {
-        Self { bitness }
+        Self::with_syntax(bitness, X86Syntax::default())
+    }
+
+    /// Create a disassembler that formats instructions in the given syntax
+    pub fn with_syntax(bitness: u32, syntax: X86Syntax) -> Self {
+        Self {
+            bitness,
+            syntax,
+            base_address: 0,
+        }
+    }
+
+    /// Load address that `disassemble` addresses are relative to
+    ///
+    /// With the image base set, methods can be disassembled by RVA while
+    /// instruction addresses and branch targets show where the code really
+    /// runs.
+    pub fn with_base_address(mut self, base_address: u64) -> Self {
+        self.set_base_address(base_address);
+        self
+    }
+
+    /// Change the base address of an existing disassembler
+    pub fn set_base_address(&mut self, base_address: u64) {
+        self.base_address = base_address;
     }
 
     /// Create a 32-bit disassembler (default for VB executables)
@@ -45,13 +184,15 @@ impl X86Disassembler {
     ///
     /// # Arguments
     /// * `code` - Raw bytes to disassemble
-    /// * `address` - Starting address (RVA or virtual address)
+    /// * `address` - Starting address, relative to the base address (an RVA
+    ///   with the image base set, a virtual address without)
     ///
     /// # Returns
     /// Vector of disassembled instructions
     pub fn disassemble(&self, code: &[u8], address: u64) -> Result<Vec<X86Instruction>> {
+        let address = self.base_address.wrapping_add(address);
         let mut decoder = Decoder::with_ip(self.bitness, code, address, DecoderOptions::NONE);
-        let mut formatter = IntelFormatter::new();
+        let mut formatter = self.syntax.formatter();
         let mut output = String::new();
         let mut instructions = Vec::new();
 
@@ -59,40 +200,56 @@ impl X86Disassembler {
             output.clear();
             formatter.format(&instr, &mut output);
 
-            let len = instr.len();
-            let mut bytes = vec![0u8; len];
-            bytes.copy_from_slice(&code[(instr.ip() - address) as usize..][..len]);
-
-            instructions.push(X86Instruction {
-                address: instr.ip(),
-                bytes,
-                text: output.clone(),
-                length: len,
-            });
+            let offset = (instr.ip() - address) as usize;
+            instructions.push(X86Instruction::from_iced(
+                &instr,
+                &code[offset..],
+                output.clone(),
+            ));
         }
 
         Ok(instructions)
     }
 
-    /// Disassemble a single instruction
+    /// Disassemble bytes into a columnar listing in NASM syntax
+    ///
+    /// Each line holds the address, the instruction bytes in hex (padded to
+    /// the longest instruction) and its NASM text, like `ndisasm` output.
+    /// Cutting the first two columns leaves source NASM can reassemble.
+    /// `address` is as for `disassemble`; the disassembler's own syntax is
+    /// ignored.
+    pub fn disassemble_to_listing(&self, code: &[u8], address: u64) -> Result<String> {
+        let nasm = X86Disassembler::with_syntax(self.bitness, X86Syntax::Nasm)
+            .with_base_address(self.base_address);
+        let instructions = nasm.disassemble(code, address)?;
+        let bytes_width = instructions.iter().map(|i| i.length * 2).max().unwrap_or(0);
+
+        let mut listing = String::new();
+        for instr in &instructions {
+            let bytes: String = instr.bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            let _ = writeln!(
+                listing,
+                "{:08X}  {:<width$}  {}",
+                instr.address,
+                bytes,
+                instr.text,
+                width = bytes_width
+            );
+        }
+        Ok(listing)
+    }
+
+    /// Disassemble a single instruction (`address` as for `disassemble`)
     pub fn disassemble_one(&self, code: &[u8], address: u64) -> Result<X86Instruction> {
+        let address = self.base_address.wrapping_add(address);
         let mut decoder = Decoder::with_ip(self.bitness, code, address, DecoderOptions::NONE);
-        let mut formatter = IntelFormatter::new();
+        let mut formatter = self.syntax.formatter();
         let mut output = String::new();
 
         if let Some(instr) = decoder.iter().next() {
             formatter.format(&instr, &mut output);
 
-            let len = instr.len();
-            let mut bytes = vec![0u8; len];
-            bytes.copy_from_slice(&code[..len]);
-
-            Ok(X86Instruction {
-                address: instr.ip(),
-                bytes,
-                text: output,
-                length: len,
-            })
+            Ok(X86Instruction::from_iced(&instr, code, output))
         } else {
             Err(Error::Decompilation("No instruction decoded".to_string()))
         }
@@ -105,6 +262,171 @@ impl Default for X86Disassembler {
     }
 }
 
+/// Basic block of native x86 code
+#[derive(Debug, Clone)]
+pub struct X86BasicBlock {
+    /// Address of the first instruction
+    pub start: u64,
+    /// Address just past the last instruction
+    pub end: u64,
+    /// Instructions in the block
+    pub instructions: Vec<X86Instruction>,
+    /// Start addresses of successor blocks (branch target first, then fall-through)
+    pub successors: Vec<u64>,
+    /// Block ends in an indirect jump whose target is not known
+    pub unresolved: bool,
+}
+
+/// Control-flow graph of a native x86 function
+///
+/// Leaders are the first instruction, every direct branch target inside the
+/// instruction range and every instruction following a block terminator.
+/// Branches to addresses outside the range (tail calls, jumps into other
+/// functions) end the block without adding an edge.
+#[derive(Debug, Clone, Default)]
+pub struct X86Cfg {
+    blocks: BTreeMap<u64, X86BasicBlock>,
+}
+
+impl X86Cfg {
+    /// Build a CFG from a linear instruction listing
+    pub fn new(instructions: Vec<X86Instruction>) -> Self {
+        let addresses: BTreeSet<u64> = instructions.iter().map(|i| i.address).collect();
+
+        let mut leaders = BTreeSet::new();
+        if let Some(first) = instructions.first() {
+            leaders.insert(first.address);
+        }
+        for instr in &instructions {
+            if matches!(
+                instr.flow,
+                X86FlowControl::ConditionalBranch | X86FlowControl::UnconditionalBranch
+            ) {
+                if let Some(target) = instr.branch_target.filter(|t| addresses.contains(t)) {
+                    leaders.insert(target);
+                }
+            }
+            if instr.flow.ends_block() && addresses.contains(&instr.next_address()) {
+                leaders.insert(instr.next_address());
+            }
+        }
+
+        let mut blocks = BTreeMap::new();
+        let mut current: Option<X86BasicBlock> = None;
+        for instr in instructions {
+            if leaders.contains(&instr.address) {
+                if let Some(block) = current.take() {
+                    blocks.insert(block.start, block);
+                }
+            }
+            let block = current.get_or_insert_with(|| X86BasicBlock {
+                start: instr.address,
+                end: instr.address,
+                instructions: Vec::new(),
+                successors: Vec::new(),
+                unresolved: false,
+            });
+            block.end = instr.next_address();
+            block.instructions.push(instr);
+        }
+        if let Some(block) = current {
+            blocks.insert(block.start, block);
+        }
+
+        for block in blocks.values_mut() {
+            let Some(last) = block.instructions.last() else {
+                continue;
+            };
+            let fall_through = Some(block.end).filter(|a| addresses.contains(a));
+            let target = last.branch_target.filter(|t| addresses.contains(t));
+
+            block.successors = match last.flow {
+                X86FlowControl::ConditionalBranch => {
+                    let mut succs: Vec<u64> = target.into_iter().collect();
+                    succs.extend(fall_through.filter(|a| Some(*a) != target));
+                    succs
+                }
+                X86FlowControl::UnconditionalBranch => target.into_iter().collect(),
+                X86FlowControl::IndirectBranch => {
+                    block.unresolved = true;
+                    Vec::new()
+                }
+                X86FlowControl::Return | X86FlowControl::Exception => Vec::new(),
+                _ => fall_through.into_iter().collect(),
+            };
+        }
+
+        Self { blocks }
+    }
+
+    /// Blocks in address order
+    pub fn blocks(&self) -> impl Iterator<Item = &X86BasicBlock> {
+        self.blocks.values()
+    }
+
+    /// Look up the block starting at `address`
+    pub fn block(&self, address: u64) -> Option<&X86BasicBlock> {
+        self.blocks.get(&address)
+    }
+
+    /// Number of basic blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether the graph has no blocks
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// Conditional edges are labelled `T` (taken) and `F` (fall-through).
+    /// Blocks ending in an unresolved indirect jump get a dashed edge to a
+    /// shared `?` node.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph cfg {\n");
+        out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+
+        for block in self.blocks.values() {
+            let mut label = format!("0x{:08X}:\\l", block.start);
+            for instr in &block.instructions {
+                let text = instr.text.replace('\\', "\\\\").replace('"', "\\\"");
+                let _ = write!(label, "  {}\\l", text);
+            }
+            let _ = writeln!(out, "    \"0x{:X}\" [label=\"{}\"];", block.start, label);
+        }
+
+        let mut any_unresolved = false;
+        for block in self.blocks.values() {
+            let conditional = block.successors.len() == 2;
+            for (i, succ) in block.successors.iter().enumerate() {
+                let attrs = match (conditional, i) {
+                    (true, 0) => " [label=\"T\"]",
+                    (true, _) => " [label=\"F\"]",
+                    _ => "",
+                };
+                let _ = writeln!(
+                    out,
+                    "    \"0x{:X}\" -> \"0x{:X}\"{};",
+                    block.start, succ, attrs
+                );
+            }
+            if block.unresolved {
+                any_unresolved = true;
+                let _ = writeln!(out, "    \"0x{:X}\" -> \"?\" [style=dashed];", block.start);
+            }
+        }
+        if any_unresolved {
+            out.push_str("    \"?\" [shape=ellipse];\n");
+        }
+
+        out.push_str("}\n");
+        out
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +476,19 @@ mod tests {
         assert!(instructions[2].text.contains("pop"));
     }
 
+    #[test]
+    fn test_base_address() {
+        let disasm = X86Disassembler::new_32bit().with_base_address(0x400000);
+
+        // CALL +0x0B at RVA 0x1000
+        let code = vec![0xE8, 0x0B, 0x00, 0x00, 0x00];
+        let instructions = disasm.disassemble(&code, 0x1000).unwrap();
+
+        assert_eq!(instructions[0].address, 0x401000);
+        assert_eq!(instructions[0].branch_target, Some(0x401010));
+        assert!(instructions[0].text.to_lowercase().contains("401010"));
+    }
+
     #[test]
     fn test_disassemble_one() {
         let disasm = X86Disassembler::new_32bit();
@@ -186,4 +521,84 @@ mod tests {
         assert!(instructions[0].text.contains("mov"));
         assert!(instructions[0].text.contains("rax"));
     }
+
+    #[test]
+    fn test_syntax_selection() {
+        // MOV EAX, 42
+        let code = vec![0xB8, 0x2A, 0x00, 0x00, 0x00];
+
+        let intel = X86Disassembler::with_syntax(32, X86Syntax::Intel)
+            .disassemble_one(&code, 0)
+            .unwrap();
+        let att = X86Disassembler::with_syntax(32, X86Syntax::ATT)
+            .disassemble_one(&code, 0)
+            .unwrap();
+
+        assert_eq!(intel.text, "mov eax,2Ah");
+        assert_eq!(att.text, "mov $0x2A,%eax");
+        assert_ne!(intel.text, att.text);
+
+        // The default disassembler keeps the Intel syntax
+        let default = X86Disassembler::new_32bit().disassemble(&code, 0).unwrap();
+        assert_eq!(default[0].text, intel.text);
+    }
+
+    #[test]
+    fn test_disassemble_to_listing() {
+        let disasm = X86Disassembler::new_32bit().with_base_address(0x400000);
+
+        // PUSH EBP; MOV EAX, 42; RET
+        let code = vec![0x55, 0xB8, 0x2A, 0x00, 0x00, 0x00, 0xC3];
+        let listing = disasm.disassemble_to_listing(&code, 0x1000).unwrap();
+
+        assert_eq!(
+            listing,
+            "00401000  55          push ebp\n\
+             00401001  B82A000000  mov eax,2Ah\n\
+             00401006  C3          ret\n"
+        );
+    }
+
+    #[test]
+    fn test_cfg_conditional_jump() {
+        let disasm = X86Disassembler::new_32bit();
+
+        // TEST EAX, EAX; JE +1; INC EAX; RET
+        let code = vec![0x85, 0xC0, 0x74, 0x01, 0x40, 0xC3];
+        let cfg = X86Cfg::new(disasm.disassemble(&code, 0x401000).unwrap());
+
+        assert_eq!(cfg.len(), 3);
+        let starts: Vec<u64> = cfg.blocks().map(|b| b.start).collect();
+        assert_eq!(starts, vec![0x401000, 0x401004, 0x401005]);
+
+        // Taken edge first, then fall-through
+        assert_eq!(
+            cfg.block(0x401000).unwrap().successors,
+            vec![0x401005, 0x401004]
+        );
+        assert_eq!(cfg.block(0x401004).unwrap().successors, vec![0x401005]);
+        assert!(cfg.block(0x401005).unwrap().successors.is_empty());
+        assert!(cfg.blocks().all(|b| !b.unresolved));
+
+        let dot = cfg.to_dot();
+        assert!(dot.starts_with("digraph cfg {"));
+        assert!(dot.contains("\"0x401000\" -> \"0x401005\" [label=\"T\"];"));
+        assert!(dot.contains("\"0x401000\" -> \"0x401004\" [label=\"F\"];"));
+        assert!(dot.contains("\"0x401004\" -> \"0x401005\";"));
+    }
+
+    #[test]
+    fn test_cfg_indirect_jump_is_unresolved() {
+        let disasm = X86Disassembler::new_32bit();
+
+        // JMP EAX; RET
+        let code = vec![0xFF, 0xE0, 0xC3];
+        let cfg = X86Cfg::new(disasm.disassemble(&code, 0).unwrap());
+
+        assert_eq!(cfg.len(), 2);
+        let head = cfg.block(0).unwrap();
+        assert!(head.unresolved);
+        assert!(head.successors.is_empty());
+        assert!(cfg.to_dot().contains("\"0x0\" -> \"?\" [style=dashed];"));
+    }
 }
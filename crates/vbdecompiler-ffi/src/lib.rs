@@ -8,9 +8,13 @@
 //! allowing the C++/Qt GUI to call into the Rust decompiler.
 
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_void};
 use std::ptr;
-use vbdecompiler_core::{Decompiler, X86Disassembler};
+use vbdecompiler_core::pe::PEFile;
+use vbdecompiler_core::vb::VBFile;
+use vbdecompiler_core::{
+    DecompilationResult, Decompiler, ProgressEvent, X86Disassembler, X86Syntax,
+};
 
 /// Opaque handle to a Decompiler instance
 #[repr(C)]
@@ -33,6 +37,37 @@ pub struct VBDecompilationResult {
     pub method_count: usize,
 }
 
+/// Progress event passed to a C progress callback
+#[repr(C)]
+pub struct VBProgressEvent {
+    /// 0 = parsing PE, 1 = parsing VB, 2 = method started, 3 = method done
+    pub kind: c_int,
+    /// Method name for "method started" events, NULL otherwise (valid only during the call)
+    pub name: *const c_char,
+    /// Number of methods finished so far ("method done" only)
+    pub index: usize,
+    /// Total number of methods ("method done" only)
+    pub total: usize,
+}
+
+/// C progress callback
+pub type VBProgressCallback = extern "C" fn(event: *const VBProgressEvent, user_data: *mut c_void);
+
+/// User data pointer handed back to the C callback
+struct UserData(*mut c_void);
+
+// SAFETY: the caller of vbdecompiler_set_progress_callback guarantees that
+// the callback and its user data may be used from any thread.
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    // Accessed through a method so closures capture the whole wrapper
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
 /// Create a new decompiler instance
 #[no_mangle]
 pub extern "C" fn vbdecompiler_new() -> *mut VBDecompilerHandle {
@@ -50,6 +85,44 @@ pub extern "C" fn vbdecompiler_free(handle: *mut VBDecompilerHandle) {
     }
 }
 
+/// Set a progress callback (NULL clears it)
+///
+/// The callback may be invoked concurrently from several worker threads, so
+/// it and `user_data` must be thread-safe.
+#[no_mangle]
+pub extern "C" fn vbdecompiler_set_progress_callback(
+    handle: *mut VBDecompilerHandle,
+    callback: Option<VBProgressCallback>,
+    user_data: *mut c_void,
+) {
+    if handle.is_null() {
+        return;
+    }
+
+    let decompiler = unsafe { &mut *(handle as *mut Decompiler) };
+    let Some(callback) = callback else {
+        decompiler.clear_progress_callback();
+        return;
+    };
+
+    let user_data = UserData(user_data);
+    decompiler.set_progress_callback(Box::new(move |event| {
+        let (kind, name, index, total) = match event {
+            ProgressEvent::ParsingPe => (0, None, 0, 0),
+            ProgressEvent::ParsingVb => (1, None, 0, 0),
+            ProgressEvent::MethodStarted { name } => (2, CString::new(name).ok(), 0, 0),
+            ProgressEvent::MethodDone { index, total } => (3, None, index, total),
+        };
+        let c_event = VBProgressEvent {
+            kind,
+            name: name.as_ref().map_or(ptr::null(), |name| name.as_ptr()),
+            index,
+            total,
+        };
+        callback(&c_event, user_data.get());
+    }));
+}
+
 /// Decompile a file
 ///
 /// Returns 0 on success, non-zero error code on failure
@@ -71,7 +144,37 @@ pub extern "C" fn vbdecompiler_decompile_file(
         Err(_) => return -2, // Invalid UTF-8
     };
 
-    match decompiler.decompile_file(path_str) {
+    store_result(decompiler.decompile_file(path_str), result)
+}
+
+/// Decompile a VB executable held in memory
+///
+/// `data` is copied, so the caller may release it once this returns.
+/// Returns 0 on success, non-zero error code on failure
+/// On success, result must be freed with vbdecompiler_free_result
+#[no_mangle]
+pub extern "C" fn vbdecompiler_decompile_buffer(
+    handle: *mut VBDecompilerHandle,
+    data: *const u8,
+    len: usize,
+    result: *mut *mut VBDecompilationResult,
+) -> c_int {
+    if handle.is_null() || data.is_null() || result.is_null() {
+        return -1; // Invalid argument
+    }
+
+    let decompiler = unsafe { &mut *(handle as *mut Decompiler) };
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+
+    store_result(decompiler.decompile_bytes(bytes), result)
+}
+
+/// Hand a decompilation result to C through `result`, returning the status code
+fn store_result(
+    decompiled: vbdecompiler_core::Result<DecompilationResult>,
+    result: *mut *mut VBDecompilationResult,
+) -> c_int {
+    match decompiled {
         Ok(res) => {
             let c_result = Box::new(VBDecompilationResult {
                 project_name: match CString::new(res.project_name) {
@@ -129,6 +232,177 @@ pub extern "C" fn vbdecompiler_last_error() -> *const c_char {
     ptr::null()
 }
 
+/// Cheaply check whether a buffer looks like a VB executable
+///
+/// Only the PE headers and the first code section are examined, so this is
+/// suited to scanning many files. Returns 1 if the VB signature is found,
+/// 0 if not and -1 for a NULL data pointer.
+#[no_mangle]
+pub extern "C" fn vbdecompiler_is_vb(data: *const u8, len: usize) -> c_int {
+    if data.is_null() {
+        return -1; // Invalid argument
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+    c_int::from(vbdecompiler_core::is_vb_executable(bytes))
+}
+
+/// One sample of an entropy profile
+#[repr(C)]
+pub struct VBEntropyPoint {
+    /// File offset of the window
+    pub offset: usize,
+    /// Shannon entropy of the window (0.0-8.0)
+    pub entropy: f64,
+}
+
+/// Compute a sliding-window entropy profile of a PE file held in memory
+///
+/// Packed files are accepted. Returns 0 on success, non-zero error code on
+/// failure. On success, points must be freed with vbdecompiler_free_entropy_profile
+#[no_mangle]
+pub extern "C" fn vbdecompiler_entropy_profile(
+    data: *const u8,
+    len: usize,
+    window: usize,
+    step: usize,
+    points: *mut *mut VBEntropyPoint,
+    count: *mut usize,
+) -> c_int {
+    if data.is_null() || points.is_null() || count.is_null() {
+        return -1; // Invalid argument
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts(data, len) }.to_vec();
+    let Ok(pe) = PEFile::from_bytes_allow_packed(bytes) else {
+        return -3; // Not a PE file
+    };
+
+    let profile: Box<[VBEntropyPoint]> = pe
+        .entropy_profile(window, step)
+        .into_iter()
+        .map(|(offset, entropy)| VBEntropyPoint { offset, entropy })
+        .collect();
+    unsafe {
+        *count = profile.len();
+        *points = Box::into_raw(profile) as *mut VBEntropyPoint;
+    }
+    0
+}
+
+/// Free an entropy profile
+#[no_mangle]
+pub extern "C" fn vbdecompiler_free_entropy_profile(points: *mut VBEntropyPoint, count: usize) {
+    if !points.is_null() {
+        unsafe {
+            let _ = Box::from_raw(ptr::slice_from_raw_parts_mut(points, count));
+        }
+    }
+}
+
+// ============================================================================
+// VB File FFI
+// ============================================================================
+
+/// Opaque handle to a parsed VB executable
+#[repr(C)]
+pub struct VBFileHandle {
+    _private: [u8; 0],
+}
+
+/// Parse a VB executable without decompiling it
+///
+/// Returns NULL on failure. The handle must be freed with vbdecompiler_close
+#[no_mangle]
+pub extern "C" fn vbdecompiler_open(path: *const c_char) -> *mut VBFileHandle {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+
+    let Ok(path_str) = unsafe { CStr::from_ptr(path) }.to_str() else {
+        return ptr::null_mut();
+    };
+
+    match Decompiler::new().parse_file(path_str) {
+        Ok(vb_file) => Box::into_raw(Box::new(vb_file)) as *mut VBFileHandle,
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Free a VB file handle
+#[no_mangle]
+pub extern "C" fn vbdecompiler_close(handle: *mut VBFileHandle) {
+    if !handle.is_null() {
+        unsafe {
+            let _ = Box::from_raw(handle as *mut VBFile);
+        }
+    }
+}
+
+/// Borrow the VBFile behind a handle, if any
+fn vb_file<'a>(handle: *const VBFileHandle) -> Option<&'a VBFile> {
+    unsafe { (handle as *const VBFile).as_ref() }
+}
+
+/// Copy a string for C, to be freed with vbdecompiler_free_string
+fn to_c_string(s: &str) -> *mut c_char {
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Get the project name (NULL if unknown)
+///
+/// The string must be freed with vbdecompiler_free_string
+#[no_mangle]
+pub extern "C" fn vbdecompiler_project_name(handle: *const VBFileHandle) -> *mut c_char {
+    vb_file(handle)
+        .and_then(VBFile::project_name)
+        .map_or(ptr::null_mut(), |name| to_c_string(&name))
+}
+
+/// Get the number of objects (0 for a NULL handle)
+#[no_mangle]
+pub extern "C" fn vbdecompiler_object_count(handle: *const VBFileHandle) -> usize {
+    vb_file(handle).map_or(0, |vb_file| vb_file.objects().len())
+}
+
+/// Get the name of an object (NULL if out of range)
+///
+/// The string must be freed with vbdecompiler_free_string
+#[no_mangle]
+pub extern "C" fn vbdecompiler_object_name(
+    handle: *const VBFileHandle,
+    object_index: usize,
+) -> *mut c_char {
+    vb_file(handle)
+        .and_then(|vb_file| vb_file.object(object_index))
+        .map_or(ptr::null_mut(), |object| to_c_string(&object.name))
+}
+
+/// Get the number of methods of an object (0 if out of range)
+#[no_mangle]
+pub extern "C" fn vbdecompiler_method_count(
+    handle: *const VBFileHandle,
+    object_index: usize,
+) -> usize {
+    vb_file(handle)
+        .and_then(|vb_file| vb_file.object(object_index))
+        .map_or(0, |object| object.method_names.len())
+}
+
+/// Get the name of a method (NULL if out of range)
+///
+/// The string must be freed with vbdecompiler_free_string
+#[no_mangle]
+pub extern "C" fn vbdecompiler_method_name(
+    handle: *const VBFileHandle,
+    object_index: usize,
+    method_index: usize,
+) -> *mut c_char {
+    vb_file(handle)
+        .and_then(|vb_file| vb_file.method_name(object_index, method_index))
+        .map_or(ptr::null_mut(), to_c_string)
+}
+
 // ============================================================================
 // X86 Disassembler FFI
 // ============================================================================
@@ -168,6 +442,25 @@ pub extern "C" fn x86_disassembler_new_with_bitness(bitness: u32) -> *mut X86Dis
     Box::into_raw(disasm) as *mut X86DisassemblerHandle
 }
 
+/// Create a new x86 disassembler with specific bitness and output syntax
+///
+/// `syntax` is 0 for Intel, 1 for AT&T, 2 for MASM and 3 for NASM. Unknown
+/// values fall back to Intel.
+#[no_mangle]
+pub extern "C" fn x86_disassembler_new_with_syntax(
+    bitness: u32,
+    syntax: c_int,
+) -> *mut X86DisassemblerHandle {
+    let syntax = match syntax {
+        1 => X86Syntax::ATT,
+        2 => X86Syntax::Masm,
+        3 => X86Syntax::Nasm,
+        _ => X86Syntax::Intel,
+    };
+    let disasm = Box::new(X86Disassembler::with_syntax(bitness, syntax));
+    Box::into_raw(disasm) as *mut X86DisassemblerHandle
+}
+
 /// Free an x86 disassembler instance
 #[no_mangle]
 pub extern "C" fn x86_disassembler_free(handle: *mut X86DisassemblerHandle) {
@@ -178,8 +471,27 @@ pub extern "C" fn x86_disassembler_free(handle: *mut X86DisassemblerHandle) {
     }
 }
 
+/// Set the load address that `x86_disassemble` addresses are relative to
+///
+/// With the image base set, code can be disassembled by RVA while
+/// instruction addresses and branch targets show the real load address.
+#[no_mangle]
+pub extern "C" fn x86_disassembler_set_base_address(
+    handle: *mut X86DisassemblerHandle,
+    base_address: u64,
+) {
+    if handle.is_null() {
+        return;
+    }
+
+    let disasm = unsafe { &mut *(handle as *mut X86Disassembler) };
+    disasm.set_base_address(base_address);
+}
+
 /// Disassemble bytes
 ///
+/// `address` is relative to the base address set with
+/// x86_disassembler_set_base_address (0 by default, making it absolute).
 /// Returns number of instructions disassembled, or -1 on error
 /// results array must be freed with x86_disassembler_free_results
 #[no_mangle]
@@ -35,6 +35,31 @@ typedef struct {
     size_t method_count;
 } VBDecompilationResult;
 
+/**
+ * Progress event kinds
+ */
+typedef enum {
+    VB_PROGRESS_PARSING_PE = 0,
+    VB_PROGRESS_PARSING_VB = 1,
+    VB_PROGRESS_METHOD_STARTED = 2,
+    VB_PROGRESS_METHOD_DONE = 3
+} VBProgressKind;
+
+/**
+ * Progress event passed to a progress callback
+ */
+typedef struct {
+    int kind;           // VBProgressKind
+    const char* name;   // Method name for METHOD_STARTED, NULL otherwise (valid only during the call)
+    size_t index;       // Methods finished so far (METHOD_DONE only)
+    size_t total;       // Total number of methods (METHOD_DONE only)
+} VBProgressEvent;
+
+/**
+ * Progress callback
+ */
+typedef void (*VBProgressCallback)(const VBProgressEvent* event, void* user_data);
+
 /**
  * Create a new decompiler instance
  * 
@@ -49,6 +74,22 @@ VBDecompilerHandle* vbdecompiler_new(void);
  */
 void vbdecompiler_free(VBDecompilerHandle* handle);
 
+/**
+ * Set a progress callback for subsequent decompilations
+ * 
+ * Methods are decompiled in parallel, so the callback may be invoked
+ * concurrently from several worker threads.
+ * 
+ * @param handle Decompiler handle
+ * @param callback Callback to invoke, or NULL to clear it
+ * @param user_data Pointer passed back to every callback invocation
+ */
+void vbdecompiler_set_progress_callback(
+    VBDecompilerHandle* handle,
+    VBProgressCallback callback,
+    void* user_data
+);
+
 /**
  * Decompile a VB executable file
  * 
@@ -66,6 +107,24 @@ int vbdecompiler_decompile_file(
     VBDecompilationResult** result
 );
 
+/**
+ * Decompile a VB executable held in memory
+ * 
+ * @param handle Decompiler handle
+ * @param data Executable bytes (copied; may be released after the call)
+ * @param len Number of bytes at data
+ * @param result Output pointer for decompilation result (must be freed with vbdecompiler_free_result)
+ * @return 0 on success, negative error code on failure
+ *         -1: Invalid argument (NULL pointer)
+ *         -3: Decompilation error
+ */
+int vbdecompiler_decompile_buffer(
+    VBDecompilerHandle* handle,
+    const uint8_t* data,
+    size_t len,
+    VBDecompilationResult** result
+);
+
 /**
  * Free a decompilation result
  * 
@@ -87,6 +146,132 @@ void vbdecompiler_free_string(char* s);
  */
 const char* vbdecompiler_last_error(void);
 
+/**
+ * Cheaply check whether a buffer looks like a VB executable
+ * 
+ * Only the PE headers and the first code section are examined, so this is
+ * suited to scanning many files. A match does not guarantee that the file
+ * decompiles.
+ * 
+ * @param data File bytes
+ * @param len Number of bytes at data
+ * @return 1 if the VB signature is found, 0 if not, -1 if data is NULL
+ */
+int vbdecompiler_is_vb(const uint8_t* data, size_t len);
+
+/**
+ * One sample of an entropy profile
+ */
+typedef struct {
+    size_t offset;      // File offset of the window
+    double entropy;     // Shannon entropy of the window (0.0-8.0)
+} VBEntropyPoint;
+
+/**
+ * Compute a sliding-window entropy profile of a PE file held in memory
+ * 
+ * Windows start every step bytes; the last one is cut short at the end of
+ * the data. Packed files are accepted.
+ * 
+ * @param data PE file bytes
+ * @param len Number of bytes at data
+ * @param window Window size in bytes
+ * @param step Distance between window starts in bytes
+ * @param points Output pointer for the samples (must be freed with vbdecompiler_free_entropy_profile)
+ * @param count Output pointer for the number of samples
+ * @return 0 on success, negative error code on failure
+ *         -1: Invalid argument (NULL pointer)
+ *         -3: Not a PE file
+ */
+int vbdecompiler_entropy_profile(
+    const uint8_t* data,
+    size_t len,
+    size_t window,
+    size_t step,
+    VBEntropyPoint** points,
+    size_t* count
+);
+
+/**
+ * Free an entropy profile
+ * 
+ * @param points Samples to free
+ * @param count Number of samples
+ */
+void vbdecompiler_free_entropy_profile(VBEntropyPoint* points, size_t count);
+
+// ============================================================================
+// VB File FFI
+// ============================================================================
+
+/**
+ * Opaque handle to a parsed VB executable
+ */
+typedef struct VBFileHandle VBFileHandle;
+
+/**
+ * Parse a VB executable without decompiling it
+ * 
+ * @param path Path to VB executable (.exe, .dll, .ocx)
+ * @return Opaque handle, or NULL on failure; must be freed with vbdecompiler_close
+ */
+VBFileHandle* vbdecompiler_open(const char* path);
+
+/**
+ * Free a VB file handle
+ * 
+ * @param handle VB file handle to free
+ */
+void vbdecompiler_close(VBFileHandle* handle);
+
+/**
+ * Get the project name
+ * 
+ * @param handle VB file handle
+ * @return Project name, or NULL if unknown (must be freed with vbdecompiler_free_string)
+ */
+char* vbdecompiler_project_name(const VBFileHandle* handle);
+
+/**
+ * Get the number of objects (forms, modules, classes)
+ * 
+ * @param handle VB file handle
+ * @return Number of objects, 0 for a NULL handle
+ */
+size_t vbdecompiler_object_count(const VBFileHandle* handle);
+
+/**
+ * Get the name of an object
+ * 
+ * @param handle VB file handle
+ * @param object_index Object index
+ * @return Object name, or NULL if out of range (must be freed with vbdecompiler_free_string)
+ */
+char* vbdecompiler_object_name(const VBFileHandle* handle, size_t object_index);
+
+/**
+ * Get the number of methods of an object
+ * 
+ * @param handle VB file handle
+ * @param object_index Object index
+ * @return Number of methods, 0 if out of range
+ */
+size_t vbdecompiler_method_count(const VBFileHandle* handle, size_t object_index);
+
+/**
+ * Get the name of a method
+ * 
+ * @param handle VB file handle
+ * @param object_index Object index
+ * @param method_index Method index within the object
+ * @return Method name, or NULL if out of range (must be freed with vbdecompiler_free_string)
+ */
+char* vbdecompiler_method_name(
+    const VBFileHandle* handle,
+    size_t object_index,
+    size_t method_index
+);
+
 // ============================================================================
 // X86 Disassembler FFI
 // ============================================================================
@@ -122,6 +307,25 @@ X86DisassemblerHandle* x86_disassembler_new(void);
  */
 X86DisassemblerHandle* x86_disassembler_new_with_bitness(uint32_t bitness);
 
+/**
+ * Assembly syntax for x86_disassembler_new_with_syntax
+ */
+typedef enum {
+    X86_SYNTAX_INTEL = 0,
+    X86_SYNTAX_ATT = 1,
+    X86_SYNTAX_MASM = 2,
+    X86_SYNTAX_NASM = 3
+} X86Syntax;
+
+/**
+ * Create a new x86 disassembler with specific bitness and output syntax
+ * 
+ * @param bitness 16, 32, or 64 bit mode
+ * @param syntax Assembly syntax (unknown values fall back to Intel)
+ * @return Opaque handle to disassembler, must be freed with x86_disassembler_free
+ */
+X86DisassemblerHandle* x86_disassembler_new_with_syntax(uint32_t bitness, int syntax);
+
 /**
  * Free an x86 disassembler instance
  * 
@@ -129,13 +333,25 @@ X86DisassemblerHandle* x86_disassembler_new_with_bitness(uint32_t bitness);
  */
 void x86_disassembler_free(X86DisassemblerHandle* handle);
 
+/**
+ * Set the load address that x86_disassemble addresses are relative to
+ * 
+ * With the image base set, code can be disassembled by RVA while
+ * instruction addresses and branch targets show the real load address.
+ * 
+ * @param handle Disassembler handle
+ * @param base_address Load address (0 by default)
+ */
+void x86_disassembler_set_base_address(X86DisassemblerHandle* handle, uint64_t base_address);
+
 /**
  * Disassemble x86 code
  * 
  * @param handle Disassembler handle
  * @param code Byte array to disassemble
  * @param code_len Length of code array
- * @param address Starting address (RVA or virtual address)
+ * @param address Starting address, relative to the base address (an RVA with
+ *                the image base set, a virtual address without)
  * @param results Output pointer for instruction array (must be freed with x86_disassembler_free_results)
  * @param count Output pointer for number of instructions
  * @return Number of instructions on success, -1 on error
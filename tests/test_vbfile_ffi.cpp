@@ -0,0 +1,66 @@
+// VBDecompiler - Visual Basic Decompiler
+// Copyright (c) 2026 VBDecompiler Project
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/**
+ * Smoke test for the VB file FFI (project tree without decompiling)
+ *
+ * Usage: test_vbfile <vb-executable>
+ */
+
+#include "vbdecompiler_ffi.h"
+#include <print>
+
+int main(int argc, char** argv) {
+    std::println("VB File FFI Test");
+    std::println("================\n");
+
+    if (argc < 2) {
+        std::println(stderr, "Usage: {} <vb-executable>", argv[0]);
+        return 1;
+    }
+
+    auto* file = vbdecompiler_open(argv[1]);
+    if (!file) {
+        std::println(stderr, "Failed to open {}", argv[1]);
+        return 1;
+    }
+
+    char* project = vbdecompiler_project_name(file);
+    std::println("Project: {}", project ? project : "(unknown)");
+    vbdecompiler_free_string(project);
+
+    size_t object_count = vbdecompiler_object_count(file);
+    if (object_count == 0) {
+        std::println(stderr, "No objects found");
+        vbdecompiler_close(file);
+        return 1;
+    }
+
+    char* first = vbdecompiler_object_name(file, 0);
+    if (!first) {
+        std::println(stderr, "Failed to read the first object name");
+        vbdecompiler_close(file);
+        return 1;
+    }
+    vbdecompiler_free_string(first);
+
+    // Print the project tree
+    for (size_t i = 0; i < object_count; ++i) {
+        char* name = vbdecompiler_object_name(file, i);
+        std::println("{}", name ? name : "(null)");
+        vbdecompiler_free_string(name);
+
+        size_t method_count = vbdecompiler_method_count(file, i);
+        for (size_t j = 0; j < method_count; ++j) {
+            char* method = vbdecompiler_method_name(file, i, j);
+            std::println("  {}", method ? method : "(null)");
+            vbdecompiler_free_string(method);
+        }
+    }
+
+    vbdecompiler_close(file);
+
+    std::println("\nTest PASSED");
+    return 0;
+}